@@ -4,7 +4,7 @@ use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
 use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
 use ark_crypto_primitives::sponge::FieldBasedCryptographicSponge;
 use ark_ff::PrimeField;
-use ark_groth16::Groth16;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_r1cs_std::alloc::AllocVar;
 use ark_r1cs_std::boolean::Boolean;
 use ark_r1cs_std::eq::EqGadget;
@@ -15,12 +15,83 @@ use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisE
 use ark_serialize::{CanonicalSerialize, Compress};
 use ark_snark::SNARK;
 use ark_test_curves::bls12_381::{Bls12_381,Fr};
+use clap::{Parser, Subcommand};
+use common::{load_from_file, save_to_file, CircuitArgs};
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
-use serde_json::json;
+use serde::Deserialize;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader};
 use std::str::FromStr;
+use std::time::Instant;
+
+// Activation function applied to the output of a layer
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Activation {
+    // No activation: l(x) = x
+    Identity,
+    // ReLU: l(x) = max(zero, x)
+    ReLU,
+    // Piecewise-linear approximation of the sigmoid: l(x) = clamp(x - zero + 2 * scale, 0, 4 * scale),
+    // which approximates 4 * scale * sigmoid((x - zero) / scale)
+    Sigmoid { scale: u64 },
+}
+
+impl Activation {
+    // Encoding of the activation absorbed in the hash of the model
+    pub fn to_field_elements<F: PrimeField>(&self) -> [F; 2] {
+        match self {
+            Activation::Identity => [F::from(0u64), F::zero()],
+            Activation::ReLU => [F::from(1u64), F::zero()],
+            Activation::Sigmoid { scale } => [F::from(2u64), F::from(*scale)],
+        }
+    }
+
+    // Apply the activation to `value`, where `zero` is the zero value of the activation
+    pub fn enforce<F: PrimeField>(&self, value: &FpVar<F>, zero: &FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+        match self {
+            Activation::Identity => Ok(value.clone()),
+            Activation::ReLU => value.is_cmp(zero, core::cmp::Ordering::Greater, true)?.select(value, zero),
+            Activation::Sigmoid { scale } => {
+                let shifted = value + FpVar::Constant(F::from(2 * scale));
+                let upper = zero + FpVar::Constant(F::from(4 * scale));
+                let clamped = shifted.is_cmp(zero, core::cmp::Ordering::Greater, true)?.select(&shifted, zero)?;
+                let clamped = clamped.is_cmp(&upper, core::cmp::Ordering::Less, true)?.select(&clamped, &upper)?;
+                Ok(clamped - zero)
+            }
+        }
+    }
+}
+
+// Structure of the network
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    // argmax(activation_2(W_2 * activation_1((W_1 * x + b_1) / 2^22) + b_2))
+    #[default]
+    TwoLayer,
+    // Linear classifier: argmax(W_1 * x + b_1), without the shift, the activations and the second layer
+    Linear,
+}
+
+impl Mode {
+    // Encoding of the mode absorbed in the hash of the model
+    pub fn to_field_element<F: PrimeField>(&self) -> F {
+        match self {
+            Mode::TwoLayer => F::from(0u64),
+            Mode::Linear => F::from(1u64),
+        }
+    }
+}
+
+fn default_activation_1() -> Activation {
+    Activation::ReLU
+}
+
+fn default_activation_2() -> Activation {
+    Activation::Identity
+}
 
 #[derive(Clone)]
 pub struct TwoLayerNN<F: PrimeField> {
@@ -29,16 +100,52 @@ pub struct TwoLayerNN<F: PrimeField> {
     pub input: Vec<F>,             // Input vector (private input)
     pub bias_1: Vec<F>,            // Bias vector for the first layer
     pub bias_2: Vec<F>,            // Bias vector for the second layer
-    pub zero_relu: F,              // Zero value for the relu function,
-    pub public_statement: F   // hash value computed with poseidon
+    pub zero_relu: F,              // Zero value for the activation functions
+    pub activation_1: Activation,  // Activation function for the first layer
+    pub activation_2: Activation,  // Activation function for the second layer
+    pub mode: Mode,                // Structure of the network
+    pub public_statement: F,  // hash value computed with poseidon
+    pub salt: Option<F>,      // Secret salt for the nullifier (private input), no nullifier if None
+    pub nullifier: Option<F>  // hash value of input||salt computed with poseidon
 }
 
+impl<F: PrimeField> TwoLayerNN<F> {
+    // Check that the dimensions of the weights, biases and input are consistent, returning a description of the first mismatch
+    pub fn check_dimensions(&self) -> Result<(), String> {
+        if self.mode == Mode::Linear {
+            if self.weight_1.is_empty() {
+                return Err(String::from("Dimension mismatch: weight_1 must have at least one row"));
+            }
+            if !self.weight_2.is_empty() || !self.bias_2.is_empty() {
+                return Err(String::from("Dimension mismatch: weight_2 and bias_2 must be empty in linear mode"));
+            }
+        } else if self.weight_1.is_empty() || self.weight_2.is_empty() {
+            return Err(String::from("Dimension mismatch: weight_1 and weight_2 must have at least one row"));
+        }
+        // Ensure matrix multiplication is valid for the first layer
+        if let Some((j, row)) = self.weight_1.iter().enumerate().find(|(_, row)| row.len() != self.input.len()) {
+            return Err(format!("Dimension mismatch: row {} of weight_1 has {} columns, but input has {} elements", j, row.len(), self.input.len()));
+        }
+        if self.bias_1.len() != self.weight_1.len() {
+            return Err(format!("Dimension mismatch: bias_1 has {} elements, but weight_1 has {} rows", self.bias_1.len(), self.weight_1.len()));
+        }
+        if self.mode == Mode::Linear {
+            return Ok(());
+        }
+        // Ensure matrix multiplication is valid for the second layer
+        if let Some((j, row)) = self.weight_2.iter().enumerate().find(|(_, row)| row.len() != self.weight_1.len()) {
+            return Err(format!("Dimension mismatch: row {} of weight_2 has {} columns, but weight_1 has {} rows", j, row.len(), self.weight_1.len()));
+        }
+        if self.bias_2.len() != self.weight_2.len() {
+            return Err(format!("Dimension mismatch: bias_2 has {} elements, but weight_2 has {} rows", self.bias_2.len(), self.weight_2.len()));
+        }
+        Ok(())
+    }
 
-impl<F: PrimeField> ConstraintSynthesizer<F> for TwoLayerNN<F> {
-    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+    // Generate the constraints, computing the native values of the second layer in parallel if `parallel` is set
+    fn synthesize(self, cs: ConstraintSystemRef<F>, parallel: bool) -> Result<(), SynthesisError> {
         // Ensure that dimensions are consistent for matrix multiplication
-        assert_eq!(self.weight_1[0].len(), self.input.len());  // Ensure matrix multiplication is valid for the first layer
-        assert_eq!(self.weight_2[0].len(), self.weight_1.len());  // Ensure matrix multiplication is valid for the second layer
+        self.check_dimensions().map_err(|_| SynthesisError::Unsatisfiable)?;
 
         // Allocate input vector as witness variables (private input)
         let input_vars: Vec<FpVar<F>> = self.input.iter()
@@ -71,7 +178,10 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for TwoLayerNN<F> {
         // Allocate relu zero value as witness variables 
         let zero_relu_var = FpVar::new_witness(cs.clone(), || Ok(self.zero_relu)).unwrap();
 
-        // Step 1: Compute the intermediate result for the first layer (weight_1 * input + bias_1)
+        // Step 1: Compute the intermediate result for the first layer (weight_1 * input + bias_1), which is the final
+        // result in linear mode
+        // The linear combinations are used directly, without allocating their values as witnesses, so the native values
+        // are computed serially while the constraints are generated
         let mut intermediate_result: Vec<FpVar<F>> = vec![];
         for (j, row) in weight_1_vars.iter().enumerate() {
             let mut linear_combination = FpVar::Constant(F::zero());
@@ -79,39 +189,39 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for TwoLayerNN<F> {
                 let term = val.clone() * input_vars[i].clone();
                 linear_combination += term;
             }
-            linear_combination += bias_1_vars[j].clone();            
-            let linear_combination_bits = linear_combination.to_bits_le()?;
-            let shifted_bits = if linear_combination_bits.len() > 22usize {
-                linear_combination_bits[22usize..].to_vec()
-            } else {
-                vec![Boolean::constant(false)] 
+            linear_combination += bias_1_vars[j].clone();
+            let intermediate_var = match self.mode {
+                Mode::TwoLayer => shift_right(cs.clone(), &linear_combination, 22usize)?,
+                Mode::Linear => linear_combination,
             };
-            let intermediate_var = FpVar::new_witness(cs.clone(), || Boolean::le_bits_to_fp_var(&shifted_bits)?.value()).unwrap();
             intermediate_result.push(intermediate_var);
         }
 
-        // Step 2: Apply ReLU activation to the intermediate result (ReLU: l(x) = max(128, x))
-        let relu_result: Vec<FpVar<F>> = intermediate_result.into_iter()
-            .map(|val| {
-                val.is_cmp(&zero_relu_var, core::cmp::Ordering::Greater, true)
-                    .unwrap()
-                    .select(&val, &zero_relu_var)
-                    .unwrap()
-            })
-            .collect();
-
-        // Step 3: Compute the final result for the second layer (weight_2 * ReLU(intermediate_result) + bias_2)
-        let mut final_result: Vec<FpVar<F>> = vec![];
-        for (j, row) in weight_2_vars.iter().enumerate() {
-            let mut linear_combination = FpVar::Constant(F::zero());
-            for (i, val) in row.iter().enumerate() {
-                let term = val.clone() * relu_result[i].clone();
-                linear_combination += term;
+        let final_result = if self.mode == Mode::Linear {
+            intermediate_result
+        } else {
+            // Step 2: Apply the activation of the first layer to the intermediate result (e.g., ReLU: l(x) = max(128, x))
+            let relu_result: Vec<FpVar<F>> = intermediate_result.iter()
+                .map(|val| self.activation_1.enforce(val, &zero_relu_var))
+                .collect::<Result<_, _>>()?;
+
+            // Step 3: Compute the final result for the second layer (activation_2(weight_2 * activation_1(intermediate_result) + bias_2))
+            let final_values = relu_result.value()
+                .map(|relu_values| linear_layer(&self.weight_2, &relu_values, &self.bias_2, parallel));
+            let mut final_result: Vec<FpVar<F>> = vec![];
+            for (j, row) in weight_2_vars.iter().enumerate() {
+                let mut linear_combination = FpVar::Constant(F::zero());
+                for (i, val) in row.iter().enumerate() {
+                    let term = val.clone() * relu_result[i].clone();
+                    linear_combination += term;
+                }
+                linear_combination += bias_2_vars[j].clone();
+                let final_var = FpVar::new_witness(cs.clone(), || final_values.as_ref().map(|values| values[j]).map_err(|e| *e))?;
+                final_var.enforce_equal(&linear_combination)?;
+                final_result.push(self.activation_2.enforce(&final_var, &zero_relu_var)?);
             }
-            linear_combination += bias_2_vars[j].clone();
-            let final_var = FpVar::new_witness(cs.clone(), || linear_combination.value()).unwrap();
-            final_result.push(final_var);
-        }
+            final_result
+        };
 
         // Step 4: Apply the argmax function to find the index of the maximum value in the final result
         let mut max_value = final_result[0].clone();
@@ -150,11 +260,17 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for TwoLayerNN<F> {
         } 
 
         sponge_model.absorb(&zero_relu_var).unwrap();
+        for activation in [self.activation_1, self.activation_2] {
+            for element in activation.to_field_elements::<F>() {
+                sponge_model.absorb(&FpVar::Constant(element))?;
+            }
+        }
+        sponge_model.absorb(&FpVar::Constant(self.mode.to_field_element::<F>()))?;
         let hash_model_var: FpVar<F> = sponge_model.squeeze_field_elements(1).unwrap()[0].clone();
 
         // Step 6: Check that hash(input||output||hash_model) is the same as the public statement 
         let mut sponge_inference = PoseidonSpongeVar::<F>::new(cs.clone(), &poseidon_config);
-        for var in input_vars {
+        for var in &input_vars {
             sponge_inference.absorb(&var)?;
         }   
         sponge_inference.absorb(&computed_output_var).unwrap();
@@ -166,32 +282,220 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for TwoLayerNN<F> {
 
         hash_inference_var.enforce_equal(&public_statement_var)?;
 
+        // Step 7: If a salt is provided, check that hash(input||salt) is the same as the nullifier
+        if let Some(salt) = self.salt {
+            let salt_var = FpVar::new_witness(cs.clone(), || Ok(salt))?;
+            let mut sponge_nullifier = PoseidonSpongeVar::<F>::new(cs.clone(), &poseidon_config);
+            for var in &input_vars {
+                sponge_nullifier.absorb(&var)?;
+            }
+            sponge_nullifier.absorb(&salt_var)?;
+            let hash_nullifier_var: FpVar<F> = sponge_nullifier.squeeze_field_elements(1)?[0].clone();
+
+            let nullifier_var = FpVar::new_input(cs.clone(), || self.nullifier.ok_or(SynthesisError::AssignmentMissing))?;
+
+            hash_nullifier_var.enforce_equal(&nullifier_var)?;
+        }
 
         Ok(())
     }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize weight matrices, input vector, biases, and output
-    let weight_1 = load_matrix("parameters/weight_1.txt");  // Load first layer weights from file
-    let weight_2 = load_matrix("parameters/weight_2.txt");  // Load second layer weights from file
-    let input = load_vector("parameters/input.txt", 0);        // Load input vector from file
-    let bias_1 = load_vector("parameters/bias_1.txt", 0);      // Load first layer biases from file
-    let bias_2 = load_vector("parameters/bias_2.txt", 0);      // Load second layer biases from file
-    let zero_relu = load_value("parameters/zero_relu.txt", 0, 0);       // Load expected output from file
-    let expected_output = load_value("parameters/expected_output.txt", 0, 0);       // Load expected output from file
-    let public_statement = compute_model_var(&weight_1, &weight_2, &bias_1, &bias_2, &zero_relu, &input, &expected_output);
-    
-    // Create the circuit instance
-    let circuit = TwoLayerNN {
-        weight_1,
-        weight_2,
-        input,
-        bias_1,
-        bias_2,
-        zero_relu,
-        public_statement,
+impl<F: PrimeField> ConstraintSynthesizer<F> for TwoLayerNN<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        self.synthesize(cs, cfg!(feature = "parallel"))
+    }
+}
+
+// Compute the native value of weights * input + bias, with the rows computed in parallel if `parallel` is set
+fn linear_layer<F: PrimeField>(weights: &[Vec<F>], input: &[F], bias: &[F], parallel: bool) -> Vec<F> {
+    let row_value = |(row, b): (&Vec<F>, &F)| row.iter().zip(input).fold(*b, |acc, (w, x)| acc + *w * x);
+
+    #[cfg(feature = "parallel")]
+    if parallel {
+        use rayon::prelude::*;
+        return weights.par_iter().zip(bias.par_iter()).map(row_value).collect();
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+
+    weights.iter().zip(bias).map(row_value).collect()
+}
+
+// Discard the `shift` least significant bits of `value`, enforcing that the result is exactly the retained bits
+fn shift_right<F: PrimeField>(cs: ConstraintSystemRef<F>, value: &FpVar<F>, shift: usize) -> Result<FpVar<F>, SynthesisError> {
+    // The decomposition is unique, so the retained value is in the range [0, 2^(MODULUS_BIT_SIZE - shift))
+    let value_bits = value.to_bits_le()?;
+    let shifted_bits = if value_bits.len() > shift {
+        value_bits[shift..].to_vec()
+    } else {
+        vec![Boolean::constant(false)]
     };
+    let shifted_value = Boolean::le_bits_to_fp_var(&shifted_bits)?;
+
+    let shifted_var = FpVar::new_witness(cs, || shifted_value.value())?;
+    shifted_var.enforce_equal(&shifted_value)?;
+    Ok(shifted_var)
+}
+
+// Parameters of the network, the input, the expected output and the optional salt, as stored in model.json
+#[derive(Deserialize)]
+pub struct ModelParameters {
+    pub weight_1: Vec<Vec<i64>>,
+    #[serde(default)]
+    pub weight_2: Vec<Vec<i64>>,
+    pub input: Vec<i64>,
+    pub bias_1: Vec<i64>,
+    #[serde(default)]
+    pub bias_2: Vec<i64>,
+    #[serde(default)]
+    pub zero_relu: i64,
+    #[serde(default = "default_activation_1")]
+    pub activation_1: Activation,
+    #[serde(default = "default_activation_2")]
+    pub activation_2: Activation,
+    #[serde(default)]
+    pub mode: Mode,
+    pub expected_output: i64,
+    #[serde(default)]
+    pub salt: Option<i64>,
+}
+
+impl ModelParameters {
+    // Load the parameters from `dir/model.json` if it exists, otherwise from the .txt files in `dir`
+    pub fn load(dir: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let json_path = format!("{}/model.json", dir);
+        if std::path::Path::new(&json_path).exists() {
+            let reader = BufReader::new(File::open(json_path)?);
+            return Ok(serde_json::from_reader(reader)?);
+        }
+
+        Ok(ModelParameters {
+            weight_1: parse_file::<i64>(&format!("{}/weight_1.txt", dir)),  // Load first layer weights from file
+            weight_2: parse_file::<i64>(&format!("{}/weight_2.txt", dir)),  // Load second layer weights from file
+            input: load_column(&format!("{}/input.txt", dir), 0),           // Load input vector from file
+            bias_1: load_column(&format!("{}/bias_1.txt", dir), 0),         // Load first layer biases from file
+            bias_2: load_column(&format!("{}/bias_2.txt", dir), 0),         // Load second layer biases from file
+            zero_relu: parse_file::<i64>(&format!("{}/zero_relu.txt", dir))[0][0],              // Load relu zero value from file
+            activation_1: default_activation_1(),
+            activation_2: default_activation_2(),
+            mode: Mode::TwoLayer,
+            expected_output: parse_file::<i64>(&format!("{}/expected_output.txt", dir))[0][0],  // Load expected output from file
+            salt: load_optional_value(&format!("{}/salt.txt", dir)),        // Load the nullifier salt from file, if any
+        })
+    }
+
+    // Construct the circuit instance, computing the public statement and the nullifier
+    pub fn to_circuit(&self) -> TwoLayerNN<Fr> {
+        let weight_1 = to_field_matrix(&self.weight_1);
+        let weight_2 = to_field_matrix(&self.weight_2);
+        let input = to_field_vector(&self.input);
+        let bias_1 = to_field_vector(&self.bias_1);
+        let bias_2 = to_field_vector(&self.bias_2);
+        let zero_relu = Fr::from(self.zero_relu);
+        let (activation_1, activation_2, mode) = (self.activation_1, self.activation_2, self.mode);
+        let public_statement = compute_model_var(&weight_1, &weight_2, &bias_1, &bias_2, &zero_relu, &[activation_1, activation_2], mode, &input, &Fr::from(self.expected_output));
+        let salt = self.salt.map(Fr::from);
+        let nullifier = salt.map(|salt| compute_nullifier(&input, &salt));
+
+        TwoLayerNN { weight_1, weight_2, input, bias_1, bias_2, zero_relu, activation_1, activation_2, mode, public_statement, salt, nullifier }
+    }
+}
+
+#[derive(Parser)]
+#[command(about = "Groth16 proof of the inference of a two-layer neural network")]
+struct Cli {
+    /// Folder containing the parameters (model.json, or the .txt files as a fallback)
+    #[arg(long, default_value = "parameters")]
+    parameters: String,
+    #[command(flatten)]
+    circuit: CircuitArgs,
+    /// Command to run: if not given, the setup, proving and verification are run in sequence
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the proving key and the verifying key
+    Setup {
+        #[arg(long, default_value = "proof/proving_key.json")]
+        proving_key: String,
+        #[arg(long, default_value = "proof/verifying_key.json")]
+        verifying_key: String,
+    },
+    /// Generate a proof with a saved proving key
+    Prove {
+        #[arg(long, default_value = "proof/proving_key.json")]
+        proving_key: String,
+        #[arg(long, default_value = "proof/proof.json")]
+        proof: String,
+        #[arg(long, default_value = "proof/public_inputs.json")]
+        public_inputs: String,
+    },
+    /// Verify a saved proof with a saved verifying key
+    Verify {
+        #[arg(long, default_value = "proof/verifying_key.json")]
+        verifying_key: String,
+        #[arg(long, default_value = "proof/proof.json")]
+        proof: String,
+        #[arg(long, default_value = "proof/public_inputs.json")]
+        public_inputs: String,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    if cli.circuit.dump_cs.is_some() || cli.circuit.stats_baseline.is_some() {
+        cli.circuit.inspect_circuit("ai_inference", load_circuit(&cli.parameters)?)?;
+    }
+    if let Some(dump_cs) = &cli.circuit.dump_cs {
+        println!("Constraint system summary has been saved to '{}'.", dump_cs);
+    }
+
+    match cli.command {
+        Some(Command::Setup { proving_key, verifying_key }) => {
+            run_setup(&cli.parameters, &proving_key, &verifying_key)?;
+            println!("Proving key and verifying key have been saved to '{}' and '{}'.", proving_key, verifying_key);
+        }
+        Some(Command::Prove { proving_key, proof, public_inputs }) => {
+            run_prove(&cli.parameters, &proving_key, &proof, &public_inputs)?;
+            println!("Proof and public inputs have been saved to '{}' and '{}'.", proof, public_inputs);
+        }
+        Some(Command::Verify { verifying_key, proof, public_inputs }) => {
+            let is_valid = run_verify(&verifying_key, &proof, &public_inputs)?;
+            println!("Proof is {}", if is_valid { "valid" } else { "invalid" });
+            if !is_valid {
+                return Err("Proof is invalid".into());
+            }
+        }
+        None => {
+            run_setup(&cli.parameters, "proof/proving_key.json", "proof/verifying_key.json")?;
+            run_prove(&cli.parameters, "proof/proving_key.json", "proof/proof.json", "proof/public_inputs.json")?;
+            let is_valid = run_verify("proof/verifying_key.json", "proof/proof.json", "proof/public_inputs.json")?;
+            assert!(is_valid, "Proof is invalid");
+
+            println!("Proof, verification key, and public input have been saved to 'proof.json', 'verifying_key.json', and 'public_input.json'.");
+        }
+    }
+
+    Ok(())
+}
+
+// Load the circuit for the parameters in `parameters_dir`, checking its dimensions
+fn load_circuit(parameters_dir: &str) -> Result<TwoLayerNN<Fr>, Box<dyn std::error::Error>> {
+    // Load the parameters (from model.json, or from the .txt files as a fallback)
+    let circuit = ModelParameters::load(parameters_dir)?.to_circuit();
+
+    // Check the dimensions of the parameters
+    circuit.check_dimensions()?;
+    Ok(circuit)
+}
+
+// Setup phase: generate the proving key (pk) and the verifying key (vk), and save them to files
+fn run_setup(parameters_dir: &str, proving_key_path: &str, verifying_key_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let circuit = load_circuit(parameters_dir)?;
 
     // Create a random number generator
     let mut rng = ChaChaRng::from_entropy();
@@ -199,60 +503,55 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cs = ark_relations::r1cs::ConstraintSystem::<Fr>::new_ref();
 
     // Generate constraints
+    let start = Instant::now();
     circuit.clone().generate_constraints(cs.clone())?;
+    println!("Constraint generation time: {:?} (parallel: {})", start.elapsed(), cfg!(feature = "parallel"));
 
     // Print the number of constraints in the circuit
     println!("Number of constraints in the circuit: {}", cs.num_constraints());
-    
-    // Setup phase - generate the proving key (pk) and verifying key (vk)
-    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
-        .map_err(|e| format!("Setup failed: {}", e))?;
-        
-    // Proof generation phase
-    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
-        .map_err(|e| format!("Proof generation failed: {}", e))?;
 
-    // Verification phase
-    let is_valid = Groth16::<Bls12_381>::verify(&vk, &[public_statement], &proof)
-        .map_err(|e| format!("Verification failed: {}", e))?;
-    assert!(is_valid, "Proof is invalid");
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit, &mut rng)
+        .map_err(|e| format!("Setup failed: {}", e))?;
 
     // Size information
-    let proving_key_size = pk.serialized_size(Compress::No);
-    let verifying_key_size = vk.serialized_size(Compress::No);
-    let proof_size = proof.serialized_size(Compress::No);
+    println!("Proving key size: {} bytes", pk.serialized_size(Compress::No));
+    println!("Verifying key size: {} bytes", vk.serialized_size(Compress::No));
+
+    save_to_file(&pk, proving_key_path, "proving_key")?;
+    save_to_file(&vk, verifying_key_path, "verifying_key")?;
+    Ok(())
+}
+
+// Proof generation phase: generate a proof with the saved proving key, and save it with the public inputs to files
+fn run_prove(parameters_dir: &str, proving_key_path: &str, proof_path: &str, public_inputs_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let circuit = load_circuit(parameters_dir)?;
+    let public_inputs: Vec<Fr> = [Some(circuit.public_statement), circuit.nullifier].into_iter().flatten().collect();
 
-    println!("Proving key size: {} bytes", proving_key_size);
-    println!("Verifying key size: {} bytes", verifying_key_size);
-    println!("Proof size: {} bytes", proof_size);
+    let pk: ProvingKey<Bls12_381> = load_from_file(proving_key_path, "proving_key")?;
 
-    // Save proof, verification key, and public input to files
-    save_to_file(&proof, "proof/proof.json", "proof")?;
-    save_to_file(&vk, "proof/verifying_key.json", "verifying_key")?;
-    save_to_file(&vec![public_statement], "proof/public_inputs.json", "public_inputs")?;
+    // Create a random number generator
+    let mut rng = ChaChaRng::from_entropy();
 
-    println!("Proof, verification key, and public input have been saved to 'proof.json', 'verifying_key.json', and 'public_input.json'.");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng)
+        .map_err(|e| format!("Proof generation failed: {}", e))?;
 
+    // Size information
+    println!("Proof size: {} bytes", proof.serialized_size(Compress::No));
+
+    save_to_file(&proof, proof_path, "proof")?;
+    save_to_file(&public_inputs, public_inputs_path, "public_inputs")?;
     Ok(())
 }
 
-// Generic function to save serializable data
-fn save_to_file<T>(
-    item: &T,
-    file_path: &str,
-    key_name: &str
-) -> Result<(), Box<dyn std::error::Error>>
-where
-    T: CanonicalSerialize,
-{
-    let mut serialized_data = vec![0; item.serialized_size(Compress::No)];
-    item.serialize_uncompressed(&mut serialized_data[..])?;
-    
-    let json_data = json!({key_name: serialized_data});
-    let json_string = serde_json::to_string_pretty(&json_data)?;
-    
-    File::create(file_path)?.write_all(json_string.as_bytes())?;
-    Ok(())
+// Verification phase: verify the saved proof against the saved verifying key and public inputs
+fn run_verify(verifying_key_path: &str, proof_path: &str, public_inputs_path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let vk: VerifyingKey<Bls12_381> = load_from_file(verifying_key_path, "verifying_key")?;
+    let proof: Proof<Bls12_381> = load_from_file(proof_path, "proof")?;
+    let public_inputs: Vec<Fr> = load_from_file(public_inputs_path, "public_inputs")?;
+
+    let is_valid = Groth16::<Bls12_381>::verify(&vk, &public_inputs, &proof)
+        .map_err(|e| format!("Verification failed: {}", e))?;
+    Ok(is_valid)
 }
 
 // Function to parse a .txt file containing a matrix
@@ -269,29 +568,30 @@ where
         .collect()
 }
 
-// Extract a Fr matrix from a .txt file
-fn load_matrix(path: &str) -> Vec<Vec<Fr>> {
-
+// Extract a column from a .txt file
+fn load_column(path: &str, col: usize) -> Vec<i64> {
     let matrix = parse_file::<i64>(path);
     
-    matrix.iter().map(|row| 
-        {row.iter().map(|&col| Fr::from(col)).collect::<Vec<Fr>>()
-        }
-    ).collect()
+    matrix.iter().map(|row| row[col]).collect()
 }
 
-// Extract a Fr vector from a .txt file
-fn load_vector(path: &str, col: usize) -> Vec<Fr> {
-    let matrix = parse_file::<i64>(path);
-    
-    matrix.iter().map(|row| {Fr::from(row[col])}).collect()
+// Extract a value from a .txt file, if the file exists
+fn load_optional_value(path: &str) -> Option<i64> {
+    if std::path::Path::new(path).exists() {
+        Some(parse_file::<i64>(path)[0][0])
+    } else {
+        None
+    }
 }
 
-// Extract a Fr value from a .txt file
-fn load_value(path: &str, row: usize, col: usize) -> Fr {
-    let matrix = parse_file::<i64>(path);
-    
-    Fr::from(matrix[row][col])
+// Convert an integer matrix into a Fr matrix
+fn to_field_matrix(matrix: &[Vec<i64>]) -> Vec<Vec<Fr>> {
+    matrix.iter().map(|row| to_field_vector(row)).collect()
+}
+
+// Convert an integer vector into a Fr vector
+fn to_field_vector(vector: &[i64]) -> Vec<Fr> {
+    vector.iter().map(|&value| Fr::from(value)).collect()
 }
 
 // Initialize poseidon sponge
@@ -314,7 +614,8 @@ fn get_poseidon_config<F: PrimeField> () -> PoseidonConfig<F> {
 }
 
 // Compute public input 
-fn compute_model_var(w1: &Vec<Vec<Fr>>, w2: &Vec<Vec<Fr>>, b1: &Vec<Fr>, b2: &Vec<Fr>, zero: &Fr, input: &Vec<Fr>, output: &Fr) -> Fr {
+#[allow(clippy::too_many_arguments)]
+fn compute_model_var(w1: &Vec<Vec<Fr>>, w2: &Vec<Vec<Fr>>, b1: &Vec<Fr>, b2: &Vec<Fr>, zero: &Fr, activations: &[Activation], mode: Mode, input: &Vec<Fr>, output: &Fr) -> Fr {
     let sponge_params = get_poseidon_config();
     
     let mut sponge1 = PoseidonSponge::<Fr>::new(&sponge_params);
@@ -340,6 +641,12 @@ fn compute_model_var(w1: &Vec<Vec<Fr>>, w2: &Vec<Vec<Fr>>, b1: &Vec<Fr>, b2: &Ve
     } 
 
     sponge1.absorb(&zero);
+    for activation in activations {
+        for element in activation.to_field_elements::<Fr>() {
+            sponge1.absorb(&element);
+        }
+    }
+    sponge1.absorb(&mode.to_field_element::<Fr>());
     let hash_model: Fr = sponge1.squeeze_field_elements(1)[0];
 
     let mut sponge2 = PoseidonSponge::<Fr>::new(&sponge_params);
@@ -351,4 +658,295 @@ fn compute_model_var(w1: &Vec<Vec<Fr>>, w2: &Vec<Vec<Fr>>, b1: &Vec<Fr>, b2: &Ve
     sponge2.absorb(&hash_model);
 
     sponge2.squeeze_native_field_elements(1)[0]
-}
\ No newline at end of file
+}
+
+// Compute nullifier
+fn compute_nullifier(input: &Vec<Fr>, salt: &Fr) -> Fr {
+    let sponge_params = get_poseidon_config();
+
+    let mut sponge = PoseidonSponge::<Fr>::new(&sponge_params);
+    for var in input {
+        sponge.absorb(&var);
+    }
+    sponge.absorb(&salt);
+
+    sponge.squeeze_native_field_elements(1)[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    // Circuit for the network in `test_parameters`
+    fn circuit(salt: Option<i64>) -> TwoLayerNN<Fr> {
+        let mut parameters = ModelParameters::load("test_parameters").unwrap();
+        parameters.salt = salt;
+        parameters.to_circuit()
+    }
+
+    fn setup() -> (ProvingKey<Bls12_381>, VerifyingKey<Bls12_381>) {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        Groth16::<Bls12_381>::circuit_specific_setup(circuit(Some(0)), &mut rng).unwrap()
+    }
+
+    // Generate and verify a proof, and return its nullifier
+    fn prove(pk: &ProvingKey<Bls12_381>, vk: &VerifyingKey<Bls12_381>, circuit: TwoLayerNN<Fr>) -> Fr {
+        let mut rng = ChaChaRng::seed_from_u64(1);
+        let public_inputs = vec![circuit.public_statement, circuit.nullifier.unwrap()];
+        let proof = Groth16::<Bls12_381>::prove(pk, circuit, &mut rng).unwrap();
+        assert!(Groth16::<Bls12_381>::verify(vk, &public_inputs, &proof).unwrap());
+        public_inputs[1]
+    }
+
+    #[test]
+    fn test_same_input_and_salt_give_equal_nullifiers() {
+        let (pk, vk) = setup();
+        let nullifier_1 = prove(&pk, &vk, circuit(Some(42)));
+        let nullifier_2 = prove(&pk, &vk, circuit(Some(42)));
+        assert_eq!(nullifier_1, nullifier_2);
+    }
+
+    #[test]
+    fn test_different_salts_give_different_nullifiers() {
+        let (pk, vk) = setup();
+        let nullifier_1 = prove(&pk, &vk, circuit(Some(42)));
+        let nullifier_2 = prove(&pk, &vk, circuit(Some(43)));
+        assert_ne!(nullifier_1, nullifier_2);
+    }
+
+    #[test]
+    fn test_nullifier_is_bound_to_salt() {
+        let mut circuit = circuit(Some(42));
+        circuit.salt = Some(Fr::from(43));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_without_salt_has_single_public_input() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit(None).generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        // The first instance variable is the constant one
+        assert_eq!(cs.num_instance_variables(), 2);
+    }
+
+    #[test]
+    fn test_tampered_shift_fails() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let value = FpVar::new_witness(cs.clone(), || Ok(Fr::from(5u64 << 22) + Fr::from(7))).unwrap();
+        let shifted = shift_right(cs.clone(), &value, 22).unwrap();
+        assert_eq!(shifted.value().unwrap(), Fr::from(5));
+        assert!(cs.is_satisfied().unwrap());
+
+        // The shifted value is the last witness allocated by `shift_right`
+        let index = cs.num_witness_variables() - 1;
+        cs.borrow_mut().unwrap().witness_assignment[index] += Fr::from(1);
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_setup_prove_verify_with_saved_artifacts() {
+        let dir = std::env::temp_dir();
+        let path = |name: &str| dir.join(format!("ai_inference_test_{}.json", name)).to_str().unwrap().to_string();
+        let (proving_key, verifying_key, proof, public_inputs) = (path("proving_key"), path("verifying_key"), path("proof"), path("public_inputs"));
+
+        run_setup("test_parameters", &proving_key, &verifying_key).unwrap();
+        run_prove("test_parameters", &proving_key, &proof, &public_inputs).unwrap();
+        assert!(run_verify(&verifying_key, &proof, &public_inputs).unwrap());
+
+        // The saved proof does not verify against different public inputs
+        save_to_file(&vec![Fr::from(1)], &public_inputs, "public_inputs").unwrap();
+        assert!(!run_verify(&verifying_key, &proof, &public_inputs).unwrap());
+
+        for file in [proving_key, verifying_key, proof, public_inputs] {
+            std::fs::remove_file(file).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_json_model() {
+        let parameters = ModelParameters::load("test_parameters/json").unwrap();
+        let txt_parameters = ModelParameters::load("test_parameters").unwrap();
+        assert_eq!(parameters.weight_1, txt_parameters.weight_1);
+        assert_eq!(parameters.bias_2, txt_parameters.bias_2);
+        assert_eq!(parameters.salt, Some(42));
+
+        let (pk, vk) = setup();
+        let circuit = parameters.to_circuit();
+        assert_eq!(circuit.public_statement, txt_parameters.to_circuit().public_statement);
+        prove(&pk, &vk, circuit);
+    }
+
+    // Reference implementation of the approximate sigmoid over the integers
+    fn hard_sigmoid(value: i64, zero: i64, scale: i64) -> i64 {
+        (value - zero + 2 * scale).clamp(0, 4 * scale)
+    }
+
+    fn sigmoid(x: f64) -> f64 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+    #[test]
+    fn test_sigmoid_approximation() {
+        let (zero, scale) = (64, 16);
+        for value in 0..128 {
+            let approximation = hard_sigmoid(value, zero, scale) as f64 / (4 * scale) as f64;
+            let reference = sigmoid((value - zero) as f64 / scale as f64);
+            assert!((approximation - reference).abs() < 0.12, "value: {}", value);
+        }
+    }
+
+    #[test]
+    fn test_sigmoid_network_matches_float_reference() {
+        let (zero, scale) = (64i64, 16i64);
+        let input = vec![1i64, 2, 3];
+        // The first layer is diagonal, with weights multiplied by 2^22 so that the shift is exact
+        let hidden = [40i64, 64, 90];
+        let weight_1: Vec<Vec<i64>> = (0..3)
+            .map(|j| (0..3).map(|i| if i == j { (hidden[j] / input[j]) << 22 } else { 0 }).collect())
+            .collect();
+        let weight_2 = vec![vec![3, 0, 0], vec![0, 2, 0], vec![0, 0, 1]];
+
+        // Float reference: sigmoid activation on the dequantized hidden values
+        let float_output: Vec<f64> = weight_2.iter()
+            .map(|row| row.iter().zip(hidden).map(|(w, h)| *w as f64 * (4 * scale) as f64 * sigmoid((h - zero) as f64 / scale as f64)).sum())
+            .collect();
+        let expected_output = (0..3).fold(0, |max, j| if float_output[j] >= float_output[max] { j } else { max });
+
+        for (j, h) in hidden.iter().enumerate() {
+            let approximation = hard_sigmoid(*h, zero, scale) as f64 / (4 * scale) as f64;
+            assert!((approximation - sigmoid((h - zero) as f64 / scale as f64)).abs() < 0.12, "hidden unit: {}", j);
+        }
+
+        let parameters = ModelParameters {
+            weight_1,
+            weight_2,
+            input,
+            bias_1: vec![0; 3],
+            bias_2: vec![0; 3],
+            zero_relu: zero,
+            activation_1: Activation::Sigmoid { scale: scale as u64 },
+            activation_2: Activation::Identity,
+            mode: Mode::TwoLayer,
+            expected_output: expected_output as i64,
+            salt: None,
+        };
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        parameters.to_circuit().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_activation_is_bound_to_model_hash() {
+        let mut parameters = ModelParameters::load("test_parameters").unwrap();
+        let relu_statement = parameters.to_circuit().public_statement;
+        parameters.activation_1 = Activation::Sigmoid { scale: 16 };
+        assert_ne!(parameters.to_circuit().public_statement, relu_statement);
+
+        // A proof for the ReLU network does not verify against the sigmoid network
+        let mut circuit = parameters.to_circuit();
+        circuit.public_statement = relu_statement;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_linear_mode_matches_reference() {
+        let weight_1 = vec![vec![3i64, 1, 4, 1], vec![5, 9, 2, 6], vec![5, 3, 5, 8], vec![9, 7, 9, 3]];
+        let bias_1 = vec![0i64, 0, 20, 7];
+        let input = vec![2i64, 7, 1, 8];
+
+        // Reference linear classifier: argmax(weight_1 * input + bias_1), the first index in case of ties
+        let logits: Vec<i64> = weight_1.iter()
+            .zip(&bias_1)
+            .map(|(row, b)| row.iter().zip(&input).map(|(w, x)| w * x).sum::<i64>() + b)
+            .collect();
+        let expected_output = (0..logits.len()).fold(0, |max, j| if logits[j] > logits[max] { j } else { max });
+        assert_eq!(expected_output, 1);
+
+        let mut parameters = ModelParameters {
+            weight_1,
+            weight_2: vec![],
+            input,
+            bias_1,
+            bias_2: vec![],
+            zero_relu: 0,
+            activation_1: Activation::Identity,
+            activation_2: Activation::Identity,
+            mode: Mode::Linear,
+            expected_output: expected_output as i64,
+            salt: None,
+        };
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        parameters.to_circuit().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // Any other output is rejected
+        let linear_statement = parameters.to_circuit().public_statement;
+        parameters.expected_output = 2;
+        let mut circuit = parameters.to_circuit();
+        circuit.public_statement = linear_statement;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        // The mode is bound to the model hash, so it cannot be swapped for the same parameters
+        parameters.expected_output = expected_output as i64;
+        let circuit = parameters.to_circuit();
+        assert_eq!(circuit.public_statement, linear_statement);
+        let two_layer_statement = compute_model_var(&circuit.weight_1, &circuit.weight_2, &circuit.bias_1, &circuit.bias_2, &circuit.zero_relu, &[circuit.activation_1, circuit.activation_2], Mode::TwoLayer, &circuit.input, &Fr::from(expected_output as u64));
+        assert_ne!(two_layer_statement, linear_statement);
+
+        // A linear model has no second layer
+        let mut circuit = parameters.to_circuit();
+        circuit.weight_2 = vec![vec![Fr::from(1); 4]];
+        assert_eq!(circuit.check_dimensions().unwrap_err(), "Dimension mismatch: weight_2 and bias_2 must be empty in linear mode");
+    }
+
+    #[test]
+    fn test_linear_mode_from_json() {
+        let parameters: ModelParameters = serde_json::from_str(r#"{
+            "weight_1": [[1, 0], [0, 2]],
+            "input": [1, 2],
+            "bias_1": [0, 0],
+            "mode": "linear",
+            "expected_output": 1
+        }"#).unwrap();
+        assert_eq!(parameters.mode, Mode::Linear);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        parameters.to_circuit().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_and_serial_constraint_systems_match() {
+        let serial_cs = ConstraintSystem::<Fr>::new_ref();
+        circuit(Some(42)).synthesize(serial_cs.clone(), false).unwrap();
+        let parallel_cs = ConstraintSystem::<Fr>::new_ref();
+        circuit(Some(42)).synthesize(parallel_cs.clone(), true).unwrap();
+
+        assert!(serial_cs.is_satisfied().unwrap());
+        assert!(parallel_cs.is_satisfied().unwrap());
+        assert_eq!(serial_cs.num_constraints(), parallel_cs.num_constraints());
+        assert_eq!(serial_cs.num_witness_variables(), parallel_cs.num_witness_variables());
+        assert_eq!(serial_cs.borrow().unwrap().witness_assignment, parallel_cs.borrow().unwrap().witness_assignment);
+    }
+
+    #[test]
+    fn test_mismatched_dimensions() {
+        let mut circuit = circuit(None);
+        // weight_2 must have as many columns as weight_1 has rows
+        circuit.weight_2 = vec![vec![Fr::from(1); circuit.weight_1.len() + 1]; circuit.weight_2.len()];
+        let error = circuit.check_dimensions().unwrap_err();
+        assert_eq!(error, "Dimension mismatch: row 0 of weight_2 has 3 columns, but weight_1 has 2 rows");
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        assert!(matches!(circuit.generate_constraints(cs), Err(SynthesisError::Unsatisfiable)));
+    }
+}
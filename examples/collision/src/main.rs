@@ -0,0 +1,320 @@
+use std::{fs::File, io::BufReader, marker::PhantomData};
+
+use common::{CircuitArgs, OutputFormat, OutputFormatArgs, max_limb_byte_len, pack_bytes_into_field_elements, save_to_file_with_format, unpack_field_elements_into_bytes};
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::Groth16;
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, eq::EqGadget, fields::fp::FpVar, uint8::UInt8, R1CSVar, ToBytesGadget};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+use ark_snark::SNARK;
+use clap::Parser;
+use ark_test_curves::bls12_381::{Bls12_381, Fr as ScalarFieldBls};
+use ark_mnt4_753::{MNT4_753, Fr as ScalarFieldMnt};
+use rand_chacha::ChaChaRng;
+use rand::SeedableRng;
+use serde_json::Value;
+use ark_crypto_primitives::crh::{sha256::{constraints::Sha256Gadget, Sha256}, CRHScheme};
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+
+// Hash function for which a collision is proven
+pub trait CollisionHash<F: PrimeField> {
+    // Computes the digest of `input` as a vector of bytes
+    fn digest(input: &[u8]) -> Vec<u8>;
+    // Computes the digest of `preimage` in the circuit
+    fn digest_var(preimage: &[UInt8<F>]) -> ark_relations::r1cs::Result<Vec<UInt8<F>>>;
+}
+
+#[derive(Clone)]
+pub struct Sha256Collision;
+
+impl<F: PrimeField> CollisionHash<F> for Sha256Collision {
+    fn digest(input: &[u8]) -> Vec<u8> {
+        Sha256::evaluate(&(), input).unwrap()
+    }
+
+    fn digest_var(preimage: &[UInt8<F>]) -> ark_relations::r1cs::Result<Vec<UInt8<F>>> {
+        Ok(Sha256Gadget::<F>::digest(preimage)?.0)
+    }
+}
+
+#[derive(Clone)]
+pub struct PoseidonCollision;
+
+impl<F: PrimeField + Absorb> CollisionHash<F> for PoseidonCollision {
+    // The digest is the little-endian encoding of the squeezed field element, in as many bytes as the field requires
+    fn digest(input: &[u8]) -> Vec<u8> {
+        let mut sponge = PoseidonSponge::<F>::new(&get_poseidon_config());
+        sponge.absorb(&input.to_vec());
+        let mut digest = sponge.squeeze_field_elements::<F>(1)[0].into_bigint().to_bytes_le();
+        digest.truncate(F::MODULUS_BIT_SIZE.div_ceil(8) as usize);
+        digest
+    }
+
+    fn digest_var(preimage: &[UInt8<F>]) -> ark_relations::r1cs::Result<Vec<UInt8<F>>> {
+        let mut sponge = PoseidonSpongeVar::<F>::new(preimage.cs(), &get_poseidon_config());
+        sponge.absorb(&preimage.to_vec())?;
+        sponge.squeeze_field_elements(1)?[0].to_bytes()
+    }
+}
+
+// Two distinct preimages whose digests agree on the first `digest_len` bytes
+// With `digest_len` equal to the full length of the digest no collision is known: truncated digests make the
+// circuit usable as a toy example
+#[derive(Clone)]
+pub struct HashCollision<F: PrimeField, H: CollisionHash<F>> {
+    pub preimage_1: Vec<u8>,
+    pub preimage_2: Vec<u8>,
+    pub digest_len: usize,
+    pub digest: Vec<F>,
+    _hash_function: PhantomData<H>,
+}
+
+impl<F: PrimeField, H: CollisionHash<F>> HashCollision<F, H> {
+    pub fn new(preimage_1: &[u8], preimage_2: &[u8], digest_len: usize) -> Result<Self, String> {
+        let digest_1 = H::digest(preimage_1);
+        let digest_2 = H::digest(preimage_2);
+        if digest_len == 0 || digest_len > digest_1.len() {
+            return Err(format!("The digest length must be between 1 and {} bytes", digest_1.len()));
+        }
+        if preimage_1 == preimage_2 {
+            return Err("The preimages must be distinct".to_string());
+        }
+        if digest_1[..digest_len] != digest_2[..digest_len] {
+            return Err(format!("The preimages do not collide on the first {} bytes of the digest", digest_len));
+        }
+
+        Ok(HashCollision {
+            preimage_1: preimage_1.to_vec(),
+            preimage_2: preimage_2.to_vec(),
+            digest_len,
+            digest: digest_to_vec_digest(&digest_1[..digest_len]),
+            _hash_function: PhantomData,
+        })
+    }
+}
+
+impl<F: PrimeField, H: CollisionHash<F>> ConstraintSynthesizer<F> for HashCollision<F, H> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> ark_relations::r1cs::Result<()> {
+        // Allocate public inputs
+        let mut public_inputs: Vec<FpVar::<F>> = Vec::new();
+        for element in self.digest.iter() {
+            public_inputs.push(FpVar::<F>::new_input(cs.clone(), || Ok(element))?);
+        };
+        let expected_digest = vec_digest_to_digest(&public_inputs, self.digest_len)?;
+
+        // Allocate the witness
+        let preimage_1 = UInt8::<F>::new_witness_vec(cs.clone(), &self.preimage_1)?;
+        let preimage_2 = UInt8::<F>::new_witness_vec(cs.clone(), &self.preimage_2)?;
+
+        // Enforce that both preimages hash to the (truncated) public digest
+        H::digest_var(&preimage_1)?[..self.digest_len].enforce_equal(&expected_digest)?;
+        H::digest_var(&preimage_2)?[..self.digest_len].enforce_equal(&expected_digest)?;
+
+        // Enforce that the preimages are distinct. Preimages of different lengths are distinct by construction
+        if preimage_1.len() == preimage_2.len() {
+            preimage_1.is_eq(&preimage_2)?.enforce_equal(&Boolean::FALSE)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[command(about = "Groth16 proof of the knowledge of a hash collision")]
+struct Cli {
+    /// Hash function: sha256 or poseidon
+    #[arg(long, default_value = "sha256")]
+    hash: String,
+    /// Curve of the Groth16 setup: bls12_381 or mnt4_753
+    #[arg(long, default_value = "bls12_381")]
+    curve: String,
+    #[command(flatten)]
+    circuit: CircuitArgs,
+    #[command(flatten)]
+    output: OutputFormatArgs,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>>{
+    let cli = Cli::parse();
+
+    // Fetch the parameters: preimage_1, preimage_2, digest_len
+    let (preimage_1, preimage_2, digest_len) = read_parameters("parameters.json")?;
+
+    let (curve, hash_function) = (cli.curve.as_str(), cli.hash.as_str());
+    let stats_name = format!("collision_{}_{}", curve, hash_function);
+    let (circuit_args, format) = (&cli.circuit, cli.output.format);
+
+    let (preimage_1, preimage_2) = (preimage_1.as_bytes(), preimage_2.as_bytes());
+    match (curve, hash_function) {
+        ("bls12_381", "sha256") => prove_and_save::<Bls12_381, _>(HashCollision::<ScalarFieldBls, Sha256Collision>::new(preimage_1, preimage_2, digest_len)?, circuit_args, format, &stats_name),
+        ("bls12_381", "poseidon") => prove_and_save::<Bls12_381, _>(HashCollision::<ScalarFieldBls, PoseidonCollision>::new(preimage_1, preimage_2, digest_len)?, circuit_args, format, &stats_name),
+        ("mnt4_753", "sha256") => prove_and_save::<MNT4_753, _>(HashCollision::<ScalarFieldMnt, Sha256Collision>::new(preimage_1, preimage_2, digest_len)?, circuit_args, format, &stats_name),
+        ("mnt4_753", "poseidon") => prove_and_save::<MNT4_753, _>(HashCollision::<ScalarFieldMnt, PoseidonCollision>::new(preimage_1, preimage_2, digest_len)?, circuit_args, format, &stats_name),
+        ("bls12_381" | "mnt4_753", _) => Err(format!("Unknown hash function: {}. Valid options are sha256 and poseidon", hash_function).into()),
+        _ => Err(format!("Unknown curve: {}. Valid options are bls12_381 and mnt4_753", curve).into()),
+    }
+}
+
+// Generate and save the proof for `circuit`, inspecting it (as `stats_name`) as requested by `circuit_args`
+fn prove_and_save<E: Pairing, H: CollisionHash<E::ScalarField> + Clone>(circuit: HashCollision<E::ScalarField, H>, circuit_args: &CircuitArgs, format: OutputFormat, stats_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    // Randomness
+    let mut rng = ChaChaRng::from_entropy();
+
+    // Constraint system summary and size
+    circuit_args.inspect_circuit(stats_name, circuit.clone())?;
+
+    // Setup
+    let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .map_err(|e| format!("Setup failed: {}",e))?;
+
+    // Proving
+    let proof = Groth16::<E>::prove(&pk, circuit.clone(), &mut rng)
+        .map_err(|e| format!("Proof generation failed: {}", e))?;
+
+    // Verifying
+    let is_valid = Groth16::<E>::verify(&vk, &circuit.digest, &proof)
+        .map_err(|e| format!("Verification failed: {}", e))?;
+    assert!(is_valid,"Proof is invalid");
+
+    // Save proof, verification key, and public input to files
+    save_to_file_with_format(&proof, &format.file_path("proof/proof"), "proof", format)?;
+    save_to_file_with_format(&vk, &format.file_path("proof/verifying_key"), "verifying_key", format)?;
+    save_to_file_with_format(&circuit.digest, &format.file_path("proof/public_inputs"), "public_inputs", format)?;
+
+    Ok(())
+}
+
+// Function to read parameters from JSON file
+fn read_parameters(path: &str) -> Result<(String, String, usize), Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let json_data: Value = serde_json::from_reader(reader)?;
+    let read_preimage = |key: &str| {
+        json_data
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(String::from)
+            .ok_or(format!("Missing {} in parameters", key))
+    };
+    let digest_len = json_data
+        .get("digest_len")
+        .and_then(|value| value.as_u64())
+        .ok_or("Missing digest_len in parameters")?;
+
+    Ok((read_preimage("preimage_1")?, read_preimage("preimage_2")?, digest_len as usize))
+}
+
+// Encode the digest in elements of F, each containing `digest_chunk_size::<F>()` bytes of the digest
+fn digest_to_vec_digest<F: PrimeField>(digest: &[u8]) -> Vec<F> {
+    pack_bytes_into_field_elements(digest, digest_chunk_size::<F>())
+}
+
+// Number of bytes of the digest encoded in each element of F: the largest number of bytes that fits in F
+fn digest_chunk_size<F: PrimeField>() -> usize {
+    max_limb_byte_len::<F>()
+}
+
+// Take a Vec<F> representation of the digest and reconstruct its first `digest_len` bytes, enforcing that each
+// element only encodes its chunk of the digest
+fn vec_digest_to_digest<F: PrimeField>(vec_digest: &[FpVar<F>], digest_len: usize) -> ark_relations::r1cs::Result<Vec<UInt8<F>>> {
+    unpack_field_elements_into_bytes(vec_digest, digest_chunk_size::<F>(), digest_len)
+}
+
+// Initialize poseidon sponge (same configuration as the ai_inference example)
+fn get_poseidon_config<F: PrimeField> () -> PoseidonConfig<F> {
+    PoseidonConfig {
+        full_rounds: 8,
+        partial_rounds: 57,
+        alpha: 5,
+        mds: vec![
+            vec![F::from(1u64), F::from(2u64), F::from(3u64)],
+            vec![F::from(4u64), F::from(5u64), F::from(6u64)],
+            vec![F::from(7u64), F::from(8u64), F::from(9u64)],
+        ],
+        ark: (0..65)
+            .map(|i| vec![F::from(i as u64), F::from(i as u64 + 1), F::from(i as u64 + 2)])
+            .collect(),
+        rate: 2,
+        capacity: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    type Fr = ScalarFieldBls;
+
+    fn prove_and_verify<E: Pairing, H: CollisionHash<E::ScalarField> + Clone>(circuit: HashCollision<E::ScalarField, H>) -> bool {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<E>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+        Groth16::<E>::verify(&vk, &circuit.digest, &proof).unwrap()
+    }
+
+    fn is_satisfied<F: PrimeField, H: CollisionHash<F>>(circuit: HashCollision<F, H>) -> bool {
+        let cs = ConstraintSystem::<F>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    // Find two distinct preimages of the same length whose digests agree on the first `digest_len` bytes
+    fn find_collision<F: PrimeField, H: CollisionHash<F>>(digest_len: usize) -> (Vec<u8>, Vec<u8>) {
+        let mut seen = std::collections::HashMap::new();
+        for i in 100..10000 {
+            let preimage = format!("zkscript-{}", i).into_bytes();
+            let digest = H::digest(&preimage)[..digest_len].to_vec();
+            if let Some(other) = seen.insert(digest, preimage.clone()) {
+                return (other, preimage);
+            }
+        }
+        panic!("No collision found");
+    }
+
+    #[test]
+    fn test_sha256_collision() {
+        // The SHA256 digests of the two preimages agree on the first two bytes: 0x7540
+        let circuit = HashCollision::<Fr, Sha256Collision>::new(b"zkscript-215", b"zkscript-388", 2).unwrap();
+        assert_eq!(circuit.digest, vec![Fr::from(0x4075u64)]);
+        assert!(prove_and_verify::<Bls12_381, _>(circuit));
+    }
+
+    #[test]
+    fn test_sha256_collision_mnt4() {
+        let circuit = HashCollision::<ScalarFieldMnt, Sha256Collision>::new(b"zkscript-102", b"zkscript-116", 1).unwrap();
+        assert!(prove_and_verify::<MNT4_753, _>(circuit));
+    }
+
+    #[test]
+    fn test_poseidon_collision() {
+        let (preimage_1, preimage_2) = find_collision::<Fr, PoseidonCollision>(1);
+        let circuit = HashCollision::<Fr, PoseidonCollision>::new(&preimage_1, &preimage_2, 1).unwrap();
+        assert!(prove_and_verify::<Bls12_381, _>(circuit));
+    }
+
+    #[test]
+    fn test_no_collision() {
+        assert!(HashCollision::<Fr, Sha256Collision>::new(b"zkscript-215", b"zkscript-388", 3).is_err());
+        assert!(HashCollision::<Fr, Sha256Collision>::new(b"zkscript-215", b"zkscript-215", 2).is_err());
+        assert!(HashCollision::<Fr, Sha256Collision>::new(b"zkscript-215", b"zkscript-388", 33).is_err());
+
+        // Swapping in a preimage that does not collide makes the constraints unsatisfiable
+        let mut circuit = HashCollision::<Fr, Sha256Collision>::new(b"zkscript-215", b"zkscript-388", 2).unwrap();
+        circuit.preimage_2 = b"zkscript-389".to_vec();
+        assert!(!is_satisfied(circuit));
+    }
+
+    #[test]
+    fn test_equal_preimages() {
+        // The inequality constraint rejects a "collision" of a preimage with itself
+        let mut circuit = HashCollision::<Fr, Sha256Collision>::new(b"zkscript-215", b"zkscript-388", 2).unwrap();
+        circuit.preimage_2 = circuit.preimage_1.clone();
+        assert!(!is_satisfied(circuit));
+    }
+}
@@ -0,0 +1,583 @@
+use std::{fs::File, io::{Read, Write}, path::Path, str::FromStr};
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{eq::EqGadget, fields::fp::FpVar, uint8::UInt8, ToBytesGadget};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use ciborium::Value as CborValue;
+use clap::Args;
+use serde_json::{json, Value};
+
+// Header of the files saved in the binary format, followed by the length of the key name (8 bytes, little-endian),
+// the key name and the serialised data
+const BINARY_MAGIC: &[u8; 4] = b"ZKSB";
+
+// Format of the files saved by `save_to_file_with_format`
+// - `Json`: {key_name: bytes}, with the bytes as an array of numbers (the format read by `script.py`)
+// - `Cbor`: a CBOR map {key_name: bytes}, with the bytes as a byte string
+// - `Binary`: `BINARY_MAGIC`, followed by the key name and the raw bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Cbor,
+    Binary,
+}
+
+impl OutputFormat {
+    // Extension of the files saved in the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Cbor => "cbor",
+            OutputFormat::Binary => "bin",
+        }
+    }
+
+    // Path of the file `file_stem` saved in the format, e.g., `proof/proof.json`
+    pub fn file_path(&self, file_stem: &str) -> String {
+        format!("{}.{}", file_stem, self.extension())
+    }
+
+    // Detect the format of a file from the extension of `file_path` or, if it is not a known extension, from its
+    // first bytes
+    fn detect(file_path: &str, data: &[u8]) -> OutputFormat {
+        match Path::new(file_path).extension().and_then(|extension| extension.to_str()) {
+            Some("json") => OutputFormat::Json,
+            Some("cbor") => OutputFormat::Cbor,
+            Some("bin") => OutputFormat::Binary,
+            _ if data.starts_with(BINARY_MAGIC) => OutputFormat::Binary,
+            _ if data.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'{') => OutputFormat::Json,
+            _ => OutputFormat::Cbor,
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(OutputFormat::Json),
+            "cbor" => Ok(OutputFormat::Cbor),
+            "binary" | "bin" => Ok(OutputFormat::Binary),
+            _ => Err(format!("Unknown format {}, expected json, cbor or binary", format)),
+        }
+    }
+}
+
+// Generic function to save serializable data in the JSON format {key_name: bytes}
+pub fn save_to_file<T>(
+    item: &T,
+    file_path: &str,
+    key_name: &str
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: CanonicalSerialize,
+{
+    save_to_file_with_format(item, file_path, key_name, OutputFormat::Json)
+}
+
+// Generic function to save serializable data in `format`
+pub fn save_to_file_with_format<T>(
+    item: &T,
+    file_path: &str,
+    key_name: &str,
+    format: OutputFormat
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: CanonicalSerialize,
+{
+    let mut serialized_data = vec![0; item.serialized_size(Compress::No)];
+    item.serialize_uncompressed(&mut serialized_data[..])?;
+
+    let file_data = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&json!({key_name: serialized_data}))?.into_bytes(),
+        OutputFormat::Cbor => {
+            let mut cbor_data = vec![];
+            let map = CborValue::Map(vec![(CborValue::Text(key_name.to_string()), CborValue::Bytes(serialized_data))]);
+            ciborium::into_writer(&map, &mut cbor_data)?;
+            cbor_data
+        },
+        OutputFormat::Binary => {
+            let mut binary_data = BINARY_MAGIC.to_vec();
+            binary_data.extend((key_name.len() as u64).to_le_bytes());
+            binary_data.extend(key_name.as_bytes());
+            binary_data.extend(serialized_data);
+            binary_data
+        },
+    };
+
+    File::create(file_path)?.write_all(&file_data)?;
+    Ok(())
+}
+
+// Generic function to load data saved with `save_to_file` or `save_to_file_with_format`, detecting the format from
+// the extension of `file_path` or from the content of the file
+pub fn load_from_file<T>(
+    file_path: &str,
+    key_name: &str
+) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: CanonicalDeserialize,
+{
+    let mut data = vec![];
+    File::open(file_path)?.read_to_end(&mut data)?;
+    let missing_key = || format!("Missing key {} in {}", key_name, file_path);
+
+    let serialized_data: Vec<u8> = match OutputFormat::detect(file_path, &data) {
+        OutputFormat::Json => {
+            let json_data: Value = serde_json::from_slice(&data)?;
+            serde_json::from_value(json_data.get(key_name).cloned().ok_or_else(missing_key)?)?
+        },
+        OutputFormat::Cbor => {
+            let cbor_data: CborValue = ciborium::from_reader(&data[..])?;
+            cbor_data
+                .as_map()
+                .and_then(|map| map.iter().find(|(key, _)| key.as_text() == Some(key_name)))
+                .and_then(|(_, value)| value.as_bytes())
+                .cloned()
+                .ok_or_else(missing_key)?
+        },
+        OutputFormat::Binary => {
+            let header_len = BINARY_MAGIC.len() + 8;
+            let key_len = data
+                .get(BINARY_MAGIC.len()..header_len)
+                .filter(|_| data.starts_with(BINARY_MAGIC))
+                .map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize)
+                .ok_or(format!("Invalid binary header in {}", file_path))?;
+            if data.get(header_len..).and_then(|rest| rest.get(..key_len)) != Some(key_name.as_bytes()) {
+                return Err(missing_key().into());
+            }
+            data[header_len + key_len..].to_vec()
+        },
+    };
+
+    Ok(T::deserialize_uncompressed(&serialized_data[..])?)
+}
+
+// Generate the constraints of `circuit` and save a summary of the constraint system to `file_path` in the JSON format
+// {num_constraints, num_public_inputs, public_inputs, num_witness_variables}, adding the number of non-zero entries
+// of the R1CS matrices if `include_matrices` is set
+pub fn dump_constraint_system<F, C>(
+    circuit: C,
+    file_path: &str,
+    include_matrices: bool
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: PrimeField,
+    C: ConstraintSynthesizer<F>,
+{
+    let cs = ConstraintSystem::<F>::new_ref();
+    circuit.generate_constraints(cs.clone())?;
+    // Inline the symbolic linear combinations, required to compute the matrices
+    cs.finalize();
+
+    // The first instance variable is the constant one, not a public input
+    let public_inputs: Vec<String> = cs
+        .borrow()
+        .ok_or("The constraint system has been dropped")?
+        .instance_assignment[1..]
+        .iter()
+        .map(|input| input.to_string())
+        .collect();
+
+    let mut summary = json!({
+        "num_constraints": cs.num_constraints(),
+        "num_public_inputs": public_inputs.len(),
+        "public_inputs": public_inputs,
+        "num_witness_variables": cs.num_witness_variables(),
+    });
+    if include_matrices {
+        let matrices = cs.to_matrices().ok_or("The constraint system does not construct the matrices")?;
+        summary["matrices"] = json!({
+            "a_num_non_zero": matrices.a_num_non_zero,
+            "b_num_non_zero": matrices.b_num_non_zero,
+            "c_num_non_zero": matrices.c_num_non_zero,
+        });
+    }
+
+    let json_string = serde_json::to_string_pretty(&summary)?;
+    File::create(file_path)?.write_all(json_string.as_bytes())?;
+    Ok(())
+}
+
+// Size of a circuit, tracked across code versions to detect constraint bloat
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub num_constraints: usize,
+    pub num_witness_variables: usize,
+    pub num_public_inputs: usize,
+}
+
+impl CircuitStats {
+    // Generate the constraints of `circuit` and return its stats
+    pub fn from_circuit<F, C>(circuit: C) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: PrimeField,
+        C: ConstraintSynthesizer<F>,
+    {
+        let cs = ConstraintSystem::<F>::new_ref();
+        circuit.generate_constraints(cs.clone())?;
+        // The first instance variable is the constant one, not a public input
+        Ok(Self {
+            num_constraints: cs.num_constraints(),
+            num_witness_variables: cs.num_witness_variables(),
+            num_public_inputs: cs.num_instance_variables() - 1,
+        })
+    }
+
+    fn to_json(self) -> Value {
+        json!({
+            "num_constraints": self.num_constraints,
+            "num_witness_variables": self.num_witness_variables,
+            "num_public_inputs": self.num_public_inputs,
+        })
+    }
+
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let read = |key: &str| {
+            value[key].as_u64().map(|n| n as usize).ok_or(format!("Missing or invalid {} in the baseline", key))
+        };
+        Ok(Self {
+            num_constraints: read("num_constraints")?,
+            num_witness_variables: read("num_witness_variables")?,
+            num_public_inputs: read("num_public_inputs")?,
+        })
+    }
+
+    // Describe every stat of `self` that grew by more than `threshold_percent` with respect to `baseline`
+    pub fn regressions(&self, baseline: &CircuitStats, threshold_percent: f64) -> Vec<String> {
+        [
+            ("num_constraints", baseline.num_constraints, self.num_constraints),
+            ("num_witness_variables", baseline.num_witness_variables, self.num_witness_variables),
+            ("num_public_inputs", baseline.num_public_inputs, self.num_public_inputs),
+        ]
+        .into_iter()
+        .filter(|(_, old, new)| *new as f64 > *old as f64 * (1.0 + threshold_percent / 100.0))
+        .map(|(stat, old, new)| format!("{}: {} -> {}", stat, old, new))
+        .collect()
+    }
+}
+
+// Compare the stats of the circuit `name` against the baseline saved in `file_path`, a JSON map {name: stats} shared
+// by the examples. Fail if any stat grew by more than `threshold_percent`. If `update` is set, save the stats of the
+// circuit to the baseline instead, keeping the stats of the other circuits
+pub fn check_circuit_stats(
+    name: &str,
+    stats: CircuitStats,
+    file_path: &str,
+    threshold_percent: f64,
+    update: bool
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut baseline: Value = match File::open(file_path) {
+        Ok(file) => serde_json::from_reader(file)?,
+        Err(e) if update && e.kind() == std::io::ErrorKind::NotFound => json!({}),
+        Err(e) => return Err(format!("Failed to read the circuit stats baseline {}: {}", file_path, e).into()),
+    };
+
+    if update {
+        baseline
+            .as_object_mut()
+            .ok_or(format!("The circuit stats baseline {} is not a JSON map", file_path))?
+            .insert(name.to_string(), stats.to_json());
+        File::create(file_path)?.write_all(serde_json::to_string_pretty(&baseline)?.as_bytes())?;
+        return Ok(());
+    }
+
+    let baseline_stats = baseline
+        .get(name)
+        .ok_or(format!("No baseline for the circuit {} in {}", name, file_path))
+        .and_then(CircuitStats::from_json)?;
+    let regressions = stats.regressions(&baseline_stats, threshold_percent);
+    if !regressions.is_empty() {
+        return Err(format!(
+            "The circuit {} grew by more than {}%: {}",
+            name,
+            threshold_percent,
+            regressions.join(", ")
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// Command line arguments shared by the examples to inspect their circuit, flattened in the `Cli` of each example
+#[derive(Args, Clone, Debug)]
+pub struct CircuitArgs {
+    /// File to which a summary of the constraint system is saved, for debugging
+    #[arg(long)]
+    pub dump_cs: Option<String>,
+    /// Add the number of non-zero entries of the R1CS matrices to the summary saved with --dump-cs
+    #[arg(long, requires = "dump_cs")]
+    pub dump_cs_matrices: bool,
+    /// Baseline of the circuit stats: the run fails if the circuit grew beyond --stats-threshold
+    #[arg(long)]
+    pub stats_baseline: Option<String>,
+    /// Growth of the circuit stats (in percent) tolerated with respect to --stats-baseline
+    #[arg(long, default_value_t = 0.0, requires = "stats_baseline")]
+    pub stats_threshold: f64,
+    /// Save the circuit stats to --stats-baseline instead of checking them
+    #[arg(long, requires = "stats_baseline")]
+    pub update_stats_baseline: bool,
+}
+
+impl CircuitArgs {
+    // Save a summary of the constraint system of `circuit` if --dump-cs is set, and check the stats of the circuit
+    // `name` against the baseline if --stats-baseline is set
+    pub fn inspect_circuit<F, C>(&self, name: &str, circuit: C) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: PrimeField,
+        C: ConstraintSynthesizer<F> + Clone,
+    {
+        if let Some(dump_cs) = &self.dump_cs {
+            dump_constraint_system(circuit.clone(), dump_cs, self.dump_cs_matrices)?;
+        }
+        if let Some(stats_baseline) = &self.stats_baseline {
+            let stats = CircuitStats::from_circuit(circuit)?;
+            check_circuit_stats(name, stats, stats_baseline, self.stats_threshold, self.update_stats_baseline)?;
+        }
+        Ok(())
+    }
+}
+
+// Command line argument selecting the format of the files saved by the examples, flattened in their `Cli`
+#[derive(Args, Clone, Copy, Debug)]
+pub struct OutputFormatArgs {
+    /// Format of the saved proof, verifying key and public inputs: json, cbor or binary
+    #[arg(long, default_value = "json")]
+    pub format: OutputFormat,
+}
+
+// Largest number of bytes that can be packed in an element of F without reduction
+pub fn max_limb_byte_len<F: PrimeField>() -> usize {
+    ((F::MODULUS_BIT_SIZE - 1) / 8) as usize
+}
+
+// Pack `bytes` in elements of F, each containing `limb_byte_len` bytes (in little-endian order). The last element
+// contains the remaining bytes if `bytes.len()` is not a multiple of `limb_byte_len`
+pub fn pack_bytes_into_field_elements<F: PrimeField>(bytes: &[u8], limb_byte_len: usize) -> Vec<F> {
+    assert!(
+        0 < limb_byte_len && limb_byte_len <= max_limb_byte_len::<F>(),
+        "The limb length must be between 1 and {} bytes",
+        max_limb_byte_len::<F>()
+    );
+    bytes.chunks(limb_byte_len).map(F::from_le_bytes_mod_order).collect()
+}
+
+// Gadget inverting `pack_bytes_into_field_elements`: unpack the `n_bytes` bytes packed in `elements`
+// The bytes of each element beyond its limb are enforced to be zero, so that each element is range-constrained to
+// its limb: otherwise, `element + k * 2^(8 * limb_byte_len)` would unpack to the same bytes as `element`
+pub fn unpack_field_elements_into_bytes<F: PrimeField>(
+    elements: &[FpVar<F>],
+    limb_byte_len: usize,
+    n_bytes: usize
+) -> ark_relations::r1cs::Result<Vec<UInt8<F>>> {
+    assert!(
+        0 < limb_byte_len && limb_byte_len <= max_limb_byte_len::<F>(),
+        "The limb length must be between 1 and {} bytes",
+        max_limb_byte_len::<F>()
+    );
+    assert_eq!(elements.len(), n_bytes.div_ceil(limb_byte_len));
+
+    let mut bytes: Vec<UInt8<F>> = Vec::with_capacity(n_bytes);
+    for (i, element) in elements.iter().enumerate() {
+        let len = limb_byte_len.min(n_bytes - i * limb_byte_len);
+        // The canonical little-endian bytes of the element
+        let element_bytes = element.to_bytes()?;
+        for byte in element_bytes[len..].iter() {
+            byte.enforce_equal(&UInt8::constant(0))?;
+        }
+        bytes.extend_from_slice(&element_bytes[..len]);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::AffineRepr;
+    use ark_groth16::Proof;
+    use ark_std::UniformRand;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+
+    #[test]
+    fn test_proof_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let proof = Proof::<Bls12_381> {
+            a: (G1Affine::generator() * Fr::rand(&mut rng)).into(),
+            b: (G2Affine::generator() * Fr::rand(&mut rng)).into(),
+            c: (G1Affine::generator() * Fr::rand(&mut rng)).into(),
+        };
+
+        let file_path = std::env::temp_dir().join("common_test_proof.json");
+        let file_path = file_path.to_str().unwrap();
+        save_to_file(&proof, file_path, "proof").unwrap();
+        let loaded_proof: Proof<Bls12_381> = load_from_file(file_path, "proof").unwrap();
+        std::fs::remove_file(file_path).unwrap();
+
+        assert_eq!(proof, loaded_proof);
+    }
+
+    #[test]
+    fn test_proof_round_trip_formats() {
+        let mut rng = ark_std::test_rng();
+        let proof = Proof::<Bls12_381> {
+            a: (G1Affine::generator() * Fr::rand(&mut rng)).into(),
+            b: (G2Affine::generator() * Fr::rand(&mut rng)).into(),
+            c: (G1Affine::generator() * Fr::rand(&mut rng)).into(),
+        };
+
+        for format in [OutputFormat::Json, OutputFormat::Cbor, OutputFormat::Binary] {
+            // The format is detected from the extension, or from the content if the extension is unknown
+            let file_stem = std::env::temp_dir().join("common_test_proof_format");
+            let file_stem = file_stem.to_str().unwrap();
+            for file_path in [format.file_path(file_stem), format!("{}.data", file_stem)] {
+                let file_path = file_path.as_str();
+                save_to_file_with_format(&proof, file_path, "proof", format).unwrap();
+                let loaded_proof: Proof<Bls12_381> = load_from_file(file_path, "proof").unwrap();
+                let loaded_wrong_key: Result<Proof<Bls12_381>, _> = load_from_file(file_path, "vk");
+                std::fs::remove_file(file_path).unwrap();
+
+                assert_eq!(proof, loaded_proof);
+                assert!(loaded_wrong_key.is_err());
+            }
+        }
+
+        assert_eq!("binary".parse::<OutputFormat>(), Ok(OutputFormat::Binary));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_pack_bytes_into_field_elements() {
+        use ark_ff::Field;
+        use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+
+        let bytes: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(97).wrapping_add(13)).collect();
+        assert_eq!(max_limb_byte_len::<Fr>(), 31);
+
+        for limb_byte_len in [31, 30] {
+            let elements = pack_bytes_into_field_elements::<Fr>(&bytes, limb_byte_len);
+            assert_eq!(elements.len(), bytes.len().div_ceil(limb_byte_len));
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let element_vars = elements
+                .iter()
+                .map(|element| FpVar::new_input(cs.clone(), || Ok(*element)))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let unpacked = unpack_field_elements_into_bytes(&element_vars, limb_byte_len, bytes.len()).unwrap();
+            assert_eq!(unpacked.value().unwrap(), bytes);
+            assert!(cs.is_satisfied().unwrap());
+
+            // An element exceeding its limb is rejected, even if its limb contains the right bytes
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let mut wrong_elements = elements.clone();
+            wrong_elements[0] += Fr::from(256u64).pow([limb_byte_len as u64]);
+            let element_vars = wrong_elements
+                .iter()
+                .map(|element| FpVar::new_input(cs.clone(), || Ok(*element)))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let unpacked = unpack_field_elements_into_bytes(&element_vars, limb_byte_len, bytes.len()).unwrap();
+            assert_eq!(unpacked.value().unwrap(), bytes);
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+
+    // Circuit proving the knowledge of `n` square roots
+    #[derive(Clone)]
+    struct SquareRoots {
+        n: usize,
+    }
+
+    impl ConstraintSynthesizer<Fr> for SquareRoots {
+        fn generate_constraints(
+            self,
+            cs: ark_relations::r1cs::ConstraintSystemRef<Fr>
+        ) -> ark_relations::r1cs::Result<()> {
+            use ark_r1cs_std::alloc::AllocVar;
+
+            for _ in 0..self.n {
+                let root = FpVar::new_witness(cs.clone(), || Ok(Fr::from(3u64)))?;
+                let square = FpVar::new_input(cs.clone(), || Ok(Fr::from(9u64)))?;
+                (&root * &root).enforce_equal(&square)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_circuit_stats() {
+        let stats = CircuitStats::from_circuit(SquareRoots { n: 10 }).unwrap();
+        assert_eq!(
+            stats,
+            CircuitStats { num_constraints: 10, num_witness_variables: 10, num_public_inputs: 10 }
+        );
+
+        let file_path = std::env::temp_dir().join("common_test_circuit_stats.json");
+        let file_path = file_path.to_str().unwrap();
+        let _ = std::fs::remove_file(file_path);
+
+        // A circuit has no regression against its own baseline
+        assert!(check_circuit_stats("square_roots", stats, file_path, 0.0, false).is_err());
+        check_circuit_stats("square_roots", stats, file_path, 0.0, true).unwrap();
+        check_circuit_stats("other", CircuitStats::from_circuit(SquareRoots { n: 1 }).unwrap(), file_path, 0.0, true)
+            .unwrap();
+        check_circuit_stats("square_roots", stats, file_path, 0.0, false).unwrap();
+
+        // A larger circuit fails the check, unless the growth is within the threshold
+        let larger = CircuitStats::from_circuit(SquareRoots { n: 11 }).unwrap();
+        assert_eq!(larger.regressions(&stats, 0.0).len(), 3);
+        let error = check_circuit_stats("square_roots", larger, file_path, 5.0, false).unwrap_err().to_string();
+        assert!(error.contains("num_constraints: 10 -> 11"), "{}", error);
+        check_circuit_stats("square_roots", larger, file_path, 10.0, false).unwrap();
+
+        // A smaller circuit is not a regression
+        let smaller = CircuitStats::from_circuit(SquareRoots { n: 9 }).unwrap();
+        check_circuit_stats("square_roots", smaller, file_path, 0.0, false).unwrap();
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_key() {
+        let file_path = std::env::temp_dir().join("common_test_missing_key.json");
+        let file_path = file_path.to_str().unwrap();
+        save_to_file(&vec![Fr::from(1)], file_path, "public_inputs").unwrap();
+        let loaded: Result<Vec<Fr>, _> = load_from_file(file_path, "proof");
+        std::fs::remove_file(file_path).unwrap();
+
+        assert!(loaded.is_err());
+    }
+
+    #[derive(clap::Parser)]
+    struct Cli {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        #[command(flatten)]
+        output: OutputFormatArgs,
+    }
+
+    #[test]
+    fn test_shared_args() {
+        use clap::Parser;
+
+        let cli = Cli::try_parse_from(["example"]).unwrap();
+        assert!(cli.circuit.dump_cs.is_none() && cli.circuit.stats_baseline.is_none());
+        assert_eq!(cli.output.format, OutputFormat::Json);
+
+        let args = ["example", "--stats-baseline", "stats.json", "--stats-threshold", "5", "--format", "bin"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.circuit.stats_baseline.as_deref(), Some("stats.json"));
+        assert_eq!(cli.circuit.stats_threshold, 5.0);
+        assert_eq!(cli.output.format, OutputFormat::Binary);
+
+        // The modifiers require the flag they modify
+        assert!(Cli::try_parse_from(["example", "--dump-cs-matrices"]).is_err());
+        assert!(Cli::try_parse_from(["example", "--update-stats-baseline"]).is_err());
+        assert!(Cli::try_parse_from(["example", "--format", "yaml"]).is_err());
+
+        // Without --dump-cs and --stats-baseline, the circuit is not inspected
+        let cli = Cli::try_parse_from(["example"]).unwrap();
+        cli.circuit.inspect_circuit("square_roots", SquareRoots { n: 1 }).unwrap();
+    }
+}
@@ -0,0 +1,261 @@
+use std::{fs::File, io::BufReader};
+
+use common::{CircuitArgs, OutputFormatArgs, save_to_file_with_format};
+
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use ark_groth16::Groth16;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, nonnative::NonNativeFieldVar, FieldVar},
+    select::CondSelectGadget,
+    ToBitsGadget,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_snark::SNARK;
+use clap::Parser;
+use rand_chacha::ChaChaRng;
+use rand::SeedableRng;
+use serde_json::Value;
+use ark_test_curves::bls12_381::{Bls12_381, Fr as ScalarFieldBls};
+use ark_test_curves::secp256k1::{Fq as Secp256k1Fq, Fr as Secp256k1Fr, G1Affine as Secp256k1Affine};
+
+type Secp256k1FqVar<F> = NonNativeFieldVar<Secp256k1Fq, F>;
+
+// Nothing-up-my-sleeve scalar used to derive the starting point of the scalar multiplication
+const OFFSET_SEED: &[u8] = b"zkscript key ownership offset";
+// Number of bits of the private key processed at each step of the scalar multiplication
+const WINDOW_SIZE: usize = 4;
+
+#[derive(Clone)]
+pub struct KeyOwnership<F: PrimeField + Absorb> {
+    private_key: Secp256k1Fr,       // private input
+    public_key: Secp256k1Affine,    // private input, committed to by `commitment`
+    commitment: F,                  // public input
+    poseidon_config: PoseidonConfig<F>,
+}
+
+impl<F: PrimeField + Absorb> ConstraintSynthesizer<F> for KeyOwnership<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> ark_relations::r1cs::Result<()> {
+        // Allocate public input
+        let commitment: FpVar<F> = FpVar::<F>::new_input(cs.clone(), || Ok(self.commitment))?;
+        // Allocate private inputs
+        let private_key: Vec<Boolean<F>> = Vec::<Boolean<F>>::new_witness(cs.clone(), || {
+            Ok(self.private_key.into_bigint().to_bits_le())
+        })?;
+        let public_key_x = Secp256k1FqVar::<F>::new_witness(cs.clone(), || Ok(self.public_key.x))?;
+        let public_key_y = Secp256k1FqVar::<F>::new_witness(cs.clone(), || Ok(self.public_key.y))?;
+
+        // Enforce public_key = private_key * G
+        // The private key is split in windows w_i of WINDOW_SIZE bits, and the accumulator is updated as
+        // acc <-- acc + (w_i + 1) * 2^(WINDOW_SIZE * i) * G, starting from acc = offset. This way, the accumulator
+        // is never the point at infinity, and at the end acc = offset + (private_key + correction) * G, where
+        // correction = sum_i 2^(WINDOW_SIZE * i)
+        let offset = offset_point();
+        let mut accumulator = constant_point::<F>(&offset);
+        let mut window_base = Secp256k1Affine::generator().into_group();
+        let mut correction = Secp256k1Fr::zero();
+        let mut window_multiplier = Secp256k1Fr::one();
+        for window in private_key.chunks(WINDOW_SIZE) {
+            let table: Vec<(Secp256k1FqVar<F>, Secp256k1FqVar<F>)> = (1..=(1u64 << WINDOW_SIZE))
+                .map(|j| constant_point::<F>(&(window_base * Secp256k1Fr::from(j)).into_affine()))
+                .collect();
+            // `conditionally_select_power_of_two_vector` expects the position in big-endian
+            let position: Vec<Boolean<F>> = window.iter().rev().cloned().collect();
+            let addend = (
+                Secp256k1FqVar::<F>::conditionally_select_power_of_two_vector(
+                    &position,
+                    &table.iter().map(|point| point.0.clone()).collect::<Vec<_>>(),
+                )?,
+                Secp256k1FqVar::<F>::conditionally_select_power_of_two_vector(
+                    &position,
+                    &table.iter().map(|point| point.1.clone()).collect::<Vec<_>>(),
+                )?,
+            );
+            accumulator = add_points(&accumulator, &addend)?;
+
+            correction += window_multiplier;
+            window_multiplier *= Secp256k1Fr::from(1u64 << WINDOW_SIZE);
+            window_base *= Secp256k1Fr::from(1u64 << WINDOW_SIZE);
+        }
+        let shifted_offset = (offset + Secp256k1Affine::generator() * correction).into_affine();
+        let expected = add_points(
+            &(public_key_x.clone(), public_key_y.clone()),
+            &constant_point::<F>(&shifted_offset),
+        )?;
+        accumulator.0.enforce_equal(&expected.0)?;
+        accumulator.1.enforce_equal(&expected.1)?;
+
+        // Enforce commitment = Poseidon(public_key)
+        let mut absorbed: Vec<FpVar<F>> = vec![];
+        for coordinate in [&public_key_x, &public_key_y] {
+            let bits = coordinate.to_bits_le()?;
+            absorbed.push(Boolean::le_bits_to_fp_var(&bits[..128])?);
+            absorbed.push(Boolean::le_bits_to_fp_var(&bits[128..256])?);
+        }
+        let mut sponge = PoseidonSpongeVar::<F>::new(cs.clone(), &self.poseidon_config);
+        sponge.absorb(&absorbed)?;
+        let computed_commitment = sponge.squeeze_field_elements(1)?;
+
+        computed_commitment[0].enforce_equal(&commitment)
+    }
+}
+
+type ScalarField = ScalarFieldBls;
+type Curve = Bls12_381;
+
+#[derive(Parser)]
+#[command(about = "Groth16 proof of the ownership of a secp256k1 private key")]
+struct Cli {
+    #[command(flatten)]
+    circuit: CircuitArgs,
+    #[command(flatten)]
+    output: OutputFormatArgs,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>>{
+    let cli = Cli::parse();
+
+    // Randomness
+    let mut rng = ChaChaRng::from_entropy();
+
+    // Fetch the private key and compute the corresponding public key and its commitment
+    let private_key = read_parameters("parameters.json")?;
+    let public_key = (Secp256k1Affine::generator() * private_key).into_affine();
+    let poseidon_config = poseidon_config::<ScalarField>();
+    let commitment = commit_to_public_key(&public_key, &poseidon_config);
+
+    // Build the circuit
+    let circuit = KeyOwnership::<ScalarField> {
+        private_key,
+        public_key,
+        commitment,
+        poseidon_config,
+    };
+
+    cli.circuit.inspect_circuit("key_ownership", circuit.clone())?;
+    let format = cli.output.format;
+
+    // Setup
+    let (pk, vk) = Groth16::<Curve>::circuit_specific_setup(circuit.clone(), &mut rng)
+        .map_err(|e| format!("Setup failed: {}",e))?;
+
+    // Proving
+    let proof = Groth16::<Curve>::prove(&pk, circuit.clone(), &mut rng)
+        .map_err(|e| format!("Proof generation failed: {}", e))?;
+
+    // Verifying
+    let is_valid = Groth16::<Curve>::verify(&vk, &[circuit.commitment], &proof)
+        .map_err(|e| format!("Verification failed: {}", e))?;
+    assert!(is_valid,"Proof is invalid");
+
+    // Save proof, verification key, and public input to files
+    save_to_file_with_format(&proof, &format.file_path("proof/proof"), "proof", format)?;
+    save_to_file_with_format(&vk, &format.file_path("proof/verifying_key"), "verifying_key", format)?;
+    save_to_file_with_format(&vec![commitment], &format.file_path("proof/public_inputs"), "public_inputs", format)?;
+
+    Ok(())
+}
+
+// Poseidon parameters with rate 2, alpha = 5, 8 full rounds and 57 partial rounds
+fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (full_rounds, partial_rounds, alpha, rate) = (8, 57, 5, 2);
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(F::MODULUS_BIT_SIZE as u64, rate, full_rounds, partial_rounds, 0);
+    PoseidonConfig::new(full_rounds as usize, partial_rounds as usize, alpha, mds, ark, rate, 1)
+}
+
+// Split the coordinates of the public key in 128-bit chunks (little-endian) and hash them with Poseidon
+fn commit_to_public_key<F: PrimeField + Absorb>(
+    public_key: &Secp256k1Affine,
+    poseidon_config: &PoseidonConfig<F>,
+) -> F {
+    let mut absorbed: Vec<F> = vec![];
+    for coordinate in [public_key.x, public_key.y] {
+        let bytes = coordinate.into_bigint().to_bytes_le();
+        absorbed.push(F::from_le_bytes_mod_order(&bytes[..16]));
+        absorbed.push(F::from_le_bytes_mod_order(&bytes[16..32]));
+    }
+    let mut sponge = PoseidonSponge::<F>::new(poseidon_config);
+    sponge.absorb(&absorbed);
+    sponge.squeeze_field_elements(1)[0]
+}
+
+fn offset_point() -> Secp256k1Affine {
+    (Secp256k1Affine::generator() * Secp256k1Fr::from_be_bytes_mod_order(OFFSET_SEED)).into_affine()
+}
+
+fn constant_point<F: PrimeField>(point: &Secp256k1Affine) -> (Secp256k1FqVar<F>, Secp256k1FqVar<F>) {
+    (Secp256k1FqVar::<F>::constant(point.x), Secp256k1FqVar::<F>::constant(point.y))
+}
+
+// Add the points `p` and `q` (in affine coordinates). The constraints are unsatisfiable if p.x == q.x
+fn add_points<F: PrimeField>(
+    p: &(Secp256k1FqVar<F>, Secp256k1FqVar<F>),
+    q: &(Secp256k1FqVar<F>, Secp256k1FqVar<F>),
+) -> Result<(Secp256k1FqVar<F>, Secp256k1FqVar<F>), SynthesisError> {
+    let ((x_p, y_p), (x_q, y_q)) = (p, q);
+    // The inverse enforces p.x != q.x
+    let gradient = (y_p - y_q) * (x_p - x_q).inverse()?;
+    let x_sum = gradient.square()? - x_p - x_q;
+    let y_sum = &gradient * (x_p - &x_sum) - y_p;
+    Ok((x_sum, y_sum))
+}
+
+// Function to read the private key from JSON file
+fn read_parameters(path: &str) -> Result<Secp256k1Fr, Box<dyn std::error::Error>>{
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let json_data: Value = serde_json::from_reader(reader)?;
+    let private_key = json_data
+        .get("private_key")
+        .and_then(|value| value.as_str())
+        .ok_or("Missing private_key in parameters")?;
+    let bytes = (0..private_key.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&private_key[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    Ok(Secp256k1Fr::from_be_bytes_mod_order(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    fn is_satisfied(private_key: Secp256k1Fr, public_key: Secp256k1Affine) -> bool {
+        let poseidon_config = poseidon_config::<ScalarField>();
+        let commitment = commit_to_public_key(&public_key, &poseidon_config);
+        let circuit = KeyOwnership::<ScalarField> {
+            private_key,
+            public_key,
+            commitment,
+            poseidon_config,
+        };
+
+        let cs = ConstraintSystem::<ScalarField>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn test_valid_key_pair() {
+        let private_key = Secp256k1Fr::from(0x1234_5678_9abc_def0u64);
+        let public_key = (Secp256k1Affine::generator() * private_key).into_affine();
+        assert!(is_satisfied(private_key, public_key));
+    }
+
+    #[test]
+    fn test_mismatched_key_pair() {
+        let private_key = Secp256k1Fr::from(0x1234_5678_9abc_def0u64);
+        let public_key = (Secp256k1Affine::generator() * (private_key + Secp256k1Fr::from(1u64))).into_affine();
+        assert!(!is_satisfied(private_key, public_key));
+    }
+}
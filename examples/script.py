@@ -22,6 +22,7 @@ from src.zkscript.groth16.mnt4_753.mnt4_753 import mnt4_753 as mnt4_753_groth
 from src.zkscript.groth16.model.groth16 import Groth16
 from src.zkscript.script_types.locking_keys.groth16 import Groth16LockingKey
 from src.zkscript.script_types.unlocking_keys.groth16 import Groth16UnlockingKey
+from src.zkscript.util.ark_serialisation import ark_field_elements_to_ints
 
 verification_flags = 1
 for f in ScriptFlags._member_names_[1:-2]:
@@ -74,47 +75,19 @@ def curve_setup(curve_arg: str) -> Union[BilinearPairingCurve, VerifyingKey, Pro
     return curve, groth16_script, vk_type, proof_type
 
 
-def load_public_inputs(public_inputs_serialized: bytes, curve: BilinearPairingCurve):
-    n_public_inputs = int.from_bytes(public_inputs_serialized[:7], byteorder="little")
-    field_length = (curve.get_order_scalar_field().bit_length() + 8) // 8
-
-    index = 8
-    public_inputs = []
-    for _ in range(n_public_inputs):
-        public_inputs.extend(
-            curve.scalar_field.deserialise(public_inputs_serialized[index : index + field_length]).to_list()
-        )
-        index += field_length
-    return [1, *public_inputs]
-
-
 def proof_to_unlock(
     public_statements,
-    proof,
+    serialised_proof,
     vk,
     groth16_script: Groth16,
+    proof_type: Proof,
 ) -> Script:
-    prepared_proof = proof.prepare_for_zkscript(
-        vk.prepare(),
-        public_statements,
-    )
-
-    unlocking_key = Groth16UnlockingKey.from_data(
+    unlocking_key = Groth16UnlockingKey.from_ark_proof(
         groth16_model=groth16_script,
-        pub=prepared_proof.public_statements,
-        A=prepared_proof.a,
-        B=prepared_proof.b,
-        C=prepared_proof.c,
-        gradients_pairings=[
-            prepared_proof.gradients_b,
-            prepared_proof.gradients_minus_gamma,
-            prepared_proof.gradients_minus_delta,
-        ],
-        gradients_multiplications=prepared_proof.gradients_multiplications,
-        max_multipliers=None,
-        gradients_additions=prepared_proof.gradients_additions,
-        inverse_miller_output=prepared_proof.inverse_miller_loop,
-        gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+        proof_type=proof_type,
+        serialised_proof=serialised_proof,
+        pub=public_statements,
+        cache_vk=vk.prepare(),
     )
     return unlocking_key.to_unlocking_script(groth16_script, True)
 
@@ -205,7 +178,7 @@ parser = argparse.ArgumentParser(
 parser.add_argument(
     "--dir",
     type=str,
-    choices=["square_root", "sha256", "ai_inference"],
+    choices=["square_root", "sha256", "ai_inference", "key_ownership", "collision"],
     help="Directory from which to get statement, proof and verifying key",
 )
 parser.add_argument(
@@ -228,16 +201,17 @@ if __name__ == "__main__":
     curve, groth16_script, vk_type, proof_type = curve_setup(args.curve)
 
     # Load proof, vk
-    proof = proof_type.deserialise(json.load(Path.open(data_dir / "proof/proof.json"))["proof"])
+    serialised_proof = json.load(Path.open(data_dir / "proof/proof.json"))["proof"]
     vk = vk_type.deserialise(json.load(Path.open(data_dir / "proof/verifying_key.json"))["verifying_key"])
     # Load public inputs
-    public_inputs = load_public_inputs(
-        json.load(Path.open(data_dir / "proof/public_inputs.json"))["public_inputs"], curve
+    public_inputs = ark_field_elements_to_ints(
+        json.load(Path.open(data_dir / "proof/public_inputs.json"))["public_inputs"],
+        curve.get_order_scalar_field(),
     )
 
     # Construct locking and unlocking scripts
     lock = vk_to_lock(vk, groth16_script)
-    unlock = proof_to_unlock(public_inputs[1:], proof, vk, groth16_script)
+    unlock = proof_to_unlock(public_inputs, serialised_proof, vk, groth16_script, proof_type)
 
     context = Context(script=unlock + lock)
     assert context.evaluate(), "Evaluation using Context failed"
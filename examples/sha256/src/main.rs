@@ -1,127 +1,565 @@
-use std::{fs::File, io::{BufReader, Write}};
+use std::{fs::File, io::{BufReader, Cursor, Read}, marker::PhantomData, num::NonZeroUsize, sync::Arc};
 
+use common::{CircuitArgs, OutputFormat, OutputFormatArgs, max_limb_byte_len, pack_bytes_into_field_elements, save_to_file_with_format, unpack_field_elements_into_bytes};
+
+use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
 use ark_groth16::Groth16;
-use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, uint8::UInt8, ToBytesGadget, eq::EqGadget};
-use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
-use ark_serialize::{CanonicalSerialize, Compress};
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, uint8::UInt8, ToBitsGadget, ToBytesGadget, eq::EqGadget, R1CSVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use ark_snark::SNARK;
-use ark_test_curves::bls12_381::{Bls12_381, Fr};
+use clap::Parser;
+use ark_test_curves::bls12_381::{Bls12_381, Fr as ScalarFieldBls};
+use ark_mnt4_753::{MNT4_753, Fr as ScalarFieldMnt};
 use rand_chacha::ChaChaRng;
 use rand::SeedableRng;
-use serde_json::{json, Value};
-use ark_crypto_primitives::crh::{sha256::{constraints::{DigestVar, Sha256Gadget}, Sha256},CRHScheme};
+use serde_json::Value;
+use ark_crypto_primitives::crh::{sha256::{constraints::{DigestVar, Sha256Gadget}, digest::Digest, Sha256},CRHScheme};
+use ark_crypto_primitives::prf::blake2s::constraints::{evaluate_blake2s, OutputVar};
+use blake2::Blake2s256;
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge,
+};
+
+// Hash function used to commit to the preimage
+pub trait CommitmentHash<F: PrimeField> {
+    // Computes the hash of `input` and encodes it in a vector of elements of F (the public inputs)
+    fn commit(input: &[u8]) -> Vec<F>;
+    // Enforces that the hash of `preimage` is encoded by `public_inputs`
+    fn enforce_commitment(preimage: &[UInt8<F>], public_inputs: &[FpVar<F>]) -> ark_relations::r1cs::Result<()>;
+    // Computes the hash of the concatenation of `chunks`. By default, the chunks are concatenated and hashed at once
+    fn commit_chunks(chunks: Chunks) -> std::io::Result<Vec<F>> {
+        Ok(Self::commit(&chunks.collect::<std::io::Result<Vec<_>>>()?.concat()))
+    }
+    // Allocates the preimage given in `chunks` and enforces that its hash is encoded by `public_inputs`
+    // By default, the whole preimage is allocated at once
+    fn enforce_commitment_chunks(cs: ConstraintSystemRef<F>, chunks: Chunks, public_inputs: &[FpVar<F>]) -> ark_relations::r1cs::Result<()> {
+        let preimage = chunks.collect::<std::io::Result<Vec<_>>>().map_err(|_| SynthesisError::AssignmentMissing)?.concat();
+        let preimage_var = UInt8::<F>::new_witness_vec(cs, &preimage)?;
+        Self::enforce_commitment(&preimage_var, public_inputs)
+    }
+}
+
+#[derive(Clone)]
+pub struct Sha256Commitment;
+
+impl<F: PrimeField> CommitmentHash<F> for Sha256Commitment {
+    // The hash is split in chunks of `hash_chunk_size::<F>()` bytes, each encoded in an element of F
+    // (two elements for BLS12-381, one for MNT4-753)
+    fn commit(input: &[u8]) -> Vec<F> {
+        let hashed_input = Sha256::evaluate(&(), input).unwrap();
+
+        hash_to_vec_hash(&hashed_input)
+    }
+
+    fn enforce_commitment(preimage: &[UInt8<F>], public_inputs: &[FpVar<F>]) -> ark_relations::r1cs::Result<()> {
+        assert_eq!(public_inputs.len(), 32usize.div_ceil(hash_chunk_size::<F>()));
+
+        // Compute SHA256 hash of witness
+        let computed_hash = Sha256Gadget::<F>::digest(preimage)?;
+        // Reconstruct expected hash from public inputs
+        let expected_hash = DigestVar::<F>(vec_hash_to_hash(public_inputs)?);
+
+        computed_hash.enforce_equal(&expected_hash)
+    }
 
+    // The chunks are hashed incrementally, without concatenating them
+    fn commit_chunks(chunks: Chunks) -> std::io::Result<Vec<F>> {
+        let mut hasher = Sha256::new();
+        for chunk in chunks {
+            hasher.update(chunk?);
+        }
+
+        Ok(hash_to_vec_hash(&hasher.finalize()))
+    }
+
+    // The chunks are allocated one at a time and fed to the gadget, which keeps the intermediate state of SHA256
+    fn enforce_commitment_chunks(cs: ConstraintSystemRef<F>, chunks: Chunks, public_inputs: &[FpVar<F>]) -> ark_relations::r1cs::Result<()> {
+        assert_eq!(public_inputs.len(), 32usize.div_ceil(hash_chunk_size::<F>()));
+
+        let mut gadget = Sha256Gadget::<F>::default();
+        for chunk in chunks {
+            let chunk = chunk.map_err(|_| SynthesisError::AssignmentMissing)?;
+            let chunk_var = UInt8::<F>::new_witness_vec(cs.clone(), &chunk)?;
+            gadget.update(&chunk_var)?;
+        }
+        let computed_hash = gadget.finalize()?;
+        let expected_hash = DigestVar::<F>(vec_hash_to_hash(public_inputs)?);
+
+        computed_hash.enforce_equal(&expected_hash)
+    }
+}
 
 #[derive(Clone)]
-pub struct Sha256Preimage<F: PrimeField> {
-    pub preimage: Vec<u8>,
-    pub hash: Vec<F>
+pub struct PoseidonCommitment;
+
+impl<F: PrimeField + Absorb> CommitmentHash<F> for PoseidonCommitment {
+    // The hash is a single element of F
+    fn commit(input: &[u8]) -> Vec<F> {
+        let mut sponge = PoseidonSponge::<F>::new(&get_poseidon_config());
+        sponge.absorb(&input.to_vec());
+        sponge.squeeze_field_elements(1)
+    }
+
+    fn enforce_commitment(preimage: &[UInt8<F>], public_inputs: &[FpVar<F>]) -> ark_relations::r1cs::Result<()> {
+        assert_eq!(public_inputs.len(),1);
+
+        let mut sponge = PoseidonSpongeVar::<F>::new(preimage.cs(), &get_poseidon_config());
+        sponge.absorb(&preimage.to_vec())?;
+        let computed_hash = sponge.squeeze_field_elements(1)?;
+
+        computed_hash[0].enforce_equal(&public_inputs[0])
+    }
 }
 
-impl<F: PrimeField> ConstraintSynthesizer<F> for Sha256Preimage<F> {
-    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> ark_relations::r1cs::Result<()> {
-        assert_eq!(self.hash.len(),2);
+#[derive(Clone)]
+pub struct Blake2sCommitment;
+
+impl<F: PrimeField> CommitmentHash<F> for Blake2sCommitment {
+    // As for SHA256, the 32-byte hash is split in chunks of `hash_chunk_size::<F>()` bytes
+    fn commit(input: &[u8]) -> Vec<F> {
+        hash_to_vec_hash(&Blake2s256::digest(input))
+    }
+
+    fn enforce_commitment(preimage: &[UInt8<F>], public_inputs: &[FpVar<F>]) -> ark_relations::r1cs::Result<()> {
+        assert_eq!(public_inputs.len(), 32usize.div_ceil(hash_chunk_size::<F>()));
+
+        // Compute Blake2s hash of witness: the gadget works on the bits of the preimage and returns the 32-bit words
+        // of the hash, which are serialised in little-endian order
+        let computed_hash = evaluate_blake2s(&preimage.to_bits_le()?)?
+            .iter()
+            .map(|word| word.to_bytes())
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        // Reconstruct expected hash from public inputs
+        let expected_hash = vec_hash_to_hash(public_inputs)?;
+
+        OutputVar::<F>(computed_hash).enforce_equal(&OutputVar::<F>(expected_hash))
+    }
+}
+
+// Iterator over the chunks of a preimage
+pub type Chunks = Box<dyn Iterator<Item = std::io::Result<Vec<u8>>>>;
+
+// Source of the chunks of a preimage. The chunks are produced anew each time the preimage is hashed or the circuit is
+// synthesized, and they are dropped once fed to the hash function, so that the preimage is never stored as a whole
+#[derive(Clone)]
+pub struct PreimageChunks {
+    open: Arc<dyn Fn() -> std::io::Result<Chunks> + Send + Sync>,
+}
+
+impl PreimageChunks {
+    // Chunks produced by the iterator returned by `open`
+    pub fn new(open: impl Fn() -> std::io::Result<Chunks> + Send + Sync + 'static) -> Self {
+        PreimageChunks { open: Arc::new(open) }
+    }
+
+    // A preimage held in memory, fed to the hash function at once
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        let bytes = Arc::new(bytes);
+        Self::new(move || Ok(Box::new(std::iter::once(Ok(bytes.to_vec())))))
+    }
+
+    // A preimage read in chunks of `chunk_size` bytes from the reader returned by `open`
+    pub fn from_reader<R: Read + 'static>(open: impl Fn() -> std::io::Result<R> + Send + Sync + 'static, chunk_size: NonZeroUsize) -> Self {
+        Self::new(move || {
+            let mut reader = open()?;
+            let mut done = false;
+            Ok(Box::new(std::iter::from_fn(move || {
+                if done {
+                    return None;
+                }
+                let mut chunk = Vec::with_capacity(chunk_size.get());
+                match reader.by_ref().take(chunk_size.get() as u64).read_to_end(&mut chunk) {
+                    Ok(0) => None,
+                    Ok(_) => Some(Ok(chunk)),
+                    Err(e) => {
+                        done = true;
+                        Some(Err(e))
+                    }
+                }
+            })))
+        })
+    }
+
+    pub fn chunks(&self) -> std::io::Result<Chunks> {
+        (self.open)()
+    }
+}
+
+#[derive(Clone)]
+pub struct HashPreimage<F: PrimeField, H: CommitmentHash<F>> {
+    // The source of the chunks in which the preimage is fed to the hash function
+    pub preimage: PreimageChunks,
+    pub hash: Vec<F>,
+    _hash_function: PhantomData<H>,
+}
+
+impl<F: PrimeField, H: CommitmentHash<F>> HashPreimage<F, H> {
+    pub fn new(preimage: &str) -> Self {
+        HashPreimage {
+            preimage: PreimageChunks::from_bytes(preimage.as_bytes().to_vec()),
+            hash: H::commit(preimage.as_bytes()),
+            _hash_function: PhantomData,
+        }
+    }
+
+    // Build the circuit for the preimage given in chunks by `preimage`, which are read once to compute the hash
+    pub fn from_chunks(preimage: PreimageChunks) -> std::io::Result<Self> {
+        Ok(HashPreimage {
+            hash: H::commit_chunks(preimage.chunks()?)?,
+            preimage,
+            _hash_function: PhantomData,
+        })
+    }
+}
 
-        // Allocate the witness
-        let preimage_var =  UInt8::<F>::new_witness_vec(cs.clone(), &self.preimage)?;
+impl<F: PrimeField, H: CommitmentHash<F>> ConstraintSynthesizer<F> for HashPreimage<F, H> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> ark_relations::r1cs::Result<()> {
         // Allocate public inputs
         let mut public_inputs: Vec<FpVar::<F>> = Vec::new();
         for element in self.hash.iter() {
             public_inputs.push(FpVar::<F>::new_input(cs.clone(), || Ok(element))?);
         };
 
-        // Compute SHA256 hash of witness
-        let computed_hash = Sha256Gadget::<F>::digest(&preimage_var)?;
-        // Reconstruct expected hash from public inputs
-        let expected_hash = DigestVar::<F>(vec_hash_to_hash(&public_inputs)?);
-        
-       computed_hash.enforce_equal(&expected_hash)
+        // Allocate the witness (chunk by chunk if supported by the hash function)
+        let chunks = self.preimage.chunks().map_err(|_| SynthesisError::AssignmentMissing)?;
+        H::enforce_commitment_chunks(cs, chunks, &public_inputs)
     }
 }
 
+// Size of the chunks in which a preimage file is read if --chunk-size is not given
+const DEFAULT_FILE_CHUNK_SIZE: NonZeroUsize = match NonZeroUsize::new(64 * 1024) {
+    Some(chunk_size) => chunk_size,
+    None => unreachable!(),
+};
+
+#[derive(Parser)]
+#[command(about = "Groth16 proof of the knowledge of a hash preimage")]
+struct Cli {
+    /// Preimage, overriding the one in parameters.json
+    #[arg(long, env = "SHA256_PREIMAGE")]
+    preimage: Option<String>,
+    /// File containing the preimage, streamed to the hash function in chunks of --chunk-size bytes (64 KB by default)
+    #[arg(long, conflicts_with = "preimage")]
+    preimage_file: Option<String>,
+    /// Hash function: sha256, poseidon or blake2s
+    #[arg(long, default_value = "sha256")]
+    hash: String,
+    /// Curve of the Groth16 setup: bls12_381 or mnt4_753
+    #[arg(long, default_value = "bls12_381")]
+    curve: String,
+    /// Feed the preimage to the hash function in chunks of this many bytes
+    #[arg(long)]
+    chunk_size: Option<NonZeroUsize>,
+    #[command(flatten)]
+    circuit: CircuitArgs,
+    #[command(flatten)]
+    output: OutputFormatArgs,
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>>{
+    let cli = Cli::parse();
+
+    // Fetch the parameters: preimage
+    // Precedence: command line (--preimage-file or --preimage) > environment (SHA256_PREIMAGE) > parameters.json
+    let preimage = match cli.preimage_file {
+        Some(path) => {
+            let chunk_size = cli.chunk_size.unwrap_or(DEFAULT_FILE_CHUNK_SIZE);
+            PreimageChunks::from_reader(move || File::open(&path).map(BufReader::new), chunk_size)
+        }
+        None => {
+            let preimage = match cli.preimage {
+                Some(preimage) => preimage,
+                None => read_parameter("parameters.json")?,
+            };
+            match cli.chunk_size {
+                Some(chunk_size) => {
+                    let preimage: Arc<[u8]> = preimage.into_bytes().into();
+                    PreimageChunks::from_reader(move || Ok(Cursor::new(preimage.clone())), chunk_size)
+                }
+                None => PreimageChunks::from_bytes(preimage.into_bytes()),
+            }
+        }
+    };
+
+    let (curve, hash_function) = (cli.curve.as_str(), cli.hash.as_str());
+    let stats_name = format!("sha256_{}_{}", curve, hash_function);
+    let (circuit_args, format) = (&cli.circuit, cli.output.format);
+
+    match (curve, hash_function) {
+        ("bls12_381", "sha256") => prove_and_save::<Bls12_381, _>(HashPreimage::<ScalarFieldBls, Sha256Commitment>::from_chunks(preimage.clone())?, circuit_args, format, &stats_name),
+        ("bls12_381", "poseidon") => prove_and_save::<Bls12_381, _>(HashPreimage::<ScalarFieldBls, PoseidonCommitment>::from_chunks(preimage.clone())?, circuit_args, format, &stats_name),
+        ("bls12_381", "blake2s") => prove_and_save::<Bls12_381, _>(HashPreimage::<ScalarFieldBls, Blake2sCommitment>::from_chunks(preimage.clone())?, circuit_args, format, &stats_name),
+        ("mnt4_753", "sha256") => prove_and_save::<MNT4_753, _>(HashPreimage::<ScalarFieldMnt, Sha256Commitment>::from_chunks(preimage.clone())?, circuit_args, format, &stats_name),
+        ("mnt4_753", "poseidon") => prove_and_save::<MNT4_753, _>(HashPreimage::<ScalarFieldMnt, PoseidonCommitment>::from_chunks(preimage.clone())?, circuit_args, format, &stats_name),
+        ("mnt4_753", "blake2s") => prove_and_save::<MNT4_753, _>(HashPreimage::<ScalarFieldMnt, Blake2sCommitment>::from_chunks(preimage.clone())?, circuit_args, format, &stats_name),
+        ("bls12_381" | "mnt4_753", _) => Err(format!("Unknown hash function: {}. Valid options are sha256, poseidon and blake2s", hash_function).into()),
+        _ => Err(format!("Unknown curve: {}. Valid options are bls12_381 and mnt4_753", curve).into()),
+    }
+}
+
+// Generate and save the proof for `circuit`, inspecting it (as `stats_name`) as requested by `circuit_args`
+fn prove_and_save<E: Pairing, H: CommitmentHash<E::ScalarField> + Clone>(circuit: HashPreimage<E::ScalarField, H>, circuit_args: &CircuitArgs, format: OutputFormat, stats_name: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Randomness
     let mut rng = ChaChaRng::from_entropy();
 
-    // Fetch the parameters: 0 - root, 1 - square
-    let preimage = read_parameter("parameters.json");
-
-    // Build the circuit
-    let circuit = Sha256Preimage::<Fr> {
-        preimage: preimage.as_bytes().to_vec(),
-        hash: input_to_vec_hash(&preimage),
-    };
+    // Constraint system summary and size
+    circuit_args.inspect_circuit(stats_name, circuit.clone())?;
 
     // Setup
-    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng)
+    let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), &mut rng)
         .map_err(|e| format!("Setup failed: {}",e))?;
 
     // Proving
-    let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng)
+    let proof = Groth16::<E>::prove(&pk, circuit.clone(), &mut rng)
         .map_err(|e| format!("Proof generation failed: {}", e))?;
 
     // Verifying
-    let is_valid = Groth16::<Bls12_381>::verify(&vk, &input_to_vec_hash(&preimage), &proof)
+    let is_valid = Groth16::<E>::verify(&vk, &circuit.hash, &proof)
         .map_err(|e| format!("Verification failed: {}", e))?;
     assert!(is_valid,"Proof is invalid");
 
     // Save proof, verification key, and public input to files
-    save_to_file(&proof,"proof/proof.json","proof")?;
-    save_to_file(&vk, "proof/verifying_key.json","verifying_key")?;
-    save_to_file(&input_to_vec_hash(&preimage), "proof/public_inputs.json","public_inputs")?;
+    save_to_file_with_format(&proof, &format.file_path("proof/proof"), "proof", format)?;
+    save_to_file_with_format(&vk, &format.file_path("proof/verifying_key"), "verifying_key", format)?;
+    save_to_file_with_format(&circuit.hash, &format.file_path("proof/public_inputs"), "public_inputs", format)?;
 
     Ok(())
 }
 
 // Function to read parameters from JSON file
-fn read_parameter(path: &str) -> String {
-    let file = File::open(path).unwrap();
+fn read_parameter(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
     let reader = BufReader::new(file);
-    let json_data: Value = serde_json::from_reader(reader).unwrap();
-    let preimage = json_data.get("preimage").cloned().unwrap();
-    String::from(preimage.as_str().unwrap())
+    let json_data: Value = serde_json::from_reader(reader)?;
+    let preimage = json_data
+        .get("preimage")
+        .and_then(|value| value.as_str())
+        .ok_or("Missing preimage in parameters")?;
+    Ok(String::from(preimage))
 }
 
-// Takes input value, computes its hash and encodes it in two elements of Fr
-fn input_to_vec_hash(input: &str) -> Vec<Fr> {
-    let input_bytes = input.as_bytes();
-    let hashed_input = Sha256::evaluate(&(), input_bytes).unwrap();
+// Encode the hash in elements of F, each containing `hash_chunk_size::<F>()` bytes of the hash
+fn hash_to_vec_hash<F: PrimeField>(hash: &[u8]) -> Vec<F> {
+    pack_bytes_into_field_elements(hash, hash_chunk_size::<F>())
+}
 
-    let mut output: Vec<Fr> = Vec::new();
-    output.push(Fr::from_le_bytes_mod_order(&hashed_input[..31]));
-    output.push(Fr::from_le_bytes_mod_order(&hashed_input[31..]));
-    output
+// Number of bytes of the hash encoded in each element of F: the largest number of bytes that fits in F
+fn hash_chunk_size<F: PrimeField>() -> usize {
+    max_limb_byte_len::<F>()
+}
 
+// Take a Vec<F> representation of the hash and reconstructs the hash, enforcing that each element only encodes its
+// chunk of the hash
+fn vec_hash_to_hash<F: PrimeField>(vec_hash: &[FpVar<F>]) -> ark_relations::r1cs::Result<Vec<UInt8<F>>> {
+    unpack_field_elements_into_bytes(vec_hash, hash_chunk_size::<F>(), 32)
 }
 
-// Take a Vec<Fr> representation of the hash and reconstructs the hash
-fn vec_hash_to_hash<F: PrimeField>(vec_hash: &Vec<FpVar<F>>) -> ark_relations::r1cs::Result<Vec<UInt8<F>>> {
-    let mut expected_hash_bytes: Vec<UInt8<F>> = Vec::new();
-    for element in vec_hash.iter() {
-        // Only use the first 31 bytes
-        let relevant_part = element.to_bytes()?[..31].to_vec();
-        expected_hash_bytes.extend(relevant_part);
-    };
-    Ok(expected_hash_bytes[..32].to_vec())
-}
-
-// Generic function to save serializable data
-fn save_to_file<T>(
-    item: &T,
-    file_path: &str,
-    key_name: &str
-) -> Result<(), Box<dyn std::error::Error>>
-where
-    T: CanonicalSerialize,
-{
-    let mut serialized_data = vec![0; item.serialized_size(Compress::No)];
-    item.serialize_uncompressed(&mut serialized_data[..])?;
-    
-    let json_data = json!({key_name: serialized_data});
-    let json_string = serde_json::to_string_pretty(&json_data)?;
-    
-    File::create(file_path)?.write_all(json_string.as_bytes())?;
-    Ok(())
-}
\ No newline at end of file
+// Initialize poseidon sponge (same configuration as the ai_inference example)
+fn get_poseidon_config<F: PrimeField> () -> PoseidonConfig<F> {
+    PoseidonConfig {
+        full_rounds: 8,
+        partial_rounds: 57,
+        alpha: 5,
+        mds: vec![
+            vec![F::from(1u64), F::from(2u64), F::from(3u64)],
+            vec![F::from(4u64), F::from(5u64), F::from(6u64)],
+            vec![F::from(7u64), F::from(8u64), F::from(9u64)],
+        ],
+        ark: (0..65)
+            .map(|i| vec![F::from(i as u64), F::from(i as u64 + 1), F::from(i as u64 + 2)])
+            .collect(),
+        rate: 2,
+        capacity: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    type Fr = ScalarFieldBls;
+
+    fn prove_and_verify<E: Pairing, H: CommitmentHash<E::ScalarField> + Clone>(circuit: HashPreimage<E::ScalarField, H>) -> bool {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<E>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+        Groth16::<E>::verify(&vk, &circuit.hash, &proof).unwrap()
+    }
+
+    fn num_constraints<F: PrimeField, H: CommitmentHash<F>>(circuit: HashPreimage<F, H>) -> usize {
+        let cs = ConstraintSystem::<F>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        cs.num_constraints()
+    }
+
+    // Chunks given in memory
+    fn chunks_of(chunks: Vec<Vec<u8>>) -> PreimageChunks {
+        let chunks = Arc::new(chunks);
+        PreimageChunks::new(move || Ok(Box::new(chunks.to_vec().into_iter().map(Ok))))
+    }
+
+    // Reader generating `len` bytes with value `i % 251`, so that large preimages are never held in memory
+    struct PatternReader {
+        position: usize,
+        len: usize,
+    }
+
+    impl Read for PatternReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.len - self.position);
+            for (i, byte) in buf[..n].iter_mut().enumerate() {
+                *byte = ((self.position + i) % 251) as u8;
+            }
+            self.position += n;
+            Ok(n)
+        }
+    }
+
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn pattern_chunks(len: usize, chunk_size: usize) -> PreimageChunks {
+        PreimageChunks::from_reader(move || Ok(PatternReader { position: 0, len }), NonZeroUsize::new(chunk_size).unwrap())
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert!(prove_and_verify::<Bls12_381, _>(HashPreimage::<Fr, Sha256Commitment>::new("Hello")));
+    }
+
+    #[test]
+    fn test_sha256_mnt4() {
+        let circuit = HashPreimage::<ScalarFieldMnt, Sha256Commitment>::new("Hello");
+        // The whole hash fits in a single element of the scalar field of MNT4-753
+        assert_eq!(circuit.hash.len(), 1);
+        assert_eq!(HashPreimage::<Fr, Sha256Commitment>::new("Hello").hash.len(), 2);
+        assert!(prove_and_verify::<MNT4_753, _>(circuit.clone()));
+
+        let bls_constraints = num_constraints(HashPreimage::<Fr, Sha256Commitment>::new("Hello"));
+        let mnt4_constraints = num_constraints(circuit);
+        println!("Number of SHA256 constraints: BLS12-381 {}, MNT4-753 {}", bls_constraints, mnt4_constraints);
+    }
+
+    #[test]
+    fn test_poseidon() {
+        assert!(prove_and_verify::<Bls12_381, _>(HashPreimage::<Fr, PoseidonCommitment>::new("Hello")));
+    }
+
+    #[test]
+    fn test_blake2s() {
+        assert!(prove_and_verify::<Bls12_381, _>(HashPreimage::<Fr, Blake2sCommitment>::new("Hello")));
+
+        let mut circuit = HashPreimage::<Fr, Blake2sCommitment>::new("Hello");
+        circuit.preimage = PreimageChunks::from_bytes("World".as_bytes().to_vec());
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        let sha256_constraints = num_constraints(HashPreimage::<Fr, Sha256Commitment>::new("Hello"));
+        let blake2s_constraints = num_constraints(HashPreimage::<Fr, Blake2sCommitment>::new("Hello"));
+        println!("Number of constraints: SHA256 {}, Blake2s {}", sha256_constraints, blake2s_constraints);
+    }
+
+    #[test]
+    fn test_wrong_preimage() {
+        let mut circuit = HashPreimage::<Fr, PoseidonCommitment>::new("Hello");
+        circuit.preimage = PreimageChunks::from_bytes("World".as_bytes().to_vec());
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_sha256_chunks() {
+        let preimage: Vec<u8> = (0..200u8).collect();
+        let mut chunks = vec![preimage[..1].to_vec(), preimage[1..64].to_vec(), preimage[64..150].to_vec(), preimage[150..].to_vec()];
+        let chunked_circuit = HashPreimage::<Fr, Sha256Commitment>::from_chunks(chunks_of(chunks.clone())).unwrap();
+        assert_eq!(chunked_circuit.hash, Sha256Commitment::commit(&preimage));
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        chunked_circuit.clone().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        let mut wrong_circuit = chunked_circuit;
+        chunks[2][0] ^= 1;
+        wrong_circuit.preimage = chunks_of(chunks);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        wrong_circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_sha256_chunks_constraints() {
+        // 1 KB preimage (16 blocks of SHA256) fed to the gadget in chunks which are not aligned with the blocks
+        let preimage = pattern(1024);
+        let mut chunks: Vec<Vec<u8>> = std::iter::once(vec![]).chain(preimage.chunks(100).map(<[u8]>::to_vec)).collect();
+        assert_eq!(chunks.len(), 12);
+        let chunked_circuit = HashPreimage::<Fr, Sha256Commitment>::from_chunks(chunks_of(chunks.clone())).unwrap();
+        assert_eq!(chunked_circuit.hash, <Sha256Commitment as CommitmentHash<Fr>>::commit(&preimage));
+
+        // The gadget processes the same blocks as when the preimage is hashed at once
+        let mut single_shot_circuit = HashPreimage::<Fr, Sha256Commitment>::from_chunks(PreimageChunks::from_bytes(preimage.clone())).unwrap();
+        assert_eq!(num_constraints(chunked_circuit.clone()), num_constraints(single_shot_circuit.clone()));
+
+        // A wrong byte in the last chunk
+        let mut wrong_circuit = chunked_circuit;
+        chunks[11][0] ^= 1;
+        wrong_circuit.preimage = chunks_of(chunks);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        wrong_circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        let mut wrong_preimage = preimage;
+        wrong_preimage[0] ^= 1;
+        single_shot_circuit.preimage = PreimageChunks::from_bytes(wrong_preimage);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        single_shot_circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_sha256_chunks_streamed() {
+        // Preimage streamed from a reader in chunks which are not aligned with the blocks, against the same preimage
+        // hashed at once: the two circuits have the same public inputs and the same number of constraints
+        let streamed_circuit = HashPreimage::<Fr, Sha256Commitment>::from_chunks(pattern_chunks(2000, 97)).unwrap();
+        let single_shot_circuit = HashPreimage::<Fr, Sha256Commitment>::from_chunks(PreimageChunks::from_bytes(pattern(2000))).unwrap();
+        assert_eq!(streamed_circuit.hash, single_shot_circuit.hash);
+        assert_eq!(num_constraints(streamed_circuit), num_constraints(single_shot_circuit));
+    }
+
+    #[test]
+    fn test_sha256_chunks_large_preimage() {
+        // 4 MB preimage streamed from a reader: hashing it incrementally must match hashing it at once
+        // Only the native hash is checked, as the constraint system of the preimage would have billions of constraints
+        let len = 4 * 1024 * 1024;
+        let streamed_circuit = HashPreimage::<Fr, Sha256Commitment>::from_chunks(pattern_chunks(len, 64 * 1024 + 3)).unwrap();
+        assert_eq!(streamed_circuit.hash, <Sha256Commitment as CommitmentHash<Fr>>::commit(&pattern(len)));
+    }
+
+    #[test]
+    fn test_sha256_chunks_read_error() {
+        let failing_chunks = || PreimageChunks::from_reader(|| Err::<PatternReader, _>(std::io::Error::other("unreadable")), NonZeroUsize::new(64).unwrap());
+        assert!(HashPreimage::<Fr, Sha256Commitment>::from_chunks(failing_chunks()).is_err());
+
+        // The reader fails after the hash is computed
+        let mut circuit = HashPreimage::<Fr, Sha256Commitment>::new("Hello");
+        circuit.preimage = failing_chunks();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        assert!(circuit.generate_constraints(cs).is_err());
+    }
+
+    #[test]
+    fn test_constraint_count() {
+        let sha256_constraints = num_constraints(HashPreimage::<Fr, Sha256Commitment>::new("Hello"));
+        let poseidon_constraints = num_constraints(HashPreimage::<Fr, PoseidonCommitment>::new("Hello"));
+        println!("Number of constraints: SHA256 {}, Poseidon {}", sha256_constraints, poseidon_constraints);
+        assert!(poseidon_constraints < sha256_constraints);
+    }
+}
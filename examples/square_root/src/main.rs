@@ -1,14 +1,16 @@
-use std::{fs::File, io::{BufReader, Write}};
+use std::{fs::File, io::BufReader};
+
+use common::{CircuitArgs, OutputFormatArgs, save_to_file_with_format};
 
 use ark_ff::PrimeField;
 use ark_groth16::Groth16;
 use ark_r1cs_std::{alloc::AllocVar, fields::{fp::FpVar, FieldVar}};
 use ark_relations::r1cs::ConstraintSynthesizer;
-use ark_serialize::{CanonicalSerialize, Compress};
 use ark_snark::SNARK;
+use clap::Parser;
 use rand_chacha::ChaChaRng;
 use rand::SeedableRng;
-use serde_json::{json, Value};
+use serde_json::Value;
 #[allow(unused_imports)]
 use ark_test_curves::bls12_381::{Bls12_381, Fr as ScalarFieldBls};
 #[allow(unused_imports)]
@@ -35,18 +37,38 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for KnowledgeOfSquareRoot<F> {
 type ScalarField = ScalarFieldMnt;
 type Curve = MNT4_753;
 
+#[derive(Parser)]
+#[command(about = "Groth16 proof of the knowledge of a square root")]
+struct Cli {
+    /// Root, overriding the one in parameters.json
+    #[arg(long, env = "SQUARE_ROOT_ROOT")]
+    root: Option<u64>,
+    /// Square, overriding the one in parameters.json
+    #[arg(long, env = "SQUARE_ROOT_SQUARE")]
+    square: Option<u64>,
+    #[command(flatten)]
+    circuit: CircuitArgs,
+    #[command(flatten)]
+    output: OutputFormatArgs,
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>>{
+    let cli = Cli::parse();
+
     // Randomness
     let mut rng = ChaChaRng::from_entropy();
 
     // Fetch the parameters: 0 - root, 1 - square
-    let parameters = read_parameters("parameters.json")?;
+    // Precedence: command line (--root, --square) > environment (SQUARE_ROOT_ROOT, SQUARE_ROOT_SQUARE) > parameters.json
+    let parameters = read_parameters("parameters.json", cli.root, cli.square)?;
 
     // Build the circuit
     let circuit = KnowledgeOfSquareRoot::<ScalarField> {
         root: parameters[0],
         square: parameters[1],
     };
+    cli.circuit.inspect_circuit("square_root", circuit.clone())?;
+    let format = cli.output.format;
 
     // Setup
     let (pk, vk) = Groth16::<Curve>::circuit_specific_setup(circuit.clone(), &mut rng)
@@ -62,44 +84,83 @@ fn main() -> Result<(), Box<dyn std::error::Error>>{
     assert!(is_valid,"Proof is invalid");
 
     // Save proof, verification key, and public input to files
-    save_to_file(&proof,"proof/proof.json","proof")?;
-    save_to_file(&vk, "proof/verifying_key.json","verifying_key")?;
-    save_to_file(&vec![parameters[1]], "proof/public_inputs.json","public_inputs")?;
+    save_to_file_with_format(&proof, &format.file_path("proof/proof"), "proof", format)?;
+    save_to_file_with_format(&vk, &format.file_path("proof/verifying_key"), "verifying_key", format)?;
+    save_to_file_with_format(&vec![parameters[1]], &format.file_path("proof/public_inputs"), "public_inputs", format)?;
 
     Ok(())
 }
 
-// Function to read parameters from JSON file
-fn read_parameters<F: PrimeField>(path: &str) -> Result<Vec<F>, Box<dyn std::error::Error>>{
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let json_data: Value = serde_json::from_reader(reader)?;
+// Function to read the parameters [root, square]: each parameter is read from the JSON file at `path`, unless it is
+// overridden by `root` or `square`
+fn read_parameters<F: PrimeField>(path: &str, root: Option<u64>, square: Option<u64>) -> Result<Vec<F>, Box<dyn std::error::Error>>{
+    let mut json_data: Option<Value> = None;
     let mut out: Vec<F> = vec![];
-    if let Some(root) = json_data.get("root") {
-        out.push(F::from(root.as_u64().unwrap()));
-    }
-    if let Some(square) = json_data.get("square") {
-        out.push(F::from(square.as_u64().unwrap()));
+    for (key, value) in [("root", root), ("square", square)] {
+        let value = match value {
+            Some(value) => value,
+            None => {
+                if json_data.is_none() {
+                    json_data = Some(serde_json::from_reader(BufReader::new(File::open(path)?))?);
+                }
+                json_data
+                    .as_ref()
+                    .and_then(|data| data.get(key))
+                    .and_then(|value| value.as_u64())
+                    .ok_or(format!("Missing {} in parameters", key))?
+            }
+        };
+        out.push(F::from(value));
     }
 
     Ok(out)
 }
 
-// Generic function to save serializable data
-fn save_to_file<T>(
-    item: &T,
-    file_path: &str,
-    key_name: &str
-) -> Result<(), Box<dyn std::error::Error>>
-where
-    T: CanonicalSerialize,
-{
-    let mut serialized_data = vec![0; item.serialized_size(Compress::No)];
-    item.serialize_uncompressed(&mut serialized_data[..])?;
-    
-    let json_data = json!({key_name: serialized_data});
-    let json_string = serde_json::to_string_pretty(&json_data)?;
-    
-    File::create(file_path)?.write_all(json_string.as_bytes())?;
-    Ok(())
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_constraint_system() {
+        let circuit = KnowledgeOfSquareRoot::<ScalarField> {
+            root: ScalarField::from(3u64),
+            square: ScalarField::from(9u64),
+        };
+
+        let file_path = std::env::temp_dir().join("square_root_test_dump_cs.json");
+        let file_path = file_path.to_str().unwrap();
+        common::dump_constraint_system(circuit, file_path, true).unwrap();
+        let summary: Value = serde_json::from_reader(BufReader::new(File::open(file_path).unwrap())).unwrap();
+        std::fs::remove_file(file_path).unwrap();
+
+        assert_eq!(summary["num_constraints"], 1);
+        assert_eq!(summary["num_public_inputs"], 1);
+        assert_eq!(summary["public_inputs"][0], "9");
+        assert!(summary.get("matrices").is_some());
+    }
+
+    #[test]
+    fn test_read_parameters_precedence() {
+        let file_path = std::env::temp_dir().join("square_root_test_parameters.json");
+        let file_path = file_path.to_str().unwrap();
+        std::fs::write(file_path, r#"{"root": 3, "square": 9}"#).unwrap();
+        let read = |args: &[&str]| {
+            let cli = Cli::try_parse_from(std::iter::once("square_root").chain(args.iter().copied()))?;
+            Ok::<_, Box<dyn std::error::Error>>(read_parameters::<ScalarField>(file_path, cli.root, cli.square)?)
+        };
+
+        // The environment variable is used over the file
+        std::env::set_var("SQUARE_ROOT_SQUARE", "16");
+        std::env::remove_var("SQUARE_ROOT_ROOT");
+        assert_eq!(read(&[]).unwrap(), vec![ScalarField::from(3u64), ScalarField::from(16u64)]);
+
+        // The command line is used over the environment variable
+        assert_eq!(read(&["--square", "25"]).unwrap(), vec![ScalarField::from(3u64), ScalarField::from(25u64)]);
+
+        std::env::set_var("SQUARE_ROOT_SQUARE", "not a number");
+        assert!(read(&[]).is_err());
+
+        std::env::remove_var("SQUARE_ROOT_SQUARE");
+        std::fs::remove_file(file_path).unwrap();
+    }
+}
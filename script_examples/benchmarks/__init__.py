@@ -0,0 +1,8 @@
+"""Benchmarks package.
+
+This package provides benchmarks to track the performance of the script generation.
+
+Modules:
+    - pairing_benchmarks: Measures the generation time and the output size of the scripts implementing the
+        sub-operations of the BLS12-381 pairing, and outputs a JSON summary.
+"""
@@ -0,0 +1,140 @@
+"""Benchmarks for the script generation of the pairing sub-operations over BLS12-381.
+
+Usage:
+    python -m script_examples.benchmarks.pairing_benchmarks [--repetitions N] [--output FILE] [BENCHMARK ...]
+
+The summary is a JSON list with one entry per benchmark, printed to stdout unless `--output` is given.
+"""
+
+import argparse
+import json
+import sys
+import time
+from collections.abc import Callable
+from dataclasses import asdict, dataclass
+from pathlib import Path
+
+from tx_engine import Script
+
+from src.zkscript.bilinear_pairings.bls12_381.bls12_381 import bls12_381
+from src.zkscript.bilinear_pairings.bls12_381.fields import fq2_script, fq12_script
+from src.zkscript.bilinear_pairings.bls12_381.final_exponentiation import final_exponentiation
+from src.zkscript.bilinear_pairings.bls12_381.line_functions import line_functions
+
+MODULO_THRESHOLD = 200 * 8
+
+
+def final_exponentiation_script() -> Script:
+    """Script computing the final exponentiation: easy part with inverse check, followed by the hard part."""
+    out = final_exponentiation.easy_exponentiation_with_inverse_check(
+        take_modulo=True, positive_modulo=False, check_constant=False, clean_constant=False
+    )
+    out += final_exponentiation.hard_exponentiation(
+        take_modulo=True, modulo_threshold=MODULO_THRESHOLD, check_constant=False, clean_constant=False
+    )
+    return out
+
+
+BENCHMARKS: dict[str, Callable[[], Script]] = {
+    "fq2_mul": lambda: fq2_script.mul(take_modulo=True, check_constant=False, clean_constant=False),
+    "fq12_mul": lambda: fq12_script.mul(take_modulo=True, check_constant=False, clean_constant=False),
+    "line_eval": lambda: line_functions.line_evaluation(take_modulo=True, check_constant=False, clean_constant=False),
+    "triple_miller_loop": lambda: bls12_381.triple_miller_loop(
+        modulo_threshold=MODULO_THRESHOLD, check_constant=False, clean_constant=False
+    ),
+    "final_exponentiation": final_exponentiation_script,
+}
+
+
+@dataclass
+class BenchmarkResult:
+    """Result of a benchmark.
+
+    Attributes:
+        name (str): The name of the benchmark.
+        repetitions (int): The number of times the script was generated.
+        mean_generation_time (float): The mean time, in seconds, taken to generate the script.
+        min_generation_time (float): The minimum time, in seconds, taken to generate the script.
+        n_opcodes (int): The number of opcodes and data pushes in the generated script.
+        script_size (int): The size of the generated script in bytes.
+    """
+
+    name: str
+    repetitions: int
+    mean_generation_time: float
+    min_generation_time: float
+    n_opcodes: int
+    script_size: int
+
+
+def run_benchmark(name: str, repetitions: int = 1) -> BenchmarkResult:
+    """Run the benchmark `name`.
+
+    Args:
+        name (str): The name of the benchmark, one of the keys of `BENCHMARKS`.
+        repetitions (int): The number of times the script is generated. Defaults to `1`.
+
+    Returns:
+        The result of the benchmark.
+
+    Raises:
+        ValueError: If `name` is not a known benchmark, or if `repetitions` is not positive.
+    """
+    if name not in BENCHMARKS:
+        msg = f"Unknown benchmark: {name}. Available benchmarks: {', '.join(BENCHMARKS)}"
+        raise ValueError(msg)
+    if repetitions < 1:
+        msg = f"The number of repetitions must be positive: repetitions = {repetitions}"
+        raise ValueError(msg)
+
+    timings = []
+    for _ in range(repetitions):
+        start = time.perf_counter()
+        script = BENCHMARKS[name]()
+        timings.append(time.perf_counter() - start)
+
+    return BenchmarkResult(
+        name=name,
+        repetitions=repetitions,
+        mean_generation_time=sum(timings) / repetitions,
+        min_generation_time=min(timings),
+        n_opcodes=len(str(script).split()),
+        script_size=len(script.raw_serialize()),
+    )
+
+
+def run_benchmarks(names: list[str] | None = None, repetitions: int = 1) -> list[BenchmarkResult]:
+    """Run the benchmarks in `names`.
+
+    Args:
+        names (list[str] | None): The benchmarks to run. Defaults to `None`, meaning that all the benchmarks are run.
+        repetitions (int): The number of times each script is generated. Defaults to `1`.
+
+    Returns:
+        The list of results, in the same order as `names`.
+    """
+    names = names if names is not None else list(BENCHMARKS)
+    return [run_benchmark(name, repetitions) for name in names]
+
+
+def main() -> None:
+    """Run the benchmarks from the command line and output the JSON summary."""
+    parser = argparse.ArgumentParser(description="Benchmark the script generation of pairing sub-operations.")
+    parser.add_argument(
+        "benchmarks", nargs="*", help=f"The benchmarks to run (default: all). Available: {', '.join(BENCHMARKS)}."
+    )
+    parser.add_argument("--repetitions", type=int, default=1, help="Number of times each script is generated.")
+    parser.add_argument("--output", type=str, default=None, help="File to which the JSON summary is written.")
+    args = parser.parse_args()
+
+    results = run_benchmarks(names=args.benchmarks or None, repetitions=args.repetitions)
+    summary = json.dumps([asdict(result) for result in results], indent=4)
+
+    if args.output is not None:
+        Path(args.output).write_text(summary + "\n")
+    else:
+        sys.stdout.write(summary + "\n")
+
+
+if __name__ == "__main__":
+    main()
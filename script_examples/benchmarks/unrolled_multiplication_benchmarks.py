@@ -0,0 +1,157 @@
+"""Comparison of the affine and projective `unrolled_multiplication` over secp256k1.
+
+Usage:
+    python -m script_examples.benchmarks.unrolled_multiplication_benchmarks [--scalar N]
+
+The affine multiplication (`EllipticCurveFq`) requires the gradients of the doublings and additions in the unlocking
+script, each of which costs the prover a field inversion. The projective multiplication (`EllipticCurveFqProjective`)
+requires no hints, but executes more field operations in the locking script. The comparison is printed as a table
+with the size of the locking and unlocking scripts and the number of field inversions computed by the prover.
+"""
+
+import argparse
+import sys
+from dataclasses import dataclass
+
+from elliptic_curves.fields.prime_field import PrimeField
+from elliptic_curves.models.ec import ShortWeierstrassEllipticCurve
+from elliptic_curves.util.zkscript import unrolled_multiplication_gradients
+from tx_engine import Script
+from tx_engine.engine.util import GROUP_ORDER_INT, PRIME_INT, Gx, Gy
+
+from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
+from src.zkscript.elliptic_curves.ec_operations_fq_projective import EllipticCurveFqProjective
+from src.zkscript.script_types.unlocking_keys.unrolled_ec_multiplication import EllipticCurveFqUnrolledUnlockingKey
+from src.zkscript.script_types.unlocking_keys.unrolled_projective_ec_multiplication import (
+    EllipticCurveFqProjectiveUnrolledUnlockingKey,
+)
+
+# Default scalar: a 256-bit scalar with about half of its bits set
+DEFAULT_SCALAR = 0xB3A5F6C1D2E4F70819A2B3C4D5E6F708192A3B4C5D6E7F8091A2B3C4D5E6F708
+
+FQ = PrimeField(PRIME_INT)
+SECP256K1 = ShortWeierstrassEllipticCurve(a=FQ(0), b=FQ(7))
+GENERATOR = SECP256K1(x=FQ(Gx), y=FQ(Gy), infinity=False)
+
+ec_fq = EllipticCurveFq(q=PRIME_INT, curve_a=0, curve_b=7)
+ec_fq_projective = EllipticCurveFqProjective(q=PRIME_INT, curve_a=0, curve_b=7)
+
+
+@dataclass
+class MultiplicationBenchmarkResult:
+    """Result of the benchmark of an `unrolled_multiplication`.
+
+    Attributes:
+        coordinates (str): The coordinates in which the multiplication is computed, `affine` or `projective`.
+        locking_script_size (int): The size of the locking script in bytes.
+        unlocking_script_size (int): The size of the unlocking script in bytes.
+        n_inversions (int): The number of field inversions the prover computes to generate the unlocking script.
+    """
+
+    coordinates: str
+    locking_script_size: int
+    unlocking_script_size: int
+    n_inversions: int
+
+
+def affine_unrolled_multiplication(scalar: int) -> tuple[Script, Script, int]:
+    """Return the scripts computing `scalar * G` in affine coordinates, `G` the generator of secp256k1.
+
+    Args:
+        scalar (int): The scalar, at most the order of secp256k1.
+
+    Returns:
+        The locking script, the unlocking script, and the number of gradients in the unlocking script. The locking
+        script leaves `[G, scalar * G]` on the stack.
+    """
+    gradients = unrolled_multiplication_gradients(scalar, GENERATOR).as_data()
+    unlocking_key = EllipticCurveFqUnrolledUnlockingKey(
+        P=GENERATOR.to_list(), a=scalar, gradients=gradients, max_multiplier=GROUP_ORDER_INT
+    )
+    unlock = unlocking_key.to_unlocking_script(ec_fq, load_modulus=True)
+    lock = ec_fq.unrolled_multiplication(
+        max_multiplier=GROUP_ORDER_INT, modulo_threshold=1, check_constant=True, clean_constant=True
+    )
+    return lock, unlock, sum(len(step) for step in gradients)
+
+
+def projective_unrolled_multiplication(scalar: int) -> tuple[Script, Script, int]:
+    """Return the scripts computing `scalar * G` in projective coordinates, `G` the generator of secp256k1.
+
+    Args:
+        scalar (int): The scalar, at most the order of secp256k1.
+
+    Returns:
+        The locking script, the unlocking script, and the number of gradients in the unlocking script (always `0`).
+        The locking script leaves `[G, scalar * G]` on the stack, in projective coordinates.
+    """
+    unlocking_key = EllipticCurveFqProjectiveUnrolledUnlockingKey(
+        P=[Gx, Gy, 1], a=scalar, max_multiplier=GROUP_ORDER_INT
+    )
+    unlock = unlocking_key.to_unlocking_script(ec_fq_projective, load_modulus=True, load_P=True)
+    lock = ec_fq_projective.unrolled_multiplication(
+        max_multiplier=GROUP_ORDER_INT, check_constant=True, clean_constant=True, positive_modulo=True
+    )
+    return lock, unlock, 0
+
+
+def compare_unrolled_multiplications(scalar: int = DEFAULT_SCALAR) -> list[MultiplicationBenchmarkResult]:
+    """Compare the affine and projective `unrolled_multiplication` for `scalar`.
+
+    Args:
+        scalar (int): The scalar by which the generator of secp256k1 is multiplied. Defaults to `DEFAULT_SCALAR`.
+
+    Returns:
+        The results for the affine and the projective multiplication, in this order.
+
+    Raises:
+        ValueError: If `scalar` is not in the range `[1, GROUP_ORDER_INT)`.
+    """
+    if not 0 < scalar < GROUP_ORDER_INT:
+        msg = f"The scalar must be in the range [1, {GROUP_ORDER_INT}): scalar = {scalar}"
+        raise ValueError(msg)
+
+    results = []
+    for coordinates, multiplication in [
+        ("affine", affine_unrolled_multiplication),
+        ("projective", projective_unrolled_multiplication),
+    ]:
+        lock, unlock, n_inversions = multiplication(scalar)
+        results.append(
+            MultiplicationBenchmarkResult(
+                coordinates=coordinates,
+                locking_script_size=len(lock.raw_serialize()),
+                unlocking_script_size=len(unlock.raw_serialize()),
+                n_inversions=n_inversions,
+            )
+        )
+    return results
+
+
+def format_table(results: list[MultiplicationBenchmarkResult]) -> str:
+    """Format `results` as a table, one row per result."""
+    header = ["coordinates", "locking script (bytes)", "unlocking script (bytes)", "inversions"]
+    rows = [
+        [result.coordinates, result.locking_script_size, result.unlocking_script_size, result.n_inversions]
+        for result in results
+    ]
+    widths = [max(len(str(row[i])) for row in [header, *rows]) for i in range(len(header))]
+    lines = [" | ".join(cell.ljust(width) for cell, width in zip(header, widths, strict=True))]
+    lines.append("-+-".join("-" * width for width in widths))
+    lines += [" | ".join(str(cell).rjust(width) for cell, width in zip(row, widths, strict=True)) for row in rows]
+    return "\n".join(lines)
+
+
+def main() -> None:
+    """Compare the affine and projective multiplications from the command line and print the table."""
+    parser = argparse.ArgumentParser(description="Compare the affine and projective unrolled multiplications.")
+    parser.add_argument(
+        "--scalar", type=lambda x: int(x, 0), default=DEFAULT_SCALAR, help="The scalar multiplying the generator."
+    )
+    args = parser.parse_args()
+
+    sys.stdout.write(format_table(compare_unrolled_multiplications(args.scalar)) + "\n")
+
+
+if __name__ == "__main__":
+    main()
@@ -1,10 +1,13 @@
 """Pedersen commitment package.
 
-This package provides scripts that implement the Pedersen commitment scheme over secp256k1.
+This package provides scripts that implement the Pedersen commitment scheme over secp256k1, and the Pedersen
+commitment scheme built on the windowed Pedersen CRH over any curve.
 
 Modules:
     - pedersen_commitment: Implements the class PedersenCommitmentSecp256k1 which has the method
         `commit` that allows to commitment to a certain commitment.
     - pedersen_unlocking_key: Implements the class PedersenCommitmentSecp256k1UnlockingKey which encapsulates
         the data needed to open a commitment to a value `m`.
+    - pedersen_crh: Implements the class PedersenCRHCommitment which has the method `verify_pedersen_opening`
+        that allows to verify the opening of a commitment computed with the Pedersen CRH used in-circuit.
 """
@@ -1,6 +1,6 @@
 """Pedersen commitment package."""
 
-from tx_engine import Script
+from tx_engine import SIGHASH, Script
 
 from src.zkscript.elliptic_curves.secp256k1.secp256k1 import Secp256k1
 from src.zkscript.script_types.stack_elements import (
@@ -27,7 +27,7 @@ class PedersenCommitmentSecp256k1:
         self.B = B
         self.H = H
 
-    def commit(self, commitment: bytes) -> Script:
+    def commit(self, commitment: bytes, sighash_flags: SIGHASH = SIGHASH.ALL_FORKID) -> Script:
         """Commitment script for Pedersen commitment scheme.
 
         Stack input:
@@ -44,6 +44,8 @@ class PedersenCommitmentSecp256k1:
 
         Args:
             commitment (bytes): The commitment.
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed. Defaults to
+                `SIGHASH.ALL_FORKID`.
 
         Returns:
             The Bitcoin script that commits to `commitment`.
@@ -88,6 +90,7 @@ class PedersenCommitmentSecp256k1:
             sig_hash_preimage=sig_hash_preimage.shift(-1),
             h=h.shift(-1),
             rolling_option=((1 << 15) - 1) ^ 1 ^ (1 << 1) ^ (1 << 14),
+            sighash_flags=sighash_flags,
         )
         out += Script.parse_string("OP_VERIFY")
 
@@ -104,6 +107,7 @@ class PedersenCommitmentSecp256k1:
             sig_hash_preimage=sig_hash_preimage.shift(-19),
             h=h.shift(-19),
             rolling_option=((1 << 15) - 1) ^ (1 << 14),
+            sighash_flags=sighash_flags,
         )
         out += Script.parse_string("OP_VERIFY")
 
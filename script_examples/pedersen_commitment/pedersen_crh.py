@@ -0,0 +1,159 @@
+"""Bitcoin scripts for the Pedersen commitment scheme built on the windowed Pedersen CRH."""
+
+from tx_engine import Script
+
+from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
+from src.zkscript.util.utility_scripts import nums_to_script
+
+
+class PedersenCRHCommitment:
+    """Bitcoin scripts for the Pedersen commitment scheme built on the windowed Pedersen CRH.
+
+    The scheme mirrors the Pedersen CRH used in-circuit (e.g., `VariableLengthPedersenCRHCompressor`): the message is
+    split into little-endian bits, which are grouped in windows of `window_size` bits. The bits of the `i`-th window
+    select the multiples `2^j * G_i` of the generator `G_i` of the window, so that the window contributes `m_i * G_i`,
+    where `m_i` is the integer whose little-endian bits are the bits of the window. The commitment is:
+        Pedersen.commit(m,r) = m_0 * G_0 + .. + m_(n-1) * G_(n-1) + r * H
+    """
+
+    def __init__(
+        self,
+        ec_fq: EllipticCurveFq,
+        generators: list[list[int]],
+        randomness_generator: list[int],
+        window_size: int,
+        max_randomness: int,
+    ):
+        """Initialise the Pedersen commitment scheme.
+
+        Args:
+            ec_fq (EllipticCurveFq): The elliptic curve arithmetic over the curve of the commitment scheme.
+            generators (list[list[int]]): The generators of the windows. `generators[i]` is the list of the
+                coordinates of `G_i`, the first generator of the `i`-th window of the CRH parameters.
+            randomness_generator (list[int]): The coordinates of `H`, the first generator of the randomness
+                parameters.
+            window_size (int): The number of bits of each window.
+            max_randomness (int): The maximum value of the randomness `r`, typically the order of the group.
+
+        Raises:
+            ValueError: If there are no generators or `window_size` is not positive.
+
+        Notes:
+            The generators must be exported from the parameters used in-circuit: they are not recomputed here.
+        """
+        if len(generators) == 0 or window_size < 1:
+            msg = f"The scheme requires at least one window of positive size: n_windows = {len(generators)}, "
+            msg += f"window_size = {window_size}"
+            raise ValueError(msg)
+        self.ec_fq = ec_fq
+        self.generators = generators
+        self.randomness_generator = randomness_generator
+        self.window_size = window_size
+        self.max_randomness = max_randomness
+
+    @property
+    def bases(self) -> list[list[int]]:
+        """The bases of the multi scalar multiplication computing the commitment: `[G_0, .., G_(n-1), H]`."""
+        return [*self.generators, self.randomness_generator]
+
+    @property
+    def max_multipliers(self) -> list[int]:
+        """The maximum values of the scalars by which `self.bases` are multiplied."""
+        return [2**self.window_size - 1] * len(self.generators) + [self.max_randomness]
+
+    def message_to_scalars(self, message: bytes) -> list[int]:
+        """Return the scalars `[m_0, .., m_(n-1)]` by which the generators of the windows are multiplied.
+
+        The message is split into little-endian bits (byte by byte), and padded with zeros to fill all the windows.
+
+        Args:
+            message (bytes): The message.
+
+        Raises:
+            ValueError: If the message does not fit in the windows.
+        """
+        n_bits = len(self.generators) * self.window_size
+        if 8 * len(message) > n_bits:
+            msg = f"The message does not fit in the windows: message bits = {8 * len(message)}, window bits = {n_bits}"
+            raise ValueError(msg)
+        bits = [(byte >> i) & 1 for byte in message for i in range(8)]
+        bits += [0] * (n_bits - len(bits))
+        return [
+            sum(bit << j for j, bit in enumerate(bits[i : i + self.window_size]))
+            for i in range(0, n_bits, self.window_size)
+        ]
+
+    def opening_scalars(self, message: bytes, randomness: int) -> list[int]:
+        """Return the scalars by which `self.bases` are multiplied to open a commitment to `message`.
+
+        Args:
+            message (bytes): The message.
+            randomness (int): The randomness of the commitment.
+
+        Raises:
+            ValueError: If the message does not fit in the windows, or the randomness is out of range.
+        """
+        if not 0 <= randomness <= self.max_randomness:
+            msg = f"The randomness must be in the range [0, {self.max_randomness}]: randomness = {randomness}"
+            raise ValueError(msg)
+        return [*self.message_to_scalars(message), randomness]
+
+    def verify_pedersen_opening(
+        self,
+        commitment: list[int],
+        modulo_threshold: int,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        x_only: bool = False,
+    ) -> Script:
+        r"""Verify the opening `(message, randomness)` of `commitment`.
+
+        Stack input:
+            - stack:    [q, ..., msm_data(opening_scalars(message, randomness))]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., 0/1]
+            - altstack: []
+
+        Where `msm_data(scalars)` is the data required to execute `EllipticCurveFq.msm_with_fixed_bases` with bases
+        `self.bases` and scalars `scalars`. It is generated by `MsmWithFixedBasesUnlockingKey` with max multipliers
+        `self.max_multipliers`.
+
+        Args:
+            commitment (list[int]): The commitment, passed as the list of its coordinates.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            x_only (bool): If `True`, only the x-coordinate of the commitment is checked, as for the digest of the
+                compressed CRH. Defaults to `False`.
+
+        Returns:
+            The Bitcoin script verifying the opening of `commitment`.
+
+        Notes:
+            The scalars are range checked by the multi scalar multiplication, so each window of the message is
+            bound to `window_size` bits. With `x_only`, `commitment` and `-commitment` are not distinguished.
+        """
+        out = self.ec_fq.msm_with_fixed_bases(
+            bases=self.bases,
+            max_multipliers=self.max_multipliers,
+            modulo_threshold=modulo_threshold,
+            take_modulo=True,
+            check_constant=check_constant,
+            clean_constant=clean_constant,
+            positive_modulo=True,
+        )
+
+        # stack in:  [..., x, y]
+        # stack out: [..., 0/1]
+        if x_only:
+            out += Script.parse_string("OP_DROP")
+            out += nums_to_script([commitment[0]])
+        else:
+            out += nums_to_script([commitment[1]])
+            out += Script.parse_string("OP_EQUALVERIFY")
+            out += nums_to_script([commitment[0]])
+        out += Script.parse_string("OP_EQUAL")
+
+        return out
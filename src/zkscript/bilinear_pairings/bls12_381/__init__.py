@@ -9,4 +9,5 @@ Modules:
     - line_functions: Line evaluation for BLS12-381.
     - miller_output_operations: Operations between Miller output and line evaluations.
     - parameters: BLS12-381 curve parameters.
+    - subgroup_membership: Subgroup membership checks for BLS12-381.
 """
@@ -14,6 +14,7 @@ from src.zkscript.bilinear_pairings.bls12_381.parameters import (
     N_POINTS_CURVE,
     N_POINTS_TWIST,
     exp_miller_loop,
+    is_miller_loop_parameter_negative,
     q,
     twisted_a,
 )
@@ -82,4 +83,5 @@ bls12_381 = PairingModel(
     easy_exponentiation_with_inverse_check=final_exponentiation.easy_exponentiation_with_inverse_check,
     hard_exponentiation=final_exponentiation.hard_exponentiation,
     size_estimation_miller_loop=size_estimation_miller_loop,
+    is_miller_loop_parameter_negative=is_miller_loop_parameter_negative,
 )
@@ -95,8 +95,7 @@ class FinalExponentiation(CyclotomicExponentiation):
         check_f_inverse += self.fq12.mul(
             take_modulo=True, positive_modulo=True, check_constant=False, clean_constant=False, is_constant_reused=False
         )  # Multiply
-        check_f_inverse += Script.parse_string(" ".join(["OP_0", "OP_EQUALVERIFY"] * 11))
-        check_f_inverse += Script.parse_string("OP_1 OP_EQUALVERIFY")
+        check_f_inverse += self.fq12.is_one(is_verify=True)
 
         # After this, the stack is: Inverse(f_quadratic) Conjugate(f_quadratic)
         # Conjugate f_quadratic
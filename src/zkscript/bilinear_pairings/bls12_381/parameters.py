@@ -3,8 +3,11 @@
 # Seed
 u = -0xD201000000010000
 
-# Signed base two decomposition of u - LSB to MSB
+# Signed base two decomposition of u - LSB to MSB. The non-adjacent form of u has the same number of non-zero digits
+# (and one digit more), so the binary decomposition is used
 exp_miller_loop = [-int(bin(abs(u))[i]) for i in range(2, len(bin(abs(u))))][::-1]
+# The Miller loop parameter u is negative: its sign is absorbed in exp_miller_loop
+is_miller_loop_parameter_negative = u < 0
 
 # Modulus
 q = (u - 1) ** 2 * (u**4 - u**2 + 1) // 3 + u
@@ -22,6 +25,20 @@ twisted_b = [b, b]
 # Non-residue
 NON_RESIDUE_FQ = -1  # List serialisation
 
+# Coefficient of the endomorphism phi(x,y) = (PHI_X * x, y) on the curve, PHI_X is a primitive cube root of unity
+PHI_X = 793479390729215512621379701633421447060886740281060493010456487427281649075476305620758731620350
+
+# Coefficients of the endomorphism psi(x,y) = (PSI_X * conjugate(x), PSI_Y * conjugate(y)) on the twisted curve
+# PSI_X = 1/(1+u)^((q-1)/3), PSI_Y = 1/(1+u)^((q-1)/2)
+PSI_X = [
+    0,
+    4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939437,
+]
+PSI_Y = [
+    2973677408986561043442465346520108879172042883009249989176415018091420807192182638567116318576472649347015917690530,
+    1028732146235106349975324479215795277384839936929757896155643118032610843298655225875571310552543014690878354869257,
+]
+
 # Embedding degrees and other constants
 EMBEDDING_DEGREE = 12
 TWIST_DEGREE = 6
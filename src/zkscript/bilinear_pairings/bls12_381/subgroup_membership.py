@@ -0,0 +1,317 @@
+"""Bitcoin scripts that perform subgroup membership checks for BLS12-381."""
+
+from tx_engine import Script
+
+from src.zkscript.bilinear_pairings.bls12_381.fields import fq2_script
+from src.zkscript.bilinear_pairings.bls12_381.parameters import PHI_X, PSI_X, PSI_Y, a, b, twisted_a, u
+from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
+from src.zkscript.elliptic_curves.ec_operations_fq2 import EllipticCurveFq2
+from src.zkscript.fields.fq2 import Fq2
+from src.zkscript.script_types.stack_elements import StackEllipticCurvePoint, StackFiniteFieldElement
+from src.zkscript.util.utility_functions import bitmask_to_boolean_list
+from src.zkscript.util.utility_scripts import (
+    bool_to_moving_function,
+    mod,
+    move,
+    nums_to_script,
+    pick,
+    roll,
+    verify_bottom_constant,
+)
+
+
+class SubgroupMembership:
+    """Subgroup membership checks for BLS12-381.
+
+    Attributes:
+        modulus (int): The characteristic of the field F_q.
+        fq2 (Fq2): Bitcoin script instance to perform arithmetic operations in F_q^2.
+        ec_fq (EllipticCurveFq): Bitcoin script instance to perform arithmetic operations on the curve.
+        ec_fq2 (EllipticCurveFq2): Bitcoin script instance to perform arithmetic operations on the twisted curve.
+        u (int): The seed of the curve.
+        phi_x (int): The coefficient multiplying the x-coordinate in the endomorphism phi.
+        psi_x (list[int]): The coefficient multiplying the conjugate of the x-coordinate in the endomorphism psi.
+        psi_y (list[int]): The coefficient multiplying the conjugate of the y-coordinate in the endomorphism psi.
+    """
+
+    def __init__(self, fq2: Fq2, u: int, phi_x: int, psi_x: list[int], psi_y: list[int]):
+        """Initialise subgroup membership checks for BLS12-381.
+
+        Args:
+            fq2 (Fq2): Bitcoin script instance to perform arithmetic operations in F_q^2.
+            u (int): The seed of the curve.
+            phi_x (int): The coefficient multiplying the x-coordinate in the endomorphism phi.
+            psi_x (list[int]): The coefficient multiplying the conjugate of the x-coordinate in the endomorphism psi.
+            psi_y (list[int]): The coefficient multiplying the conjugate of the y-coordinate in the endomorphism psi.
+        """
+        self.modulus = fq2.modulus
+        self.fq2 = fq2
+        self.ec_fq = EllipticCurveFq(q=fq2.modulus, curve_a=a, curve_b=b)
+        self.ec_fq2 = EllipticCurveFq2(q=fq2.modulus, curve_a=twisted_a, fq2=fq2)
+        self.u = u
+        self.phi_x = phi_x
+        self.psi_x = psi_x
+        self.psi_y = psi_y
+
+    def __multiplication_of_negated_point(
+        self, ec: EllipticCurveFq | EllipticCurveFq2, extension_degree: int, scalar: int
+    ) -> Script:
+        """Compute [scalar](-P) with double-and-add, using the gradients provided in the unlocking script.
+
+        Stack input:
+            - stack:    [q, ..., gradients, P], `P` is a point on the curve over F_q^extension_degree
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., P, [scalar](-P)]
+            - altstack: []
+
+        Args:
+            ec (EllipticCurveFq | EllipticCurveFq2): Bitcoin script instance to perform arithmetic operations on the
+                curve to which `P` belongs.
+            extension_degree (int): The extension degree of the field over which the curve is defined.
+            scalar (int): The positive scalar by which `-P` is multiplied.
+
+        Returns:
+            Script to compute [scalar](-P).
+
+        Preconditions:
+            - The gradients are computed as follows, with `exp` the binary expansion of `scalar` from MSB to LSB:
+                gradients = []
+                T = -P
+                for bit in exp[1:]:
+                    gradients.append(T.gradient(T))
+                    T = T + T
+                    if bit == 1:
+                        gradients.append(T.gradient(-P))
+                        T = T + (-P)
+                and they are loaded on the stack in reverse order, i.e., `gradients[0]` is right below `P`.
+        """
+        n = extension_degree
+        T = StackEllipticCurvePoint(
+            StackFiniteFieldElement(2 * n - 1, False, n),
+            StackFiniteFieldElement(n - 1, False, n),
+        )
+        minus_P = StackEllipticCurvePoint(
+            StackFiniteFieldElement(4 * n - 1, False, n),
+            StackFiniteFieldElement(3 * n - 1, True, n),
+        )
+
+        out = Script()
+        is_first_step = True
+        for bit in [int(bit) for bit in bin(scalar)[3:]]:
+            if is_first_step:
+                # stack in:  [q, ..., gradients, gradient, P]
+                # stack out: [q, ..., gradients, P, 2*(-P)]
+                out += ec.point_algebraic_doubling(
+                    take_modulo=True,
+                    check_constant=False,
+                    clean_constant=False,
+                    verify_gradient=True,
+                    positive_modulo=True,
+                    gradient=StackFiniteFieldElement(3 * n - 1, False, n),
+                    P=T.set_negate(True),
+                    rolling_option=1,
+                )
+                is_first_step = False
+            else:
+                # stack in:  [q, ..., gradients, gradient, P, T]
+                # stack out: [q, ..., gradients, P, 2T]
+                out += ec.point_algebraic_doubling(
+                    take_modulo=True,
+                    check_constant=False,
+                    clean_constant=False,
+                    verify_gradient=True,
+                    positive_modulo=True,
+                    gradient=StackFiniteFieldElement(5 * n - 1, False, n),
+                    P=T,
+                    rolling_option=3,
+                )
+            if bit == 1:
+                # stack in:  [q, ..., gradients, gradient, P, T]
+                # stack out: [q, ..., gradients, P, T - P]
+                out += ec.point_algebraic_addition(
+                    take_modulo=True,
+                    check_constant=False,
+                    clean_constant=False,
+                    verify_gradient=True,
+                    positive_modulo=True,
+                    gradient=StackFiniteFieldElement(5 * n - 1, False, n),
+                    P=minus_P,
+                    Q=T,
+                    rolling_option=5,
+                )
+
+        return out
+
+    def g1_in_subgroup(
+        self,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        rolling_option: int = 1,
+    ) -> Script:
+        """Check that a point on the curve belongs to G1.
+
+        The check is the one from https://eprint.iacr.org/2021/1130.pdf: a point `P` in E(F_q) belongs to G1 if
+        and only if phi(P) = [-u^2]P, where phi(x,y) = (phi_x * x, y) and `phi_x` is a primitive cube root of
+        unity. The multiplication [-u^2]P is computed with a double-and-add over the bits of u^2 starting from -P,
+        using the gradients provided in the unlocking script.
+
+        Stack input:
+            - stack:    [q, ..., gradients, P], `P` is a point in E(F_q), `gradients` are the gradients required
+                to compute [-u^2]P
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., {P}, 0/1]
+            - altstack: []
+
+        where {P} means that the element is there if it is picked, it is not there if it is rolled.
+
+        Args:
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            rolling_option (int): If `1`, `P` is rolled, if `0` it is picked. Defaults to `1`.
+
+        Returns:
+            Script to check that `P` belongs to G1.
+
+        Preconditions:
+            - `P` is on the curve and is not the point at infinity.
+            - The gradients are computed as follows, with `exp` the binary expansion of u^2 from MSB to LSB:
+                gradients = []
+                T = -P
+                for bit in exp[1:]:
+                    gradients.append(T.gradient(T))
+                    T = T + T
+                    if bit == 1:
+                        gradients.append(T.gradient(-P))
+                        T = T + (-P)
+                and they are loaded on the stack in reverse order, i.e., `gradients[0]` is right below `P`.
+
+        Notes:
+            If `P` is not in G1, the script fails or leaves `0` on top of the stack.
+        """
+        is_p_rolled = bitmask_to_boolean_list(rolling_option, 1)[0]
+
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        # stack in:  [q, ..., gradients, P]
+        # stack out: [q, ..., P, [-u^2]P]
+        out += self.__multiplication_of_negated_point(ec=self.ec_fq, extension_degree=1, scalar=self.u**2)
+
+        # Compute phi(P)
+        # stack in:  [q, ..., P, [-u^2]P]
+        # stack out: [q, ..., {P}, [-u^2]P, yP, phi(P)_x]
+        out += move(
+            StackEllipticCurvePoint(StackFiniteFieldElement(3, False, 1), StackFiniteFieldElement(2, False, 1)),
+            bool_to_moving_function(is_p_rolled),
+        )
+        out += Script.parse_string("OP_SWAP")
+        out += nums_to_script([self.phi_x])
+        out += Script.parse_string("OP_MUL")
+        out += roll(position=-1, n_elements=1) if clean_constant else pick(position=-1, n_elements=1)
+        out += mod(stack_preparation="", is_positive=True, is_constant_reused=False)
+
+        # Check phi(P) == [-u^2]P
+        # stack in:  [q, ..., {P}, [-u^2]P, yP, phi(P)_x]
+        # stack out: [q, ..., {P}, 0/1]
+        out += Script.parse_string("OP_SWAP OP_ROT OP_EQUALVERIFY OP_EQUAL")
+
+        return out
+
+    def g2_in_subgroup(
+        self,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        rolling_option: int = 1,
+    ) -> Script:
+        """Check that a point on the twisted curve belongs to G2.
+
+        The check is the one from https://eprint.iacr.org/2022/352.pdf: a point `Q` in E'(F_q^2) belongs to G2 if
+        and only if psi(Q) = [u]Q, where psi is the untwist-Frobenius-twist endomorphism. The multiplication [u]Q
+        is computed with a double-and-add over the bits of |u| starting from -Q, using the gradients provided in the
+        unlocking script.
+
+        Stack input:
+            - stack:    [q, ..., gradients, Q], `Q` is a point in E'(F_q^2), `gradients` are the gradients required
+                to compute [u]Q
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., {Q}, 0/1]
+            - altstack: []
+
+        where {Q} means that the element is there if it is picked, it is not there if it is rolled.
+
+        Args:
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            rolling_option (int): If `1`, `Q` is rolled, if `0` it is picked. Defaults to `1`.
+
+        Returns:
+            Script to check that `Q` belongs to G2.
+
+        Preconditions:
+            - `Q` is on the twisted curve and is not the point at infinity.
+            - The gradients are computed as follows, with `exp_u` the binary expansion of |u| from MSB to LSB:
+                gradients = []
+                T = -Q
+                for bit in exp_u[1:]:
+                    gradients.append(T.gradient(T))
+                    T = T + T
+                    if bit == 1:
+                        gradients.append(T.gradient(-Q))
+                        T = T + (-Q)
+                and they are loaded on the stack in reverse order, i.e., `gradients[0]` is right below `Q`.
+
+        Notes:
+            If `Q` is not in G2, the script fails or leaves `0` on top of the stack.
+        """
+        is_q_rolled = bitmask_to_boolean_list(rolling_option, 1)[0]
+
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        # Compute [u]Q = [|u|](-Q), u < 0
+        # stack in:  [q, ..., gradients, Q]
+        # stack out: [q, ..., Q, [u]Q]
+        out += self.__multiplication_of_negated_point(ec=self.ec_fq2, extension_degree=2, scalar=abs(self.u))
+
+        # Compute psi(Q)
+        # stack in:  [q, ..., Q, [u]Q]
+        # stack out: [q, ..., {Q}, [u]Q, psi(Q)]
+        out += move(
+            StackEllipticCurvePoint(StackFiniteFieldElement(7, False, 2), StackFiniteFieldElement(5, False, 2)),
+            bool_to_moving_function(is_q_rolled),
+        )  # Move Q on top
+        out += Script.parse_string("OP_2SWAP")  # Swap xQ and yQ
+        out += self.fq2.conjugate(take_modulo=False, check_constant=False, clean_constant=False)
+        out += nums_to_script(self.psi_x)
+        out += self.fq2.mul(
+            take_modulo=True,
+            positive_modulo=True,
+            check_constant=False,
+            clean_constant=False,
+            is_constant_reused=False,
+        )
+        out += Script.parse_string("OP_2SWAP")  # Swap yQ and psi(Q)_x
+        out += self.fq2.conjugate(take_modulo=False, check_constant=False, clean_constant=False)
+        out += nums_to_script(self.psi_y)
+        out += self.fq2.mul(
+            take_modulo=True,
+            positive_modulo=True,
+            check_constant=False,
+            clean_constant=clean_constant,
+            is_constant_reused=False,
+        )
+
+        # Check psi(Q) == [u]Q
+        # stack in:  [q, ..., {Q}, [u]Q, psi(Q)]
+        # stack out: [q, ..., {Q}, 0/1]
+        out += Script.parse_string("OP_4 OP_ROLL OP_EQUALVERIFY OP_3 OP_ROLL OP_EQUALVERIFY OP_ROT OP_EQUALVERIFY")
+        out += Script.parse_string("OP_EQUAL")
+
+        return out
+
+
+subgroup_membership = SubgroupMembership(fq2=fq2_script, u=u, phi_x=PHI_X, psi_x=PSI_X, psi_y=PSI_Y)
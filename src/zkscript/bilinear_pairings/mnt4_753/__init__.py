@@ -9,4 +9,5 @@ Modules:
     - miller_output_operations: Operations between Miller output (of type Fq4) and line evaluations.
     - bls12_381: Build pairing model for MNT4-753.
     - parameters: MNT4-753 curve parameters.
+    - subgroup_membership: Subgroup membership checks for MNT4-753.
 """
@@ -93,8 +93,7 @@ class FinalExponentiation(CyclotomicExponentiation):
         check_f_inverse += self.fq4.mul(
             take_modulo=True, positive_modulo=True, check_constant=False, clean_constant=False, is_constant_reused=False
         )  # Multiply
-        check_f_inverse += Script.parse_string(" ".join(["OP_0", "OP_EQUALVERIFY"] * 3))
-        check_f_inverse += Script.parse_string("OP_1 OP_EQUALVERIFY")
+        check_f_inverse += self.fq4.is_one(is_verify=True)
 
         # After this, the stack is: Inverse(f) Conjugate(f)
         easy_exponentiation = self.fq4.frobenius_even(
@@ -14,6 +14,7 @@ from src.zkscript.bilinear_pairings.mnt4_753.parameters import (
     N_POINTS_CURVE,
     N_POINTS_TWIST,
     exp_miller_loop,
+    is_miller_loop_parameter_negative,
     q,
     twisted_a,
 )
@@ -60,4 +61,5 @@ mnt4_753 = PairingModel(
     easy_exponentiation_with_inverse_check=final_exponentiation.easy_exponentiation_with_inverse_check,
     hard_exponentiation=final_exponentiation.hard_exponentiation,
     size_estimation_miller_loop=size_estimation_miller_loop,
+    is_miller_loop_parameter_negative=is_miller_loop_parameter_negative,
 )
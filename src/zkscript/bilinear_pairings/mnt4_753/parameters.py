@@ -1,389 +1,14 @@
 """Curve parameters for MNT4-753."""
 
+from src.zkscript.util.utility_functions import naf_expansion
+
 # Seed
 u = -0x15474B1D641A3FD86DCBCEE5DCDA7FE51852C8CBE26E600733B714AA43C31A66B0344C4E2C428B07A7713041BA18000
 
-# Signed base two decomposition of abs(u) - MSB to LSB (minus_exp_u is LSB to MSB)
-minus_exp_miller_loop = [
-    1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    0,
-    1,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    1,
-    0,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    0,
-    0,
-    0,
-    1,
-    0,
-    0,
-    -1,
-    0,
-    0,
-    -1,
-    0,
-    1,
-    0,
-    0,
-    0,
-    1,
-    0,
-    -1,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-    0,
-][::-1]
-exp_miller_loop = [-el for el in minus_exp_miller_loop]
+# Non-adjacent form of u - LSB to MSB
+exp_miller_loop = naf_expansion(u)
+# The Miller loop parameter u is negative: its sign is absorbed in exp_miller_loop
+is_miller_loop_parameter_negative = u < 0
 
 # Modulus
 q = u**2 + u + 1
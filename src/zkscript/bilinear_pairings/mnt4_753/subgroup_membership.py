@@ -0,0 +1,73 @@
+"""Bitcoin scripts that perform subgroup membership checks for MNT4-753."""
+
+from tx_engine import Script
+
+from src.zkscript.bilinear_pairings.mnt4_753.parameters import N_POINTS_CURVE, q
+from src.zkscript.util.utility_functions import bitmask_to_boolean_list
+from src.zkscript.util.utility_scripts import roll, verify_bottom_constant
+
+
+class SubgroupMembership:
+    """Subgroup membership checks for MNT4-753.
+
+    Attributes:
+        modulus (int): The characteristic of the field F_q.
+    """
+
+    def __init__(self, modulus: int):
+        """Initialise subgroup membership checks for MNT4-753.
+
+        Args:
+            modulus (int): The characteristic of the field F_q.
+        """
+        self.modulus = modulus
+
+    def g1_in_subgroup(
+        self,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        rolling_option: int = 1,
+    ) -> Script:
+        """Check that a point on the curve belongs to G1.
+
+        The group E(F_q) of MNT4-753 has prime order r = q + 1 - t (the cofactor of G1 is `1`), so every point on
+        the curve belongs to G1 and the check requires no computation.
+
+        Stack input:
+            - stack:    [q, ..., P], `P` is a point in E(F_q)
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., {P}, 1]
+            - altstack: []
+
+        where {P} means that the element is there if it is picked, it is not there if it is rolled.
+
+        Args:
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            rolling_option (int): If `1`, `P` is rolled, if `0` it is picked. Defaults to `1`.
+
+        Returns:
+            Script to check that `P` belongs to G1.
+
+        Preconditions:
+            - `P` is on the curve and is not the point at infinity.
+
+        Notes:
+            The check requires no gradients: the unlocking script only contains `P`.
+        """
+        is_p_rolled = bitmask_to_boolean_list(rolling_option, 1)[0]
+
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+        if is_p_rolled:
+            out += Script.parse_string(" ".join(["OP_DROP"] * N_POINTS_CURVE))
+        if clean_constant:
+            out += roll(position=-1, n_elements=1)
+            out += Script.parse_string("OP_DROP")
+        out += Script.parse_string("OP_1")
+
+        return out
+
+
+subgroup_membership = SubgroupMembership(modulus=q)
@@ -7,5 +7,6 @@ Modules:
     - miller_loop.
     - model_definition.
     - pairing.
+    - subgroup_membership.
     - triple_miller_loop.
 """
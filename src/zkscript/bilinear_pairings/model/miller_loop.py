@@ -6,7 +6,7 @@ from tx_engine import Script
 
 from src.zkscript.script_types.stack_elements import StackEllipticCurvePoint, StackFiniteFieldElement
 from src.zkscript.util.utility_functions import boolean_list_to_bitmask, optimise_script
-from src.zkscript.util.utility_scripts import move, pick, roll, verify_bottom_constant
+from src.zkscript.util.utility_scripts import mod, move, pick, roll, verify_bottom_constant
 
 
 class MillerLoop:
@@ -305,7 +305,166 @@ class MillerLoop:
                 assumed to be in Fq)
         """
         out = verify_bottom_constant(self.modulus) if check_constant else Script()
+        out += self.__initialise_miller_loop()
+        out += self.__miller_loop_steps(
+            first_step=len(self.exp_miller_loop) - 2,
+            last_step=0,
+            modulo_threshold=modulo_threshold,
+            positive_modulo=positive_modulo,
+            verify_gradients=verify_gradients,
+            clean_constant=clean_constant,
+        )
+        out += self.__finalise_miller_loop()
+
+        return optimise_script(out)
+
+    def save_accumulator(
+        self,
+        step: int,
+        modulo_threshold: int,
+        verify_gradients: bool = True,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+    ) -> Script:
+        """Evaluation of the first part of the Miller loop at points `P` and `Q`, up to `step` included.
+
+        The Miller loop iterates over the digits of `exp_miller_loop` from `len(exp_miller_loop) - 2` down to `0`. This
+        script performs the iterations from `len(exp_miller_loop) - 2` down to `step`, and returns the accumulator of
+        the loop, i.e., the partial multiple `T` of `Q` and the partial Miller output `f`, reduced modulo `q`. The
+        Miller loop can then be resumed from the accumulator with `resume_from_accumulator(step)`, for instance in a
+        subsequent transaction if the Miller loop does not fit in a single script.
+
+        Stack input:
+            - stack:    [q, ..., gradients, P, Q], `P` is a point on E(F_q), `Q` is a point on E'(F_q^{k/d}),
+                `gradients` is the sequence of gradients required by the iterations up to `step`
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., P, Q, T, f], `T` is a point on E'(F_q^{k/d}), `f` is in F_q^k, and both have
+                coordinates in `[0, q)`
+            - altstack: []
+
+        Args:
+            step (int): The last iteration performed by the script. Must satisfy
+                `1 <= step <= len(exp_miller_loop) - 3`.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            verify_gradients (bool): If `True` the validity of the gradients used for the Miller loop is verified.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+
+        Returns:
+            Script to evaluate the first part of the Miller loop at points `P` and `Q`.
+
+        Raises:
+            ValueError: If `step` is not in `[1, len(exp_miller_loop) - 3]`.
+
+        Preconditions:
+            `P` and `Q` are not the point at infinity.
+        """
+        self.__check_accumulator_step(step)
+
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+        out += self.__initialise_miller_loop()
+        out += self.__miller_loop_steps(
+            first_step=len(self.exp_miller_loop) - 2,
+            last_step=step,
+            modulo_threshold=modulo_threshold,
+            positive_modulo=False,
+            verify_gradients=verify_gradients,
+            clean_constant=False,
+        )
+        # stack in:  [P, Q, T, f]
+        # stack out: [P, Q, T % q, f % q]
+        n_elements = self.N_POINTS_TWIST + self.N_ELEMENTS_MILLER_OUTPUT
+        out += Script.parse_string(" ".join(["OP_TOALTSTACK"] * (n_elements - 1)))
+        out += roll(position=-1, n_elements=1) if clean_constant else pick(position=-1, n_elements=1)
+        out += mod(stack_preparation="", is_positive=True)
+        for _ in range(n_elements - 2):
+            out += mod(stack_preparation="OP_FROMALTSTACK OP_ROT", is_positive=True)
+        out += mod(stack_preparation="OP_FROMALTSTACK OP_ROT", is_positive=True, is_constant_reused=False)
+
+        return optimise_script(out)
+
+    def resume_from_accumulator(
+        self,
+        step: int,
+        modulo_threshold: int,
+        positive_modulo: bool = True,
+        verify_gradients: bool = True,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+    ) -> Script:
+        """Evaluation of the second part of the Miller loop at points `P` and `Q`, from the accumulator after `step`.
+
+        This script performs the iterations of the Miller loop from `step - 1` down to `0`, starting from the
+        accumulator `(T, f)` returned by `save_accumulator(step)`. The script `save_accumulator(step)` followed by
+        `resume_from_accumulator(step)` computes the same output as `miller_loop`.
+
+        Stack input:
+            - stack:    [q, ..., gradients, P, Q, T, f], `P` is a point on E(F_q), `Q` is a point on E'(F_q^{k/d}),
+                `T` and `f` are the accumulator returned by `save_accumulator(step)`, `gradients` is the sequence of
+                gradients required by the iterations after `step`
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., wQ, miller(P,Q)], `miller(P,Q) = f_(w,Q)(P)` is in F_q^k
+            - altstack: []
+
+        Args:
+            step (int): The last iteration performed by `save_accumulator`. Must satisfy
+                `1 <= step <= len(exp_miller_loop) - 3`.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+            verify_gradients (bool): If `True` the validity of the gradients used for the Miller loop is verified.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+
+        Returns:
+            Script to evaluate the second part of the Miller loop at points `P` and `Q`.
+
+        Raises:
+            ValueError: If `step` is not in `[1, len(exp_miller_loop) - 3]`.
+
+        Preconditions:
+            The coordinates of `T` and `f` are in `[0, q)`.
+        """
+        self.__check_accumulator_step(step)
+
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+        out += self.__miller_loop_steps(
+            first_step=step - 1,
+            last_step=0,
+            modulo_threshold=modulo_threshold,
+            positive_modulo=positive_modulo,
+            verify_gradients=verify_gradients,
+            clean_constant=clean_constant,
+        )
+        out += self.__finalise_miller_loop()
+
+        return optimise_script(out)
+
+    def __check_accumulator_step(self, step: int) -> None:
+        """Check that the Miller loop can be split after `step`.
 
+        The accumulator is only an element of F_q^k once the first two iterations have been performed, and the
+        second part of the Miller loop must perform at least one iteration.
+        """
+        if not 1 <= step <= len(self.exp_miller_loop) - 3:
+            msg = f"The step must be between 1 and {len(self.exp_miller_loop) - 3}: step = {step}"
+            raise ValueError(msg)
+
+    def __initialise_miller_loop(self) -> Script:
+        """Generate the script to initialise the point `T` used to compute `w*Q` in the Miller loop.
+
+        Stack input:
+            - stack:    [q, ..., P, Q]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., P, Q, T], where `T = Q` (or `T = -Q` if the Miller loop parameter is negative)
+            - altstack: []
+        """
+        out = Script()
         # stack in:  [P, Q]
         # stack out: [P, Q, T]
         for j in range(self.N_POINTS_TWIST):
@@ -313,6 +472,33 @@ class MillerLoop:
             if self.exp_miller_loop[-1] == -1 and j >= self.N_POINTS_TWIST // 2:
                 out += Script.parse_string("OP_NEGATE")
 
+        return out
+
+    def __miller_loop_steps(
+        self,
+        first_step: int,
+        last_step: int,
+        modulo_threshold: int,
+        positive_modulo: bool,
+        verify_gradients: bool,
+        clean_constant: bool | None,
+    ) -> Script:
+        """Generate the script to perform the steps `first_step, first_step - 1, ..., last_step` of the Miller loop.
+
+        Stack input:
+            - stack:    [q, ..., gradients, P, Q, T, {f}], where `f` is only present if `first_step` is not the first
+                step of the Miller loop
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., P, Q, T', f'], where `T'` and `f'` are the values of `T` and `f` after `last_step`
+            - altstack: []
+
+        Notes:
+            The sizes of `T` and `f` at `first_step` are assumed to be at most the size of `q`.
+        """
+        out = Script()
+
         BIT_SIZE_Q = ceil(log2(self.modulus))
         size_point_multiplication = BIT_SIZE_Q
         size_miller_output = BIT_SIZE_Q
@@ -323,26 +509,11 @@ class MillerLoop:
         gradient_doubling = StackFiniteFieldElement(
             2 * self.N_POINTS_TWIST + self.N_POINTS_CURVE + self.extension_degree - 1, False, self.extension_degree
         )
-        P = StackEllipticCurvePoint(
-            StackFiniteFieldElement(2 * self.N_POINTS_TWIST + self.N_POINTS_CURVE - 1, False, self.N_POINTS_CURVE // 2),
-            StackFiniteFieldElement(
-                2 * self.N_POINTS_TWIST + self.N_POINTS_CURVE // 2 - 1, False, self.N_POINTS_CURVE // 2
-            ),
-        )
-        Q = StackEllipticCurvePoint(
-            StackFiniteFieldElement(2 * self.N_POINTS_TWIST - 1, False, self.N_POINTS_TWIST // 2),
-            StackFiniteFieldElement(
-                self.N_POINTS_TWIST + self.N_POINTS_TWIST // 2 - 1, False, self.N_POINTS_TWIST // 2
-            ),
-        )
-        T = StackEllipticCurvePoint(
-            StackFiniteFieldElement(self.N_POINTS_TWIST - 1, False, self.N_POINTS_TWIST // 2),
-            StackFiniteFieldElement(self.N_POINTS_TWIST // 2 - 1, False, self.N_POINTS_TWIST // 2),
-        )
-        # stack in:  [P, Q, T]
-        # stack out: [w*Q, miller(P,Q)]
+        P, Q, T = self.__points_on_stack()
+        # stack in:  [P, Q, T, {f}]
+        # stack out: [P, Q, T', f']
         gradient_tracker = 0
-        for i in range(len(self.exp_miller_loop) - 2, -1, -1):
+        for i in range(first_step, last_step - 1, -1):
             positive_modulo_i = positive_modulo if i == 0 else False
             clean_constant_i = clean_constant if i == 0 else False
 
@@ -427,10 +598,43 @@ class MillerLoop:
                     T=T,
                 )
                 gradient_tracker += 2 * self.extension_degree if not verify_gradients else 0
-        # stack in:  [P, Q, w*Q, miller(P,Q)]
-        # stack out: [w*Q, miller(P,Q)]
+
+        return out
+
+    def __points_on_stack(self) -> tuple[StackEllipticCurvePoint, StackEllipticCurvePoint, StackEllipticCurvePoint]:
+        """Return the positions of the points `P`, `Q` and `T` in the stack `[..., P, Q, T]`."""
+        P = StackEllipticCurvePoint(
+            StackFiniteFieldElement(2 * self.N_POINTS_TWIST + self.N_POINTS_CURVE - 1, False, self.N_POINTS_CURVE // 2),
+            StackFiniteFieldElement(
+                2 * self.N_POINTS_TWIST + self.N_POINTS_CURVE // 2 - 1, False, self.N_POINTS_CURVE // 2
+            ),
+        )
+        Q = StackEllipticCurvePoint(
+            StackFiniteFieldElement(2 * self.N_POINTS_TWIST - 1, False, self.N_POINTS_TWIST // 2),
+            StackFiniteFieldElement(
+                self.N_POINTS_TWIST + self.N_POINTS_TWIST // 2 - 1, False, self.N_POINTS_TWIST // 2
+            ),
+        )
+        T = StackEllipticCurvePoint(
+            StackFiniteFieldElement(self.N_POINTS_TWIST - 1, False, self.N_POINTS_TWIST // 2),
+            StackFiniteFieldElement(self.N_POINTS_TWIST // 2 - 1, False, self.N_POINTS_TWIST // 2),
+        )
+        return P, Q, T
+
+    def __finalise_miller_loop(self) -> Script:
+        """Generate the script to remove the points `P` and `Q` from the stack at the end of the Miller loop.
+
+        Stack input:
+            - stack:    [q, ..., P, Q, w*Q, miller(P,Q)]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., w*Q, miller(P,Q)]
+            - altstack: []
+        """
+        P, Q, _ = self.__points_on_stack()
+        out = Script()
         out += move(Q.shift(self.N_ELEMENTS_MILLER_OUTPUT), roll)  # Roll Q
         out += move(P.shift(self.N_ELEMENTS_MILLER_OUTPUT), roll)  # Roll P
         out += Script.parse_string(" ".join(["OP_DROP"] * (self.N_POINTS_TWIST + self.N_POINTS_CURVE)))
-
-        return optimise_script(out)
+        return out
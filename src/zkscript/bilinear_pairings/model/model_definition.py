@@ -45,12 +45,15 @@ class PairingModel(MillerLoop, TripleMillerLoop, TripleMillerLoopProj, Pairing):
         easy_exponentiation_with_inverse_check,
         hard_exponentiation,
         size_estimation_miller_loop,
+        is_miller_loop_parameter_negative=False,
     ):
         """Initialise the pairing model.
 
         Args:
             q: Characteristic of the field over which the pairing is defined.
-            exp_miller_loop: Expansion of value over which the Miller loop is carried out.
+            exp_miller_loop: Signed base two expansion (LSB to MSB) of the value over which the Miller loop is carried
+                out. Digits equal to `-1` are handled by adding `-Q` (e.g., if `exp_miller_loop` is the non-adjacent
+                form of the value, see `naf_expansion`).
             extension_degree: Extension degree.
             n_points_curve: Number of integers needed to define a point on the base curve.
             n_points_twist: Number of integers needed to define a point on the twisted curve.
@@ -102,9 +105,26 @@ class PairingModel(MillerLoop, TripleMillerLoop, TripleMillerLoopProj, Pairing):
             hard_exponentiation: Script to compute hard exponentiation.
             size_estimation_miller_loop: function to estimate the size of the elements computed while executing
                 the Miller loop.
+            is_miller_loop_parameter_negative: Whether the value over which the Miller loop is carried out is
+                negative. Defaults to `False`.
+
+        Raises:
+            ValueError: If the sign of the most significant digit of `exp_miller_loop` does not match
+                `is_miller_loop_parameter_negative`.
+
+        Notes:
+            A negative Miller loop parameter `u` is handled through `exp_miller_loop`, which must be the signed
+            expansion of `u` itself (e.g., the expansion of `abs(u)` with every digit negated). The Miller loop then
+            starts from `-Q` and computes `f_{abs(u),-Q}(P)`, which is equal to `f_{u,Q}(P)` up to the final
+            exponentiation. Hence, there is no need to conjugate the output of the Miller loop.
         """
+        if (exp_miller_loop[-1] == -1) != is_miller_loop_parameter_negative:
+            msg = "The sign of the most significant digit of exp_miller_loop must match the sign of the parameter"
+            raise ValueError(msg)
+
         self.modulus = q
         self.exp_miller_loop = exp_miller_loop
+        self.is_miller_loop_parameter_negative = is_miller_loop_parameter_negative
         self.extension_degree = extension_degree
         self.N_POINTS_CURVE = n_points_curve
         self.N_POINTS_TWIST = n_points_twist
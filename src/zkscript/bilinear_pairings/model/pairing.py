@@ -3,7 +3,7 @@
 from tx_engine import Script
 
 from src.zkscript.script_types.stack_elements import StackFiniteFieldElement
-from src.zkscript.util.utility_functions import optimise_script
+from src.zkscript.util.utility_functions import optimise_labelled_scripts, optimise_script
 from src.zkscript.util.utility_scripts import pick, roll, verify_bottom_constant
 
 
@@ -139,7 +139,8 @@ class Pairing:
         is_precomputed_gradients_on_stack: bool = True,
         precomputed_gradients: list[list[list[list[int]]]] | None = None,
         is_miller_loop_proj: bool = False,
-    ) -> Script:
+        debug_labels: bool = False,
+    ) -> Script | tuple[Script, list[tuple[int, str]]]:
         """Product of three bilinear pairings.
 
         Stack input:
@@ -169,9 +170,13 @@ class Pairing:
                     - precomputed_gradients[1]: gradients required to compute w*(-delta)
             is_miller_loop_proj (bool): boolean flag to switch between the projective and non projective implementation
                 of the Miller loop. Default to False.
+            debug_labels (bool): If `True`, also return the list of `(byte_offset, label)` pairs marking the
+                beginning of the Miller loop (`"miller_loop_start"`) and of the final exponentiation (`"final_exp"`).
+                Defaults to `False`.
 
         Returns:
-            Script to compute the product of three bilinear pairings e(P1,Q1) * e(P2,Q2) * e(P3,Q3).
+            Script to compute the product of three bilinear pairings e(P1,Q1) * e(P2,Q2) * e(P3,Q3). If
+            `debug_labels` is `True`, the script is returned together with the list of labels.
 
         Notes:
             At the moment, this function does not handle the case where one of the Pi's or one of the Qi's is the
@@ -187,15 +192,16 @@ class Pairing:
         # After this, the stack is:
         # [miller(P1,Q1) * miller(P2,Q2) * miller(P3,Q3)]^-1
         # [miller(P1,Q1) * miller(P2,Q2) * miller(P3,Q3)]
+        miller_loop = Script()
         if is_miller_loop_proj:
-            out += self.triple_miller_loop_proj(
+            miller_loop += self.triple_miller_loop_proj(
                 modulo_threshold=modulo_threshold, positive_modulo=True, check_constant=False, clean_constant=False
             )
             # There are no gradients on the stack if we use projective coordinates in the Miller loop.
             gradient_tracker = 0
 
         else:
-            out += self.triple_miller_loop(
+            miller_loop += self.triple_miller_loop(
                 modulo_threshold=modulo_threshold,
                 positive_modulo=False,
                 verify_gradients=verify_gradients,
@@ -217,7 +223,7 @@ class Pairing:
                 [1 if i == 0 else 2 for i in self.exp_miller_loop[:-1]]
             )
 
-        out += easy_exponentiation_with_inverse_check(
+        final_exponentiation = easy_exponentiation_with_inverse_check(
             take_modulo=True,
             positive_modulo=False,
             check_constant=False,
@@ -229,7 +235,7 @@ class Pairing:
             f=StackFiniteFieldElement(self.N_ELEMENTS_MILLER_OUTPUT - 1, False, self.N_ELEMENTS_MILLER_OUTPUT),
         )
 
-        out += hard_exponentiation(
+        final_exponentiation += hard_exponentiation(
             take_modulo=True,
             modulo_threshold=modulo_threshold,
             positive_modulo=positive_modulo,
@@ -237,4 +243,9 @@ class Pairing:
             clean_constant=clean_constant,
         )
 
-        return optimise_script(out)
+        if debug_labels:
+            return optimise_labelled_scripts(
+                [(out, []), (miller_loop, [(0, "miller_loop_start")]), (final_exponentiation, [(0, "final_exp")])]
+            )
+
+        return optimise_script(out + miller_loop + final_exponentiation)
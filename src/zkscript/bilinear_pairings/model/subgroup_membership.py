@@ -0,0 +1,44 @@
+"""Interface of the subgroup membership checks of the curves over which pairings are computed."""
+
+from typing import Protocol
+
+from tx_engine import Script
+
+
+class G1SubgroupMembership(Protocol):
+    """Subgroup membership check for G1, implemented by the curve-specific subgroup membership classes.
+
+    Attributes:
+        modulus (int): The characteristic of the field F_q.
+    """
+
+    modulus: int
+
+    def g1_in_subgroup(
+        self,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        rolling_option: int = 1,
+    ) -> Script:
+        """Check that a point on the curve belongs to G1.
+
+        Stack input:
+            - stack:    [q, ..., gradients, P], `P` is a point in E(F_q), `gradients` are the gradients required
+                by the check of the curve (possibly none)
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., {P}, 0/1]
+            - altstack: []
+
+        where {P} means that the element is there if it is picked, it is not there if it is rolled.
+
+        Args:
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            rolling_option (int): If `1`, `P` is rolled, if `0` it is picked. Defaults to `1`.
+
+        Returns:
+            Script to check that `P` belongs to G1.
+        """
+        ...
@@ -272,7 +272,7 @@ class TripleMillerLoop:
                     - precomputed_gradients[1]: gradients required to compute w*(-delta)
 
         """
-        # stack in:  [gradient_(2*T1), P1, P2, P3, Q1, Q2, Q3, T1, T2, T3, {f_i^2}]
+        # stack in:  [gradient_(2*T1), P1, P2, P3, Q1, Q2, Q3, T1, T2, T3, {f_i}]
         # stack out: [gradient_(2*T1), P1, P2, P3, Q1, Q2, Q3, T1, T2, T3, gradient_(2*T2), gradient_(2*T3), {f_i^2}]
         out = (
             Script()
@@ -285,6 +285,7 @@ class TripleMillerLoop:
             )  # since it is without addition, len(precomputed_gradients[0]) == 1
         if loop_i != len(self.exp_miller_loop) - 2:
             out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * self.N_ELEMENTS_MILLER_OUTPUT))
+            out += self.miller_loop_output_square(take_modulo=False, check_constant=False, clean_constant=False)
 
         shift_injected_gradients = 2 * self.extension_degree
         shift_miller_output = 0 if loop_i == len(self.exp_miller_loop) - 2 else self.N_ELEMENTS_MILLER_OUTPUT
@@ -859,7 +860,7 @@ class TripleMillerLoop:
                     - precomputed_gradients[1]: the two gradients required to compute w*(-delta)
 
         """
-        # stack in:  [gradient_(2* T1 ± Q1), gradient_(2*T1), P1, P2, P3, Q1, Q2, Q3, T1, T2, T3, {f_i^2}]
+        # stack in:  [gradient_(2* T1 ± Q1), gradient_(2*T1), P1, P2, P3, Q1, Q2, Q3, T1, T2, T3, {f_i}]
         # stack out: [gradient_(2* T1 ± Q1), gradient_(2*T1), P1, P2, P3, Q1, Q2, Q3, T1, T2, T3,
         #                gradient_(2* T2 ± Q2), gradient_(2* T3 ± Q3), gradient_(2*T2), gradient_(2*T3), {f_i^2}]
         out = (
@@ -873,6 +874,7 @@ class TripleMillerLoop:
 
         if loop_i != len(self.exp_miller_loop) - 2:
             out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * self.N_ELEMENTS_MILLER_OUTPUT))
+            out += self.miller_loop_output_square(take_modulo=False, check_constant=False, clean_constant=False)
 
         shift_injected_gradients = 4 * self.extension_degree
         shift_miller_output = 0 if loop_i == len(self.exp_miller_loop) - 2 else self.N_ELEMENTS_MILLER_OUTPUT
@@ -1357,9 +1359,11 @@ class TripleMillerLoop:
                 True,
             )
 
-            if loop_i != len(self.exp_miller_loop) - 2:
+            if loop_i != len(self.exp_miller_loop) - 2 and is_precomputed_gradients_on_stack:
                 # stack in:  [P1, P2, P3, Q1, Q2, Q3, T1, T2, T3, f_i]
                 # stack out: [P1, P2, P3, Q1, Q2, Q3, T1, T2, T3, f_i^2]
+                # If the gradients are injected, f_i is squared after the injection, so that the accumulator is
+                # moved to the altstack and back only once per iteration.
                 out += self.miller_loop_output_square(take_modulo=False, check_constant=False, clean_constant=False)
             precomputed_gradient = (
                 None
@@ -1429,10 +1433,12 @@ class TripleMillerLoop:
 
         # stack in:  [P1, P2, P3, Q1, Q2, Q3, w*Q1, w*Q2, w*Q3, (miller(P1,Q1) * miller(P2,Q2) * miller(P3,Q3))]
         # stack out: [(miller(P1,Q1) * miller(P2,Q2) * miller(P3,Q3))]
-        out += roll(
-            position=6 * self.N_POINTS_TWIST + 3 * self.N_POINTS_CURVE + self.N_ELEMENTS_MILLER_OUTPUT - 1,
-            n_elements=6 * self.N_POINTS_TWIST + 3 * self.N_POINTS_CURVE,
+        # The accumulator is parked on the altstack while the points below it are dropped
+        n_elements_to_drop = 6 * self.N_POINTS_TWIST + 3 * self.N_POINTS_CURVE
+        out += Script.parse_string(" ".join(["OP_TOALTSTACK"] * self.N_ELEMENTS_MILLER_OUTPUT))
+        out += Script.parse_string(
+            " ".join(["OP_2DROP"] * (n_elements_to_drop // 2) + ["OP_DROP"] * (n_elements_to_drop % 2))
         )
-        out += Script.parse_string(" ".join(["OP_DROP"] * (6 * self.N_POINTS_TWIST + 3 * self.N_POINTS_CURVE)))
+        out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * self.N_ELEMENTS_MILLER_OUTPUT))
 
         return optimise_script(out)
@@ -0,0 +1,99 @@
+"""Registry of the curves supported by the library, to select their parameters by name."""
+
+from dataclasses import dataclass
+
+from tx_engine.engine.util import GROUP_ORDER_INT, PRIME_INT
+
+from src.zkscript.bilinear_pairings.bls12_381 import parameters as bls12_381_parameters
+from src.zkscript.bilinear_pairings.mnt4_753 import parameters as mnt4_753_parameters
+from src.zkscript.elliptic_curves.edwards25519 import parameters as edwards25519_parameters
+
+
+@dataclass(frozen=True)
+class CurveParameters:
+    """Parameters of an elliptic curve.
+
+    Attributes:
+        name (str): The name of the curve.
+        form (str): The form of the equation of the curve: `"short_weierstrass"` for `y^2 = x^3 + a*x + b`,
+            `"twisted_edwards"` for `a*x^2 + y^2 = 1 + b*x^2*y^2`.
+        modulus (int): The prime over which the curve is defined.
+        group_order (int): The order of the subgroup used by the library (e.g., the order of the subgroup generated
+            by the base point, or the r-torsion for pairing-friendly curves).
+        a (int): The coefficient `a` of the equation of the curve.
+        b (int): The coefficient `b` of the equation of the curve (`d` for twisted Edwards curves).
+    """
+
+    name: str
+    form: str
+    modulus: int
+    group_order: int
+    a: int
+    b: int
+
+    def field_byte_width(self) -> int:
+        """Return the number of bytes of the fixed-width encoding of an element of the base field.
+
+        Witness builders must pad the field elements to this width (e.g., 32 bytes for secp256k1, 48 for BLS12-381,
+        95 for MNT4-753). Elements of extension fields are encoded coefficient-wise, each coefficient with this width.
+        """
+        return (self.modulus.bit_length() + 7) // 8
+
+
+CURVES: dict[str, CurveParameters] = {
+    curve.name: curve
+    for curve in [
+        CurveParameters(
+            name="secp256k1",
+            form="short_weierstrass",
+            modulus=PRIME_INT,
+            group_order=GROUP_ORDER_INT,
+            a=0,
+            b=7,
+        ),
+        CurveParameters(
+            name="edwards25519",
+            form="twisted_edwards",
+            modulus=edwards25519_parameters.q,
+            group_order=edwards25519_parameters.GROUP_ORDER,
+            a=edwards25519_parameters.a,
+            b=edwards25519_parameters.d,
+        ),
+        CurveParameters(
+            name="bls12_381",
+            form="short_weierstrass",
+            modulus=bls12_381_parameters.q,
+            group_order=bls12_381_parameters.r,
+            a=bls12_381_parameters.a,
+            b=bls12_381_parameters.b,
+        ),
+        CurveParameters(
+            name="mnt4_753",
+            form="short_weierstrass",
+            modulus=mnt4_753_parameters.q,
+            group_order=mnt4_753_parameters.r,
+            a=mnt4_753_parameters.a,
+            b=mnt4_753_parameters.b,
+        ),
+    ]
+}
+
+
+def get_curve(name: str) -> CurveParameters:
+    """Return the parameters of the curve `name`.
+
+    Args:
+        name (str): The name of the curve, case insensitive (e.g., `"secp256k1"` or `"BLS12_381"`). Dashes are
+            accepted in place of underscores (e.g., `"bls12-381"`).
+
+    Returns:
+        The parameters of the curve.
+
+    Raises:
+        ValueError: If the curve is not in `CURVES`.
+    """
+    key = name.lower().replace("-", "_")
+    if key not in CURVES:
+        msg = f"Unknown curve {name}, the supported curves are: {', '.join(CURVES)}"
+        raise ValueError(msg)
+    return CURVES[key]
@@ -966,6 +966,274 @@ class EllipticCurveFq:
 
         return out
 
+    def point_equal(
+        self,
+        is_verify: bool = False,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+    ) -> Script:
+        """Check whether P = Q, where either point can be the point at infinity.
+
+        The point at infinity is the point encoded as (0x00,0x00). Two points are equal if they are both the point at
+        infinity, or if neither is and their coordinates are equal modulo `q`.
+
+        Stack input:
+            - stack    = [q, .., P, Q]
+            - altstack = []
+
+        Stack output:
+            - stack    = [{q}, .., P == Q] ([{q}, ..] or fail if `is_verify`)
+            - altstack = []
+
+        Args:
+            is_verify (bool): If `True`, fail if P != Q instead of leaving the result on the stack. Defaults to
+                `False`.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+
+        Returns:
+            A Bitcoin script that checks whether `P = Q`.
+        """
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        mod_q = mod(
+            stack_preparation="OP_DEPTH OP_1SUB OP_PICK",
+            is_mod_on_top=True,
+            is_constant_reused=False,
+            is_positive=False,
+        )
+
+        # Check if P and Q are the point at infinity
+        # stack in:  [q, .., x_P, y_P, x_Q, y_Q]
+        # stack out: [q, .., x_P, y_P, x_Q, y_Q, (P == O), (Q == O)]
+        out += Script.parse_string("OP_2OVER OP_CAT 0x0000 OP_EQUAL")
+        out += Script.parse_string("OP_2 OP_PICK OP_2 OP_PICK OP_CAT 0x0000 OP_EQUAL")
+
+        # If one of the points is the point at infinity, P = Q if and only if both are
+        out += Script.parse_string("OP_2DUP OP_BOOLOR OP_IF")
+        out += Script.parse_string("OP_NUMEQUAL OP_TOALTSTACK OP_2DROP OP_2DROP OP_FROMALTSTACK")
+        out += Script.parse_string("OP_ELSE")
+
+        # Otherwise, compare the coordinates
+        # stack in:  [q, .., x_P, y_P, x_Q, y_Q, 0, 0]
+        # stack out: [q, .., P == Q]
+        out += Script.parse_string("OP_2DROP OP_ROT OP_SUB")  # Compute y_Q - y_P
+        out += mod_q
+        out += Script.parse_string("OP_NOT OP_ROT OP_ROT OP_SUB")  # Compute x_P - x_Q
+        out += mod_q
+        out += Script.parse_string("OP_NOT OP_BOOLAND")
+        out += Script.parse_string("OP_ENDIF")
+
+        if is_verify:
+            out += Script.parse_string("OP_VERIFY")
+
+        out += roll(position=-1, n_elements=1) + Script.parse_string("OP_DROP") if clean_constant else Script()
+
+        return out
+
+    def verify_point_addition(
+        self,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+    ) -> Script:
+        """Verify that R = P + Q using the gradient supplied in the unlocking script.
+
+        Instead of computing P + Q, the script verifies the gradient `lambda` of the line through P and Q
+        (tangent to the curve at P if P = Q), and then checks that:
+            x_R = lambda^2 - x_P - x_Q
+            y_R = lambda * (x_P - x_R) - y_P
+        If P = -Q, the script checks that R is the point at infinity.
+
+        Stack input:
+            - stack    = [q, .., gradient, P, Q, R]
+            - altstack = []
+
+        Stack output:
+            - stack    = [{q}, ..] or fail
+            - altstack = []
+
+        Args:
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+
+        Returns:
+            A Bitcoin script that verifies that `R = P + Q`, handling the cases P != Q, P = Q and P = -Q.
+
+        Preconditions:
+            - P and Q are points on E(F_q), different from the point at infinity
+            - If P != -Q, then gradient is the gradient of the line through P and Q (tangent at P if P = Q)
+            - If P = -Q, then do not put gradient, and R is (0x00,0x00)
+
+        Notes:
+            This script is smaller than `point_addition_with_unknown_points` as it verifies the result rather than
+            computing and reducing it.
+        """
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        mod_q = mod(
+            stack_preparation="OP_DEPTH OP_1SUB OP_PICK",
+            is_mod_on_top=True,
+            is_constant_reused=False,
+            is_positive=False,
+        )
+
+        # Check if x_P = x_Q, leave the result on the stack
+        # stack in:  [q, .., {gradient}, x_P, y_P, x_Q, y_Q, x_R, y_R]
+        # stack out: [q, .., {gradient}, x_P, y_P, x_Q, y_Q, x_R, y_R, (x_P == x_Q)]
+        is_x_equal = Script.parse_string("OP_3 OP_PICK OP_6 OP_PICK OP_SUB")  # Compute x_Q - x_P
+        is_x_equal += mod_q
+        is_x_equal += Script.parse_string("OP_NOT")
+
+        # Check if P = -Q
+        out += is_x_equal
+        out += Script.parse_string("OP_3 OP_PICK OP_6 OP_PICK OP_ADD")  # Compute y_Q + y_P
+        out += mod_q
+        out += Script.parse_string("OP_NOT OP_BOOLAND")
+        out += Script.parse_string("OP_IF")
+
+        # If P = -Q, check that R is (0x00,0x00)
+        out += Script.parse_string("0x00 0x00")
+        out += self.point_equal(is_verify=True)
+        out += Script.parse_string("OP_2DROP OP_2DROP")
+
+        out += Script.parse_string("OP_ELSE")
+
+        # Validate gradient
+        # stack in:  [q, .., gradient, x_P, y_P, x_Q, y_Q, x_R, y_R]
+        # stack out: [q, .., gradient, x_P, y_P, x_Q, y_Q, x_R, y_R] or fail
+        out += is_x_equal
+        # If P = Q, compute 2 * gradient * y_P - (3 * x_P^2 + a)
+        out += Script.parse_string("OP_IF")
+        out += Script.parse_string("OP_4 OP_PICK OP_2 OP_MUL")  # Compute 2 * y_P
+        out += Script.parse_string("OP_7 OP_PICK OP_MUL")  # Compute 2 * gradient * y_P
+        out += Script.parse_string("OP_6 OP_PICK OP_DUP OP_MUL OP_3 OP_MUL")  # Compute 3 * x_P^2
+        if self.curve_a != 0:
+            out += nums_to_script([self.curve_a]) + Script.parse_string("OP_ADD")  # Compute 3 * x_P^2 + a
+        out += Script.parse_string("OP_SUB")
+        # If P != Q, compute gradient * (x_Q - x_P) - (y_Q - y_P)
+        out += Script.parse_string("OP_ELSE")
+        out += Script.parse_string("OP_3 OP_PICK OP_6 OP_PICK OP_SUB")  # Compute x_Q - x_P
+        out += Script.parse_string("OP_7 OP_PICK OP_MUL")  # Compute gradient * (x_Q - x_P)
+        out += Script.parse_string("OP_3 OP_PICK OP_SUB OP_5 OP_PICK OP_ADD")  # Subtract y_Q - y_P
+        out += Script.parse_string("OP_ENDIF")
+        out += mod_q
+        out += Script.parse_string("OP_0 OP_EQUALVERIFY")
+
+        # Verify x_R = gradient^2 - x_P - x_Q
+        # stack in:  [q, .., gradient, x_P, y_P, x_Q, y_Q, x_R, y_R]
+        # stack out: [q, .., gradient, x_P, y_P, x_R, y_R] or fail
+        out += Script.parse_string("OP_ROT OP_DROP")  # Drop y_Q
+        out += Script.parse_string("OP_ROT OP_2 OP_PICK OP_ADD")  # Compute x_Q + x_R
+        out += Script.parse_string("OP_4 OP_PICK OP_ADD")  # Compute x_P + x_Q + x_R
+        out += Script.parse_string("OP_5 OP_PICK OP_DUP OP_MUL OP_SWAP OP_SUB")  # Compute gradient^2 - x_P - x_Q - x_R
+        out += mod_q
+        out += Script.parse_string("OP_0 OP_EQUALVERIFY")
+
+        # Verify y_R = gradient * (x_P - x_R) - y_P
+        # stack in:  [q, .., gradient, x_P, y_P, x_R, y_R]
+        # stack out: [q, ..] or fail
+        out += Script.parse_string("OP_ROT OP_ADD")  # Compute y_P + y_R
+        out += Script.parse_string("OP_SWAP OP_ROT OP_SWAP OP_SUB")  # Compute x_P - x_R
+        out += Script.parse_string("OP_ROT OP_MUL OP_SWAP OP_SUB")  # Compute gradient * (x_P - x_R) - y_P - y_R
+        out += mod_q
+        out += Script.parse_string("OP_0 OP_EQUALVERIFY")
+
+        out += Script.parse_string("OP_ENDIF")
+
+        out += roll(position=-1, n_elements=1) + Script.parse_string("OP_DROP") if clean_constant else Script()
+
+        return out
+
+    def verify_point_doubling(
+        self,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+    ) -> Script:
+        """Verify that R = 2P using the gradient supplied in the unlocking script.
+
+        Instead of computing 2P, the script verifies the gradient `lambda` of the line tangent to the curve at P,
+        i.e., that 2 * y_P * lambda = 3 * x_P^2 + a, and then checks that:
+            x_R = lambda^2 - 2 * x_P
+            y_R = lambda * (x_P - x_R) - y_P
+        If y_P = 0, the script checks that R is the point at infinity.
+
+        Stack input:
+            - stack    = [q, .., gradient, P, R]
+            - altstack = []
+
+        Stack output:
+            - stack    = [{q}, ..] or fail
+            - altstack = []
+
+        Args:
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+
+        Returns:
+            A Bitcoin script that verifies that `R = 2P`, handling the case y_P = 0.
+
+        Preconditions:
+            - P is a point on E(F_q), different from the point at infinity
+            - If y_P != 0, then gradient is the gradient of the line tangent at P
+            - If y_P = 0, then do not put gradient, and R is (0x00,0x00)
+        """
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        mod_q = mod(
+            stack_preparation="OP_DEPTH OP_1SUB OP_PICK",
+            is_mod_on_top=True,
+            is_constant_reused=False,
+            is_positive=False,
+        )
+
+        # Check if y_P = 0
+        # stack in:  [q, .., {gradient}, x_P, y_P, x_R, y_R]
+        # stack out: [q, .., {gradient}, x_P, y_P, x_R, y_R, (y_P == 0)]
+        out += Script.parse_string("OP_2 OP_PICK")
+        out += mod_q
+        out += Script.parse_string("OP_NOT")
+        out += Script.parse_string("OP_IF")
+
+        # If y_P = 0, check that R is (0x00,0x00)
+        out += Script.parse_string("OP_CAT 0x0000 OP_EQUALVERIFY OP_2DROP")
+
+        out += Script.parse_string("OP_ELSE")
+
+        # Validate gradient: 2 * gradient * y_P - (3 * x_P^2 + a) = 0
+        # stack in:  [q, .., gradient, x_P, y_P, x_R, y_R]
+        # stack out: [q, .., gradient, x_P, y_P, x_R, y_R] or fail
+        out += Script.parse_string("OP_2 OP_PICK OP_2 OP_MUL")  # Compute 2 * y_P
+        out += Script.parse_string("OP_5 OP_PICK OP_MUL")  # Compute 2 * gradient * y_P
+        out += Script.parse_string("OP_4 OP_PICK OP_DUP OP_MUL OP_3 OP_MUL")  # Compute 3 * x_P^2
+        if self.curve_a != 0:
+            out += nums_to_script([self.curve_a]) + Script.parse_string("OP_ADD")  # Compute 3 * x_P^2 + a
+        out += Script.parse_string("OP_SUB")
+        out += mod_q
+        out += Script.parse_string("OP_0 OP_EQUALVERIFY")
+
+        # Verify x_R = gradient^2 - 2 * x_P
+        # stack in:  [q, .., gradient, x_P, y_P, x_R, y_R]
+        # stack out: [q, .., gradient, x_P, y_P, x_R, y_R] or fail
+        out += Script.parse_string("OP_OVER OP_4 OP_PICK OP_2 OP_MUL OP_ADD")  # Compute 2 * x_P + x_R
+        out += Script.parse_string("OP_5 OP_PICK OP_DUP OP_MUL OP_SWAP OP_SUB")  # Compute gradient^2 - 2 * x_P - x_R
+        out += mod_q
+        out += Script.parse_string("OP_0 OP_EQUALVERIFY")
+
+        # Verify y_R = gradient * (x_P - x_R) - y_P
+        # stack in:  [q, .., gradient, x_P, y_P, x_R, y_R]
+        # stack out: [q, ..] or fail
+        out += Script.parse_string("OP_ROT OP_ADD")  # Compute y_P + y_R
+        out += Script.parse_string("OP_SWAP OP_ROT OP_SWAP OP_SUB")  # Compute x_P - x_R
+        out += Script.parse_string("OP_ROT OP_MUL OP_SWAP OP_SUB")  # Compute gradient * (x_P - x_R) - y_P - y_R
+        out += mod_q
+        out += Script.parse_string("OP_0 OP_EQUALVERIFY")
+
+        out += Script.parse_string("OP_ENDIF")
+
+        out += roll(position=-1, n_elements=1) + Script.parse_string("OP_DROP") if clean_constant else Script()
+
+        return out
+
     def multi_addition(
         self,
         n_points_on_stack: int,
@@ -1196,6 +1464,219 @@ class EllipticCurveFq:
 
         return out
 
+    def __known_base_multiples(self, base: list[int], n_bits: int) -> list[list[int]]:
+        """Compute the multiples `[base, 2*base, .., 2^(n_bits-1)*base]` of a point on E(F_q).
+
+        Args:
+            base (list[int]): The coordinates `[x, y]` of the point.
+            n_bits (int): The number of multiples to compute.
+
+        Returns:
+            The list of the coordinates of the multiples `2^i * base`.
+
+        Raises:
+            ValueError: If one of the multiples is the point at infinity.
+        """
+        q = self.modulus
+        multiples = [[base[0] % q, base[1] % q]]
+        for _ in range(n_bits - 1):
+            x, y = multiples[-1]
+            if y == 0:
+                msg = "The multiples of the base must not be the point at infinity"
+                raise ValueError(msg)
+            gradient = (3 * x * x + self.curve_a) * pow(2 * y, -1, q) % q
+            x_doubled = (gradient * gradient - 2 * x) % q
+            multiples.append([x_doubled, (gradient * (x - x_doubled) - y) % q])
+        return multiples
+
+    def multiply_known_base_msb(
+        self,
+        base: list[int],
+        max_multiplier: int,
+        modulo_threshold: int,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        positive_modulo: bool = True,
+    ) -> Script:
+        """Multiply the fixed point `base` with MSB-first double-and-add.
+
+        Stack input:
+            - stack:    [q, ..., marker_a_is_zero, gradient_operations], where `marker_a_is_zero` and
+                `gradient_operations` are as in `self.unrolled_multiplication`
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, ..., a*base]
+            - altstack: []
+
+        Args:
+            base (list[int]): The coordinates `[x, y]` of the point to multiply, hard-coded in the script.
+            max_multiplier (int): The maximum value of the scalar `a`.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+
+        Returns:
+            Script to multiply `base` using double-and-add scalar multiplication.
+
+        Notes:
+            The unlocking script is the one of `EllipticCurveFqUnrolledUnlockingKey` with `load_P=False`.
+        """
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        # stack out: [marker_a_is_zero, gradient_operations, base]
+        out += nums_to_script(base)
+        # stack out: [base, a*base]
+        out += self.unrolled_multiplication(
+            max_multiplier=max_multiplier,
+            modulo_threshold=modulo_threshold,
+            check_constant=False,
+            clean_constant=False,
+            positive_modulo=positive_modulo,
+        )
+        # stack out: [a*base]
+        out += Script.parse_string("OP_2SWAP OP_2DROP")
+
+        out += roll(position=-1, n_elements=1) + Script.parse_string("OP_DROP") if clean_constant else Script()
+
+        return out
+
+    def multiply_known_base_lsb(
+        self,
+        base: list[int],
+        max_multiplier: int,
+        modulo_threshold: int,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        positive_modulo: bool = True,
+    ) -> Script:
+        """Multiply the fixed point `base` with LSB-first add-only multiplication.
+
+        The multiples `2^i * base` are hard-coded in the script, so that no doubling is computed: for every bit `a_i`
+        of the scalar `a`, the script adds `2^i * base` to the accumulator if `a_i = 1`.
+
+        Stack input:
+            - stack:    [q, ..., gradient_(n-1), a_(n-1), ..., gradient_1, a_1, gradient_0, a_0], where
+                `n = log2(max_multiplier) + 1`, `a_i` is `OP_1` if the i-th bit of `a` is set, else `OP_0`, and
+                `gradient_i` is the gradient of the line through `2^i * base` and `(a mod 2^i) * base`. The gradient
+                is omitted if `a_i = 0` or `a mod 2^i = 0`
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, ..., a*base]
+            - altstack: []
+
+        Args:
+            base (list[int]): The coordinates `[x, y]` of the point to multiply, hard-coded in the script.
+            max_multiplier (int): The maximum value of the scalar `a`.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+
+        Returns:
+            Script to multiply `base` by adding its hard-coded multiples.
+
+        Notes:
+            The accumulator is only ever summed with the reduced points `2^i * base`, and the gradients are reduced
+            modulo `q`. Hence, with the notation of `self.unrolled_multiplication`, every addition makes the size
+            of the accumulator grow at most to `log_2(q) + max(current_size, 2 log_2(q)) + 3`.
+        """
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        size_q = ceil(log2(self.modulus))
+        current_size = size_q
+
+        # The accumulator starts as the point at infinity
+        # stack out: [gradient_(n-1), a_(n-1), ..., gradient_0, a_0, 0x00, 0x00]
+        out += Script.parse_string("0x00 0x00")
+
+        # stack in:  [gradient_(n-1), a_(n-1), ..., gradient_0, a_0, T]
+        # stack out: [a*base]
+        for multiple in self.__known_base_multiples(base, int(log2(max_multiplier)) + 1):
+            size_after_operations = size_q + max(current_size, 2 * size_q) + 3
+            if size_after_operations > modulo_threshold:
+                take_modulo = True
+                current_size = size_q
+            else:
+                take_modulo = False
+                current_size = size_after_operations
+
+            # stack in:  [gradient_i, a_i, T]
+            # stack out: [gradient_i, T, a_i]
+            out += roll(position=2, n_elements=1)
+            # stack in:  [gradient_i, T, a_i]
+            # stack out: [T] if a_i = 0, else [T + 2^i * base]
+            out += Script.parse_string("OP_IF")
+            out += nums_to_script(multiple)
+            out += self.point_addition_with_unknown_points(
+                take_modulo=take_modulo, positive_modulo=False, check_constant=False, clean_constant=False
+            )
+            out += Script.parse_string("OP_ENDIF")
+
+        # Reduce the result, unless it is the point at infinity
+        out += Script.parse_string("OP_2DUP OP_CAT 0x0000 OP_EQUAL OP_NOT OP_IF")
+        out += Script.parse_string("OP_TOALTSTACK")
+        out += pick(position=-1, n_elements=1)
+        out += mod(stack_preparation="", is_positive=positive_modulo)
+        out += mod(is_positive=positive_modulo, is_constant_reused=False)
+        out += Script.parse_string("OP_ENDIF")
+
+        out += roll(position=-1, n_elements=1) + Script.parse_string("OP_DROP") if clean_constant else Script()
+
+        return out
+
+    def multiply_known_base_auto(
+        self,
+        base: list[int],
+        max_multiplier: int,
+        modulo_threshold: int,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        positive_modulo: bool = True,
+    ) -> tuple[str, Script]:
+        """Multiply the fixed point `base` with the strategy yielding the smaller script.
+
+        Both `self.multiply_known_base_msb` and `self.multiply_known_base_lsb` are generated, and the shorter one
+        is returned. In case of a tie, the MSB-first strategy is chosen.
+
+        Stack input:
+            - stack:    [q, ..., unlocking data], where the unlocking data is the one required by the selected strategy
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, ..., a*base]
+            - altstack: []
+
+        Args:
+            base (list[int]): The coordinates `[x, y]` of the point to multiply, hard-coded in the script.
+            max_multiplier (int): The maximum value of the scalar `a`.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+
+        Returns:
+            The selected strategy, `"msb"` or `"lsb"`, and the script multiplying `base`. The strategy determines the
+            unlocking key to use: `EllipticCurveFqUnrolledUnlockingKey` for `"msb"`,
+            `EllipticCurveFqKnownBaseLsbUnlockingKey` for `"lsb"`.
+        """
+        arguments = {
+            "base": base,
+            "max_multiplier": max_multiplier,
+            "modulo_threshold": modulo_threshold,
+            "check_constant": check_constant,
+            "clean_constant": clean_constant,
+            "positive_modulo": positive_modulo,
+        }
+        msb_script = self.multiply_known_base_msb(**arguments)
+        lsb_script = self.multiply_known_base_lsb(**arguments)
+
+        if len(lsb_script.raw_serialize()) < len(msb_script.raw_serialize()):
+            return "lsb", lsb_script
+        return "msb", msb_script
+
     def msm_with_fixed_bases(
         self,
         bases: list[list[int]],
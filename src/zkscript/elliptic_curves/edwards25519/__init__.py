@@ -0,0 +1,13 @@
+"""edwards25519 package.
+
+This package provides scripts to perform arithmetic on edwards25519 and to verify Ed25519 signatures.
+
+Modules:
+    - edwards25519: Implements the class Edwards25519 which has methods:
+        - point_addition: Computes P + Q in extended coordinates
+        - point_addition_with_fixed_point: Computes P + Q for a fixed point Q
+        - point_doubling: Computes 2P in extended coordinates
+        - verify_eddsa: Verifies an Ed25519 signature (RFC 8032)
+    - parameters: The parameters of edwards25519.
+    - util: Reference implementation of the edwards25519 arithmetic and point (de)compression.
+"""
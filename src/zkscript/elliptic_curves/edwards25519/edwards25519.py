@@ -0,0 +1,421 @@
+"""Bitcoin scripts that perform arithmetic on edwards25519 and verify Ed25519 signatures."""
+
+from tx_engine import Script
+
+from src.zkscript.elliptic_curves.edwards25519.parameters import (
+    COFACTOR,
+    GROUP_ORDER,
+    N_BITS_SCALAR,
+    Bx,
+    By,
+    d,
+    q,
+)
+from src.zkscript.elliptic_curves.edwards25519.util import (
+    ENCODING_BYTES,
+    decompress_point,
+    edwards_addition,
+)
+from src.zkscript.script_types.stack_elements import StackNumber
+from src.zkscript.util.sha512 import sha512
+from src.zkscript.util.utility_scripts import (
+    bool_to_moving_function,
+    mod,
+    move,
+    nums_to_script,
+    verify_bottom_constant,
+)
+
+
+class Edwards25519:
+    """Construct Bitcoin scripts that perform arithmetic on edwards25519 and verify Ed25519 signatures.
+
+    edwards25519 is the twisted Edwards curve -x^2 + y^2 = 1 + d * x^2 * y^2 over F_q, q = 2^255 - 19. Arithmetic is
+    performed in extended coordinates: the point (x, y) is represented on the stack as the list of four numbers
+    [X, Y, Z, T], where x = X/Z, y = Y/Z and x * y = T/Z. The neutral element is [0, 1, 1, 0]. The formulas used are
+    complete, so they hold for any pair of points, including the neutral element and equal points.
+
+    Attributes:
+        MODULUS (int): The characteristic of the field F_q.
+        GROUP_ORDER (int): The order of the subgroup generated by the base point.
+        D (int): The `d` coefficient of the curve equation.
+        Bx (int): The x coordinate of the base point.
+        By (int): The y coordinate of the base point.
+    """
+
+    MODULUS: int = q
+    GROUP_ORDER: int = GROUP_ORDER
+    D: int = d
+    Bx: int = Bx
+    By: int = By
+
+    def __take_modulo(
+        self,
+        take_modulo: bool,
+        positive_modulo: bool,
+        clean_constant: bool | None,
+    ) -> Script:
+        """Reduce the coordinates of the point [X, Y, Z, T], with X on the stack and Y, Z, T on the altstack.
+
+        Stack input:
+            - stack:    [q, .., X]
+            - altstack: [T, Z, Y]
+
+        Stack output:
+            - stack:    [{q}, .., X, Y, Z, T]
+            - altstack: []
+        """
+        if not take_modulo:
+            return Script.parse_string("OP_FROMALTSTACK OP_FROMALTSTACK OP_FROMALTSTACK")
+        out = move(StackNumber(-1, False), bool_to_moving_function(clean_constant))
+        out += mod(stack_preparation="", is_positive=positive_modulo)
+        out += mod(is_positive=positive_modulo)
+        out += mod(is_positive=positive_modulo)
+        out += mod(is_positive=positive_modulo, is_constant_reused=False)
+        return out
+
+    def __extended_coordinates_output(
+        self,
+        take_modulo: bool,
+        positive_modulo: bool,
+        clean_constant: bool | None,
+    ) -> Script:
+        """Compute the output of the extended coordinates formulas.
+
+        Stack input:
+            - stack:    [q, .., G, F, H, E]
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, .., E*F, G*H, F*G, E*H]
+            - altstack: []
+        """
+        out = Script.parse_string("OP_2DUP OP_MUL OP_TOALTSTACK")  # Compute T3 = E*H
+        out += Script.parse_string("OP_3 OP_PICK OP_3 OP_PICK OP_MUL OP_TOALTSTACK")  # Compute Z3 = F*G
+        out += Script.parse_string("OP_ROT OP_MUL")  # Compute X3 = E*F
+        out += Script.parse_string("OP_ROT OP_ROT OP_MUL OP_TOALTSTACK")  # Compute Y3 = G*H
+        out += self.__take_modulo(
+            take_modulo=take_modulo, positive_modulo=positive_modulo, clean_constant=clean_constant
+        )
+        return out
+
+    def point_addition(
+        self,
+        take_modulo: bool,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        positive_modulo: bool = True,
+    ) -> Script:
+        """Add two points of edwards25519 in extended coordinates.
+
+        Stack input:
+            - stack:    [q, .., P, Q]
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, .., P + Q]
+            - altstack: []
+
+        Args:
+            take_modulo (bool): If `True`, the result is reduced modulo q.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+
+        Returns:
+            Script to compute P + Q.
+
+        Notes:
+            The script implements the formulas `add-2008-hwcd-3` for twisted Edwards curves with a = -1.
+        """
+        out = verify_bottom_constant(self.MODULUS) if check_constant else Script()
+
+        # stack in:  [X1, Y1, Z1, T1, X2, Y2, Z2, T2]
+        # stack out: [X1, Y1, X2, Y2, C := 2*d*T1*T2, D := 2*Z1*Z2]
+        out += Script.parse_string("OP_4 OP_ROLL OP_MUL")
+        out += nums_to_script([2 * self.D])
+        out += Script.parse_string("OP_MUL OP_SWAP OP_4 OP_ROLL OP_MUL OP_2 OP_MUL")
+        # stack out: [X1, Y1, X2, Y2, G := D + C, F := D - C]
+        out += Script.parse_string("OP_2DUP OP_ADD OP_ROT OP_ROT OP_SWAP OP_SUB")
+        # stack out: [G, F, Y2 + X2, Y2 - X2, Y1 + X1, Y1 - X1]
+        out += Script.parse_string("OP_2SWAP OP_2DUP OP_ADD OP_ROT OP_ROT OP_SWAP OP_SUB")
+        out += Script.parse_string("OP_2ROT OP_2DUP OP_ADD OP_ROT OP_ROT OP_SWAP OP_SUB")
+        # stack out: [G, F, B := (Y1 + X1)*(Y2 + X2), A := (Y1 - X1)*(Y2 - X2)]
+        out += Script.parse_string("OP_ROT OP_MUL OP_ROT OP_ROT OP_MUL OP_SWAP")
+        # stack out: [G, F, H := B + A, E := B - A]
+        out += Script.parse_string("OP_2DUP OP_ADD OP_ROT OP_ROT OP_SUB")
+
+        out += self.__extended_coordinates_output(
+            take_modulo=take_modulo, positive_modulo=positive_modulo, clean_constant=clean_constant
+        )
+        return out
+
+    def point_addition_with_fixed_point(
+        self,
+        Q: list[int],  # noqa: N803
+        take_modulo: bool,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        positive_modulo: bool = True,
+    ) -> Script:
+        """Add the fixed point `Q` to a point of edwards25519 in extended coordinates.
+
+        Stack input:
+            - stack:    [q, .., P]
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, .., P + Q]
+            - altstack: []
+
+        Args:
+            Q (list[int]): The point to add, in affine coordinates.
+            take_modulo (bool): If `True`, the result is reduced modulo q.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+
+        Returns:
+            Script to compute P + Q.
+
+        Notes:
+            The script implements the formulas `madd-2008-hwcd-3` for twisted Edwards curves with a = -1. The
+            values `y + x`, `y - x` and `2*d*x*y` of `Q` are precomputed.
+        """
+        x2, y2 = Q
+        out = verify_bottom_constant(self.MODULUS) if check_constant else Script()
+
+        # stack in:  [X1, Y1, Z1, T1]
+        # stack out: [X1, Y1, G := D + C, F := D - C], where C := 2*d*x2*y2*T1, D := 2*Z1
+        out += nums_to_script([2 * self.D * x2 * y2 % self.MODULUS])
+        out += Script.parse_string("OP_MUL OP_SWAP OP_2 OP_MUL")
+        out += Script.parse_string("OP_2DUP OP_ADD OP_ROT OP_ROT OP_SWAP OP_SUB")
+        # stack out: [G, F, B := (Y1 + X1)*(y2 + x2), A := (Y1 - X1)*(y2 - x2)]
+        out += Script.parse_string("OP_2SWAP OP_2DUP OP_ADD")
+        out += nums_to_script([(y2 + x2) % self.MODULUS])
+        out += Script.parse_string("OP_MUL OP_ROT OP_ROT OP_SWAP OP_SUB")
+        out += nums_to_script([(y2 - x2) % self.MODULUS])
+        out += Script.parse_string("OP_MUL")
+        # stack out: [G, F, H := B + A, E := B - A]
+        out += Script.parse_string("OP_2DUP OP_ADD OP_ROT OP_ROT OP_SUB")
+
+        out += self.__extended_coordinates_output(
+            take_modulo=take_modulo, positive_modulo=positive_modulo, clean_constant=clean_constant
+        )
+        return out
+
+    def point_doubling(
+        self,
+        take_modulo: bool,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        positive_modulo: bool = True,
+    ) -> Script:
+        """Double a point of edwards25519 in extended coordinates.
+
+        Stack input:
+            - stack:    [q, .., P]
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, .., 2P]
+            - altstack: []
+
+        Args:
+            take_modulo (bool): If `True`, the result is reduced modulo q.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+
+        Returns:
+            Script to compute 2P.
+
+        Notes:
+            The script implements the formulas `dbl-2008-hwcd` for twisted Edwards curves with a = -1.
+        """
+        out = verify_bottom_constant(self.MODULUS) if check_constant else Script()
+
+        # stack in:  [X1, Y1, Z1, T1]
+        # stack out: [C := 2*Z1^2, S := (X1 + Y1)^2, A := X1^2, B := Y1^2]
+        out += Script.parse_string("OP_DROP OP_DUP OP_MUL OP_2 OP_MUL OP_ROT OP_ROT")
+        out += Script.parse_string("OP_2DUP OP_ADD OP_DUP OP_MUL")
+        out += Script.parse_string("OP_ROT OP_DUP OP_MUL OP_ROT OP_DUP OP_MUL")
+        # stack out: [C, G := B - A, E := S - A - B, H := - A - B]
+        out += Script.parse_string("OP_2DUP OP_ADD OP_ROT OP_ROT OP_SWAP OP_SUB")
+        out += Script.parse_string("OP_SWAP OP_ROT OP_OVER OP_SUB OP_SWAP OP_NEGATE")
+        # stack out: [G, F := G - C, H, E]
+        out += Script.parse_string("OP_3 OP_ROLL OP_3 OP_PICK OP_SWAP OP_SUB OP_ROT OP_ROT OP_SWAP")
+
+        out += self.__extended_coordinates_output(
+            take_modulo=take_modulo, positive_modulo=positive_modulo, clean_constant=clean_constant
+        )
+        return out
+
+    def __decompress_r(self) -> Script:
+        """Decode the encoding of the point R of an Ed25519 signature, and compute -R in extended coordinates.
+
+        Stack input:
+            - stack:    [q, .., x, R]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, .., R] or fail
+            - altstack: [-R]
+
+        where `R` is the 32-byte encoding of the point and `x` its purported x coordinate.
+
+        Notes:
+            The script fails if `R` is not the canonical encoding of the point `(x, y)` of edwards25519, where `y`
+            is the number encoded by the 255 least significant bits of `R`.
+        """
+        # stack out: [R, x, sign, y]
+        out = Script.parse_string("OP_TUCK 0x00 OP_CAT OP_BIN2NUM OP_DUP")
+        out += nums_to_script([2**255])
+        out += Script.parse_string("OP_DIV OP_SWAP")
+        out += nums_to_script([2**255])
+        out += Script.parse_string("OP_MOD")
+        # Check that y < q, x < q and that sign is the parity of x
+        # stack out: [R, y, x]
+        out += Script.parse_string("OP_DUP")
+        out += nums_to_script([self.MODULUS])
+        out += Script.parse_string("OP_LESSTHAN OP_VERIFY OP_ROT OP_DUP OP_0")
+        out += nums_to_script([self.MODULUS])
+        out += Script.parse_string("OP_WITHIN OP_VERIFY OP_DUP OP_2 OP_MOD OP_3 OP_ROLL OP_NUMEQUALVERIFY")
+        # Check that (x,y) is on the curve: y^2 - x^2 - 1 - d*x^2*y^2 = 0 mod q
+        out += Script.parse_string("OP_2DUP OP_DUP OP_MUL OP_SWAP OP_DUP OP_MUL OP_2DUP OP_MUL")
+        out += nums_to_script([self.D])
+        out += Script.parse_string("OP_MUL OP_ROT OP_ROT OP_SWAP OP_SUB OP_SWAP OP_SUB OP_1SUB")
+        out += nums_to_script([self.MODULUS])
+        out += Script.parse_string("OP_MOD OP_NOT OP_VERIFY")
+        # stack out:    [R]
+        # altstack out: [-R] = [-x, y, 1, -x*y]
+        out += Script.parse_string("OP_NEGATE OP_SWAP OP_2DUP OP_MUL OP_1 OP_SWAP")
+        out += Script.parse_string(" ".join(["OP_TOALTSTACK"] * 4))
+        return out
+
+    def __fixed_base_multiplication(self, base: list[int]) -> Script:
+        """Add `n * base` to the point on top of the stack, where `n` is given by its bits.
+
+        Stack input:
+            - stack:    [q, .., n_0, .., n_(N_BITS_SCALAR-1), P]
+            - altstack: [..]
+
+        Stack output:
+            - stack:    [q, .., P + n * base]
+            - altstack: [.., n]
+
+        The multiples `2^i * base` are precomputed, and `2^i * base` is added to `P` if `n_i != 0`. The bits are
+        normalised with `OP_0NOTEQUAL`, so that the value `n` recomposed from them is the scalar by which `base` is
+        multiplied.
+        """
+        multiples = [base]
+        for _ in range(N_BITS_SCALAR - 1):
+            multiples.append(edwards_addition(multiples[-1], multiples[-1]))
+
+        out = Script.parse_string("OP_0 OP_TOALTSTACK")
+        for i in range(N_BITS_SCALAR - 1, -1, -1):
+            # stack out:    [q, .., n_0, .., n_(i-1), P, n_i != 0]
+            # altstack out: [.., sum_(j=i)^(N_BITS_SCALAR-1) 2^(j-i) * n_j]
+            out += Script.parse_string("OP_FROMALTSTACK OP_2 OP_MUL OP_5 OP_ROLL OP_0NOTEQUAL OP_TUCK OP_ADD")
+            out += Script.parse_string("OP_TOALTSTACK OP_IF")
+            out += self.point_addition_with_fixed_point(
+                Q=multiples[i], take_modulo=True, check_constant=False, clean_constant=False, positive_modulo=False
+            )
+            out += Script.parse_string("OP_ENDIF")
+        return out
+
+    def verify_eddsa(
+        self,
+        pubkey: bytes,
+        msg: bytes,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+    ) -> Script:
+        """Verify an Ed25519 signature of `msg` with respect to `pubkey`.
+
+        The script checks the verification equation of RFC 8032 (Section 5.1.7): [8][s]B == [8]R + [8][k]A, where
+        `B` is the base point, `A` is the point encoded by `pubkey`, `(R, s)` is the signature, and
+        `k = SHA512(R || pubkey || msg) mod GROUP_ORDER` is computed in script.
+
+        Stack input:
+            - stack:    [q, s_0, .., s_252, k_0, .., k_252, S, x, R]
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, 0/1]
+            - altstack: []
+
+        where:
+            - R is the 32-byte encoding of the point R, and x is its x coordinate
+            - S is the 32-byte encoding of the scalar s
+            - s_i and k_i are the bits of s and k, respectively (from the least to the most significant)
+
+        Args:
+            pubkey (bytes): The 32-byte encoding of the public key.
+            msg (bytes): The signed message.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+
+        Returns:
+            Script to verify the signature. The script fails if the encodings of `R` or `s` are not canonical, or if
+            the bits supplied do not match `s` and `k`. It returns `0` if the verification equation does not hold.
+
+        Raises:
+            ValueError: If `pubkey` is not the canonical encoding of a point of edwards25519.
+
+        Notes:
+            Bitcoin script has no opcode for SHA-512, so `k` is computed with the script `sha512`, which makes up
+            for most of the size of the verifier for short messages.
+        """
+        A = decompress_point(pubkey)  # noqa: N806
+        minus_A = [(-A[0]) % self.MODULUS, A[1]]  # noqa: N806
+
+        out = verify_bottom_constant(self.MODULUS) if check_constant else Script()
+
+        # Decompress R
+        # stack out:    [q, s_0, .., s_252, k_0, .., k_252, S, R]
+        # altstack out: [-R]
+        out += self.__decompress_r()
+
+        # Decode s and check that it is reduced modulo GROUP_ORDER
+        # stack out:    [q, s_0, .., s_252, k_0, .., k_252, R]
+        # altstack out: [-R, s]
+        out += Script.parse_string("OP_SWAP 0x00 OP_CAT OP_BIN2NUM OP_DUP")
+        out += nums_to_script([self.GROUP_ORDER])
+        out += Script.parse_string("OP_LESSTHAN OP_VERIFY OP_TOALTSTACK")
+
+        # Compute k = SHA512(R || pubkey || msg) mod GROUP_ORDER
+        # stack out:    [q, s_0, .., s_252, k_0, .., k_252]
+        # altstack out: [-R, s, k]
+        out.append_pushdata(pubkey + msg)
+        out += Script.parse_string("OP_CAT")
+        out += sha512(2 * ENCODING_BYTES + len(msg))
+        out += Script.parse_string("0x00 OP_CAT OP_BIN2NUM")
+        out += nums_to_script([self.GROUP_ORDER])
+        out += Script.parse_string("OP_MOD OP_TOALTSTACK")
+
+        # Compute [s]B - [k]A, and check that the bits match k and s
+        # stack out:    [q, [s]B - [k]A]
+        # altstack out: [-R]
+        out += nums_to_script([0, 1, 1, 0])
+        out += self.__fixed_base_multiplication(minus_A)
+        out += Script.parse_string("OP_FROMALTSTACK OP_FROMALTSTACK OP_NUMEQUALVERIFY")
+        out += self.__fixed_base_multiplication([self.Bx, self.By])
+        out += Script.parse_string("OP_FROMALTSTACK OP_FROMALTSTACK OP_NUMEQUALVERIFY")
+
+        # Compute [8]([s]B - [k]A - R)
+        # stack out: [q, [8]([s]B - [k]A - R)]
+        out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * 4))
+        out += self.point_addition(take_modulo=True, check_constant=False, clean_constant=False, positive_modulo=False)
+        for _ in range(COFACTOR.bit_length() - 1):
+            out += self.point_doubling(
+                take_modulo=True, check_constant=False, clean_constant=False, positive_modulo=False
+            )
+
+        # Check that [8]([s]B - [k]A - R) is the neutral element: X = 0 and Y = Z
+        # stack out: [{q}, 0/1]
+        out += Script.parse_string("OP_DROP OP_SUB")
+        out += move(StackNumber(-1, False), bool_to_moving_function(clean_constant))
+        out += Script.parse_string("OP_MOD OP_NOT OP_SWAP OP_NOT OP_BOOLAND")
+
+        return out
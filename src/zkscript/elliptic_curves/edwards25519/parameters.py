@@ -0,0 +1,21 @@
+"""Curve parameters for edwards25519, the twisted Edwards curve -x^2 + y^2 = 1 + d * x^2 * y^2 used by Ed25519."""
+
+# Modulus
+q = 2**255 - 19
+
+# Curve coefficients
+a = -1
+d = (-121665 * pow(121666, -1, q)) % q
+
+# Order of the subgroup generated by the base point
+GROUP_ORDER = 2**252 + 27742317777372353535851937790883648493
+
+# Cofactor
+COFACTOR = 8
+
+# Base point
+Bx = 15112221349535400772501151409588531511454012693041857206046113283949847762202
+By = 46316835694926478169428394003475163141307993866256225615783033603165251855960
+
+# Number of bits of the scalars in a (canonical) signature
+N_BITS_SCALAR = GROUP_ORDER.bit_length()
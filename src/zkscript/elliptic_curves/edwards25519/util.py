@@ -0,0 +1,75 @@
+"""Reference implementation of the edwards25519 arithmetic used to construct scripts and unlocking keys."""
+
+from hashlib import sha512
+
+from src.zkscript.elliptic_curves.edwards25519.parameters import GROUP_ORDER, d, q
+
+ENCODING_BYTES = 32
+
+
+def edwards_addition(P: list[int], Q: list[int]) -> list[int]:  # noqa: N803
+    """Add the points `P` and `Q` of edwards25519, given in affine coordinates."""
+    x1, y1 = P
+    x2, y2 = Q
+    t = d * x1 * x2 * y1 * y2 % q
+    return [
+        (x1 * y2 + x2 * y1) * pow(1 + t, -1, q) % q,
+        (y1 * y2 + x1 * x2) * pow(1 - t, -1, q) % q,
+    ]
+
+
+def edwards_scalar_multiplication(n: int, P: list[int]) -> list[int]:  # noqa: N803
+    """Compute `n * P` for a non-negative integer `n` and a point `P` of edwards25519."""
+    out = [0, 1]
+    for bit in bin(n)[2:]:
+        out = edwards_addition(out, out)
+        if bit == "1":
+            out = edwards_addition(out, P)
+    return out
+
+
+def compress_point(P: list[int]) -> bytes:  # noqa: N803
+    """Return the 32-byte encoding of the point `P` of edwards25519 (RFC 8032, Section 5.1.2)."""
+    x, y = P
+    return (y | ((x & 1) << 255)).to_bytes(ENCODING_BYTES, "little")
+
+
+def decompress_point(encoding: bytes) -> list[int]:
+    """Decode the 32-byte encoding of a point of edwards25519 (RFC 8032, Section 5.1.3).
+
+    Args:
+        encoding (bytes): The encoding of the point.
+
+    Returns:
+        The point, in affine coordinates.
+
+    Raises:
+        ValueError: If `encoding` is not the canonical encoding of a point of edwards25519.
+    """
+    if len(encoding) != ENCODING_BYTES:
+        msg = f"The encoding of a point must be {ENCODING_BYTES} bytes long: len(encoding) = {len(encoding)}"
+        raise ValueError(msg)
+    n = int.from_bytes(encoding, "little")
+    y, sign = n % 2**255, n >> 255
+    if y >= q:
+        msg = "The y coordinate of the point is not reduced modulo q"
+        raise ValueError(msg)
+
+    u, v = (y * y - 1) % q, (d * y * y + 1) % q
+    x = u * pow(v, 3, q) * pow(u * pow(v, 7, q), (q - 5) // 8, q) % q
+    if v * x * x % q == (-u) % q:
+        x = x * pow(2, (q - 1) // 4, q) % q
+    if v * x * x % q != u:
+        msg = "The encoding does not correspond to a point of edwards25519"
+        raise ValueError(msg)
+    if x == 0 and sign == 1:
+        msg = "The encoding does not correspond to a point of edwards25519"
+        raise ValueError(msg)
+    if x % 2 != sign:
+        x = q - x
+    return [x, y]
+
+
+def eddsa_challenge(r_encoding: bytes, pubkey: bytes, msg: bytes) -> int:
+    """Return the Ed25519 challenge `k = SHA512(R || A || msg) mod GROUP_ORDER` (RFC 8032, Section 5.1.7)."""
+    return int.from_bytes(sha512(r_encoding + pubkey + msg).digest(), "little") % GROUP_ORDER
@@ -8,4 +8,7 @@ Modules:
         - verify_base_point_multiplication: Verifies that A = aG
         - verify_point_multiplication_up_to_sign: Verifies that Q = ± bP
         - verify_point_multiplication: Verifies that Q = bP
+        - verify_point_multiplication_with_sign: Verifies that Q = ± bP and outputs the sign
+    - util: Utility scripts, and `decompress_point` and `lift_x` to decode the compressed and x-only (BIP340)
+        public keys passed to the unlocking keys
 """
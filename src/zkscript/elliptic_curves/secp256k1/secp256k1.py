@@ -64,6 +64,7 @@ class Secp256k1:
         h: StackFiniteFieldElement,
         rolling_option: int,
         is_verify: bool,
+        sighash_flags: SIGHASH,
     ) -> Script:
         """Verify that `h` is the little-endian, minimally encoded representation of `HASH256(sig_hash_preimage)`.
 
@@ -83,6 +84,7 @@ class Secp256k1:
             rolling_option (int): Bitmask detailing which of `h`, `sig_hash_preimage` should be removed from the stack
                 after execution.
             is_verify (bool): If `True`, the script consumes the result of the equality check.
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed.
 
         Returns:
             The script that verifies that `h` is the little-endian, minimally encoded representation of
@@ -103,7 +105,7 @@ class Secp256k1:
 
         # Verify that `sig_hash_preimage` is the sig_hash_preimage of the spending transaction
         out += TransactionIntrospection.pushtx(
-            sighash_flags=SIGHASH.ALL_FORKID,
+            sighash_flags=sighash_flags,
             data=sig_hash_preimage.shift(-is_h_rolled),
             rolling_option=is_sig_hash_preimage_rolled,
             clean_constants=clean_constants,
@@ -124,6 +126,7 @@ class Secp256k1:
         a: StackFiniteFieldElement = StackFiniteFieldElement(1, False, 1),  # noqa: B008
         A: StackBaseElement | StackEllipticCurvePoint = StackBaseElement(0),  # noqa: B008, N803
         rolling_option: int = (1 << 3) - 1,
+        sighash_flags: SIGHASH = SIGHASH.ALL_FORKID,
     ) -> Script:
         r"""Verify that A = (± a + additional_constant + epsilon)G.
 
@@ -154,6 +157,8 @@ class Secp256k1:
                 verifies A = (± a + additional_constant + epsilon)G. Defaults to `StackBaseElement(0)`.
             rolling_option (int): Bitmask detailing which elements among `h`, `a`, and `A` should be removed from
                 the stack after execution.
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed. Defaults to
+                `SIGHASH.ALL_FORKID`.
 
         Returns:
             The script that verifies A = (± a + additional_constant + epsilon)G.
@@ -218,7 +223,8 @@ class Secp256k1:
         out += Script.parse_string("0x30 OP_FROMALTSTACK")
         out += nums_to_script([36])
         out += Script.parse_string("OP_ADD OP_CAT OP_SWAP OP_CAT")  # Construct DER(Gx,s)
-        out += Script.parse_string("0x41 OP_CAT")  # Append SIGHASH_ALL
+        out.append_pushdata(sighash_flags.to_bytes())
+        out += Script.parse_string("OP_CAT")  # Append sighash flags
 
         # Enforce Der(Gx,s) A OP_CHECKSIG
         if isinstance(A, StackEllipticCurvePoint):
@@ -248,6 +254,7 @@ class Secp256k1:
             StackFiniteFieldElement(0, False, 1),  # noqa: B008
         ),
         rolling_option: int = (1 << 3) - 1,
+        sighash_flags: SIGHASH = SIGHASH.ALL_FORKID,
     ) -> Script:
         """Verify that A = (a + additional_constant)G without performing validity checks on the data.
 
@@ -281,6 +288,8 @@ class Secp256k1:
                 ),
             rolling_option (int): Bitmask detailing which elements among `h`, `a`, and `A` should be removed
                 from the stack after execution.
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed. Defaults to
+                `SIGHASH.ALL_FORKID`.
 
         Returns:
             The script that verifies A = (a + additional_constant)G.
@@ -343,6 +352,7 @@ class Secp256k1:
             h=h.shift(3 - 2 * is_A_rolled),
             a=a.shift(3 - 2 * is_A_rolled).set_negate(True),
             rolling_option=boolean_list_to_bitmask([False, False, True]),
+            sighash_flags=sighash_flags,
         )
 
         # Verify that A = (a + additional_constant + epsilon)G
@@ -357,6 +367,7 @@ class Secp256k1:
             h=h.shift(1 - 2 * is_A_rolled),
             a=a.shift(1 - 2 * is_A_rolled),
             rolling_option=boolean_list_to_bitmask([is_h_rolled, is_a_rolled, True]),
+            sighash_flags=sighash_flags,
         )
 
         return out
@@ -375,6 +386,7 @@ class Secp256k1:
             StackFiniteFieldElement(0, False, 1),  # noqa: B008
         ),
         rolling_option: int = (1 << 4) - 1,
+        sighash_flags: SIGHASH = SIGHASH.ALL_FORKID,
     ) -> Script:
         """Verify that A = (a + additional_constant)G.
 
@@ -411,6 +423,8 @@ class Secp256k1:
                 ),
             rolling_option (int): Bitmask detailing which elements among `sig_hash_preimage`, `h`, `a`, and `A`
                 should be removed from the stack after execution.
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed. Defaults to
+                `SIGHASH.ALL_FORKID`.
 
         Returns:
             The script that verifies A = (a + additional_constant)G.
@@ -447,6 +461,7 @@ class Secp256k1:
             a=a,
             A=A,
             rolling_option=boolean_list_to_bitmask([False, is_a_rolled, is_A_rolled]),
+            sighash_flags=sighash_flags,
         )
 
         # Verify that h is the sighash and leave result on the stack
@@ -456,6 +471,7 @@ class Secp256k1:
             h=h.shift(-is_a_rolled - 2 * is_A_rolled),
             rolling_option=boolean_list_to_bitmask([is_sig_hash_preimage_rolled, is_h_rolled]),
             is_verify=False,
+            sighash_flags=sighash_flags,
         )
 
         return out
@@ -484,6 +500,7 @@ class Secp256k1:
             StackFiniteFieldElement(0, False, 1),  # noqa: B008
         ),
         rolling_option: int = (1 << 9) - 1,
+        sighash_flags: SIGHASH = SIGHASH.ALL_FORKID,
     ) -> Script:
         """Verify Q = ± b * P.
 
@@ -536,6 +553,8 @@ class Secp256k1:
                     )`
             rolling_option (int): Bitmask detailing which of the elements used by the script should be removed
                 from the stack after execution.
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed. Defaults to
+                `SIGHASH.ALL_FORKID`.
 
         Returns:
             The script that verifies Q = ± b * P.
@@ -696,8 +715,10 @@ class Secp256k1:
             rolling_option=boolean_list_to_bitmask([False, True]),
         )  # Convert x_coordinate_target_times_b_inverse to s-component
         out += Script.parse_string(
-            "OP_CAT OP_SIZE OP_SWAP OP_CAT 0x30 OP_SWAP OP_CAT 0x41 OP_CAT"
+            "OP_CAT OP_SIZE OP_SWAP OP_CAT 0x30 OP_SWAP OP_CAT"
         )  # Construct Der(Q.x,x_coordinate_target_times_b_inverse)
+        out.append_pushdata(sighash_flags.to_bytes())
+        out += Script.parse_string("OP_CAT")  # Append sighash flags
 
         # Convert (P - h_times_x_coordinate_target_inverse_times_G) to compressed pubkey
         # stack in:     [GROUP_ORDER, Gx, 0x0220||Gx_bytes||02, .., h, .., b,
@@ -742,6 +763,7 @@ class Secp256k1:
             ),
             A=h_times_x_coordinate_target_inverse_times_G,
             rolling_option=boolean_list_to_bitmask([False, list_rolling_options[4], list_rolling_options[8]]),
+            sighash_flags=sighash_flags,
         )
 
         if list_rolling_options[6]:
@@ -771,6 +793,7 @@ class Secp256k1:
             ),
             rolling_option=boolean_list_to_bitmask([list_rolling_options[0], list_rolling_options[1]]),
             is_verify=False,
+            sighash_flags=sighash_flags,
         )
 
         return out
@@ -819,6 +842,7 @@ class Secp256k1:
             StackFiniteFieldElement(0, False, 1),  # noqa: B008
         ),
         rolling_option: int = (1 << 15) - 1,
+        sighash_flags: SIGHASH = SIGHASH.ALL_FORKID,
     ) -> Script:
         """Verify Q = bP.
 
@@ -895,6 +919,8 @@ class Secp256k1:
                     )`
             rolling_option (int): Bitmask detailing which of the elements used by the script should be removed
                 from the stack after execution.
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed. Defaults to
+                `SIGHASH.ALL_FORKID`.
 
         Returns:
             The script that verifies Q = b * P.
@@ -1041,6 +1067,7 @@ class Secp256k1:
             a=d[0].shift(-2 * list_rolling_options[14]),
             A=D[0].shift(-2 * list_rolling_options[14]),
             rolling_option=boolean_list_to_bitmask([False, False, list_rolling_options[9]]),
+            sighash_flags=sighash_flags,
         )
 
         # verify D[1] = (d[1]-1)* G
@@ -1063,6 +1090,7 @@ class Secp256k1:
             a=d[1].shift(-2 * list_rolling_options[9] - 2 * list_rolling_options[14]),
             A=D[1].shift(-2 * list_rolling_options[14]),
             rolling_option=boolean_list_to_bitmask([False, False, list_rolling_options[10]]),
+            sighash_flags=sighash_flags,
         )
 
         # verify D[2] = b * G
@@ -1090,6 +1118,7 @@ class Secp256k1:
             ),
             a=b.shift(-2 * list_rolling_options[14] + 2),
             rolling_option=boolean_list_to_bitmask([False, False, True]),
+            sighash_flags=sighash_flags,
         )
 
         # verify d[1] * (Q + bG)_x = h mod GROUP_ORDER
@@ -1161,7 +1190,9 @@ class Secp256k1:
         out += Script.parse_string("OP_TOALTSTACK")
         out += x_coordinate_to_r_component()
         out += Script.parse_string("OP_FROMALTSTACK")
-        out += Script.parse_string("OP_CAT OP_SIZE OP_SWAP OP_CAT 0x30 OP_SWAP OP_CAT 0x41 OP_CAT")
+        out += Script.parse_string("OP_CAT OP_SIZE OP_SWAP OP_CAT 0x30 OP_SWAP OP_CAT")
+        out.append_pushdata(sighash_flags.to_bytes())
+        out += Script.parse_string("OP_CAT")
         out += Script.parse_string("OP_FROMALTSTACK OP_FROMALTSTACK")
         out += stack_elliptic_curve_point_to_compressed_pubkey()
         out += Script.parse_string("OP_CHECKSIGVERIFY")
@@ -1241,7 +1272,9 @@ class Secp256k1:
         out += Script.parse_string("OP_TOALTSTACK")
         out += x_coordinate_to_r_component()
         out += Script.parse_string("OP_FROMALTSTACK")
-        out += Script.parse_string("OP_CAT OP_SIZE OP_SWAP OP_CAT 0x30 OP_SWAP OP_CAT 0x41 OP_CAT")
+        out += Script.parse_string("OP_CAT OP_SIZE OP_SWAP OP_CAT 0x30 OP_SWAP OP_CAT")
+        out.append_pushdata(sighash_flags.to_bytes())
+        out += Script.parse_string("OP_CAT")
         out += Script.parse_string("OP_FROMALTSTACK OP_FROMALTSTACK")
         out += stack_elliptic_curve_point_to_compressed_pubkey()
         out += Script.parse_string("OP_CHECKSIGVERIFY")
@@ -1288,6 +1321,220 @@ class Secp256k1:
             ),
             rolling_option=boolean_list_to_bitmask([list_rolling_options[0], list_rolling_options[1]]),
             is_verify=False,
+            sighash_flags=sighash_flags,
         )
 
         return out
+
+    @classmethod
+    def verify_point_multiplication_with_sign(
+        cls,
+        check_constants: bool = False,
+        clean_constants: bool = False,
+        sig_hash_preimage: StackBaseElement = StackBaseElement(20),  # noqa: B008
+        h: StackFiniteFieldElement = StackFiniteFieldElement(19, False, 1),  # noqa: B008
+        s: tuple[StackFiniteFieldElement] = (
+            StackFiniteFieldElement(18, False, 1),
+            StackFiniteFieldElement(17, False, 1),
+        ),
+        gradients: tuple[StackFiniteFieldElement] = (
+            StackFiniteFieldElement(16, False, 1),
+            StackFiniteFieldElement(15, False, 1),
+            StackFiniteFieldElement(14, False, 1),
+        ),
+        d: tuple[StackFiniteFieldElement] = (
+            StackFiniteFieldElement(13, False, 1),
+            StackFiniteFieldElement(12, False, 1),
+        ),
+        D: tuple[StackEllipticCurvePoint] = (  # noqa: N803
+            StackEllipticCurvePoint(
+                StackFiniteFieldElement(11, False, 1),
+                StackFiniteFieldElement(10, False, 1),
+            ),
+            StackEllipticCurvePoint(
+                StackFiniteFieldElement(9, False, 1),
+                StackFiniteFieldElement(8, False, 1),
+            ),
+            StackEllipticCurvePoint(
+                StackFiniteFieldElement(7, False, 1),
+                StackFiniteFieldElement(6, False, 1),
+            ),
+        ),
+        Q: StackEllipticCurvePoint = StackEllipticCurvePoint(  # noqa: B008, N803
+            StackFiniteFieldElement(5, False, 1),  # noqa: B008
+            StackFiniteFieldElement(4, False, 1),  # noqa: B008
+        ),
+        b: StackFiniteFieldElement = StackFiniteFieldElement(3, False, 1),  # noqa: B008
+        P: StackEllipticCurvePoint = StackEllipticCurvePoint(  # noqa: B008, N803
+            StackFiniteFieldElement(2, False, 1),  # noqa: B008
+            StackFiniteFieldElement(1, False, 1),  # noqa: B008
+        ),
+        rolling_option: int = (1 << 15) - 1,
+        sighash_flags: SIGHASH = SIGHASH.ALL_FORKID,
+    ) -> Script:
+        """Verify Q = ± bP and output the sign.
+
+        The sign is a witness hint on top of the stack: `sign = 0` if Q = bP, `sign = 1` if Q = -bP. The sign is
+        checked as follows: if `sign = 1`, the script replaces Q with -Q (i.e., it replaces Q_y with MODULUS - Q_y),
+        and then it verifies that the resulting point is equal to bP with `verify_point_multiplication`. Hence, the
+        script fails if the hint is wrong, and the sign left on the stack disambiguates the two cases left open by
+        `verify_point_multiplication_up_to_sign`.
+
+        Stack input:
+            - stack: [GROUP_ORDER, Gx, 0x0220||Gx_bytes||02, MODULUS, .. h, .., s, .., gradients,
+                        .., d, .., D, .., Q, .., b, .., P, .., sign]
+            - altstack: []
+        Stack output:
+            - stack: [GROUP_ORDER, Gx, 0x0220||Gx_bytes||02, MODULUS, .. h, .., s, .., gradients,
+                        .., d, .., D, .., (-1)^sign Q, .., b, .., P, .., sign, 1] or fail
+            - altstack: []
+
+        Args:
+            check_constants (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constants (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            sig_hash_preimage (StackBaseElement): The position in the stack of `sig_hash_preimage` of the spending
+                transaction. Defaults to `StackBaseElement(20)`
+            h (StackFiniteFieldElement): The position of the sighash of the transaction in which the script is executed.
+                Defaults to `StackFiniteFieldElement(19, False, 1)`.
+            s (tuple[StackFiniteFieldElement]): The position in the stack of:
+                    s[0] = (bP)_x / b mod GROUP_ORDER, s[1] = (bP + bG)_x / b mod GROUP_ORDER
+                Defaults to `(StackFiniteFieldElement(18, False, 1), StackFiniteFieldElement(17, False, 1))`.
+            gradients (tuple[StackFiniteFieldElement]): The position in the stack of the gradients required by
+                `verify_point_multiplication` for the point bP. Defaults to `StackFiniteFieldElement(16, False, 1)`,
+                `StackFiniteFieldElement(15, False, 1)`, `StackFiniteFieldElement(14, False, 1)`.
+            d (tuple[StackFiniteFieldElement]): The position in the stack of:
+                    d[0] = h / (bP)_x mod GROUP_ORDER, d[1] = h / (bP + bG)_x mod GROUP_ORDER
+                Defaults to `(StackFiniteFieldElement(13, False, 1), StackFiniteFieldElement(12, False, 1))`.
+            D (tuple[StackEllipticCurvePoint]): The position in the stack of:
+                    D[0] = d[0] * G, D[1] = d[1] * G, D[2] = b * G
+                Defaults to the points at positions (11, 10), (9, 8), (7, 6).
+            Q (StackEllipticCurvePoint): The position in the stack of the point `Q` for which the script verifies
+                Q = ± b * P. Defaults to:
+                `StackEllipticCurvePoint(
+                    StackFiniteFieldElement(5, False, 1),
+                    StackFiniteFieldElement(4, False, 1),
+                )`
+            b (StackFiniteFieldElement): The position in the stack of the element `b` for which the script verifies
+                Q = ± b * P. Defaults to: `StackFiniteFieldElement(3, False, 1)`.
+            P (StackEllipticCurvePoint): The position in the stack of the point `P` for which the script verifies
+                Q = ± b * P. Defaults to:
+                    `StackEllipticCurvePoint(
+                        StackFiniteFieldElement(2, False, 1),
+                        StackFiniteFieldElement(1, False, 1),
+                    )`
+            rolling_option (int): Bitmask detailing which of the elements used by the script should be removed
+                from the stack after execution (see `verify_point_multiplication`).
+            sighash_flags (SIGHASH): The sighash flags with which `h` was computed. Defaults to
+                `SIGHASH.ALL_FORKID`.
+
+        Returns:
+            The script that verifies Q = ± b * P and outputs the sign.
+
+        Notes:
+            The sign is normalised to `0` or `1` before being used.
+            If `Q` is not rolled, it is left on the stack as (-1)^sign Q = bP.
+            The hints `s`, `gradients`, `d` and `D` are the ones for the point bP, not for Q.
+            This script removes MODULUS from the bottom of the stack after execution.
+            This script can only be used on:
+                * b != 0 mod GROUP_ORDER.
+                * MODULUS - GROUP_ORDER < Q_x, (bP + bG)_x < GROUP_ORDER.
+        """
+        out = (
+            verify_bottom_constants(
+                [
+                    encode_num(cls.GROUP_ORDER),
+                    encode_num(cls.Gx),
+                    bytes.fromhex("0220") + cls.Gx_bytes + bytes.fromhex("02"),
+                    encode_num(cls.MODULUS),
+                ]
+            )
+            if check_constants
+            else Script()
+        )
+
+        # Put the sign on the altstack
+        out += Script.parse_string("OP_0NOTEQUAL OP_DUP OP_TOALTSTACK")
+
+        # If sign = 1, replace Q with -Q
+        # stack in:     [GROUP_ORDER, Gx, 0x0220||Gx_bytes||02, MODULUS, .., Q, .., b, .., P, .., sign]
+        # stack out:    [GROUP_ORDER, Gx, 0x0220||Gx_bytes||02, MODULUS, .., (-1)^sign Q, .., b, .., P, ..]
+        # altstack out: [sign]
+        out += Script.parse_string("OP_IF")
+        out += roll(position=Q.y.position - 1, n_elements=1)  # Roll Q_y
+        out += pick(position=-4, n_elements=1)  # Pick MODULUS
+        out += Script.parse_string("OP_SWAP OP_SUB")  # Compute MODULUS - Q_y
+        out += roll(
+            position=Q.y.position - 1, n_elements=Q.y.position - 1
+        )  # Put MODULUS - Q_y back in the position of Q_y
+        out += Script.parse_string("OP_ENDIF")
+
+        out += cls.verify_point_multiplication(
+            check_constants=False,
+            clean_constants=clean_constants,
+            sig_hash_preimage=sig_hash_preimage.shift(-1),
+            h=h.shift(-1),
+            s=tuple(element.shift(-1) for element in s),
+            gradients=tuple(element.shift(-1) for element in gradients),
+            d=tuple(element.shift(-1) for element in d),
+            D=tuple(point.shift(-1) for point in D),
+            Q=Q.shift(-1),
+            b=b.shift(-1),
+            P=P.shift(-1),
+            rolling_option=rolling_option,
+            sighash_flags=sighash_flags,
+        )
+
+        # Output the sign below the result of the sighash verification
+        out += Script.parse_string("OP_FROMALTSTACK OP_SWAP")
+
+        return out
+
+    @staticmethod
+    def verify_multisig(m: int, pubkeys: list[bytes], is_checkmultisigverify: bool = False) -> Script:
+        """Generate the locking script of an m-of-n multisig.
+
+        Stack input:
+            - stack:    [.., 0, sig_1, .., sig_m]
+            - altstack: []
+
+        Stack output:
+            - stack:    [.., 0/1] or fail (`is_checkmultisigverify = True`)
+            - altstack: []
+
+        Args:
+            m (int): The number of signatures required to unlock the script.
+            pubkeys (list[bytes]): The `n` public keys (compressed or uncompressed) allowed to sign.
+            is_checkmultisigverify (bool): Whether to execute OP_CHECKMULTISIGVERIFY or OP_CHECKMULTISIG. Defaults
+                to `False` (OP_CHECKMULTISIG).
+
+        Returns:
+            The script `OP_m <pubkey_1> .. <pubkey_n> OP_n OP_CHECKMULTISIG`.
+
+        Raises:
+            ValueError: If `pubkeys` is empty, if `m` is not in `[1, n]`, or if a public key is neither 33 nor 65
+                bytes long.
+
+        Note:
+            Because of an off-by-one bug in the original implementation, OP_CHECKMULTISIG pops one more element than
+            required. The signatures must be preceded by a dummy element, which must be the empty byte string (`0`),
+            see `Secp256k1MultisigUnlockingKey`. The signatures must be in the same order as the public keys they
+            are checked against.
+        """
+        if len(pubkeys) == 0:
+            msg = "The list of public keys must not be empty"
+            raise ValueError(msg)
+        if not 1 <= m <= len(pubkeys):
+            msg = f"The number of required signatures must be between 1 and {len(pubkeys)}: m = {m}"
+            raise ValueError(msg)
+        for i, pubkey in enumerate(pubkeys):
+            if len(pubkey) not in {33, 65}:
+                msg = f"The public key at index {i} must be 33 or 65 bytes long: len = {len(pubkey)}"
+                raise ValueError(msg)
+
+        out = nums_to_script([m])
+        for pubkey in pubkeys:
+            out.append_pushdata(pubkey)
+        out += nums_to_script([len(pubkeys)])
+        out += Script.parse_string("OP_CHECKMULTISIGVERIFY" if is_checkmultisigverify else "OP_CHECKMULTISIG")
+
+        return out
@@ -1,6 +1,7 @@
 """Utility scritps for secp256k1 package."""
 
 from tx_engine import Script
+from tx_engine.engine.util import PRIME_INT
 
 from src.zkscript.script_types.stack_elements import (
     StackEllipticCurvePoint,
@@ -16,6 +17,81 @@ from src.zkscript.util.utility_scripts import (
     roll,
 )
 
+COMPRESSED_PUBKEY_BYTES = 33
+X_ONLY_PUBKEY_BYTES = 32
+
+
+def _square_root_of_rhs(x: int) -> int | None:
+    """Return a square root of `x^3 + 7` modulo PRIME_INT, or `None` if `x` is not the x coordinate of a point."""
+    # PRIME_INT = 3 mod 4, so the square root of y^2 is (y^2)^((PRIME_INT + 1) / 4)
+    y = pow(x**3 + 7, (PRIME_INT + 1) // 4, PRIME_INT)
+    if x >= PRIME_INT or y * y % PRIME_INT != (x**3 + 7) % PRIME_INT:
+        return None
+    return y
+
+
+def lift_x(x_only: bytes, even_y: bool = True) -> list[int]:
+    """Decode the 32-byte x-only encoding of a point of secp256k1 (BIP340).
+
+    Args:
+        x_only (bytes): The big-endian encoding of the x coordinate of the point.
+        even_y (bool): If `True`, the point with even y coordinate is returned (as in BIP340), else the one with odd
+            y coordinate. Defaults to `True`.
+
+    Returns:
+        The point, in affine coordinates.
+
+    Raises:
+        ValueError: If `x_only` is not 32 bytes long, or if there is no point of secp256k1 with x coordinate
+            `x_only`.
+    """
+    if len(x_only) != X_ONLY_PUBKEY_BYTES:
+        msg = f"The x-only public key must be 32 bytes long: {x_only.hex()}"
+        raise ValueError(msg)
+    y = _square_root_of_rhs(int.from_bytes(x_only))
+    if y is None:
+        msg = f"The x-only public key is not the x coordinate of a point of secp256k1: {x_only.hex()}"
+        raise ValueError(msg)
+    return [int.from_bytes(x_only), y if (y % 2 == 0) == even_y else PRIME_INT - y]
+
+
+def decompress_point(pubkey: bytes) -> list[int]:
+    """Decode the 33-byte compressed encoding `02||x` or `03||x` of a point of secp256k1 (SEC 1, Section 2.3.4).
+
+    Args:
+        pubkey (bytes): The compressed encoding of the point.
+
+    Returns:
+        The point, in affine coordinates.
+
+    Raises:
+        ValueError: If `pubkey` is not the compressed encoding of a point of secp256k1.
+    """
+    if len(pubkey) != COMPRESSED_PUBKEY_BYTES or pubkey[0] not in {2, 3}:
+        msg = f"The compressed public key must be 33 bytes long and start with 02 or 03: {pubkey.hex()}"
+        raise ValueError(msg)
+    if _square_root_of_rhs(int.from_bytes(pubkey[1:])) is None:
+        msg = f"The compressed public key is not the encoding of a point of secp256k1: {pubkey.hex()}"
+        raise ValueError(msg)
+    return lift_x(pubkey[1:], even_y=pubkey[0] == 2)  # noqa: PLR2004
+
+
+def pubkey_to_point(pubkey: list[int] | bytes, is_compressed: bool) -> list[int]:
+    """Return the affine coordinates of the public key `pubkey`.
+
+    Args:
+        pubkey (list[int] | bytes): The public key, either as affine coordinates or, if `is_compressed`, as the
+            33-byte compressed encoding or the 32-byte x-only encoding (BIP340).
+        is_compressed (bool): If `True`, `pubkey` is decoded with `decompress_point` or, if it is 32 bytes long, with
+            `lift_x`.
+
+    Returns:
+        The affine coordinates of `pubkey`.
+    """
+    if not is_compressed:
+        return pubkey
+    return lift_x(pubkey) if len(pubkey) == X_ONLY_PUBKEY_BYTES else decompress_point(pubkey)
+
 
 def stack_elliptic_curve_point_to_compressed_pubkey(
     A: StackEllipticCurvePoint = StackEllipticCurvePoint(  # noqa: B008, N803
@@ -16,6 +16,8 @@ Modules:
     quadratic extension of F_q^6, which is built as a cubic extension of F_q^2.
     - fq12_3_over_2_over_2: Contains the Fq12Cubic class for arithmetic operations over the finite field F_q^12 built as
     cubic extension of F_q^4, which is built as a quadratic extension of F_q^2.
+    - util: Reference implementation of the field arithmetic (e.g., square roots in F_q and F_q^2) used to construct
+    unlocking keys.
 
 Usage example:
     >>> from src.zkscript.fields.fq2 import Fq2
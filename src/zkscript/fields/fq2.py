@@ -644,3 +644,66 @@ class Fq2(PrimeFieldExtension):
             )
 
         return out
+
+    def sqrt(
+        self,
+        take_modulo: bool,
+        positive_modulo: bool = True,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        is_constant_reused: bool | None = None,
+    ) -> Script:
+        """Witness-assisted square root in F_q^2.
+
+        The script checks that the witness `y` is a square root of `x`, i.e., that `y^2 = x mod q`, and fails
+        otherwise. The witness can be computed with `src.zkscript.fields.util.fq2_sqrt`. If `x` is not a square in
+        F_q^2, no witness satisfies the script.
+
+        Stack input:
+            - stack:    [q, ..., x := (x0, x1), y := (y0, y1)]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., y := (y0, y1)]
+            - altstack: []
+
+        Args:
+            take_modulo (bool): If `True`, the result is reduced modulo `q`.
+            positive_modulo (bool): If `True` the modulo of the result is taken positive. Defaults to `True`.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            is_constant_reused (bool | None, optional): If `True`, `q` remains as the second-to-top element on the stack
+                after execution. Defaults to `None`.
+
+        Returns:
+            Script to verify the square root of an element in F_q^2.
+
+        Note:
+            Both `y` and `-y` are square roots of `x`: the script accepts either of them. When the root is used to
+            decompress a point, the sign of the root must be checked separately.
+        """
+        out = verify_bottom_constant(self.modulus) if check_constant else Script()
+
+        # stack in:     [.., x0, x1, y0, y1]
+        # stack out:    [.., y0, y1, y0^2 + y1^2 * self.non_residue, x0]
+        # altstack out: [x1 - 2 * y0 * y1]
+        out += Script.parse_string("OP_2DUP")
+        out += self.square(take_modulo=False)
+        out += Script.parse_string("OP_2ROT OP_ROT OP_SUB OP_TOALTSTACK")
+
+        # stack in:     [.., y0, y1, y0^2 + y1^2 * self.non_residue, x0]
+        # altstack in:  [x1 - 2 * y0 * y1]
+        # stack out:    [.., y0, y1]
+        # altstack out: []
+        out += Script.parse_string("OP_SUB")
+        out += pick(position=-1, n_elements=1)
+        out += Script.parse_string("OP_TUCK OP_MOD OP_0 OP_NUMEQUALVERIFY")
+        out += Script.parse_string("OP_FROMALTSTACK OP_SWAP OP_MOD OP_0 OP_NUMEQUALVERIFY")
+
+        if take_modulo:
+            out += Script.parse_string("OP_TOALTSTACK")
+            out += self.take_modulo(
+                positive_modulo=positive_modulo, clean_constant=clean_constant, is_constant_reused=is_constant_reused
+            )
+
+        return out
@@ -478,3 +478,33 @@ class PrimeFieldExtension:
         )
 
         return out
+
+    def is_one(self, is_verify: bool = True) -> Script:
+        """Check whether an element of F_q^n is equal to one.
+
+        Stack input:
+            - stack:    [q, ..., x := (x0, .., xn)]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ...] if `is_verify` else [q, ..., x == 1]
+            - altstack: []
+
+        Args:
+            is_verify (bool): If `True`, the script fails unless `x == 1`. Else, the result of the check is left on
+                the stack. Defaults to `True`.
+
+        Returns:
+            Script to check whether `x` is equal to one in F_q^n.
+
+        Notes:
+            The coordinates of `x` are assumed to be reduced modulo `q`, as the check is done on their canonical
+            representatives. Instead of comparing `x` to one coordinate by coordinate, the script folds
+            `(x1, .., xn)` with `OP_BOOLOR`, so no coordinate is moved on the stack.
+        """
+        out = Script.parse_string(" ".join(["OP_BOOLOR"] * (self.extension_degree - 2)))
+        out += Script.parse_string(
+            "OP_NOT OP_VERIFY OP_1 OP_EQUALVERIFY" if is_verify else "OP_NOT OP_SWAP OP_1 OP_EQUAL OP_BOOLAND"
+        )
+
+        return out
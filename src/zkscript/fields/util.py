@@ -0,0 +1,84 @@
+"""Reference implementation of the field arithmetic used to construct unlocking keys."""
+
+
+def is_square_fq(a: int, q: int) -> bool:
+    """Return whether `a` is a square in F_q, for an odd prime `q` (Euler's criterion)."""
+    return a % q == 0 or pow(a, (q - 1) // 2, q) == 1
+
+
+def fq_sqrt(a: int, q: int) -> int:
+    """Compute a square root of `a` in F_q, for an odd prime `q` (Tonelli-Shanks).
+
+    Args:
+        a (int): The element of F_q.
+        q (int): The characteristic of F_q.
+
+    Returns:
+        An integer `x` in `[0, q)` such that `x^2 = a mod q`.
+
+    Raises:
+        ValueError: If `a` is not a square in F_q.
+    """
+    a %= q
+    if not is_square_fq(a, q):
+        msg = f"{a} is not a square modulo {q}"
+        raise ValueError(msg)
+    if a == 0:
+        return 0
+    if q % 4 == 3:
+        return pow(a, (q + 1) // 4, q)
+
+    # Write q - 1 = odd * 2^two_adicity and pick a non-residue z
+    odd, two_adicity = q - 1, 0
+    while odd % 2 == 0:
+        odd, two_adicity = odd // 2, two_adicity + 1
+    z = next(z for z in range(2, q) if not is_square_fq(z, q))
+
+    m, c, t, x = two_adicity, pow(z, odd, q), pow(a, odd, q), pow(a, (odd + 1) // 2, q)
+    while t != 1:
+        i, t_power = 0, t
+        while t_power != 1:
+            t_power, i = t_power * t_power % q, i + 1
+        b = pow(c, 2 ** (m - i - 1), q)
+        m, c, t, x = i, b * b % q, t * b * b % q, x * b % q
+    return x
+
+
+def fq2_sqrt(x: list[int], q: int, non_residue: int) -> list[int]:
+    """Compute a square root of `x` in F_q^2 = F_q[u] / (u^2 - non_residue), for an odd prime `q`.
+
+    The square root is computed with the complex method: if `x = x0 + x1 * u` is a square, then its norm
+    `N(x) = x0^2 - non_residue * x1^2` is a square in F_q, and the square root is `y0 + y1 * u` with
+    `y0^2 = (x0 ± sqrt(N(x))) / 2` and `y1 = x1 / (2 * y0)`.
+
+    Args:
+        x (list[int]): The element of F_q^2, as the list of its coordinates `[x0, x1]`.
+        q (int): The characteristic of F_q.
+        non_residue (int): The non-residue defining the quadratic extension.
+
+    Returns:
+        The coordinates `[y0, y1]`, in `[0, q)`, of an element `y` such that `y^2 = x`.
+
+    Raises:
+        ValueError: If `x` is not a square in F_q^2.
+    """
+    x0, x1 = x[0] % q, x[1] % q
+
+    if x1 == 0:
+        # x is in F_q: either x0 is a square in F_q, or x0 / non_residue is
+        if is_square_fq(x0, q):
+            return [fq_sqrt(x0, q), 0]
+        return [0, fq_sqrt(x0 * pow(non_residue, -1, q), q)]
+
+    norm = (x0 * x0 - non_residue * x1 * x1) % q
+    if not is_square_fq(norm, q):
+        msg = f"{[x0, x1]} is not a square in F_q^2"
+        raise ValueError(msg)
+    alpha = fq_sqrt(norm, q)
+    inverse_of_two = pow(2, -1, q)
+    delta = (x0 + alpha) * inverse_of_two % q
+    if not is_square_fq(delta, q):
+        delta = (x0 - alpha) * inverse_of_two % q
+    y0 = fq_sqrt(delta, q)
+    y1 = x1 * pow(2 * y0, -1, q) % q
+    return [y0, y1]
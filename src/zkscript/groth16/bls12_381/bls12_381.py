@@ -2,6 +2,9 @@
 
 from src.zkscript.bilinear_pairings.bls12_381.bls12_381 import bls12_381 as bls12_381_pairing_model
 from src.zkscript.bilinear_pairings.bls12_381.parameters import a, b, r
+from src.zkscript.bilinear_pairings.bls12_381.subgroup_membership import subgroup_membership
 from src.zkscript.groth16.model.groth16 import Groth16
 
-bls12_381 = Groth16(pairing_model=bls12_381_pairing_model, curve_a=a, curve_b=b, r=r)
+bls12_381 = Groth16(
+    pairing_model=bls12_381_pairing_model, curve_a=a, curve_b=b, r=r, subgroup_membership=subgroup_membership
+)
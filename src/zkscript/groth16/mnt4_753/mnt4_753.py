@@ -2,6 +2,9 @@
 
 from src.zkscript.bilinear_pairings.mnt4_753.mnt4_753 import mnt4_753 as mnt4_753_pairing_model
 from src.zkscript.bilinear_pairings.mnt4_753.parameters import a, b, r
+from src.zkscript.bilinear_pairings.mnt4_753.subgroup_membership import subgroup_membership
 from src.zkscript.groth16.model.groth16 import Groth16
 
-mnt4_753 = Groth16(pairing_model=mnt4_753_pairing_model, curve_a=a, curve_b=b, r=r)
+mnt4_753 = Groth16(
+    pairing_model=mnt4_753_pairing_model, curve_a=a, curve_b=b, r=r, subgroup_membership=subgroup_membership
+)
@@ -1,8 +1,12 @@
 """Bitcoin scripts that perform Groth16 proof verification."""
 
+import logging
+from math import log2
+
 from tx_engine import Script, encode_num, hash256d
 
 from src.zkscript.bilinear_pairings.model.model_definition import PairingModel
+from src.zkscript.bilinear_pairings.model.subgroup_membership import G1SubgroupMembership
 
 # Pairing
 # Script implementations
@@ -14,8 +18,21 @@ from src.zkscript.script_types.locking_keys.groth16_proj import (
     Groth16ProjLockingKey,
     Groth16ProjLockingKeyWithPrecomputedMsm,
 )
-from src.zkscript.util.utility_functions import optimise_script
-from src.zkscript.util.utility_scripts import nums_to_script, roll, verify_bottom_constant
+from src.zkscript.script_types.unlocking_keys.msm_with_fixed_bases import MsmWithFixedBasesUnlockingKey
+from src.zkscript.util.utility_functions import (
+    check_stack_limits,
+    optimise_labelled_scripts,
+    optimise_script,
+    script_num_size,
+)
+from src.zkscript.util.utility_scripts import nums_to_script, pick, roll, verify_bottom_constant
+from src.zkscript.util.verification_chain import chain_stage, state_commitment
+
+
+# Number of violations of the stack limits reported when the check fails
+MAX_REPORTED_VIOLATIONS = 10
+
+logger = logging.getLogger(__name__)
 
 
 class Groth16:
@@ -25,9 +42,18 @@ class Groth16:
         pairing_model: Pairing model used to instantiate Groth16.
         curve_a (int): A coefficient of the base curve over which Groth16 is instantiated.
         r (int): The order of G1/G2/GT.
+        subgroup_membership (G1SubgroupMembership | None): Subgroup membership checks for the curve over which
+            Groth16 is instantiated. `None` if they are not available.
     """
 
-    def __init__(self, pairing_model: PairingModel, curve_a: int, curve_b: int, r: int):
+    def __init__(
+        self,
+        pairing_model: PairingModel,
+        curve_a: int,
+        curve_b: int,
+        r: int,
+        subgroup_membership: G1SubgroupMembership | None = None,
+    ):
         """Initialise the Groth16 class.
 
         Args:
@@ -35,87 +61,374 @@ class Groth16:
             curve_a (int): A coefficient of the base curve over which Groth16 is instantiated.
             curve_b (int): B coefficient of the base curve over which Groth16 is instantiated.
             r (int): The order of G1/G2/GT.
+            subgroup_membership (G1SubgroupMembership | None): Subgroup membership checks for the curve over which
+                Groth16 is instantiated. Defaults to `None`, meaning that they are not available.
         """
         self.pairing_model = pairing_model
         self.curve_a = curve_a
         self.curve_b = curve_b
         self.r = r
+        self.subgroup_membership = subgroup_membership
 
-    def __gradients_to_hash_commitment(self, locking_key: Groth16LockingKey) -> bytes:
-        """Construct the hash commitment for the gradients of -gamma and -delta.
+    def __commitment_chunks(self, locking_key: Groth16LockingKey, commitment_chunk_size: int | None) -> list[range]:
+        """Split the iterations of the Miller loop into the chunks committed to by the gradients hash commitments.
 
         Args:
             locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
                 CRS needed by the verifier.
+            commitment_chunk_size (int | None): The number of iterations of the Miller loop whose gradients are
+                committed to by each hash. If `None`, all the gradients are committed to by a single hash.
+
+        Returns:
+            The list of ranges of iterations of the Miller loop, one for each hash commitment.
+
+        Raises:
+            ValueError: If `commitment_chunk_size` is not positive.
         """
-        verification_hash = b""
-        for i in range(len(locking_key.gradients_pairings[0])):
-            for j in range(len(locking_key.gradients_pairings[0][i])):
-                for k in range(1, -1, -1):
-                    for s in range(self.pairing_model.extension_degree - 1, -1, -1):
-                        verification_hash = encode_num(locking_key.gradients_pairings[k][i][j][s]) + verification_hash
-                        verification_hash = hash256d(verification_hash)
-        return verification_hash
-
-    def __verify_hash_commitment(self, locking_key: Groth16LockingKey, verification_hash: bytes) -> Script:
-        """Script that verifies that the gradients contained in `locking_key` commit to verification_hash.
+        n_iterations = len(locking_key.gradients_pairings[0])
+        if commitment_chunk_size is None:
+            return [range(n_iterations)]
+        if commitment_chunk_size < 1:
+            msg = f"The commitment chunk size must be positive: commitment_chunk_size = {commitment_chunk_size}"
+            raise ValueError(msg)
+        return [
+            range(start, min(start + commitment_chunk_size, n_iterations))
+            for start in range(0, n_iterations, commitment_chunk_size)
+        ]
+
+    def gradients_to_hash_commitments(
+        self, locking_key: Groth16LockingKey, commitment_chunk_size: int | None = None
+    ) -> list[bytes]:
+        """Construct the hash commitments for the gradients of -gamma and -delta.
+
+        Args:
+            locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
+                CRS needed by the verifier.
+            commitment_chunk_size (int | None): The number of iterations of the Miller loop whose gradients are
+                committed to by each hash. Defaults to `None`, meaning that all the gradients are committed to by a
+                single hash.
+
+        Returns:
+            The list of hash commitments, one for each chunk of iterations of the Miller loop, in the order in which
+            the iterations are executed.
+
+        Raises:
+            ValueError: If `commitment_chunk_size` is not positive.
+        """
+        verification_hashes = []
+        for chunk in self.__commitment_chunks(locking_key, commitment_chunk_size):
+            verification_hash = b""
+            for i in chunk:
+                for j in range(len(locking_key.gradients_pairings[0][i])):
+                    for k in range(1, -1, -1):
+                        for s in range(self.pairing_model.extension_degree - 1, -1, -1):
+                            verification_hash = (
+                                encode_num(locking_key.gradients_pairings[k][i][j][s]) + verification_hash
+                            )
+                            verification_hash = hash256d(verification_hash)
+            verification_hashes.append(verification_hash)
+        return verification_hashes
+
+    def __verify_hash_commitment(
+        self,
+        locking_key: Groth16LockingKey,
+        verification_hashes: list[bytes],
+        commitment_chunk_size: int | None,
+        terminal: bool = False,
+    ) -> Script:
+        """Script that verifies that the gradients contained in `locking_key` commit to verification_hashes.
 
         Stack input:
             - stack: [.., gradients_pairing]
 
         Stack output:
-            - stack: [.., 0/1]
+            - stack: [.., 0/1] or fail ([..] or fail if `terminal`)
 
         Args:
             locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
                 CRS needed by the verifier.
-            verification_hash (bytes): The hash commitment against which we verify the gradients contained in
-                `locking_key`.
+            verification_hashes (list[bytes]): The hash commitments against which we verify the gradients contained in
+                `locking_key`, one for each chunk of iterations of the Miller loop.
+            commitment_chunk_size (int | None): The number of iterations of the Miller loop whose gradients are
+                committed to by each hash. If `None`, all the gradients are committed to by a single hash.
+            terminal (bool): If `True`, the commitment of the last chunk is also checked with `OP_EQUALVERIFY`.
+                Defaults to `False`.
+
+        Notes:
+            The commitments of all the chunks but the last one are checked with `OP_EQUALVERIFY`, so that the script
+            fails at the first chunk containing an invalid gradient.
         """
-        list_of_opcodes = []
-        for i in range(len(locking_key.gradients_pairings[0]) - 1, -1, -1):
-            for _ in range(len(locking_key.gradients_pairings[0][i]) - 1, -1, -1):
-                for _ in range(1, 3):
-                    for _ in range(self.pairing_model.extension_degree):
-                        list_of_opcodes.append("OP_HASH256")
-                        list_of_opcodes.append("OP_CAT")
-        string_of_opcodes = " ".join(list_of_opcodes[:-1])
-        out = Script.parse_string(string_of_opcodes)
-        out.append_pushdata(verification_hash)
-        out += Script.parse_string("OP_EQUAL")
+        chunks = self.__commitment_chunks(locking_key, commitment_chunk_size)
+        out = Script()
+        for n, (chunk, verification_hash) in enumerate(zip(chunks, verification_hashes, strict=True)):
+            n_gradients = sum(2 * len(locking_key.gradients_pairings[0][i]) for i in chunk)
+            list_of_opcodes = ["OP_HASH256", "OP_CAT"] * (n_gradients * self.pairing_model.extension_degree)
+            out += Script.parse_string(" ".join(list_of_opcodes[:-1]))
+            out.append_pushdata(verification_hash)
+            out += Script.parse_string("OP_EQUAL" if n == len(chunks) - 1 and not terminal else "OP_EQUALVERIFY")
         return out
 
-    def groth16_verifier(
+    def commitment_opcode_count(self, locking_key: Groth16LockingKey, commitment_chunk_size: int | None = None) -> int:
+        """Return the number of opcodes used by the verifier to check the gradients hash commitments.
+
+        Args:
+            locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
+                CRS needed by the verifier.
+            commitment_chunk_size (int | None): The number of iterations of the Miller loop whose gradients are
+                committed to by each hash. Defaults to `None`, meaning that all the gradients are committed to by a
+                single hash.
+
+        Returns:
+            The number of opcodes (including the pushes of the commitments) of the script reconstructing and checking
+            the hash commitments of the gradients of -gamma and -delta. This is `0` if
+            `locking_key.has_precomputed_gradients` is `True`, as the gradients are then hard-coded in the verifier.
+
+        Raises:
+            ValueError: If `commitment_chunk_size` is not positive.
+        """
+        if locking_key.has_precomputed_gradients:
+            return 0
+        verification_hashes = self.gradients_to_hash_commitments(
+            locking_key=locking_key, commitment_chunk_size=commitment_chunk_size
+        )
+        script = self.__verify_hash_commitment(
+            locking_key=locking_key,
+            verification_hashes=verification_hashes,
+            commitment_chunk_size=commitment_chunk_size,
+        )
+        return len(script.to_string().split())
+
+    def baked_bases(self, locking_key: Groth16LockingKey | Groth16ProjLockingKey) -> list[list[int]]:
+        """Return the points `gamma_abc` hard-coded in the verifier generated from `locking_key`.
+
+        Args:
+            locking_key (Groth16LockingKey | Groth16ProjLockingKey): Locking key used to generate the verifier.
+                Encapsulates the data of the CRS needed by the verifier.
+
+        Returns:
+            The affine coordinates `[x, y]` of `gamma_abc[0], ..., gamma_abc[l]`, where `gamma_abc[1], ...,
+            gamma_abc[l]` are the fixed bases of the multi-scalar multiplication, and `gamma_abc[0]` is added to its
+            result. Points given in projective coordinates `[x, y, z]` are mapped to `[x/z, y/z]`, and the point at
+            infinity is returned as `[0, 0]`.
+        """
+        q = self.pairing_model.modulus
+        bases = []
+        for point in locking_key.gamma_abc:
+            if len(point) == 2:  # noqa: PLR2004
+                bases.append(list(point))
+            elif point[2] % q == 0:
+                bases.append([0, 0])
+            else:
+                z_inverse = pow(point[2], -1, q)
+                bases.append([point[0] * z_inverse % q, point[1] * z_inverse % q])
+        return bases
+
+    def public_inputs_to_hash_commitment(self, pub: list[int]) -> bytes:
+        """Construct the hash commitment to the public inputs checked by the verifier in public-input hashing mode.
+
+        Args:
+            pub (list[int]): The list of public statements, without the leading `1`.
+
+        Returns:
+            The hash commitment to `pub`, computed as `state_commitment(pub)` (see
+            `src.zkscript.util.verification_chain`).
+        """
+        return state_commitment(pub)
+
+    def __verify_public_inputs_commitment(
+        self, n_public_inputs: int, max_multipliers: list[int], public_inputs_commitment: bytes
+    ) -> Script:
+        """Script that verifies that the public inputs supplied in the unlocking script commit to a hash.
+
+        Stack input:
+            - stack:    [q, ..., a_l, gradients[a_l,gamma_abc[l]], ..., a_1, gradients[a_1,gamma_abc[1]]]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., a_l, gradients[a_l,gamma_abc[l]], ..., a_1, gradients[a_1,gamma_abc[1]]] or fail
+            - altstack: []
+
+        Args:
+            n_public_inputs (int): The number of public inputs `l`.
+            max_multipliers (list[int]): List where each element max_multipliers[i] is the max value of the i-th
+                public statement.
+            public_inputs_commitment (bytes): The commitment computed by `public_inputs_to_hash_commitment`.
+
+        Notes:
+            The public inputs are extracted from the unlocking data of the MSM, which must be generated with
+            `extractable_inputs = l`.
+        """
+        out = Script()
+        # stack out:    [q, ..., a_l, gradients[a_l,gamma_abc[l]], ..., a_1, gradients[a_1,gamma_abc[1]]]
+        # altstack out: [a_1, ..., a_l]
+        for i in range(n_public_inputs):
+            out += MsmWithFixedBasesUnlockingKey.extract_scalar_as_unsigned(
+                max_multipliers=max_multipliers, index=i, rolling_option=False
+            )
+            # The extracted scalar is 1 if a_i = 0: check the marker `marker_a_is_zero`
+            n_blocks = sum(int(log2(max_multipliers[j])) for j in range(i + 1))
+            out += pick(position=n_blocks * 4 + i + 1, n_elements=1)
+            out += Script.parse_string("OP_IF OP_DROP OP_0 OP_ENDIF OP_TOALTSTACK")
+
+        # Check the commitment
+        out += Script.parse_string("OP_FROMALTSTACK OP_HASH256")
+        out += Script.parse_string(" ".join(["OP_FROMALTSTACK OP_SWAP OP_CAT OP_HASH256"] * (n_public_inputs - 1)))
+        out.append_pushdata(public_inputs_commitment)
+        out += Script.parse_string("OP_EQUALVERIFY")
+
+        return out
+
+    def __verify_witness_binding(self, locking_key: Groth16LockingKey) -> Script:
+        """Script that verifies that the proof and the hints of the pairing in the unlocking script are bound.
+
+        Stack input:
+            - stack:    [q, ..., witness_commitment, inverse_miller_loop_triple_pairing,
+                            (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
+                                sum_(i=0)^l a_i * gamma_abc[i]]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., witness_commitment, inverse_miller_loop_triple_pairing,
+                            (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
+                                sum_(i=0)^l a_i * gamma_abc[i]] or fail
+            - altstack: []
+
+        Args:
+            locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
+                CRS needed by the verifier.
+
+        Notes:
+            The bound elements are the ones of the state of the verification chain between `q` and the MSM: the
+            inverse of the output of the Miller loop, the gradients of the pairing supplied in the unlocking script
+            (the gradients through the multiples of `B`, which are always supplied, and the ones of -gamma and -delta
+            if `locking_key.has_precomputed_gradients` is `False`), and `A, B, C`. Each of them must be in `[0, q)`,
+            and `witness_commitment` must be their `state_commitment` (see `src.zkscript.util.verification_chain`).
+        """
+        n_points_curve = self.pairing_model.N_POINTS_CURVE
+        # The bound elements are the state of the verification chain without q and the MSM
+        n_elements = self.verification_chain_state_size(locking_key) - 1 - n_points_curve
+
+        # The commitment is computed from the top to the bottom of the bound elements, as in `commit_to_state`
+        # stack in:  [q, ..., witness_commitment, x_0, .., x_(n_elements-1), sum_(i=0)^l a_i * gamma_abc[i]]
+        # stack out: [q, ..., witness_commitment, x_0, .., x_(n_elements-1), sum_(i=0)^l a_i * gamma_abc[i],
+        #               state_commitment([x_0, .., x_(n_elements-1)])] or fail
+        out = Script()
+        for i in range(n_elements):
+            # From the second element on, the partial commitment is on top of the stack
+            position = n_points_curve + i if i == 0 else n_points_curve + i + 1
+            # Check that x_(n_elements-1-i) is in [0, q)
+            out += pick(position=position, n_elements=1)
+            out += Script.parse_string("OP_0")
+            out += pick(position=-1, n_elements=1)
+            out += Script.parse_string("OP_WITHIN OP_VERIFY")
+            # Hash x_(n_elements-1-i) into the commitment
+            out += pick(position=position, n_elements=1)
+            out += Script.parse_string("OP_HASH256" if i == 0 else "OP_SWAP OP_CAT OP_HASH256")
+
+        # stack in:  [q, ..., witness_commitment, x_0, .., x_(n_elements-1), sum_(i=0)^l a_i * gamma_abc[i],
+        #               state_commitment([x_0, .., x_(n_elements-1)])]
+        # stack out: [q, ..., witness_commitment, x_0, .., x_(n_elements-1), sum_(i=0)^l a_i * gamma_abc[i]] or fail
+        out += pick(position=n_elements + n_points_curve + 1, n_elements=1)
+        out += Script.parse_string("OP_EQUALVERIFY")
+        return out
+
+    def __verify_g1_subgroup_membership(self) -> Script:
+        """Script that verifies that the proof elements A and C belong to G1.
+
+        Stack input:
+            - stack:    [q, ..., gradients_subgroup_a, A, B, gradients_subgroup_c, C, sum_(i=0)^l a_i * gamma_abc[i]]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., A, B, C, sum_(i=0)^l a_i * gamma_abc[i]] or fail
+            - altstack: []
+
+        Raises:
+            ValueError: If `self.subgroup_membership` is `None`.
+        """
+        if self.subgroup_membership is None:
+            msg = "Subgroup membership checks are not available for this curve"
+            raise ValueError(msg)
+
+        n_points_curve = self.pairing_model.N_POINTS_CURVE
+        n_points_twist = self.pairing_model.N_POINTS_TWIST
+
+        # stack in:     [q, ..., gradients_subgroup_a, A, B, gradients_subgroup_c, C, sum_(i=0)^l a_i * gamma_abc[i]]
+        # stack out:    [q, ..., gradients_subgroup_a, A, B, C]
+        # altstack out: [sum_(i=0)^l a_i * gamma_abc[i]]
+        out = Script.parse_string(" ".join(["OP_TOALTSTACK"] * n_points_curve))
+        out += self.subgroup_membership.g1_in_subgroup(check_constant=False, clean_constant=False, rolling_option=0)
+        out += Script.parse_string("OP_VERIFY")
+
+        # stack in:     [q, ..., gradients_subgroup_a, A, B, C]
+        # altstack in:  [sum_(i=0)^l a_i * gamma_abc[i]]
+        # stack out:    [q, ..., A]
+        # altstack out: [sum_(i=0)^l a_i * gamma_abc[i], C, B]
+        out += Script.parse_string(" ".join(["OP_TOALTSTACK"] * (n_points_curve + n_points_twist)))
+        out += self.subgroup_membership.g1_in_subgroup(check_constant=False, clean_constant=False, rolling_option=0)
+        out += Script.parse_string("OP_VERIFY")
+
+        # stack in:     [q, ..., A]
+        # altstack in:  [sum_(i=0)^l a_i * gamma_abc[i], C, B]
+        # stack out:    [q, ..., A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
+        # altstack out: []
+        out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * (2 * n_points_curve + n_points_twist)))
+
+        return out
+
+    def __subgroup_checks(self, check_subgroup_membership: bool, skip_subgroup_checks: bool) -> Script:
+        """Script that verifies that the proof elements A and C belong to G1, unless the checks are skipped.
+
+        Stack input:
+            - stack:    [q, ..., (gradients_subgroup_a if checked), A, B, (gradients_subgroup_c if checked), C,
+                            sum_(i=0)^l a_i * gamma_abc[i]]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., A, B, C, sum_(i=0)^l a_i * gamma_abc[i]] or fail
+            - altstack: []
+
+        Args:
+            check_subgroup_membership (bool): If `True`, check that A and C belong to G1.
+            skip_subgroup_checks (bool): If `True`, omit the checks requested by `check_subgroup_membership`.
+
+        Returns:
+            The script performing the checks, or an empty script if they are not requested or skipped.
+
+        Raises:
+            ValueError: If the checks are performed but `self.subgroup_membership` is `None`.
+        """
+        if not check_subgroup_membership:
+            return Script()
+
+        if skip_subgroup_checks:
+            logger.warning(
+                "Groth16 verifier generated without the requested subgroup checks: A and C must come from a trusted "
+                "prover"
+            )
+            return Script()
+
+        return self.__verify_g1_subgroup_membership()
+
+    def __gamma_abc_msm(
         self,
         locking_key: Groth16LockingKey,
         modulo_threshold: int,
-        extractable_inputs: int = 0,
-        max_multipliers: list[int] | None = None,
-        check_constant: bool | None = None,
-        clean_constant: bool | None = None,
+        extractable_inputs: int,
+        max_multipliers: list[int] | None,
+        positive_modulo: bool,
     ) -> Script:
-        """Groth16 verifier.
+        """Script that computes sum_(i=0)^l a_i * gamma_abc[i].
 
         Stack input:
-            - stack:    [q, ..., inverse_miller_loop_triple_pairing,
-                            (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
-                                gradient[gamma_abc[0], sum_(i=1)^l a_i * gamma_abc[i]],
-                                    gradient[sum_(i=1)^(l-1) a_i * gamma_abc[i], a_1 * gamma_abc[1]], ...,
-                                        gradient[a_(l-1) * gamma_abc[l-1], a_l * gamma_abc[l]],
-                                            a_2, gradients[a_2,gamma_abc[l]], ..., a_1, gradients[a_1,gamma_abc[1]]]
-
-                where:
-                - [a_i, gradients[a_i,gamma_abc[i]]] is the input required to execute `unrolled_multiplication` from
-                    EllipticCurveFqUnrolled (except for gamma_abc[i], which is hard coded into the script)
-                - gradient[sum_(i=1)^(j-1) a_i * gamma_abc[i], a_j * gamma_abc[j]] is the gradient through
-                    a_j * gamma_abc[j] and sum_(i=1)^(j-1) a_i * gamma_abc[i] to compute their sum
-                - gradients_pairing are the gradients needed to execute the method `self.triple_pairing()`
-                    (from the Pairing class) to compute the triple pairing. These may not be on the stack based on
-                    the value of `locking_key.has_precomputed_gradients`.
+            - stack:    [q, ..., gradient[gamma_abc[0], sum_(i=1)^l a_i * gamma_abc[i]],
+                            gradient[sum_(i=1)^(l-1) a_i * gamma_abc[i], a_1 * gamma_abc[1]], ...,
+                                gradient[a_(l-1) * gamma_abc[l-1], a_l * gamma_abc[l]],
+                                    a_2, gradients[a_2,gamma_abc[l]], ..., a_1, gradients[a_1,gamma_abc[1]]]
             - altstack: []
 
         Stack output:
-            - stack:    [q, ..., True/False]
+            - stack:    [q, ..., sum_(i=0)^l a_i * gamma_abc[i]]
             - altstack: []
 
         Args:
@@ -123,19 +436,10 @@ class Groth16:
                 CRS needed by the verifier.
             modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
             extractable_inputs (int): The number of public inputs which should be extractable in script.
-                Defaults to `0`.
-            max_multipliers (list[int]): List where each element max_multipliers[i] is the max value of the i-th public
-                statement.
-            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
-            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
-
-        Returns:
-            Script to verify the equation e(A,B) = alpha_beta * e(sum_(i=0)^(l) a_i * gamma_abc[i], gamma) * e(C, delta)
-            which we turn into  e(A,B) * e(sum_(i=0)^(l) a_i * gamma_abc[i], - gamma) * e(C, - delta) = alpha_beta.
-            The LHS of the equation is a triple pairing defined in bilinear_pairings/model/triple_pairing.py
-
-        Notes:
-            a_0 = 1.
+            max_multipliers (list[int] | None): List where each element max_multipliers[i] is the max value of the
+                i-th public statement. If `None`, all the public statements are bounded by `r`.
+            positive_modulo (bool): If `True`, the coordinates of the sum are reduced to their canonical
+                representatives.
         """
         max_multipliers = (
             max_multipliers if max_multipliers is not None else [self.r] * (len(locking_key.gamma_abc) - 1)
@@ -144,8 +448,7 @@ class Groth16:
         # Elliptic curve arithmetic
         ec_fq = EllipticCurveFq(q=self.pairing_model.modulus, curve_a=self.curve_a, curve_b=self.curve_b)
 
-        out = verify_bottom_constant(self.pairing_model.modulus) if check_constant else Script()
-
+        msm = Script()
         # stack in:     [q, ..., inverse_miller_loop_triple_pairing,
         #                   (gradients_pairing if not locking_key.has_precomputed_gradients),
         #                       A, B, C, gradient[gamma_abc[0], sum_(i=1)^l a_i * gamma_abc[i]],
@@ -156,7 +459,7 @@ class Groth16:
         #                   (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
         #                       gradient[gamma_abc[0], sum_(i=1)^l a_i * gamma_abc[i]],
         #                           sum_(i=1)^l a_i * gamma_abc[i]]
-        out += ec_fq.msm_with_fixed_bases(
+        msm += ec_fq.msm_with_fixed_bases(
             bases=locking_key.gamma_abc[1:],
             max_multipliers=max_multipliers,
             modulo_threshold=modulo_threshold,
@@ -168,7 +471,7 @@ class Groth16:
         )
 
         # Load gamma_abc[0] to the stack
-        out += nums_to_script(locking_key.gamma_abc[0])
+        msm += nums_to_script(locking_key.gamma_abc[0])
 
         # stack in:    [q, ..., inverse_miller_loop_triple_pairing,
         #                  (gradients_pairing if not locking_key.has_precomputed_gradients),
@@ -177,22 +480,187 @@ class Groth16:
         # stack out:   [q, ..., inverse_miller_loop_triple_pairing,
         #                  (gradients_pairing if not locking_key.has_precomputed_gradients),
         #                      A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
-        out += ec_fq.point_addition_with_unknown_points(
-            take_modulo=True, positive_modulo=False, check_constant=False, clean_constant=False
+        msm += ec_fq.point_addition_with_unknown_points(
+            take_modulo=True, positive_modulo=positive_modulo, check_constant=False, clean_constant=False
+        )
+
+        return msm
+
+    def __check_stack_limits(self, script: Script, stack_limits: tuple[int, int] | None) -> None:
+        """Check that `script` stays within `stack_limits`, if given.
+
+        Args:
+            script (Script): The script to check.
+            stack_limits (tuple[int, int] | None): The pair `(max_elements, max_element_bytes)`, or `None` to skip
+                the check.
+
+        Raises:
+            ValueError: If `script` exceeds `stack_limits`.
+        """
+        if stack_limits is None:
+            return
+        # The elements supplied by the unlocking script are at most as big as the modulus
+        violations = check_stack_limits(
+            script,
+            *stack_limits,
+            unknown_element_bytes=script_num_size(self.pairing_model.modulus),
+            max_violations=MAX_REPORTED_VIOLATIONS,
+        )
+        if violations:
+            msg = "The Groth16 verifier exceeds the stack limits:\n" + "\n".join(violations)
+            raise ValueError(msg)
+
+    def groth16_verifier(
+        self,
+        locking_key: Groth16LockingKey,
+        modulo_threshold: int,
+        extractable_inputs: int = 0,
+        max_multipliers: list[int] | None = None,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+        check_subgroup_membership: bool = False,
+        skip_subgroup_checks: bool = False,
+        debug_labels: bool = False,
+        stack_limits: tuple[int, int] | None = None,
+        commitment_chunk_size: int | None = None,
+        public_inputs_commitment: bytes | None = None,
+        terminal: bool = False,
+        bind_witness: bool = False,
+    ) -> Script | tuple[Script, list[tuple[int, str]]]:
+        """Groth16 verifier.
+
+        Stack input:
+            - stack:    [q, ..., (witness_commitment if bind_witness), inverse_miller_loop_triple_pairing,
+                            (gradients_pairing if not locking_key.has_precomputed_gradients),
+                                (gradients_subgroup_a if subgroup checked), A, B,
+                                    (gradients_subgroup_c if subgroup checked), C,
+                                gradient[gamma_abc[0], sum_(i=1)^l a_i * gamma_abc[i]],
+                                    gradient[sum_(i=1)^(l-1) a_i * gamma_abc[i], a_1 * gamma_abc[1]], ...,
+                                        gradient[a_(l-1) * gamma_abc[l-1], a_l * gamma_abc[l]],
+                                            a_2, gradients[a_2,gamma_abc[l]], ..., a_1, gradients[a_1,gamma_abc[1]]]
+
+                where:
+                - [a_i, gradients[a_i,gamma_abc[i]]] is the input required to execute `unrolled_multiplication` from
+                    EllipticCurveFqUnrolled (except for gamma_abc[i], which is hard coded into the script)
+                - gradient[sum_(i=1)^(j-1) a_i * gamma_abc[i], a_j * gamma_abc[j]] is the gradient through
+                    a_j * gamma_abc[j] and sum_(i=1)^(j-1) a_i * gamma_abc[i] to compute their sum
+                - gradients_pairing are the gradients needed to execute the method `self.triple_pairing()`
+                    (from the Pairing class) to compute the triple pairing. These may not be on the stack based on
+                    the value of `locking_key.has_precomputed_gradients`.
+                - gradients_subgroup_a and gradients_subgroup_c are the gradients needed to execute the method
+                    `g1_in_subgroup` (from the SubgroupMembership class) for A and C, respectively.
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, ..., (witness_commitment if bind_witness), True/False] ([q, ...,
+                            (witness_commitment if bind_witness)] or fail if `terminal`)
+            - altstack: []
+
+        Args:
+            locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
+                CRS needed by the verifier.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            extractable_inputs (int): The number of public inputs which should be extractable in script.
+                Defaults to `0`.
+            max_multipliers (list[int]): List where each element max_multipliers[i] is the max value of the i-th public
+                statement.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            check_subgroup_membership (bool): If `True`, check that A and C belong to G1. Defaults to `False`.
+            skip_subgroup_checks (bool): If `True`, omit the checks requested by `check_subgroup_membership`, which
+                produces a smaller verifier. Only safe if A and C come from a trusted prover, see the notes below.
+                Skipping the checks is logged as a warning. Defaults to `False`.
+            debug_labels (bool): If `True`, also return the list of `(byte_offset, label)` pairs marking the
+                boundaries of the logical stages of the verifier. Defaults to `False`.
+            stack_limits (tuple[int, int] | None): If not `None`, the pair `(max_elements, max_element_bytes)` used to
+                statically check the verifier with `check_stack_limits`. Defaults to `None`.
+            commitment_chunk_size (int | None): If `locking_key.has_precomputed_gradients` is `False`, the number of
+                iterations of the Miller loop whose gradients are committed to by each hash. Chunking the commitment
+                makes the verifier slightly larger, but localises invalid gradients. Defaults to `None`, meaning that
+                all the gradients are committed to by a single hash.
+            public_inputs_commitment (bytes | None): If not `None`, the verifier runs in public-input hashing mode:
+                it checks that the public inputs supplied in the unlocking script commit to
+                `public_inputs_commitment` (see `public_inputs_to_hash_commitment`) before computing the MSM. All the
+                public inputs are then extractable, and `extractable_inputs` is ignored: the unlocking script must be
+                generated with `extractable_inputs = l`. Defaults to `None`.
+            terminal (bool): If `True`, the final check of the verifier uses `OP_EQUALVERIFY`, so that the script fails
+                if the proof is not valid and leaves nothing on the stack: the verifier can be followed by other checks
+                (e.g., a signature check) without dropping its result. If `False`, the verifier leaves the result of
+                the final check on the stack, so that it can be combined with other boolean checks. Defaults to
+                `False`.
+            bind_witness (bool): If `True`, the verifier checks that the inverse of the output of the Miller loop, the
+                gradients of the pairing supplied in the unlocking script and the proof `(A, B, C)` hash to the
+                `witness_commitment` supplied in the unlocking script (see `Groth16UnlockingKey.witness_commitment`)
+                before computing the pairing. This binds the hints to the proof, see
+                `groth16_verifier_with_precomputed_msm`. Defaults to `False`.
+
+        Returns:
+            Script to verify the equation e(A,B) = alpha_beta * e(sum_(i=0)^(l) a_i * gamma_abc[i], gamma) * e(C, delta)
+            which we turn into  e(A,B) * e(sum_(i=0)^(l) a_i * gamma_abc[i], - gamma) * e(C, - delta) = alpha_beta.
+            The LHS of the equation is a triple pairing defined in bilinear_pairings/model/triple_pairing.py
+            If `debug_labels` is `True`, the script is returned together with the list of labels.
+
+        Raises:
+            ValueError: If the subgroup checks are performed but `self.subgroup_membership` is `None`, if
+                `stack_limits` is not `None` and the verifier exceeds them, or if `commitment_chunk_size` is not
+                positive.
+
+        Notes:
+            a_0 = 1.
+            In public-input hashing mode, the public inputs are bound by the locking script through a single 32-byte
+            commitment, while the spender supplies them as part of the data of the MSM.
+            The subgroup checks are performed if `check_subgroup_membership` is `True` and `skip_subgroup_checks` is
+            `False`: the default behaviour is not affected by `skip_subgroup_checks`. Without the checks, the verifier
+            only checks that A and C are points of the curve. If G1 has a non-trivial cofactor (e.g., BLS12-381), a
+            malicious prover could then supply points outside G1, for which the pairing equation does not carry the
+            guarantees of Groth16. `skip_subgroup_checks` must only be used if A and C are trusted to belong to G1,
+            e.g., because the proof is generated by the same party that generates the locking script. The unlocking
+            script must then be generated without `gradients_subgroup_a` and `gradients_subgroup_c`.
+        """
+        out = verify_bottom_constant(self.pairing_model.modulus) if check_constant else Script()
+
+        if public_inputs_commitment is not None:
+            n_public_inputs = len(locking_key.gamma_abc) - 1
+            extractable_inputs = n_public_inputs
+            out += self.__verify_public_inputs_commitment(
+                n_public_inputs=n_public_inputs,
+                max_multipliers=max_multipliers if max_multipliers is not None else [self.r] * n_public_inputs,
+                public_inputs_commitment=public_inputs_commitment,
+            )
+
+        msm = self.__gamma_abc_msm(
+            locking_key=locking_key,
+            modulo_threshold=modulo_threshold,
+            extractable_inputs=extractable_inputs,
+            max_multipliers=max_multipliers,
+            positive_modulo=False,
         )
 
         # stack in:    [q, ..., inverse_miller_loop_triple_pairing,
         #                  (gradients_pairing if not locking_key.has_precomputed_gradients),
         #                      A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
         # stack out: [q, ..., 0/1]
-        out += self.groth16_verifier_with_precomputed_msm(
+        verifier = self.groth16_verifier_with_precomputed_msm(
             locking_key=locking_key,
             modulo_threshold=modulo_threshold,
             check_constant=False,
             clean_constant=clean_constant,
+            check_subgroup_membership=check_subgroup_membership,
+            skip_subgroup_checks=skip_subgroup_checks,
+            debug_labels=debug_labels,
+            commitment_chunk_size=commitment_chunk_size,
+            terminal=terminal,
+            bind_witness=bind_witness,
         )
 
-        return optimise_script(out)
+        if debug_labels:
+            script, labels = optimise_labelled_scripts([(out, []), (msm, [(0, "gamma_abc_msm")]), verifier])
+            self.__check_stack_limits(script, stack_limits)
+            return script, labels
+
+        out = optimise_script(out + msm + verifier)
+        self.__check_stack_limits(out, stack_limits)
+        return out
 
     def groth16_verifier_with_precomputed_msm(
         self,
@@ -200,22 +668,34 @@ class Groth16:
         modulo_threshold: int,
         check_constant: bool | None = None,
         clean_constant: bool | None = None,
-    ) -> Script:
+        check_subgroup_membership: bool = False,
+        skip_subgroup_checks: bool = False,
+        debug_labels: bool = False,
+        stack_limits: tuple[int, int] | None = None,
+        commitment_chunk_size: int | None = None,
+        terminal: bool = False,
+        bind_witness: bool = False,
+    ) -> Script | tuple[Script, list[tuple[int, str]]]:
         """Groth16 verifier.
 
         Stack input:
-            - stack:    [q, ..., inverse_miller_loop_triple_pairing,
+            - stack:    [q, ..., (witness_commitment if bind_witness), inverse_miller_loop_triple_pairing,
                             (gradients_pairing if not locking_key.has_precomputed_gradients),
-                                A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
+                                (gradients_subgroup_a if subgroup checked), A, B,
+                                    (gradients_subgroup_c if subgroup checked), C,
+                                        sum_(i=0)^l a_i * gamma_abc[i]]
                 where:
                 - gradients_pairing are the gradients needed to execute the method `self.triple_pairing()`
                     (from the Pairing class) to compute the triple pairing. If `locking_key.has_precomputed_gradients`
                     is `False`, these are already on the stack, and are verified at the end of the script. If `True`,
                     they are injected during the execution of the triple pairing.
+                - gradients_subgroup_a and gradients_subgroup_c are the gradients needed to execute the method
+                    `g1_in_subgroup` (from the SubgroupMembership class) for A and C, respectively.
             - altstack: []
 
         Stack output:
-            - stack:    [q, ..., True/False]
+            - stack:    [q, ..., (witness_commitment if bind_witness), True/False] ([q, ...,
+                            (witness_commitment if bind_witness)] or fail if `terminal`)
             - altstack: []
 
         Args:
@@ -224,17 +704,67 @@ class Groth16:
             modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
             check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
             clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            check_subgroup_membership (bool): If `True`, check that A and C belong to G1. Defaults to `False`.
+            skip_subgroup_checks (bool): If `True`, omit the checks requested by `check_subgroup_membership`, see
+                `groth16_verifier`. Defaults to `False`.
+            debug_labels (bool): If `True`, also return the list of `(byte_offset, label)` pairs marking the
+                boundaries of the logical stages of the verifier. Defaults to `False`.
+            stack_limits (tuple[int, int] | None): If not `None`, the pair `(max_elements, max_element_bytes)` used to
+                statically check the verifier with `check_stack_limits`. Defaults to `None`.
+            commitment_chunk_size (int | None): If `locking_key.has_precomputed_gradients` is `False`, the number of
+                iterations of the Miller loop whose gradients are committed to by each hash. Chunking the commitment
+                makes the verifier slightly larger, but localises invalid gradients. Defaults to `None`, meaning that
+                all the gradients are committed to by a single hash.
+            terminal (bool): If `True`, the final check of the verifier uses `OP_EQUALVERIFY` and leaves nothing on
+                the stack, see `groth16_verifier`. Defaults to `False`.
+            bind_witness (bool): If `True`, the verifier checks that the hints of the pairing and the proof supplied in
+                the unlocking script hash to the `witness_commitment` supplied below them, so that the hints are bound
+                to the proof, see the notes below. Defaults to `False`.
 
         Returns:
             Script to verify the equation e(A,B) = alpha_beta * e(sum_(i=0)^(l) a_i * gamma_abc[i], gamma) * e(C, delta)
             which we turn into  e(A,B) * e(sum_(i=0)^(l) a_i * gamma_abc[i], - gamma) * e(C, - delta) = alpha_beta.
             The LHS of the equation is a triple pairing defined in bilinear_pairings/model/triple_pairing.py
+            If `debug_labels` is `True`, the script is returned together with the list of labels.
+
+        Raises:
+            ValueError: If the subgroup checks are performed but `self.subgroup_membership` is `None`, if
+                `stack_limits` is not `None` and the verifier exceeds them, or if `commitment_chunk_size` is not
+                positive.
 
         Notes:
             a_0 = 1.
+            The soundness of the verifier relies on the checks performed while computing the pairing: the gradients
+            through the multiples of `B` are verified at each step of the Miller loop, the inverse of the output of
+            the Miller loop is verified against the output computed by the script, and the gradients of -gamma and
+            -delta are either hard-coded or checked against the gradients hash commitments. These checks hold modulo
+            `q`, so they do not fix the representative of each hint, and they are performed step by step, late in the
+            script. With `bind_witness`, the hints and the proof are bound before any of them is used: each of them
+            must be in `[0, q)`, and `witness_commitment = state_commitment([inverse_miller_output, gradients_pairing,
+            A, B, C])` must hold. The verifier is generated without knowing the proof, so any valid proof is accepted
+            together with its own hints and commitment. For a fixed commitment, the collision resistance of
+            `hash256` prevents the spender from supplying other hints or another proof, e.g., the hints of a
+            different valid proof, and the range checks make the commitment a function of the hints and the proof
+            alone. The commitment is left on the stack below the result, so that the rest of the locking script can
+            tie it to the proof committed to elsewhere, e.g., in a previous transaction of a verification chain.
+            The binding does not cover the gradients of the subgroup membership checks, which are verified before
+            it, nor the MSM, which is computed by the locking script from the public inputs.
         """
         out = verify_bottom_constant(self.pairing_model.modulus) if check_constant else Script()
 
+        out += self.__subgroup_checks(
+            check_subgroup_membership=check_subgroup_membership, skip_subgroup_checks=skip_subgroup_checks
+        )
+
+        # stack in:  [q, ..., witness_commitment, inverse_miller_loop_triple_pairing,
+        #                (gradients_pairing if not locking_key.has_precomputed_gradients),
+        #                    A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
+        # stack out: [q, ..., witness_commitment, inverse_miller_loop_triple_pairing,
+        #                (gradients_pairing if not locking_key.has_precomputed_gradients),
+        #                    A, B, C, sum_(i=0)^l a_i * gamma_abc[i]] or fail
+        if bind_witness:
+            out += self.__verify_witness_binding(locking_key=locking_key)
+
         # stack in:  [q, ..., inverse_miller_loop_triple_pairing,
         #                (gradients_pairing if not locking_key.has_precomputed_gradients),
         #                    A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
@@ -258,7 +788,7 @@ class Groth16:
         # altstack in: [(gradients_pairing if locking_key.has_precomputed_gradients)]
         # stack out: [q, ..., (gradients_pairing if not locking_key.has_precomputed_gradients),
         #                   pairing(A,B) * pairing(sum_(i=0)^(l) a_i * gamma_abc[i], -gamma) * pairing(C, -delta)]
-        out += self.pairing_model.triple_pairing(
+        pairing = self.pairing_model.triple_pairing(
             modulo_threshold=modulo_threshold,
             positive_modulo=True,
             verify_gradients=(True, False, False),
@@ -266,17 +796,19 @@ class Groth16:
             clean_constant=clean_constant,
             is_precomputed_gradients_on_stack=not locking_key.has_precomputed_gradients,
             precomputed_gradients=locking_key.gradients_pairings,
+            debug_labels=debug_labels,
         )
 
+        pairing_check = Script()
         # Verify pairing(A,B) * pairing(sum_(i=0)^(l) a_i * gamma_abc[i], -gamma) * pairing(C, -delta) == alpha_beta
         # stack in:  [q, ..., (gradients_pairing if not locking_key.has_precomputed_gradients),
         #                   pairing(A,B) * pairing(sum_(i=0)^(l) a_i * gamma_abc[i], -gamma) * pairing(C, -delta)]
         # stack out: [q, ..., 0/1] if locking_key.has_precomputed_gradients else ([q, ..., gradients_pairing] or fail)
         for i, el in enumerate(locking_key.alpha_beta[::-1]):
-            out += nums_to_script([el])
-            out += (
+            pairing_check += nums_to_script([el])
+            pairing_check += (
                 Script.parse_string("OP_EQUAL")
-                if locking_key.has_precomputed_gradients and i == len(locking_key.alpha_beta) - 1
+                if locking_key.has_precomputed_gradients and i == len(locking_key.alpha_beta) - 1 and not terminal
                 else Script.parse_string("OP_EQUALVERIFY")
             )
 
@@ -285,11 +817,152 @@ class Groth16:
         # stack in:  [q, ..., gradients_pairing] if not locking_key.has_precomputed_gradients
         # stack out: [q, ..., 0/1]
         if not locking_key.has_precomputed_gradients:
-            # Hash used to verify the gradients of -gamma and -delta
-            verification_hash = self.__gradients_to_hash_commitment(locking_key=locking_key)
-            out += self.__verify_hash_commitment(locking_key=locking_key, verification_hash=verification_hash)
+            # Hashes used to verify the gradients of -gamma and -delta
+            verification_hashes = self.gradients_to_hash_commitments(
+                locking_key=locking_key, commitment_chunk_size=commitment_chunk_size
+            )
+            pairing_check += self.__verify_hash_commitment(
+                locking_key=locking_key,
+                verification_hashes=verification_hashes,
+                commitment_chunk_size=commitment_chunk_size,
+                terminal=terminal,
+            )
+
+        if debug_labels:
+            script, labels = optimise_labelled_scripts([(out, []), pairing, (pairing_check, [])])
+            self.__check_stack_limits(script, stack_limits)
+            return script, labels
+
+        out = optimise_script(out + pairing + pairing_check)
+        self.__check_stack_limits(out, stack_limits)
+        return out
+
+    def verification_chain_state_size(self, locking_key: Groth16LockingKey) -> int:
+        """Return the number of elements of the state passed between the stages of the Groth16 verification chain.
+
+        Args:
+            locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
+                CRS needed by the verifier.
+
+        Returns:
+            The number of elements of the state `[q, inverse_miller_loop_triple_pairing,
+            (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
+            sum_(i=0)^l a_i * gamma_abc[i]]` computed by `groth16_verifier_msm_stage`.
+        """
+        n_gradients = sum(len(gradients) for gradients in locking_key.gradients_pairings[0])
+        n_gradients *= 1 if locking_key.has_precomputed_gradients else 3
+        return (
+            1
+            + self.pairing_model.N_ELEMENTS_MILLER_OUTPUT
+            + n_gradients * self.pairing_model.extension_degree
+            + 3 * self.pairing_model.N_POINTS_CURVE
+            + self.pairing_model.N_POINTS_TWIST
+        )
+
+    def groth16_verifier_msm_stage(
+        self,
+        locking_key: Groth16LockingKey,
+        modulo_threshold: int,
+        max_multipliers: list[int] | None = None,
+        check_constant: bool | None = None,
+    ) -> Script:
+        """First stage of the Groth16 verification chain: compute sum_(i=0)^l a_i * gamma_abc[i].
+
+        The Groth16 verifier is split in two stages, executed in different spends: this stage computes the MSM of the
+        public inputs, and `groth16_verifier_pairing_stage` executes `groth16_verifier_with_precomputed_msm`. The
+        stages are bound together by the commitment to the state at the end of this stage, see
+        `src.zkscript.util.verification_chain`.
+
+        Stack input:
+            - stack:    [q, inverse_miller_loop_triple_pairing,
+                            (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
+                                gradient[gamma_abc[0], sum_(i=1)^l a_i * gamma_abc[i]],
+                                    gradient[sum_(i=1)^(l-1) a_i * gamma_abc[i], a_1 * gamma_abc[1]], ...,
+                                        gradient[a_(l-1) * gamma_abc[l-1], a_l * gamma_abc[l]],
+                                            a_2, gradients[a_2,gamma_abc[l]], ..., a_1, gradients[a_1,gamma_abc[1]]]
+            - altstack: []
+
+        Stack output:
+            - stack:    [state_commitment([q, inverse_miller_loop_triple_pairing,
+                            (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
+                                sum_(i=0)^l a_i * gamma_abc[i]])]
+            - altstack: []
+
+        Args:
+            locking_key (Groth16LockingKey): Locking key used to generate the verifier. Encapsulates the data of the
+                CRS needed by the verifier.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            max_multipliers (list[int]): List where each element max_multipliers[i] is the max value of the i-th public
+                statement.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+
+        Returns:
+            The locking script of the first stage of the verification chain.
+
+        Notes:
+            The coordinates of sum_(i=0)^l a_i * gamma_abc[i] are reduced to their canonical representatives, so
+            that the state commitment is uniquely determined by the proof and the public inputs.
+        """
+        out = verify_bottom_constant(self.pairing_model.modulus) if check_constant else Script()
+        out += self.__gamma_abc_msm(
+            locking_key=locking_key,
+            modulo_threshold=modulo_threshold,
+            extractable_inputs=0,
+            max_multipliers=max_multipliers,
+            positive_modulo=True,
+        )
+        return optimise_script(
+            chain_stage(script=out, n_elements_out=self.verification_chain_state_size(locking_key))
+        )
+
+    def groth16_verifier_pairing_stage(
+        self,
+        locking_key: Groth16LockingKeyWithPrecomputedMsm,
+        modulo_threshold: int,
+        input_commitment: bytes,
+        clean_constant: bool | None = None,
+        commitment_chunk_size: int | None = None,
+    ) -> Script:
+        """Second stage of the Groth16 verification chain: verify the proof given the MSM of the public inputs.
+
+        Stack input:
+            - stack:    [q, inverse_miller_loop_triple_pairing,
+                            (gradients_pairing if not locking_key.has_precomputed_gradients), A, B, C,
+                                sum_(i=0)^l a_i * gamma_abc[i]]
+            - altstack: []
+
+        Stack output:
+            - stack:    [(q if not clean_constant), True/False] or fail
+            - altstack: []
+
+        Args:
+            locking_key (Groth16LockingKeyWithPrecomputedMsm): Locking key used to generate the verifier.
+                Encapsulates the data of the CRS needed by the verifier.
+            modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
+            input_commitment (bytes): The commitment computed by `groth16_verifier_msm_stage`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            commitment_chunk_size (int | None): If `locking_key.has_precomputed_gradients` is `False`, the number of
+                iterations of the Miller loop whose gradients are committed to by each hash. Defaults to `None`,
+                meaning that all the gradients are committed to by a single hash.
 
-        return optimise_script(out)
+        Returns:
+            The locking script of the second stage of the verification chain.
+
+        Notes:
+            `q` is not checked, as it is part of the state committed to by `input_commitment`.
+        """
+        verifier = self.groth16_verifier_with_precomputed_msm(
+            locking_key=locking_key,
+            modulo_threshold=modulo_threshold,
+            check_constant=False,
+            clean_constant=clean_constant,
+            commitment_chunk_size=commitment_chunk_size,
+        )
+        return chain_stage(
+            script=verifier,
+            n_elements_in=self.verification_chain_state_size(locking_key),
+            input_commitment=input_commitment,
+        )
 
     def groth16_verifier_proj(
         self,
@@ -299,7 +972,9 @@ class Groth16:
         max_multipliers: list[int] | None = None,
         check_constant: bool | None = None,
         clean_constant: bool | None = None,
-    ) -> Script:
+        terminal: bool = False,
+        debug_labels: bool = False,
+    ) -> Script | tuple[Script, list[tuple[int, str]]]:
         """Groth16 verifier with projective coordinates.
 
         Stack input:
@@ -307,7 +982,7 @@ class Groth16:
             - altstack: []
 
         Stack output:
-            - stack:    [q, ..., True/False]
+            - stack:    [q, ..., True/False] ([q, ...] or fail if `terminal`)
             - altstack: []
 
         Args:
@@ -320,11 +995,16 @@ class Groth16:
                 statement.
             check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
             clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            terminal (bool): If `True`, the final check of the verifier uses `OP_EQUALVERIFY` and leaves nothing on
+                the stack, see `groth16_verifier`. Defaults to `False`.
+            debug_labels (bool): If `True`, also return the list of `(byte_offset, label)` pairs marking the
+                boundaries of the logical stages of the verifier, see `groth16_verifier`. Defaults to `False`.
 
         Returns:
             Script to verify the equation e(A,B) = alpha_beta * e(sum_(i=0)^(l) a_i * gamma_abc[i], gamma) * e(C, delta)
             which we turn into  e(A,B) * e(sum_(i=0)^(l) a_i * gamma_abc[i], - gamma) * e(C, - delta) = alpha_beta.
             The LHS of the equation is a triple pairing defined in bilinear_pairings/model/triple_pairing.py
+            If `debug_labels` is `True`, the script is returned together with the list of labels.
 
         Notes:
             a_0 = 1.
@@ -340,7 +1020,7 @@ class Groth16:
 
         # stack in:     [q, ..., inverse_miller_loop_triple_pairing, A, B, C, ..., a_2, a_1],
         # stack out:    [q, ..., inverse_miller_loop_triple_pairing, A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
-        out += ec_fq.msm_with_fixed_bases(
+        msm = ec_fq.msm_with_fixed_bases(
             bases=locking_key.gamma_abc[1:],
             max_multipliers=max_multipliers,
             take_modulo=True,
@@ -351,20 +1031,20 @@ class Groth16:
         )
 
         # Load gamma_abc[0] to the stack
-        out += nums_to_script(locking_key.gamma_abc[0])
+        msm += nums_to_script(locking_key.gamma_abc[0])
 
         # if gamma_abc[0] is affine, it is mapped to
         if len(locking_key.gamma_abc[0]) == 2:  # noqa PLR2004
-            out += Script.parse_string("OP_1")
+            msm += Script.parse_string("OP_1")
 
         # Sum gamma_abc[0] (a_0 = 1)
-        out += ec_fq.point_addition_with_unknown_points(
+        msm += ec_fq.point_addition_with_unknown_points(
             take_modulo=True, positive_modulo=False, check_constant=False, clean_constant=False
         )
 
         # Convert sum_(i=0)^l a_i * gamma_abc[i] in affine coordinates
         # Compute the inverse of the z coordinate
-        out += self.pairing_model.inverse_fq(
+        msm += self.pairing_model.inverse_fq(
             take_modulo=True,
             positive_modulo=False,
             check_constant=False,
@@ -374,18 +1054,23 @@ class Groth16:
             mod_frequency=modulo_threshold // (self.pairing_model.modulus.bit_length() * 3 + 3),
         )
         # Multiply x and y for z^-1
-        out += Script.parse_string("OP_TUCK OP_MUL OP_TOALTSTACK OP_MUL OP_FROMALTSTACK")
+        msm += Script.parse_string("OP_TUCK OP_MUL OP_TOALTSTACK OP_MUL OP_FROMALTSTACK")
 
         # stack in:    [q, ..., inverse_miller_loop_triple_pairing, A, B, C, sum_(i=0)^l a_i * gamma_abc[i]]
         # stack out: [q, ..., 0/1]
-        out += self.groth16_verifier_proj_with_precomputed_msm(
+        verifier = self.groth16_verifier_proj_with_precomputed_msm(
             locking_key=locking_key,
             modulo_threshold=modulo_threshold,
             check_constant=False,
             clean_constant=clean_constant,
+            terminal=terminal,
+            debug_labels=debug_labels,
         )
 
-        return optimise_script(out)
+        if debug_labels:
+            return optimise_labelled_scripts([(out, []), (msm, [(0, "gamma_abc_msm")]), verifier])
+
+        return optimise_script(out + msm + verifier)
 
     def groth16_verifier_proj_with_precomputed_msm(
         self,
@@ -393,7 +1078,9 @@ class Groth16:
         modulo_threshold: int,
         check_constant: bool | None = None,
         clean_constant: bool | None = None,
-    ) -> Script:
+        terminal: bool = False,
+        debug_labels: bool = False,
+    ) -> Script | tuple[Script, list[tuple[int, str]]]:
         """Groth16 verifier.
 
         Stack input:
@@ -401,7 +1088,7 @@ class Groth16:
             - altstack: []
 
         Stack output:
-            - stack:    [q, ..., True/False]
+            - stack:    [q, ..., True/False] ([q, ...] or fail if `terminal`)
             - altstack: []
 
         Args:
@@ -410,11 +1097,16 @@ class Groth16:
             modulo_threshold (int): Bit-length threshold. Values whose bit-length exceeds it are reduced modulo `q`.
             check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
             clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+            terminal (bool): If `True`, the final check of the verifier uses `OP_EQUALVERIFY` and leaves nothing on
+                the stack, see `groth16_verifier`. Defaults to `False`.
+            debug_labels (bool): If `True`, also return the list of `(byte_offset, label)` pairs marking the
+                boundaries of the logical stages of the verifier, see `groth16_verifier`. Defaults to `False`.
 
         Returns:
             Script to verify the equation e(A,B) = alpha_beta * e(sum_(i=0)^(l) a_i * gamma_abc[i], gamma) * e(C, delta)
             which we turn into  e(A,B) * e(sum_(i=0)^(l) a_i * gamma_abc[i], - gamma) * e(C, - delta) = alpha_beta.
             The LHS of the equation is a triple pairing defined in bilinear_pairings/model/triple_pairing.py
+            If `debug_labels` is `True`, the script is returned together with the list of labels.
 
         Notes:
             a_0 = 1.
@@ -438,23 +1130,28 @@ class Groth16:
         # stack in:  [q, ..., inverse_miller_loop_triple_pairing, A, sum_(i=0)^l a_i * gamma_abc[i], C, B,
         #               -gamma, -delta]
         # stack out: [q, ..., pairing(A,B) * pairing(sum_(i=0)^(l) a_i * gamma_abc[i], -gamma) * pairing(C, -delta)]
-        out += self.pairing_model.triple_pairing(
+        pairing = self.pairing_model.triple_pairing(
             modulo_threshold=modulo_threshold,
             positive_modulo=True,
             check_constant=False,
             clean_constant=clean_constant,
             is_miller_loop_proj=True,
+            debug_labels=debug_labels,
         )
 
+        pairing_check = Script()
         # Verify pairing(A,B) * pairing(sum_(i=0)^(l) a_i * gamma_abc[i], -gamma) * pairing(C, -delta) == alpha_beta
         # stack in:  [q, ..., pairing(A,B) * pairing(sum_(i=0)^(l) a_i * gamma_abc[i], -gamma) * pairing(C, -delta)]
         # stack out: [q, ..., 0/1]
         for i, el in enumerate(locking_key.alpha_beta[::-1]):
-            out += nums_to_script([el])
-            out += (
+            pairing_check += nums_to_script([el])
+            pairing_check += (
                 Script.parse_string("OP_EQUAL")
-                if i == len(locking_key.alpha_beta) - 1
+                if i == len(locking_key.alpha_beta) - 1 and not terminal
                 else Script.parse_string("OP_EQUALVERIFY")
             )
 
-        return optimise_script(out)
+        if debug_labels:
+            return optimise_labelled_scripts([(out, []), pairing, (pairing_check, [])])
+
+        return optimise_script(out + pairing + pairing_check)
@@ -142,6 +142,7 @@ class RefTx:
                 max_multipliers=max_multipliers,
                 check_constant=check_constant,
                 clean_constant=True,
+                terminal=True,
             )
         else:
             out += self.groth16_model.groth16_verifier(
@@ -151,8 +152,8 @@ class RefTx:
                 max_multipliers=max_multipliers,
                 check_constant=check_constant,
                 clean_constant=True,
+                terminal=True,
             )
-        out += Script.parse_string("OP_VERIFY")
 
         # stack in:     [..]
         # altstack in:  [chunks(sighash(stx))]
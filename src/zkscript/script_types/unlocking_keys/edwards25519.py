@@ -0,0 +1,50 @@
+"""Unlocking keys for edwards25519."""
+
+from dataclasses import dataclass
+
+from tx_engine import Script
+
+from src.zkscript.elliptic_curves.edwards25519.parameters import N_BITS_SCALAR, q
+from src.zkscript.elliptic_curves.edwards25519.util import ENCODING_BYTES, decompress_point, eddsa_challenge
+from src.zkscript.util.utility_scripts import nums_to_script
+
+
+@dataclass
+class EdDSAUnlockingKey:
+    """Class encapsulating the data required to generate an unlocking script for Ed25519 signature verification.
+
+    Attributes:
+        pubkey (bytes): The 32-byte encoding of the public key.
+        msg (bytes): The signed message.
+        signature (bytes): The 64-byte signature `R || S`.
+    """
+
+    pubkey: bytes
+    msg: bytes
+    signature: bytes
+
+    def to_unlocking_script(self, append_constants: bool = True) -> Script:
+        """Return the unlocking script required by `Edwards25519.verify_eddsa`.
+
+        Args:
+            append_constants (bool): If `True`, loads the constant required by `Edwards25519.verify_eddsa`.
+                Defaults to `True`.
+
+        Raises:
+            ValueError: If the first half of `signature` is not the encoding of a point of edwards25519.
+        """
+        r_encoding, s_encoding = self.signature[:ENCODING_BYTES], self.signature[ENCODING_BYTES:]
+        s = int.from_bytes(s_encoding, "little")
+        k = eddsa_challenge(r_encoding, self.pubkey, self.msg)
+
+        out = Script()
+        if append_constants:
+            out += nums_to_script([q])
+
+        out += nums_to_script([(s >> i) & 1 for i in range(N_BITS_SCALAR)])
+        out += nums_to_script([(k >> i) & 1 for i in range(N_BITS_SCALAR)])
+        out.append_pushdata(s_encoding)
+        out += nums_to_script([decompress_point(r_encoding)[0]])
+        out.append_pushdata(r_encoding)
+
+        return out
@@ -1,7 +1,7 @@
 """Unlocking keys for Groth16."""
 
 from dataclasses import dataclass
-from typing import Self
+from typing import Any, Self
 
 from tx_engine import Script
 
@@ -9,6 +9,24 @@ from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
 from src.zkscript.groth16.model.groth16 import Groth16
 from src.zkscript.script_types.unlocking_keys.msm_with_fixed_bases import MsmWithFixedBasesUnlockingKey
 from src.zkscript.util.utility_scripts import nums_to_script
+from src.zkscript.util.verification_chain import state_commitment
+
+
+def _witness_commitment(
+    unlocking_key: "Groth16UnlockingKey | Groth16UnlockingKeyWithPrecomputedMsm",
+) -> bytes:
+    """Return the commitment to the hints of the pairing and the proof in the unlocking script of `unlocking_key`.
+
+    The committed elements are the inverse of the output of the Miller loop, the gradients of the pairing and the
+    proof `(A, B, C)`, in the order in which they are loaded by `to_unlocking_script`.
+    """
+    elements = list(unlocking_key.inverse_miller_output)
+    for i in range(len(unlocking_key.gradients_pairings[0]) - 1, -1, -1):
+        for j in range(len(unlocking_key.gradients_pairings[0][i]) - 1, -1, -1):
+            for k in range(3) if unlocking_key.has_precomputed_gradients else range(1):
+                elements += unlocking_key.gradients_pairings[k][i][j]
+    elements += [*unlocking_key.A, *unlocking_key.B, *unlocking_key.C]
+    return state_commitment(elements)
 
 
 @dataclass
@@ -33,6 +51,10 @@ class Groth16UnlockingKey:
             gamma_abc[0] + \sum_(i=1)^l pub[i] * gamma_abc[i+1]
         has_precomputed_gradients (bool): Flag determining if the precomputed gradients used to compute
             w*(-gamma) and w*(-delta) are in the unlocking script. Defaults to `True`.
+        gradients_subgroup_a (list[list[int]] | None): The gradients required to check that `A` belongs to G1.
+            `None` if the subgroup membership of `A` is not checked. Defaults to `None`.
+        gradients_subgroup_c (list[list[int]] | None): The gradients required to check that `C` belongs to G1.
+            `None` if the subgroup membership of `C` is not checked. Defaults to `None`.
     """
 
     pub: list[int]
@@ -44,6 +66,8 @@ class Groth16UnlockingKey:
     msm_key: MsmWithFixedBasesUnlockingKey
     gradient_gamma_abc_zero: list[int]
     has_precomputed_gradients: bool = True
+    gradients_subgroup_a: list[list[int]] | None = None
+    gradients_subgroup_c: list[list[int]] | None = None
 
     @staticmethod
     def from_data(
@@ -59,6 +83,8 @@ class Groth16UnlockingKey:
         inverse_miller_output: list[int],
         gradient_gamma_abc_zero: list[int],
         has_precomputed_gradients: bool = True,
+        gradients_subgroup_a: list[list[int]] | None = None,
+        gradients_subgroup_c: list[list[int]] | None = None,
     ) -> Self:
         r"""Construct an instance of `Self` from the provided data.
 
@@ -85,6 +111,10 @@ class Groth16UnlockingKey:
                 gamma_abc[0] + \sum_(i=1)^l pub[i] * gamma_abc[i+1]
             has_precomputed_gradients (bool): Flag determining if the precomputed gradients used to compute
                 w*(-gamma) and w*(-delta) are in the unlocking script. Defaults to `True`.
+            gradients_subgroup_a (list[list[int]] | None): The gradients required to check that `A` belongs to G1.
+                `None` if the subgroup membership of `A` is not checked. Defaults to `None`.
+            gradients_subgroup_c (list[list[int]] | None): The gradients required to check that `C` belongs to G1.
+                `None` if the subgroup membership of `C` is not checked. Defaults to `None`.
         """
         max_multipliers = max_multipliers if max_multipliers is not None else [groth16_model.r] * len(pub)
         msm_key = MsmWithFixedBasesUnlockingKey.from_data(
@@ -104,6 +134,61 @@ class Groth16UnlockingKey:
             msm_key,
             gradient_gamma_abc_zero,
             has_precomputed_gradients,
+            gradients_subgroup_a,
+            gradients_subgroup_c,
+        )
+
+    @staticmethod
+    def from_ark_proof(
+        groth16_model: Groth16,
+        proof_type: type,
+        serialised_proof: bytes | list[int],
+        pub: list[int],
+        cache_vk: Any,
+        max_multipliers: list[int] | None = None,
+        has_precomputed_gradients: bool = True,
+    ) -> Self:
+        r"""Construct an instance of `Self` from a proof serialised with arkworks.
+
+        The proof is deserialised, and the gradients and the inverse of the Miller loop output required by the
+        verifier are computed from it, so that the unlocking script returned by `to_unlocking_script` is laid out
+        in the order expected by `groth16_model.groth16_verifier`.
+
+        Args:
+            groth16_model (Groth16): The Groth16 script model used to construct the groth16_verifier script.
+            proof_type (type): The `elliptic_curves` proof class of the curve over which the proof was generated,
+                e.g., `ProofBls12381` or `ProofMnt4753`.
+            serialised_proof (bytes | list[int]): The uncompressed arkworks serialisation of the Groth16 proof.
+            pub (list[int]): The list of public statements, without the leading `1`. See
+                `src.zkscript.util.ark_serialisation.ark_field_elements_to_ints` to deserialise the public inputs
+                serialised with arkworks.
+            cache_vk (PreparedVerifyingKey): The verifying key prepared with `vk.prepare()`. It contains the
+                precomputed gradients of `-gamma` and `-delta`.
+            max_multipliers (list[int] | None): `max_multipliers[i]` is the maximum multiplier allowed for the
+                multiplication of gamma_abc[i]. Defaults to `None`, meaning `groth16_model.r`.
+            has_precomputed_gradients (bool): Flag determining if the precomputed gradients used to compute
+                w*(-gamma) and w*(-delta) are in the unlocking script. Defaults to `True`.
+        """
+        proof = proof_type.deserialise(list(serialised_proof))
+        prepared_proof = proof.prepare_for_zkscript(cache_vk, pub)
+
+        return Groth16UnlockingKey.from_data(
+            groth16_model=groth16_model,
+            pub=prepared_proof.public_statements,
+            A=prepared_proof.a,
+            B=prepared_proof.b,
+            C=prepared_proof.c,
+            gradients_pairings=[
+                prepared_proof.gradients_b,
+                prepared_proof.gradients_minus_gamma,
+                prepared_proof.gradients_minus_delta,
+            ],
+            gradients_multiplications=prepared_proof.gradients_multiplications,
+            max_multipliers=max_multipliers,
+            gradients_additions=prepared_proof.gradients_additions,
+            inverse_miller_output=prepared_proof.inverse_miller_loop,
+            gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+            has_precomputed_gradients=has_precomputed_gradients,
         )
 
     def to_unlocking_script(
@@ -111,6 +196,7 @@ class Groth16UnlockingKey:
         groth16_model: Groth16,
         load_modulus: bool = True,
         extractable_inputs: int = 0,
+        bind_witness: bool = False,
     ) -> Script:
         r"""Return the script needed to execute the groth16_verifier script.
 
@@ -118,11 +204,17 @@ class Groth16UnlockingKey:
             groth16_model (Groth16): The Groth16 script model used to construct the groth16_verifier script.
             load_modulus (bool): Whether or not to load the modulus. Defaults to `True`.
             extractable_inputs (int): The number of inputs that are extractable in script. Defaults to `0`.
+            bind_witness (bool): Whether or not to load the commitment returned by `witness_commitment`, required
+                by the verifier generated with `bind_witness`. Defaults to `False`.
         """
         ec_fq = EllipticCurveFq(groth16_model.pairing_model.modulus, groth16_model.curve_a, groth16_model.curve_b)
 
         out = nums_to_script([groth16_model.pairing_model.modulus]) if load_modulus else Script()
 
+        # Load the commitment to the hints of the pairing and the proof
+        if bind_witness:
+            out.append_pushdata(self.witness_commitment())
+
         # Load inverse_miller_output inverse
         out += nums_to_script(self.inverse_miller_output)
 
@@ -135,9 +227,15 @@ class Groth16UnlockingKey:
                         out += nums_to_script(self.gradients_pairings[k][i][j])
                 else:
                     out += nums_to_script(self.gradients_pairings[0][i][j])
-        # Load A, B, C
+        # Load A, B, C, together with the gradients to check their subgroup membership
+        if self.gradients_subgroup_a is not None:
+            for gradient in self.gradients_subgroup_a[::-1]:
+                out += nums_to_script(gradient)
         out += nums_to_script(self.A)
         out += nums_to_script(self.B)
+        if self.gradients_subgroup_c is not None:
+            for gradient in self.gradients_subgroup_c[::-1]:
+                out += nums_to_script(gradient)
         out += nums_to_script(self.C)
 
         # Sum w/ gamma_abc
@@ -152,6 +250,14 @@ class Groth16UnlockingKey:
 
         return out
 
+    def witness_commitment(self) -> bytes:
+        """Return the commitment checked by the verifier generated with `bind_witness`.
+
+        The commitment is the `state_commitment` of the inverse of the output of the Miller loop, the gradients of
+        the pairing supplied in the unlocking script and the proof `(A, B, C)`, see `Groth16.groth16_verifier`.
+        """
+        return _witness_commitment(self)
+
 
 @dataclass
 class Groth16UnlockingKeyWithPrecomputedMsm:
@@ -171,6 +277,10 @@ class Groth16UnlockingKeyWithPrecomputedMsm:
         precomputed_msm: the sum \sum_(i=0)^l a_i * gamma_abc[i]
         has_precomputed_gradients (bool): Flag determining if the precomputed gradients used to compute
             w*(-gamma) and w*(-delta) are in the unlocking script. Defaults to `True`.
+        gradients_subgroup_a (list[list[int]] | None): The gradients required to check that `A` belongs to G1.
+            `None` if the subgroup membership of `A` is not checked. Defaults to `None`.
+        gradients_subgroup_c (list[list[int]] | None): The gradients required to check that `C` belongs to G1.
+            `None` if the subgroup membership of `C` is not checked. Defaults to `None`.
     """
 
     A: list[int]
@@ -180,11 +290,14 @@ class Groth16UnlockingKeyWithPrecomputedMsm:
     inverse_miller_output: list[int]
     precomputed_msm: list[int]
     has_precomputed_gradients: bool = True
+    gradients_subgroup_a: list[list[int]] | None = None
+    gradients_subgroup_c: list[list[int]] | None = None
 
     def to_unlocking_script(
         self,
         groth16_model: Groth16,
         load_modulus: bool = True,
+        bind_witness: bool = False,
     ) -> Script:
         r"""Return the script needed to execute the groth16_verifier script.
 
@@ -193,9 +306,15 @@ class Groth16UnlockingKeyWithPrecomputedMsm:
             max_multipliers (list[int] | None): The integer n such that |pub[i]| <= n for all i. If passed as
                 None, then n = groth16_model.r.
             load_modulus (bool): Whether or not to load the modulus. Defaults to `True`.
+            bind_witness (bool): Whether or not to load the commitment returned by `witness_commitment`, required
+                by the verifier generated with `bind_witness`. Defaults to `False`.
         """
         out = nums_to_script([groth16_model.pairing_model.modulus]) if load_modulus else Script()
 
+        # Load the commitment to the hints of the pairing and the proof
+        if bind_witness:
+            out.append_pushdata(self.witness_commitment())
+
         # Load inverse_miller_output inverse
         out += nums_to_script(self.inverse_miller_output)
 
@@ -209,12 +328,166 @@ class Groth16UnlockingKeyWithPrecomputedMsm:
                 else:
                     out += nums_to_script(self.gradients_pairings[0][i][j])
 
-        # Load A, B, C
+        # Load A, B, C, together with the gradients to check their subgroup membership
+        if self.gradients_subgroup_a is not None:
+            for gradient in self.gradients_subgroup_a[::-1]:
+                out += nums_to_script(gradient)
         out += nums_to_script(self.A)
         out += nums_to_script(self.B)
+        if self.gradients_subgroup_c is not None:
+            for gradient in self.gradients_subgroup_c[::-1]:
+                out += nums_to_script(gradient)
         out += nums_to_script(self.C)
 
         # Load precomputed msm
         out += nums_to_script(self.precomputed_msm)
 
         return out
+
+    def witness_commitment(self) -> bytes:
+        """Return the commitment checked by the verifier generated with `bind_witness`.
+
+        See `Groth16UnlockingKey.witness_commitment`.
+        """
+        return _witness_commitment(self)
+
+
+@dataclass
+class Groth16GradientHints:
+    r"""Hints computed from the proof and the public inputs, required by the Groth16 verifier.
+
+    Attributes:
+        gradients_pairings (list[list[list[list[int]]]]): list of gradients required to compute the pairings
+            in the Groth16 verification equation. See `Groth16UnlockingKey`.
+        gradients_multiplications (list[list[list[list[int]]]]): the gradients to execute the script
+            `unrolled_multiplication` that computes `pub[i] * gamma_abc[i]`
+        gradients_additions (list[list[int]]): `gradients_additions[i]` is the gradient of the addition
+            `gamma_abc[n-i-2] + (\sum_(j=n-i-1)^(n-1) gamma_abc[j]`
+        inverse_miller_output (list[int]): the inverse of
+            miller(A,B) * miller(gamma_abc[0] + \sum_{i >=0} pub[i] * gamma_abc[i+1], -gamma) * miller(C, -delta)
+        gradient_gamma_abc_zero (list[int]): The gradient required to compute the sum
+            gamma_abc[0] + \sum_(i=1)^l pub[i] * gamma_abc[i+1]
+    """
+
+    gradients_pairings: list[list[list[list[int]]]]
+    gradients_multiplications: list[list[list[list[int]]]]
+    gradients_additions: list[list[int]]
+    inverse_miller_output: list[int]
+    gradient_gamma_abc_zero: list[int]
+
+    @staticmethod
+    def from_prepared_proof(prepared_proof: Any) -> Self:
+        """Construct an instance of `Self` from a proof prepared with `proof.prepare_for_zkscript`.
+
+        Args:
+            prepared_proof (PreparedProof): The proof prepared for zkscript with the `elliptic_curves` package.
+        """
+        return Groth16GradientHints(
+            gradients_pairings=[
+                prepared_proof.gradients_b,
+                prepared_proof.gradients_minus_gamma,
+                prepared_proof.gradients_minus_delta,
+            ],
+            gradients_multiplications=prepared_proof.gradients_multiplications,
+            gradients_additions=prepared_proof.gradients_additions,
+            inverse_miller_output=prepared_proof.inverse_miller_loop,
+            gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+        )
+
+
+@dataclass
+class Groth16Witness:
+    """Builder of the unlocking script of the Groth16 verifier from named fields.
+
+    The fields can be passed to the constructor or set one by one. `build` checks that all the required fields are
+    set and returns the unlocking script, with its elements in the order expected by `groth16_model.groth16_verifier`.
+
+    Attributes:
+        proof_a (list[int] | None): Component `A` of the zk proof.
+        proof_b (list[int] | None): Component `B` of the zk proof.
+        proof_c (list[int] | None): Component `C` of the zk proof.
+        public_inputs (list[int] | None): The list of public statements, without the leading `1`.
+        gradient_hints (Groth16GradientHints | None): The hints computed from the proof and the public inputs.
+        max_multipliers (list[int] | None): `max_multipliers[i]` is the maximum multiplier allowed for the
+            multiplication of gamma_abc[i]. Defaults to `None`, meaning `groth16_model.r`.
+        has_precomputed_gradients (bool): Flag determining if the precomputed gradients used to compute
+            w*(-gamma) and w*(-delta) are in the unlocking script. Defaults to `True`.
+
+    Example:
+        >>> witness = Groth16Witness()
+        >>> witness.proof_a = prepared_proof.a
+        >>> witness.proof_b = prepared_proof.b
+        >>> witness.proof_c = prepared_proof.c
+        >>> witness.public_inputs = prepared_proof.public_statements
+        >>> witness.gradient_hints = Groth16GradientHints.from_prepared_proof(prepared_proof)
+        >>> unlock = witness.build(bls12_381)
+    """
+
+    proof_a: list[int] | None = None
+    proof_b: list[int] | None = None
+    proof_c: list[int] | None = None
+    public_inputs: list[int] | None = None
+    gradient_hints: Groth16GradientHints | None = None
+    max_multipliers: list[int] | None = None
+    has_precomputed_gradients: bool = True
+
+    REQUIRED_FIELDS = ("proof_a", "proof_b", "proof_c", "public_inputs", "gradient_hints")
+
+    def missing_fields(self) -> list[str]:
+        """Return the names of the required fields that are not set."""
+        return [field for field in self.REQUIRED_FIELDS if getattr(self, field) is None]
+
+    def to_unlocking_key(self, groth16_model: Groth16) -> Groth16UnlockingKey:
+        """Return the unlocking key corresponding to the witness.
+
+        Args:
+            groth16_model (Groth16): The Groth16 script model used to construct the groth16_verifier script.
+
+        Raises:
+            ValueError: If some required fields are not set, or if the number of public inputs does not match the
+                gradient hints.
+        """
+        missing_fields = self.missing_fields()
+        if len(missing_fields) != 0:
+            msg = f"The following fields of the witness are not set: {', '.join(missing_fields)}"
+            raise ValueError(msg)
+        if len(self.public_inputs) != len(self.gradient_hints.gradients_multiplications):
+            msg = (
+                f"The gradient hints are for {len(self.gradient_hints.gradients_multiplications)} public inputs, "
+                f"but {len(self.public_inputs)} public inputs were set"
+            )
+            raise ValueError(msg)
+        if self.max_multipliers is not None and len(self.max_multipliers) != len(self.public_inputs):
+            msg = f"Expected {len(self.public_inputs)} max multipliers, got {len(self.max_multipliers)}"
+            raise ValueError(msg)
+
+        return Groth16UnlockingKey.from_data(
+            groth16_model=groth16_model,
+            pub=self.public_inputs,
+            A=self.proof_a,
+            B=self.proof_b,
+            C=self.proof_c,
+            gradients_pairings=self.gradient_hints.gradients_pairings,
+            gradients_multiplications=self.gradient_hints.gradients_multiplications,
+            max_multipliers=self.max_multipliers,
+            gradients_additions=self.gradient_hints.gradients_additions,
+            inverse_miller_output=self.gradient_hints.inverse_miller_output,
+            gradient_gamma_abc_zero=self.gradient_hints.gradient_gamma_abc_zero,
+            has_precomputed_gradients=self.has_precomputed_gradients,
+        )
+
+    def build(self, groth16_model: Groth16, load_modulus: bool = True, extractable_inputs: int = 0) -> Script:
+        """Return the unlocking script of the Groth16 verifier.
+
+        Args:
+            groth16_model (Groth16): The Groth16 script model used to construct the groth16_verifier script.
+            load_modulus (bool): Whether or not to load the modulus. Defaults to `True`.
+            extractable_inputs (int): The number of inputs that are extractable in script. Defaults to `0`.
+
+        Raises:
+            ValueError: If some required fields are not set, or if the number of public inputs does not match the
+                gradient hints.
+        """
+        return self.to_unlocking_key(groth16_model).to_unlocking_script(
+            groth16_model, load_modulus=load_modulus, extractable_inputs=extractable_inputs
+        )
@@ -0,0 +1,75 @@
+"""Unlocking key for `multiply_known_base_lsb` in EllipticCurveFq."""
+
+from dataclasses import dataclass
+from math import log2
+
+from tx_engine import Script
+
+from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
+from src.zkscript.util.utility_scripts import nums_to_script
+
+
+@dataclass
+class EllipticCurveFqKnownBaseLsbUnlockingKey:
+    """Gradients and bits required to multiply a fixed base with LSB-first add-only multiplication.
+
+    Args:
+        a (int): The scalar `a` used to multiply the base.
+        gradients (list[list[int]]): `gradients[i]` is the gradient of the line through `2^i * base` and
+            `(a mod 2^i) * base`, or the empty list if the i-th bit of `a` is not set or `a mod 2^i = 0`.
+        max_multiplier (int): The maximum value of `a`.
+
+    Preconditions:
+        The list `gradients` is computed as follows. The function `get_gradient` is assumed to return the gradient of
+        the line through two points.
+            gradients = []
+            T, multiple = infinity, base
+            for i in range(int(log2(max_multiplier)) + 1):
+                if (a >> i) & 1 == 1 and not T.is_infinity():
+                    gradients.append(T.get_gradient(multiple).to_list())
+                else:
+                    gradients.append([])
+                T = T + multiple if (a >> i) & 1 == 1 else T
+                multiple = multiple + multiple
+
+    Example:
+        >>> from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
+        >>> from src.zkscript.script_types.unlocking_keys.known_base_ec_multiplication import (
+        ...     EllipticCurveFqKnownBaseLsbUnlockingKey,
+        ... )
+        >>>
+        >>> ec_curve = EllipticCurveFq(q=17, curve_a=0, curve_b=7)
+        >>> unlocking_key = EllipticCurveFqKnownBaseLsbUnlockingKey(a=5, gradients=[[], [], [3]], max_multiplier=4)
+        >>> unlocking_key.to_unlocking_script(ec_curve)
+        0x11 OP_3 OP_1 OP_0 OP_1
+
+             ^    ^    ^    ^    ^
+             q   grad  a_2  a_1  a_0
+    """
+
+    a: int
+    gradients: list[list[int]]
+    max_multiplier: int
+
+    def to_unlocking_script(self, ec_over_fq: EllipticCurveFq, load_modulus: bool = True) -> Script:
+        """Return the unlocking script required by the multiply_known_base_lsb script.
+
+        Args:
+            ec_over_fq (EllipticCurveFq): The instantiation of ec arithmetic over Fq used to
+                construct the multiply_known_base_lsb locking script.
+            load_modulus (bool): Whether or not to load the modulus on the stack. Defaults to `True`.
+        """
+        n_bits = int(log2(self.max_multiplier)) + 1
+        assert 0 <= self.a < 2**n_bits, "The scalar exceeds the number of bits allowed by the max multiplier"
+
+        out = nums_to_script([ec_over_fq.modulus]) if load_modulus else Script()
+
+        # Load the gradients and the bits, the least significant bit goes on top
+        for i in range(n_bits - 1, -1, -1):
+            if (self.a >> i) & 1:
+                out += nums_to_script(self.gradients[i]) if len(self.gradients[i]) != 0 else Script()
+                out += Script.parse_string("OP_1")
+            else:
+                out += Script.parse_string("OP_0")
+
+        return out
@@ -1,10 +1,11 @@
 """Unlocking keys for secp256k1."""
 
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 
 from tx_engine import Script, encode_num
 from tx_engine.engine.util import GROUP_ORDER_INT, PRIME_INT, Gx, Gx_bytes
 
+from src.zkscript.elliptic_curves.secp256k1.util import pubkey_to_point
 from src.zkscript.util.utility_scripts import nums_to_script
 
 
@@ -17,13 +18,16 @@ class Secp256k1BasePointMultiplicationUnlockingKey:
             script is used.
         h (bytes): The sighash of the transaction in which the unlocking script is used.
         a (int): The purported discrete logarithm of the point A.
-        A (list[int]): The purported point a * G.
+        A (list[int] | bytes): The purported point a * G, as affine coordinates or, if `is_compressed_pubkey`, as a
+            33-byte compressed public key or a 32-byte x-only public key.
+        is_compressed_pubkey (bool): If `True`, `A` is a compressed or x-only public key. Defaults to `False`.
     """
 
     sig_hash_preimage: bytes
     h: bytes
     a: int
-    A: list[int]
+    A: list[int] | bytes
+    is_compressed_pubkey: bool = field(default=False, kw_only=True)
 
     def to_unlocking_script(self, append_constants: bool = True) -> Script:
         """Return the unlocking script required by `self.verify_base_point_multiplication`.
@@ -41,7 +45,7 @@ class Secp256k1BasePointMultiplicationUnlockingKey:
         out.append_pushdata(self.sig_hash_preimage)
         out.append_pushdata(encode_num(int.from_bytes(self.h)))
         out += nums_to_script([self.a])
-        out += nums_to_script(self.A)
+        out += nums_to_script(pubkey_to_point(self.A, self.is_compressed_pubkey))
 
         return out
 
@@ -60,8 +64,10 @@ class Secp256k1PointMultiplicationUpToSignUnlockingKey:
             h / Q_x mod GROUP_ORDER_INT.
         gradient (int): The gradient through P and (h / Q_x)G.
         Q (list[int]): The purported point Q = ± bP.
-        P (list[int]): The purported point such that Q = ± bP.
+        P (list[int] | bytes): The purported point such that Q = ± bP, as affine coordinates or, if
+            `is_compressed_pubkey`, as a 33-byte compressed public key or a 32-byte x-only public key.
         h_times_x_coordinate_target_inverse_times_G (list[int]): The point (h / Q_x) G.
+        is_compressed_pubkey (bool): If `True`, `P` is a compressed or x-only public key. Defaults to `False`.
     """
 
     sig_hash_preimage: bytes
@@ -71,8 +77,9 @@ class Secp256k1PointMultiplicationUpToSignUnlockingKey:
     h_times_x_coordinate_target_inverse: int
     gradient: int
     Q: list[int]
-    P: list[int]
+    P: list[int] | bytes
     h_times_x_coordinate_target_inverse_times_G: list[int]  # noqa: N815
+    is_compressed_pubkey: bool = field(default=False, kw_only=True)
 
     def to_unlocking_script(self, append_constants: bool = True) -> Script:
         """Return the unlocking script required by `self.verify_base_point_multiplication`.
@@ -95,7 +102,7 @@ class Secp256k1PointMultiplicationUpToSignUnlockingKey:
             [self.b, self.x_coordinate_target_times_b_inverse, self.h_times_x_coordinate_target_inverse, self.gradient]
         )
         out += nums_to_script(self.Q)
-        out += nums_to_script(self.P)
+        out += nums_to_script(pubkey_to_point(self.P, self.is_compressed_pubkey))
         out += nums_to_script(self.h_times_x_coordinate_target_inverse_times_G)
 
         return out
@@ -121,7 +128,9 @@ class Secp256k1PointMultiplicationUnlockingKey:
             D[0] = (h / Q_x)*G, D[1] = (h / (Q + bG)_x) * G, D[2] = b * G
         Q (list[int]): The purported point Q = bP.
         b (int): The purported discrete logarithm of the point Q with respect to P: Q = bP.
-        P (list[int]): The purported point Q = bP.
+        P (list[int] | bytes): The purported point Q = bP, as affine coordinates or, if `is_compressed_pubkey`, as a
+            33-byte compressed public key or a 32-byte x-only public key.
+        is_compressed_pubkey (bool): If `True`, `P` is a compressed or x-only public key. Defaults to `False`.
     """
 
     sig_hash_preimage: bytes
@@ -132,7 +141,8 @@ class Secp256k1PointMultiplicationUnlockingKey:
     D: list[list[int]]
     Q: list[int]
     b: int
-    P: list[int]
+    P: list[int] | bytes
+    is_compressed_pubkey: bool = field(default=False, kw_only=True)
 
     def to_unlocking_script(self, append_constants: bool = True) -> Script:
         """Return the unlocking script required by `self.verify_base_point_multiplication`.
@@ -158,6 +168,64 @@ class Secp256k1PointMultiplicationUnlockingKey:
             out += nums_to_script(D_)
         out += nums_to_script(self.Q)
         out += nums_to_script([self.b])
-        out += nums_to_script(self.P)
+        out += nums_to_script(pubkey_to_point(self.P, self.is_compressed_pubkey))
+
+        return out
+
+
+@dataclass
+class Secp256k1PointMultiplicationWithSignUnlockingKey(Secp256k1PointMultiplicationUnlockingKey):
+    """Class encapsulating the data required to generate an unlocking script for point multiplication with sign.
+
+    Attributes:
+        sig_hash_preimage (bytes): The preimage of the sighash of the transaction in which the unlocking
+            script is used.
+        h (bytes): The sighash of the transaction in which the unlocking script is used.
+        s (list[int]): The integers `s` of `Secp256k1PointMultiplicationUnlockingKey` for the point bP.
+        gradients (list[int]): The gradients of `Secp256k1PointMultiplicationUnlockingKey` for the point bP.
+        d (list[int]): The integers `d` of `Secp256k1PointMultiplicationUnlockingKey` for the point bP.
+        D (list[list[int]): The points `D` of `Secp256k1PointMultiplicationUnlockingKey` for the point bP.
+        Q (list[int]): The purported point Q = ± bP.
+        b (int): The purported discrete logarithm of the point Q (up to sign) with respect to P: Q = ± bP.
+        P (list[int] | bytes): The purported point such that Q = ± bP, as affine coordinates or, if
+            `is_compressed_pubkey`, as a 33-byte compressed public key or a 32-byte x-only public key.
+        sign (int): `0` if Q = bP, `1` if Q = -bP.
+        is_compressed_pubkey (bool): If `True`, `P` is a compressed or x-only public key. Defaults to `False`.
+    """
+
+    sign: int
+
+    def to_unlocking_script(self, append_constants: bool = True) -> Script:
+        """Return the unlocking script required by `self.verify_point_multiplication_with_sign`.
+
+        Args:
+            append_constants (bool): If `True`, loads the constant required by
+                `self.verify_point_multiplication_with_sign`. Defaults to `True`.
+        """
+        out = super().to_unlocking_script(append_constants)
+        out += nums_to_script([self.sign])
+
+        return out
+
+
+@dataclass
+class Secp256k1MultisigUnlockingKey:
+    """Class encapsulating the data required to generate an unlocking script for an m-of-n multisig.
+
+    Attributes:
+        signatures (list[bytes]): The `m` DER encoded signatures (with the sighash flag appended), in the same order
+            as the public keys they are checked against.
+    """
+
+    signatures: list[bytes]
+
+    def to_unlocking_script(self) -> Script:
+        """Return the unlocking script required by `Secp256k1.verify_multisig`.
+
+        The signatures are preceded by the dummy element `0` consumed by OP_CHECKMULTISIG.
+        """
+        out = Script.parse_string("OP_0")
+        for signature in self.signatures:
+            out.append_pushdata(signature)
 
         return out
@@ -0,0 +1,44 @@
+"""Unlocking keys for subgroup membership checks."""
+
+from dataclasses import dataclass
+
+from tx_engine import Script
+
+from src.zkscript.bilinear_pairings.model.subgroup_membership import G1SubgroupMembership
+from src.zkscript.util.utility_scripts import nums_to_script
+
+
+@dataclass
+class SubgroupMembershipUnlockingKey:
+    """Class encapsulating the data required to generate an unlocking script for `g1_in_subgroup` or `g2_in_subgroup`.
+
+    Attributes:
+        P (list[int]): The point whose subgroup membership is checked.
+        gradients (list[list[int]]): The gradients required to compute the scalar multiplication in the check, in
+            the order in which they are consumed by the script. Refer to the preconditions of
+            `SubgroupMembership.g1_in_subgroup` and `SubgroupMembership.g2_in_subgroup`.
+    """
+
+    P: list[int]
+    gradients: list[list[int]]
+
+    def to_unlocking_script(self, subgroup_membership: G1SubgroupMembership, load_modulus: bool = True) -> Script:
+        """Return the script needed to execute the `g1_in_subgroup` or `g2_in_subgroup` script.
+
+        Args:
+            subgroup_membership (G1SubgroupMembership): The instance used to construct the locking script.
+            load_modulus (bool): Whether or not to load the modulus on the stack. Defaults to `True`.
+
+        Returns:
+            Script pushing [self.gradients, self.P] on the stack.
+        """
+        out = nums_to_script([subgroup_membership.modulus]) if load_modulus else Script()
+
+        # Load the gradients
+        for gradient in self.gradients[::-1]:
+            out += nums_to_script(gradient)
+
+        # Load P
+        out += nums_to_script(self.P)
+
+        return out
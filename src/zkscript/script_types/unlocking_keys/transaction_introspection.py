@@ -7,6 +7,12 @@ from tx_engine import SIGHASH, Script, Tx, hash256d
 from tx_engine import sig_hash_preimage as tx_to_sig_hash_preimage
 from tx_engine.engine.util import GROUP_ORDER_INT, Gx, Gx_bytes
 
+from src.zkscript.transaction_introspection.sighash_midstates import (
+    SighashMidstates,
+    hashed_sighash_data,
+    serialise_tx_parts,
+    var_int,
+)
 from src.zkscript.util.utility_scripts import nums_to_script
 
 
@@ -116,3 +122,53 @@ class PushTxBitShiftUnlockingKey:
         out.append_pushdata(sig_hash_preimage if is_sig_hash_preimage else sig_hash)
 
         return out
+
+
+@dataclass
+class SighashPreimageUnlockingKey:
+    """Class encapsulating the data required to construct the sighash preimage from midstates.
+
+    Attributes:
+        tx (Tx): The transaction for which we want to construct the unlocking script.
+        index (int): The index of the UTXO for which we want to construct the unlocking script.
+        script_pubkey (Script): The script_pubkey of the outpoint we want to construct the unlocking
+            script for.
+        prev_amount (int): The amount of the outpoint we want to construct the unlocking
+            script for.
+    """
+
+    tx: Tx
+    index: int
+    script_pubkey: Script
+    prev_amount: int
+
+    def to_unlocking_script(self, sighash_flags: SIGHASH, midstates: SighashMidstates) -> Script:
+        """Construct unlocking script for the `sighash_preimage_from_midstates` method.
+
+        Args:
+            sighash_flags (SIGHASH): The sighash flag with which the locking script was constructed.
+            midstates (SighashMidstates): The midstates with which the locking script was constructed. The data
+                hashed to compute the digests set to `None` is loaded on the stack.
+        """
+        parts = serialise_tx_parts(self.tx)
+        prevouts, sequences, outputs = hashed_sighash_data(self.tx, self.index, sighash_flags)
+        script_code = self.script_pubkey.raw_serialize()
+
+        elements = [
+            parts.version,
+            prevouts if midstates.hash_prevouts is None else None,
+            sequences if midstates.hash_sequence is None else None,
+            parts.outpoints[self.index],
+            var_int(len(script_code)) + script_code,
+            self.prev_amount.to_bytes(8, "little"),
+            parts.sequences[self.index],
+            outputs if midstates.hash_outputs is None else None,
+            parts.locktime,
+        ]
+
+        out = Script()
+        for element in elements:
+            if element is not None:
+                out.append_pushdata(element)
+
+        return out
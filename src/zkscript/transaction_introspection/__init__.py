@@ -5,6 +5,10 @@ This package provides modules for constructing Bitcoin scripts for transaction i
 Modules:
     - transaction_introspection: Contains the TransactionIntrospection class for scripts that achieve
         transaction introspection. Reference for implementation: https://hackmd.io/@federicobarbacovi/By6zkFmfyl
+    - sighash_midstates: Contains the precomputation of the digests of the sighash preimage that are fixed when the
+        locking script is built.
+    - shared_sighash: Contains the computation of the sighash preimages of several inputs of a transaction from the
+        section they share, and the verification of their ECDSA signatures.
 
 Usage example:
     >>> from tx_engine import SIGHASH
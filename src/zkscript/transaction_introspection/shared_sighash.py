@@ -0,0 +1,226 @@
+"""Sighash preimages of several inputs of the same transaction, computed from a shared section.
+
+The sighash preimage of the input `i` of a transaction is:
+    version || hashPrevouts || hashSequence || outpoint_i || scriptCode_i || amount_i || nSequence_i ||
+        hashOutputs || nLocktime || sighash type
+Unless the sighash flags contain SIGHASH_SINGLE, the prefix `version || hashPrevouts || hashSequence` and the suffix
+`hashOutputs || nLocktime || sighash type` do not depend on the input. They are computed once, and the preimage of
+each input only requires its own outpoint, scriptCode, amount and nSequence.
+"""
+
+from dataclasses import dataclass
+
+from tx_engine import SIGHASH, Script, Tx, hash256d
+from tx_engine.engine.util import GROUP_ORDER_INT, PRIME_INT, Gx, Gy
+
+from src.zkscript.transaction_introspection.sighash_midstates import (
+    SIGHASH_BASE_TYPE_MASK,
+    SIGHASH_SINGLE,
+    precompute_sighash_midstates,
+    serialise_tx_parts,
+    sighash_type,
+    var_int,
+)
+
+
+@dataclass
+class SharedSighashPreimage:
+    """The section of the sighash preimage shared by the inputs of a transaction.
+
+    Attributes:
+        prefix (bytes): The shared prefix `version || hashPrevouts || hashSequence`.
+        suffix (bytes): The shared suffix `hashOutputs || nLocktime || sighash type`.
+        outpoints (list[bytes]): `outpoints[i]` is the serialised outpoint of the i-th input.
+        sequences (list[bytes]): `sequences[i]` is the serialised nSequence of the i-th input.
+    """
+
+    prefix: bytes
+    suffix: bytes
+    outpoints: list[bytes]
+    sequences: list[bytes]
+
+    @classmethod
+    def from_tx(cls, tx: Tx, sighash_flags: SIGHASH) -> "SharedSighashPreimage":
+        """Compute the section of the sighash preimage shared by the inputs of `tx`.
+
+        Args:
+            tx (Tx): The transaction.
+            sighash_flags (SIGHASH): The sighash flags with which all the inputs are signed.
+
+        Returns:
+            The shared section of the sighash preimage.
+
+        Raises:
+            ValueError: If `sighash_flags` contains SIGHASH_SINGLE: `hashOutputs` depends on the input, so the
+                suffix is not shared.
+        """
+        flags = sighash_type(sighash_flags)
+        if flags & SIGHASH_BASE_TYPE_MASK == SIGHASH_SINGLE:
+            msg = "The sighash preimage is not shared by the inputs for SIGHASH_SINGLE"
+            raise ValueError(msg)
+
+        parts = serialise_tx_parts(tx)
+        # The digests do not depend on the input for the supported flags
+        midstates = precompute_sighash_midstates(tx, 0, sighash_flags)
+
+        return cls(
+            prefix=parts.version + midstates.hash_prevouts + midstates.hash_sequence,
+            suffix=midstates.hash_outputs + parts.locktime + flags.to_bytes(4, "little"),
+            outpoints=parts.outpoints,
+            sequences=parts.sequences,
+        )
+
+    def preimage(self, index: int, script_pubkey: Script, prev_amount: int) -> bytes:
+        """Return the sighash preimage of the input `index`.
+
+        Args:
+            index (int): The index of the input.
+            script_pubkey (Script): The script_pubkey of the outpoint spent by the input.
+            prev_amount (int): The amount of the outpoint spent by the input.
+        """
+        script_code = script_pubkey.raw_serialize()
+        return (
+            self.prefix
+            + self.outpoints[index]
+            + var_int(len(script_code))
+            + script_code
+            + prev_amount.to_bytes(8, "little")
+            + self.sequences[index]
+            + self.suffix
+        )
+
+    def sighash(self, index: int, script_pubkey: Script, prev_amount: int) -> bytes:
+        """Return the sighash (the double SHA256 of the sighash preimage) of the input `index`.
+
+        Args:
+            index (int): The index of the input.
+            script_pubkey (Script): The script_pubkey of the outpoint spent by the input.
+            prev_amount (int): The amount of the outpoint spent by the input.
+        """
+        return hash256d(self.preimage(index, script_pubkey, prev_amount))
+
+
+@dataclass
+class InputSignature:
+    """The ECDSA signature of an input, together with the data required to verify it.
+
+    Attributes:
+        index (int): The index of the signed input.
+        script_pubkey (Script): The script_pubkey of the outpoint spent by the input.
+        prev_amount (int): The amount of the outpoint spent by the input.
+        signature (bytes): The DER encoded signature, with the sighash flags appended.
+        pubkey (bytes): The public key (compressed or uncompressed) against which the signature is verified.
+    """
+
+    index: int
+    script_pubkey: Script
+    prev_amount: int
+    signature: bytes
+    pubkey: bytes
+
+
+def verify_input_signatures(tx: Tx, sighash_flags: SIGHASH, signatures: list[InputSignature]) -> bool:
+    """Verify the ECDSA signatures of several inputs of `tx`, computing the shared section of the preimage once.
+
+    Args:
+        tx (Tx): The transaction.
+        sighash_flags (SIGHASH): The sighash flags with which all the inputs are signed.
+        signatures (list[InputSignature]): The signatures to verify.
+
+    Returns:
+        `True` if all the signatures are valid and were made with `sighash_flags`, `False` otherwise.
+    """
+    shared = SharedSighashPreimage.from_tx(tx, sighash_flags)
+    for input_signature in signatures:
+        if input_signature.signature[-1:] != sighash_flags.to_bytes():
+            return False
+        sighash = shared.sighash(input_signature.index, input_signature.script_pubkey, input_signature.prev_amount)
+        if not verify_ecdsa(sighash, input_signature.signature[:-1], input_signature.pubkey):
+            return False
+    return True
+
+
+def verify_ecdsa(sighash: bytes, der_signature: bytes, pubkey: bytes) -> bool:
+    """Verify the secp256k1 ECDSA signature `der_signature` of `sighash` against `pubkey`.
+
+    Args:
+        sighash (bytes): The signed digest, read as a big-endian integer.
+        der_signature (bytes): The DER encoded signature, without the sighash flags.
+        pubkey (bytes): The public key, compressed (33 bytes) or uncompressed (65 bytes).
+
+    Returns:
+        `True` if the signature is valid, `False` otherwise (including if the signature or the public key are
+        malformed).
+    """
+    rs = _parse_der_signature(der_signature)
+    P = _parse_pubkey(pubkey)
+    if rs is None or P is None:
+        return False
+    r, s = rs
+    if not (0 < r < GROUP_ORDER_INT and 0 < s < GROUP_ORDER_INT):
+        return False
+
+    s_inverse = pow(s, -1, GROUP_ORDER_INT)
+    u = int.from_bytes(sighash) * s_inverse % GROUP_ORDER_INT
+    v = r * s_inverse % GROUP_ORDER_INT
+    R = _point_add(_point_multiply((Gx, Gy), u), _point_multiply(P, v))
+
+    return R is not None and R[0] % GROUP_ORDER_INT == r
+
+
+def _parse_der_signature(der_signature: bytes) -> tuple[int, int] | None:
+    """Return the pair `(r, s)` encoded in `der_signature`, or `None` if it is malformed."""
+    if not 8 <= len(der_signature) <= 72 or der_signature[:2] != bytes([0x30, len(der_signature) - 2]):  # noqa: PLR2004
+        return None
+    components, offset = [], 2
+    for _ in range(2):
+        if offset + 2 > len(der_signature) or der_signature[offset] != 0x02:  # noqa: PLR2004
+            return None
+        length = der_signature[offset + 1]
+        components.append(int.from_bytes(der_signature[offset + 2 : offset + 2 + length]))
+        offset += 2 + length
+    if offset != len(der_signature):
+        return None
+    return components[0], components[1]
+
+
+def _parse_pubkey(pubkey: bytes) -> tuple[int, int] | None:
+    """Return the point encoded in `pubkey`, or `None` if it is not a valid point of secp256k1."""
+    if len(pubkey) == 33 and pubkey[0] in {2, 3}:  # noqa: PLR2004
+        x = int.from_bytes(pubkey[1:])
+        y = pow(x**3 + 7, (PRIME_INT + 1) // 4, PRIME_INT)
+        if y % 2 != pubkey[0] % 2:
+            y = PRIME_INT - y
+    elif len(pubkey) == 65 and pubkey[0] == 4:  # noqa: PLR2004
+        x, y = int.from_bytes(pubkey[1:33]), int.from_bytes(pubkey[33:])
+    else:
+        return None
+    if x >= PRIME_INT or (y * y - x**3 - 7) % PRIME_INT != 0:
+        return None
+    return x, y
+
+
+def _point_add(P: tuple[int, int] | None, Q: tuple[int, int] | None) -> tuple[int, int] | None:  # noqa: N803
+    """Affine addition on secp256k1, with `None` the point at infinity."""
+    if P is None:
+        return Q
+    if Q is None:
+        return P
+    if P[0] == Q[0] and (P[1] + Q[1]) % PRIME_INT == 0:
+        return None
+    if P == Q:
+        gradient = 3 * P[0] * P[0] * pow(2 * P[1], -1, PRIME_INT) % PRIME_INT
+    else:
+        gradient = (Q[1] - P[1]) * pow(Q[0] - P[0], -1, PRIME_INT) % PRIME_INT
+    x = (gradient * gradient - P[0] - Q[0]) % PRIME_INT
+    return x, (gradient * (P[0] - x) - P[1]) % PRIME_INT
+
+
+def _point_multiply(P: tuple[int, int], n: int) -> tuple[int, int] | None:  # noqa: N803
+    """Double-and-add scalar multiplication on secp256k1."""
+    out = None
+    for bit in bin(n)[2:]:
+        out = _point_add(out, out)
+        if bit == "1":
+            out = _point_add(out, P)
+    return out
@@ -0,0 +1,181 @@
+"""Precomputation of the parts of the sighash preimage that are fixed at build time.
+
+The sighash preimage (see
+https://github.com/bitcoin-sv/bitcoin-sv/blob/master/doc/abc/replay-protected-sighash.md#digest-algorithm) is:
+    version || hashPrevouts || hashSequence || outpoint || scriptCode || amount || nSequence || hashOutputs ||
+        nLocktime || sighash type
+where `hashPrevouts`, `hashSequence` and `hashOutputs` are double SHA256 digests of serialised data of the
+transaction. If the data they hash is fixed when the locking script is built, the digests can be hard-coded in the
+script, so that only the variable portion of the transaction is hashed on the stack.
+"""
+
+from dataclasses import dataclass
+
+from tx_engine import SIGHASH, Tx, hash256d
+
+SIGHASH_ANYONECANPAY = 0x80
+SIGHASH_BASE_TYPE_MASK = 0x1F
+SIGHASH_NONE = 0x02
+SIGHASH_SINGLE = 0x03
+ZERO_DIGEST = bytes(32)
+
+
+@dataclass
+class SerialisedTxParts:
+    """The parts of the serialisation of a transaction that enter the sighash preimage.
+
+    Attributes:
+        version (bytes): The serialised version.
+        outpoints (list[bytes]): `outpoints[i]` is the serialised outpoint (txid || index) of the i-th input.
+        sequences (list[bytes]): `sequences[i]` is the serialised nSequence of the i-th input.
+        outputs (list[bytes]): `outputs[i]` is the serialised i-th output (amount || script length || script).
+        locktime (bytes): The serialised nLocktime.
+    """
+
+    version: bytes
+    outpoints: list[bytes]
+    sequences: list[bytes]
+    outputs: list[bytes]
+    locktime: bytes
+
+
+@dataclass
+class SighashMidstates:
+    """The digests of the sighash preimage that are hard-coded in the locking script.
+
+    A digest set to `None` is computed on the stack from the data in the unlocking script.
+
+    Attributes:
+        hash_prevouts (bytes | None): The digest `hashPrevouts`.
+        hash_sequence (bytes | None): The digest `hashSequence`.
+        hash_outputs (bytes | None): The digest `hashOutputs`.
+    """
+
+    hash_prevouts: bytes | None
+    hash_sequence: bytes | None
+    hash_outputs: bytes | None
+
+
+def var_int(n: int) -> bytes:
+    """Encode `n` as a Bitcoin VarInt."""
+    if n < 0xFD:  # noqa: PLR2004
+        return n.to_bytes(1, "little")
+    if n <= 0xFFFF:  # noqa: PLR2004
+        return b"\xfd" + n.to_bytes(2, "little")
+    if n <= 0xFFFFFFFF:  # noqa: PLR2004
+        return b"\xfe" + n.to_bytes(4, "little")
+    return b"\xff" + n.to_bytes(8, "little")
+
+
+def _read_var_int(data: bytes, offset: int) -> tuple[int, int]:
+    """Read the VarInt at position `offset` of `data`, and return it together with the offset after it."""
+    prefix = data[offset]
+    length = {0xFD: 2, 0xFE: 4, 0xFF: 8}.get(prefix, 0)
+    if length == 0:
+        return prefix, offset + 1
+    return int.from_bytes(data[offset + 1 : offset + 1 + length], "little"), offset + 1 + length
+
+
+def serialise_tx_parts(tx: Tx) -> SerialisedTxParts:
+    """Split the serialisation of `tx` in the parts that enter the sighash preimage.
+
+    Args:
+        tx (Tx): The transaction.
+
+    Returns:
+        The serialised parts of `tx`.
+    """
+    data = tx.serialize()
+
+    version, offset = data[:4], 4
+    n_inputs, offset = _read_var_int(data, offset)
+    outpoints, sequences = [], []
+    for _ in range(n_inputs):
+        outpoints.append(data[offset : offset + 36])
+        script_length, offset = _read_var_int(data, offset + 36)
+        offset += script_length
+        sequences.append(data[offset : offset + 4])
+        offset += 4
+    n_outputs, offset = _read_var_int(data, offset)
+    outputs = []
+    for _ in range(n_outputs):
+        script_length, script_offset = _read_var_int(data, offset + 8)
+        outputs.append(data[offset : script_offset + script_length])
+        offset = script_offset + script_length
+
+    return SerialisedTxParts(
+        version=version, outpoints=outpoints, sequences=sequences, outputs=outputs, locktime=data[offset : offset + 4]
+    )
+
+
+def sighash_type(sighash_flags: SIGHASH) -> int:
+    """Return the sighash type of `sighash_flags` as an integer."""
+    return sighash_flags.to_bytes()[0]
+
+
+def hashed_sighash_data(tx: Tx, index: int, sighash_flags: SIGHASH) -> tuple[bytes | None, bytes | None, bytes | None]:
+    """Return the data hashed to compute `hashPrevouts`, `hashSequence` and `hashOutputs`.
+
+    Args:
+        tx (Tx): The transaction.
+        index (int): The index of the input being signed.
+        sighash_flags (SIGHASH): The sighash flags.
+
+    Returns:
+        The data hashed to compute `hashPrevouts`, `hashSequence` and `hashOutputs`, respectively. An element is
+        `None` if, for the given sighash flags, the corresponding digest is the zero digest.
+    """
+    parts = serialise_tx_parts(tx)
+    flags = sighash_type(sighash_flags)
+    anyone_can_pay = flags & SIGHASH_ANYONECANPAY != 0
+    base_type = flags & SIGHASH_BASE_TYPE_MASK
+
+    prevouts = None if anyone_can_pay else b"".join(parts.outpoints)
+    sequences = None if anyone_can_pay or base_type in [SIGHASH_NONE, SIGHASH_SINGLE] else b"".join(parts.sequences)
+    if base_type not in [SIGHASH_NONE, SIGHASH_SINGLE]:
+        outputs = b"".join(parts.outputs)
+    elif base_type == SIGHASH_SINGLE and index < len(parts.outputs):
+        outputs = parts.outputs[index]
+    else:
+        outputs = None
+
+    return prevouts, sequences, outputs
+
+
+def precompute_sighash_midstates(
+    tx_template: Tx,
+    index: int,
+    sighash_flags: SIGHASH,
+    fixed_prevouts: bool = True,
+    fixed_sequences: bool = True,
+    fixed_outputs: bool = True,
+) -> SighashMidstates:
+    """Precompute the digests of the sighash preimage that are fixed at build time.
+
+    Args:
+        tx_template (Tx): A transaction whose fixed parts are the ones of the spending transaction.
+        index (int): The index of the input being signed.
+        sighash_flags (SIGHASH): The sighash flags.
+        fixed_prevouts (bool): If `True`, the outpoints of `tx_template` are the ones of the spending transaction.
+            Defaults to `True`.
+        fixed_sequences (bool): If `True`, the nSequence of the inputs of `tx_template` are the ones of the spending
+            transaction. Defaults to `True`.
+        fixed_outputs (bool): If `True`, the outputs of `tx_template` are the ones of the spending transaction.
+            Defaults to `True`.
+
+    Returns:
+        The midstates to hard-code in the locking script. A digest is `None` if it depends on data that is not
+        fixed. The digests that are zero for the given sighash flags are always precomputed.
+    """
+    prevouts, sequences, outputs = hashed_sighash_data(tx_template, index, sighash_flags)
+
+    def digest(data: bytes | None, is_fixed: bool) -> bytes | None:
+        if data is None:
+            return ZERO_DIGEST
+        return hash256d(data) if is_fixed else None
+
+    return SighashMidstates(
+        hash_prevouts=digest(prevouts, fixed_prevouts),
+        hash_sequence=digest(sequences, fixed_sequences),
+        hash_outputs=digest(outputs, fixed_outputs),
+    )
@@ -4,6 +4,7 @@ from tx_engine import SIGHASH, Script, encode_num, hash256d
 from tx_engine.engine.util import GROUP_ORDER_INT, Gx, Gx_bytes
 
 from src.zkscript.script_types.stack_elements import StackBaseElement
+from src.zkscript.transaction_introspection.sighash_midstates import SighashMidstates, sighash_type
 from src.zkscript.util.utility_scripts import (
     bool_to_moving_function,
     bytes_to_unsigned,
@@ -223,3 +224,54 @@ class TransactionIntrospection:
         out += Script.parse_string("OP_CHECKSIGVERIFY" if is_checksigverify else "OP_CHECKSIG")
 
         return out
+
+    @staticmethod
+    def sighash_preimage_from_midstates(sighash_flags: SIGHASH, midstates: SighashMidstates) -> Script:
+        """Construct the sighash preimage on the stack, hard-coding the digests that are fixed at build time.
+
+        Stack input:
+            - stack:    [.., version, {prevouts}, {sequences}, outpoint, script_code, amount, sequence, {outputs},
+                            locktime], where `{prevouts}`, `{sequences}` and `{outputs}` are the serialised data hashed
+                            to compute `hashPrevouts`, `hashSequence` and `hashOutputs`, and are only present if the
+                            corresponding digest in `midstates` is `None`. `script_code` is prefixed by its length.
+            - altstack: []
+
+        Stack output:
+            - stack:    [.., sig_hash_preimage]
+            - altstack: []
+
+        Args:
+            sighash_flags (SIGHASH): Sighash flag with which the message should be constructed.
+            midstates (SighashMidstates): The digests hard-coded in the script, see `precompute_sighash_midstates`.
+
+        Returns:
+            The script constructing the sighash preimage. It can be followed by `pushtx` to check it against the
+            spending transaction.
+        """
+        out = Script()
+
+        def insert_digest(digest: bytes | None) -> Script:
+            # stack in:  [.., {data}, tail]
+            # stack out: [.., digest || tail]
+            script = Script()
+            if digest is None:
+                script += Script.parse_string("OP_SWAP OP_HASH256 OP_SWAP")
+            else:
+                script.append_pushdata(digest)
+                script += Script.parse_string("OP_SWAP")
+            script += Script.parse_string("OP_CAT")
+            return script
+
+        # stack out: [.., locktime || sighash type]
+        out.append_pushdata(sighash_type(sighash_flags).to_bytes(4, "little"))
+        out += Script.parse_string("OP_CAT")
+        # stack out: [.., version, {prevouts}, {sequences}, hashOutputs || locktime || sighash type]
+        out += insert_digest(midstates.hash_outputs)
+        # stack out: [.., version, {prevouts}, {sequences}, outpoint || .. || sighash type]
+        out += Script.parse_string(" ".join(["OP_CAT"] * 4))
+        # stack out: [.., version || .. || sighash type]
+        out += insert_digest(midstates.hash_sequence)
+        out += insert_digest(midstates.hash_prevouts)
+        out += Script.parse_string("OP_CAT")
+
+        return out
@@ -0,0 +1,151 @@
+"""Decode data serialised with arkworks (`ark-serialize`) into the integer encoding used by the library.
+
+arkworks serialises:
+    - a prime field element as the little-endian encoding of its canonical representative, using
+        `(modulus.bit_length() + 7) // 8` bytes
+    - an element of an extension (tower) field as the concatenation of the serialisations of its coefficients over
+        the prime field, in the order in which they are listed by the library (e.g., `c0` before `c1`)
+    - a vector as its length (8 bytes, little-endian) followed by the serialisation of its elements
+
+Tools other than arkworks may encode the field elements in big-endian order: all the functions of this module take
+an `endianness` argument, which defaults to `Endianness.LITTLE`. The length of a vector is always little-endian.
+"""
+
+from enum import Enum
+
+LENGTH_BYTES = 8
+
+
+class Endianness(str, Enum):
+    """The byte order used to encode the field elements."""
+
+    LITTLE = "little"
+    BIG = "big"
+
+
+def ark_field_element_size(modulus: int) -> int:
+    """Return the number of bytes used by arkworks to serialise an element of the prime field of order `modulus`."""
+    return (modulus.bit_length() + 7) // 8
+
+
+def ark_tower_field_element_to_ints(
+    serialised: bytes | list[int],
+    modulus: int,
+    extension_degree: int = 1,
+    endianness: Endianness = Endianness.LITTLE,
+) -> list[int]:
+    """Deserialise an element of an extension of the prime field of order `modulus`.
+
+    Args:
+        serialised (bytes | list[int]): The serialisation of the element.
+        modulus (int): The order of the prime field.
+        extension_degree (int): The degree of the extension over the prime field. Defaults to `1`.
+        endianness (Endianness): The byte order of the coefficients. Defaults to `Endianness.LITTLE`.
+
+    Returns:
+        The list of the canonical representatives of the coefficients of the element over the prime field.
+
+    Raises:
+        ValueError: If the length of `serialised` is not `extension_degree` times the size of a prime field
+            element, or if one of the coefficients is not smaller than `modulus`.
+    """
+    serialised = bytes(serialised)
+    element_size = ark_field_element_size(modulus)
+    if len(serialised) != extension_degree * element_size:
+        msg = f"Expected {extension_degree} coefficients of {element_size} bytes, got {len(serialised)} bytes"
+        raise ValueError(msg)
+
+    out = []
+    for i in range(extension_degree):
+        coefficient = int.from_bytes(serialised[i * element_size : (i + 1) * element_size], byteorder=endianness)
+        if coefficient >= modulus:
+            msg = f"The coefficient at index {i} is not reduced modulo {modulus}"
+            raise ValueError(msg)
+        out.append(coefficient)
+
+    return out
+
+
+def ints_to_ark_tower_field_element(
+    element: list[int],
+    modulus: int,
+    endianness: Endianness = Endianness.LITTLE,
+) -> bytes:
+    """Serialise an element of an extension of the prime field of order `modulus`.
+
+    Args:
+        element (list[int]): The coefficients of the element over the prime field.
+        modulus (int): The order of the prime field.
+        endianness (Endianness): The byte order of the coefficients. Defaults to `Endianness.LITTLE`.
+
+    Returns:
+        The serialisation of the element, the inverse of `ark_tower_field_element_to_ints`.
+    """
+    element_size = ark_field_element_size(modulus)
+    return b"".join(coefficient.to_bytes(element_size, byteorder=endianness) for coefficient in element)
+
+
+def ark_field_elements_to_ints(
+    serialised: bytes | list[int],
+    modulus: int,
+    endianness: Endianness = Endianness.LITTLE,
+) -> list[int]:
+    """Deserialise a vector of prime field elements serialised with arkworks.
+
+    Args:
+        serialised (bytes | list[int]): The arkworks serialisation of a `Vec<F>`, e.g., the public inputs of a
+            Groth16 proof.
+        modulus (int): The order of the prime field `F`.
+        endianness (Endianness): The byte order of the field elements. Defaults to `Endianness.LITTLE`.
+
+    Returns:
+        The list of the canonical representatives of the field elements.
+
+    Raises:
+        ValueError: If the length of `serialised` does not match the number of elements it declares, or if one
+            of the elements is not smaller than `modulus`.
+    """
+    serialised = bytes(serialised)
+    if len(serialised) < LENGTH_BYTES:
+        msg = "The serialisation is too short to contain the length of the vector"
+        raise ValueError(msg)
+
+    n_elements = int.from_bytes(serialised[:LENGTH_BYTES], byteorder="little")
+    element_size = ark_field_element_size(modulus)
+    if len(serialised) != LENGTH_BYTES + n_elements * element_size:
+        msg = (
+            f"Expected {n_elements} field elements of {element_size} bytes, "
+            f"got {len(serialised) - LENGTH_BYTES} bytes"
+        )
+        raise ValueError(msg)
+
+    out = []
+    for i in range(n_elements):
+        start = LENGTH_BYTES + i * element_size
+        element = int.from_bytes(serialised[start : start + element_size], byteorder=endianness)
+        if element >= modulus:
+            msg = f"The field element at index {i} is not reduced modulo {modulus}"
+            raise ValueError(msg)
+        out.append(element)
+
+    return out
+
+
+def ints_to_ark_field_elements(
+    elements: list[int],
+    modulus: int,
+    endianness: Endianness = Endianness.LITTLE,
+) -> bytes:
+    """Serialise a vector of prime field elements as arkworks does.
+
+    Args:
+        elements (list[int]): The field elements.
+        modulus (int): The order of the prime field.
+        endianness (Endianness): The byte order of the field elements. Defaults to `Endianness.LITTLE`.
+
+    Returns:
+        The serialisation of `elements`, the inverse of `ark_field_elements_to_ints`.
+    """
+    return len(elements).to_bytes(LENGTH_BYTES, byteorder="little") + ints_to_ark_tower_field_element(
+        elements, modulus, endianness
+    )
@@ -0,0 +1,219 @@
+"""Bitcoin scripts operating on big integers split into limbs.
+
+A non-negative big integer `x = sum_(i=0)^(n_limbs-1) x_i * 2^(limb_bits * i)`, with `0 <= x_i < 2^limb_bits`, is
+represented on the stack by its `n_limbs` limbs as script numbers, the most significant limb at the bottom:
+    [..., x_(n_limbs-1), ..., x_1, x_0]
+The scripts only operate on the limbs with the arithmetic opcodes, so the size of the numbers on which they operate
+is bounded by the size of the limbs, whatever the size of the big integers. Carries and borrows are propagated from
+the least significant limb to the most significant one. The limbs are assumed to be in the range
+`[0, 2^limb_bits)`: this is not checked by the scripts.
+"""
+
+from tx_engine import Script
+
+from src.zkscript.util.utility_scripts import nums_to_script, roll
+
+
+def to_limbs(x: int, n_limbs: int, limb_bits: int) -> list[int]:
+    """Split `x` into limbs.
+
+    Args:
+        x (int): The big integer.
+        n_limbs (int): The number of limbs.
+        limb_bits (int): The number of bits of each limb.
+
+    Returns:
+        The limbs `[x_(n_limbs-1), ..., x_0]` of `x`, in the order in which they are pushed on the stack.
+
+    Raises:
+        ValueError: If `x` is negative or does not fit in `n_limbs` limbs of `limb_bits` bits.
+    """
+    if x < 0 or x.bit_length() > n_limbs * limb_bits:
+        msg = f"The integer does not fit in {n_limbs} limbs of {limb_bits} bits: x = {x}"
+        raise ValueError(msg)
+    mask = (1 << limb_bits) - 1
+    return [(x >> (limb_bits * i)) & mask for i in range(n_limbs - 1, -1, -1)]
+
+
+def from_limbs(limbs: list[int], limb_bits: int) -> int:
+    """Return the big integer whose limbs are `limbs`.
+
+    Args:
+        limbs (list[int]): The limbs `[x_(n_limbs-1), ..., x_0]`, in the order in which they are on the stack.
+        limb_bits (int): The number of bits of each limb.
+    """
+    x = 0
+    for limb in limbs:
+        x = (x << limb_bits) + limb
+    return x
+
+
+def _limb_by_limb(n_limbs: int, initial: str, limb_script: Script, is_output_on_altstack: bool) -> Script:
+    """Process the big integers `a` and `b` limb by limb, from the least significant limb.
+
+    Stack input:
+        - stack:    [..., a_(n_limbs-1), ..., a_0, b_(n_limbs-1), ..., b_0]
+        - altstack: []
+
+    Stack output:
+        - stack:    [..., state_(n_limbs-1), (out_(n_limbs-1), ..., out_0 if `is_output_on_altstack`)]
+        - altstack: []
+
+    Args:
+        n_limbs (int): The number of limbs of `a` and `b`.
+        initial (str): The opcode pushing the state `state_(-1)` before the first limb is processed.
+        limb_script (Script): The script processing the limb `i`: it takes `[..., state_(i-1), b_i, a_i]` as
+            input, and leaves `[..., state_i]`. If `is_output_on_altstack`, it also pushes `out_i` on the altstack.
+        is_output_on_altstack (bool): If `True`, the limbs `out_i` are moved from the altstack to the stack at the
+            end of the script.
+
+    Raises:
+        ValueError: If `n_limbs` is not positive.
+    """
+    if n_limbs < 1:
+        msg = f"The big integers must have at least one limb: n_limbs = {n_limbs}"
+        raise ValueError(msg)
+
+    out = Script.parse_string(initial)
+    for i in range(n_limbs):
+        # stack in:  [..., a_(n_limbs-1), ..., a_i, b_(n_limbs-1), ..., b_i, state_(i-1)]
+        # stack out: [..., a_(n_limbs-1), ..., a_(i+1), b_(n_limbs-1), ..., b_(i+1), state_(i-1), b_i, a_i]
+        out += Script.parse_string("OP_SWAP")
+        out += roll(position=n_limbs - i + 1, n_elements=1)
+        # stack in:  [..., state_(i-1), b_i, a_i]
+        # stack out: [..., state_i]
+        out += limb_script
+    if is_output_on_altstack:
+        out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * n_limbs))
+    return out
+
+
+def _bigint_compare(n_limbs: int, or_equal: bool) -> Script:
+    """Compare the big integers `a` and `b` limb by limb, from the least significant limb.
+
+    The result after processing the limbs `0, ..., i` is `(a_i < b_i) or (a_i == b_i and result_(i-1))`, where
+    `result_(-1)` is `or_equal`.
+    """
+    limb_script = Script.parse_string(
+        "OP_2DUP OP_GREATERTHAN OP_TOALTSTACK OP_NUMEQUAL OP_BOOLAND OP_FROMALTSTACK OP_BOOLOR"
+    )
+    return _limb_by_limb(
+        n_limbs=n_limbs, initial="OP_1" if or_equal else "OP_0", limb_script=limb_script, is_output_on_altstack=False
+    )
+
+
+def bigint_lt(n_limbs: int) -> Script:
+    """Check whether the big integer `a` is less than the big integer `b`.
+
+    Stack input:
+        - stack:    [..., a_(n_limbs-1), ..., a_0, b_(n_limbs-1), ..., b_0]
+        - altstack: []
+
+    Stack output:
+        - stack:    [..., a < b]
+        - altstack: []
+
+    Args:
+        n_limbs (int): The number of limbs of `a` and `b`.
+
+    Returns:
+        Script leaving `1` on the stack if `a < b`, `0` otherwise.
+
+    Raises:
+        ValueError: If `n_limbs` is not positive.
+    """
+    return _bigint_compare(n_limbs=n_limbs, or_equal=False)
+
+
+def bigint_le(n_limbs: int) -> Script:
+    """Check whether the big integer `a` is less than or equal to the big integer `b`.
+
+    Stack input:
+        - stack:    [..., a_(n_limbs-1), ..., a_0, b_(n_limbs-1), ..., b_0]
+        - altstack: []
+
+    Stack output:
+        - stack:    [..., a <= b]
+        - altstack: []
+
+    Args:
+        n_limbs (int): The number of limbs of `a` and `b`.
+
+    Returns:
+        Script leaving `1` on the stack if `a <= b`, `0` otherwise.
+
+    Raises:
+        ValueError: If `n_limbs` is not positive.
+    """
+    return _bigint_compare(n_limbs=n_limbs, or_equal=True)
+
+
+def bigint_add(n_limbs: int, limb_bits: int) -> Script:
+    """Add the big integers `a` and `b`, propagating the carries across the limbs.
+
+    Stack input:
+        - stack:    [..., a_(n_limbs-1), ..., a_0, b_(n_limbs-1), ..., b_0]
+        - altstack: []
+
+    Stack output:
+        - stack:    [..., carry, s_(n_limbs-1), ..., s_0]
+        - altstack: []
+
+    Args:
+        n_limbs (int): The number of limbs of `a` and `b`.
+        limb_bits (int): The number of bits of each limb.
+
+    Returns:
+        Script computing the `n_limbs + 1` limbs of `s = a + b`, the most significant one being the carry (`0` or
+        `1`).
+
+    Raises:
+        ValueError: If `n_limbs` is not positive.
+
+    Notes:
+        The sum is not reduced: if `a` and `b` are elements of a field encoded with `n_limbs` limbs, the carry is `1`
+        when `a + b` does not fit in `n_limbs` limbs, and `s` might be larger than the modulus even if the carry is
+        `0`. The reduction is left to a later step, which takes the `n_limbs + 1` limbs of `s` as input.
+    """
+    # stack in:  [..., carry_(i-1), b_i, a_i]
+    # stack out: [..., carry_i]
+    # altstack out: [s_i]
+    limb_script = Script.parse_string("OP_ADD OP_ADD")
+    limb_script += nums_to_script([1 << limb_bits])
+    limb_script += Script.parse_string("OP_2DUP OP_MOD OP_TOALTSTACK OP_DIV")
+    return _limb_by_limb(n_limbs=n_limbs, initial="OP_0", limb_script=limb_script, is_output_on_altstack=True)
+
+
+def bigint_sub(n_limbs: int, limb_bits: int) -> Script:
+    """Subtract the big integer `b` from the big integer `a`, propagating the borrows across the limbs.
+
+    Stack input:
+        - stack:    [..., a_(n_limbs-1), ..., a_0, b_(n_limbs-1), ..., b_0]
+        - altstack: []
+
+    Stack output:
+        - stack:    [..., borrow, d_(n_limbs-1), ..., d_0]
+        - altstack: []
+
+    Args:
+        n_limbs (int): The number of limbs of `a` and `b`.
+        limb_bits (int): The number of bits of each limb.
+
+    Returns:
+        Script computing the `n_limbs` limbs of `d = a - b + borrow * 2^(n_limbs * limb_bits)`, where `borrow` is
+        `1` if `a < b` and `0` otherwise.
+
+    Raises:
+        ValueError: If `n_limbs` is not positive.
+
+    Notes:
+        If `a` and `b` are elements of a field encoded with `n_limbs` limbs, the modulus must be added to `d` when
+        `borrow` is `1`. This is left to a later step.
+    """
+    # stack in:  [..., borrow_(i-1), b_i, a_i]
+    # stack out: [..., borrow_i]
+    # altstack out: [d_i]
+    limb_script = Script.parse_string("OP_SWAP OP_SUB OP_SWAP OP_SUB OP_DUP OP_0 OP_LESSTHAN OP_TUCK OP_IF")
+    limb_script += nums_to_script([1 << limb_bits])
+    limb_script += Script.parse_string("OP_ADD OP_ENDIF OP_TOALTSTACK")
+    return _limb_by_limb(n_limbs=n_limbs, initial="OP_0", limb_script=limb_script, is_output_on_altstack=True)
@@ -0,0 +1,251 @@
+"""Bitcoin scripts computing the Poseidon hash of field elements.
+
+The implementation follows the Poseidon sponge of `ark-crypto-primitives` (`PoseidonSponge`), configured by the same
+parameters as `PoseidonConfig`:
+    - the state is made of `rate + capacity` elements of F_q, initialised to zero
+    - the inputs are added to the last `rate` elements of the state, and the state is permuted every time the rate is
+        full and there are more inputs to absorb
+    - the hash is the element of the state at index `capacity` after the last permutation
+
+The permutation is made of `full_rounds / 2` full rounds, `partial_rounds` partial rounds, and `full_rounds / 2` full
+rounds. Each round adds the round constants to the state, applies the S-box `x -> x^alpha` (to the whole state in the
+full rounds, to the first element in the partial rounds), and multiplies the state by the MDS matrix.
+"""
+
+from tx_engine import Script
+
+from src.zkscript.util.utility_scripts import (
+    mod,
+    nums_to_script,
+    pick,
+    pow_fixed_exponent,
+    roll,
+    verify_bottom_constant,
+)
+
+
+class Poseidon:
+    """Construct Bitcoin scripts that compute the Poseidon hash over F_q.
+
+    Attributes:
+        MODULUS (int): The characteristic of the field F_q.
+        full_rounds (int): The number of full rounds of the permutation.
+        partial_rounds (int): The number of partial rounds of the permutation.
+        alpha (int): The exponent of the S-box.
+        mds (list[list[int]]): The MDS matrix, of size `(rate + capacity) x (rate + capacity)`.
+        ark (list[list[int]]): The round constants: `ark[i]` is the list of the `rate + capacity` constants added to
+            the state in the i-th round.
+        rate (int): The number of elements of the state absorbing the inputs.
+        capacity (int): The number of elements of the state not absorbing the inputs.
+    """
+
+    def __init__(
+        self,
+        q: int,
+        full_rounds: int,
+        partial_rounds: int,
+        alpha: int,
+        mds: list[list[int]],
+        ark: list[list[int]],
+        rate: int,
+        capacity: int,
+    ):
+        """Initialise the Poseidon hash function over F_q.
+
+        Args:
+            q (int): The characteristic of the field F_q.
+            full_rounds (int): The number of full rounds of the permutation.
+            partial_rounds (int): The number of partial rounds of the permutation.
+            alpha (int): The exponent of the S-box.
+            mds (list[list[int]]): The MDS matrix.
+            ark (list[list[int]]): The round constants, one list of `rate + capacity` elements per round.
+            rate (int): The number of elements of the state absorbing the inputs.
+            capacity (int): The number of elements of the state not absorbing the inputs.
+
+        Raises:
+            ValueError: If `full_rounds` is odd, if `alpha` is smaller than `2`, or if the sizes of `mds` and `ark`
+                do not match the width `rate + capacity` of the state and the number of rounds.
+        """
+        width = rate + capacity
+        if full_rounds % 2 != 0:
+            msg = f"The number of full rounds must be even: full_rounds = {full_rounds}"
+            raise ValueError(msg)
+        if alpha < 2:  # noqa: PLR2004
+            msg = f"The exponent of the S-box must be at least 2: alpha = {alpha}"
+            raise ValueError(msg)
+        if len(mds) != width or any(len(row) != width for row in mds):
+            msg = f"The MDS matrix must be of size {width} x {width}"
+            raise ValueError(msg)
+        if len(ark) != full_rounds + partial_rounds or any(len(constants) != width for constants in ark):
+            msg = f"Expected {full_rounds + partial_rounds} lists of {width} round constants"
+            raise ValueError(msg)
+
+        self.MODULUS = q
+        self.full_rounds = full_rounds
+        self.partial_rounds = partial_rounds
+        self.alpha = alpha
+        self.mds = mds
+        self.ark = ark
+        self.rate = rate
+        self.capacity = capacity
+
+    def __s_box(self, round_constant: int) -> Script:
+        """Add `round_constant` to the element on top of the stack and apply the S-box to the result.
+
+        Stack input:
+            - stack:    [q, .., x]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, .., (x + round_constant)^alpha]
+            - altstack: []
+        """
+        out = nums_to_script([round_constant]) + Script.parse_string("OP_ADD") if round_constant != 0 else Script()
+        out += pow_fixed_exponent(self.alpha, is_positive=False)
+        return out
+
+    def __mds(self, order: list[int], offsets: list[int]) -> Script:
+        """Multiply the state by the MDS matrix.
+
+        Stack input:
+            - stack:    [q, .., s_order[0], .., s_order[t-1]]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, .., s'_0, .., s'_(t-1)]
+            - altstack: []
+
+        where `s'_i = offsets[i] + sum_j mds[i][j] * s_j` (reduced modulo `q`).
+
+        Args:
+            order (list[int]): The indices of the elements of the state, in the order in which they are on the stack.
+            offsets (list[int]): The constants added to the result.
+        """
+        width = self.rate + self.capacity
+
+        out = Script()
+        # stack out:    [q, .., s_order[0], .., s_order[t-1]]
+        # altstack out: [s'_(t-1), .., s'_0]
+        for i in range(width - 1, -1, -1):
+            for j in range(width):
+                out += pick(position=width - 1 - order.index(j) + (j != 0), n_elements=1)
+                if self.mds[i][j] != 1:
+                    out += nums_to_script([self.mds[i][j]]) + Script.parse_string("OP_MUL")
+                out += Script.parse_string("OP_ADD") if j != 0 else Script()
+            out += nums_to_script([offsets[i]]) + Script.parse_string("OP_ADD") if offsets[i] != 0 else Script()
+            out += pick(position=-1, n_elements=1)
+            out += mod(stack_preparation="", is_positive=False, is_constant_reused=False)
+            out += Script.parse_string("OP_TOALTSTACK")
+
+        # stack out: [q, .., s'_0, .., s'_(t-1)]
+        out += Script.parse_string(" ".join(["OP_2DROP"] * (width // 2) + ["OP_DROP"] * (width % 2)))
+        out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * width))
+
+        return out
+
+    def permutation(self) -> Script:
+        """Apply the Poseidon permutation to the state on top of the stack.
+
+        Stack input:
+            - stack:    [q, .., s_0, .., s_(t-1)]
+            - altstack: []
+
+        Stack output:
+            - stack:    [q, .., s'_0, .., s'_(t-1)]
+            - altstack: []
+
+        where `t = rate + capacity`, and `s'` is the permutation of `s`. The elements of the output state are
+        reduced modulo `q`, but not necessarily positive.
+
+        Returns:
+            Script to apply the Poseidon permutation.
+        """
+        width = self.rate + self.capacity
+        half_full_rounds = self.full_rounds // 2
+
+        out = Script()
+        for round_number, constants in enumerate(self.ark):
+            if half_full_rounds <= round_number < half_full_rounds + self.partial_rounds:
+                # stack out: [q, .., s_1 + c_1, .., s_(t-1) + c_(t-1), (s_0 + c_0)^alpha], the constants c_1, ..,
+                # c_(t-1) are added during the multiplication by the MDS matrix
+                out += roll(position=width - 1, n_elements=1)
+                out += self.__s_box(constants[0])
+                offsets = [
+                    sum(self.mds[i][j] * constants[j] for j in range(1, width)) % self.MODULUS for i in range(width)
+                ]
+                out += self.__mds(order=[*range(1, width), 0], offsets=offsets)
+            else:
+                # stack out: [q, .., (s_0 + c_0)^alpha, .., (s_(t-1) + c_(t-1))^alpha]
+                for constant in constants:
+                    out += roll(position=width - 1, n_elements=1)
+                    out += self.__s_box(constant)
+                out += self.__mds(order=list(range(width)), offsets=[0] * width)
+
+        return out
+
+    def poseidon_hash(
+        self,
+        n_inputs: int,
+        check_constant: bool | None = None,
+        clean_constant: bool | None = None,
+    ) -> Script:
+        """Compute the Poseidon hash of the `n_inputs` field elements on top of the stack.
+
+        Stack input:
+            - stack:    [q, .., x_0, .., x_(n_inputs-1)]
+            - altstack: []
+
+        Stack output:
+            - stack:    [{q}, .., poseidon(x_0, .., x_(n_inputs-1))]
+            - altstack: []
+
+        Args:
+            n_inputs (int): The number of field elements to hash.
+            check_constant (bool | None): If `True`, check if `q` is valid before proceeding. Defaults to `None`.
+            clean_constant (bool | None): If `True`, remove `q` from the bottom of the stack. Defaults to `None`.
+
+        Returns:
+            Script to compute the Poseidon hash of `x_0, .., x_(n_inputs-1)`. The hash is the positive representative
+            modulo `q`, and coincides with the output of the sponge of `ark-crypto-primitives` absorbing the inputs
+            and squeezing a single field element.
+
+        Raises:
+            ValueError: If `n_inputs` is not positive.
+
+        Notes:
+            The script is large: each round of the permutation requires a multiplication of the state by the MDS
+            matrix, and the S-box is applied to the whole state in the full rounds.
+        """
+        if n_inputs < 1:
+            msg = f"The number of inputs must be positive: n_inputs = {n_inputs}"
+            raise ValueError(msg)
+
+        width = self.rate + self.capacity
+
+        out = verify_bottom_constant(self.MODULUS) if check_constant else Script()
+
+        # stack out: [q, .., x_0, .., x_(n_inputs-1), 0, .., 0]
+        out += Script.parse_string(" ".join(["OP_0"] * width))
+
+        for chunk_start in range(0, n_inputs, self.rate):
+            chunk = range(chunk_start, min(chunk_start + self.rate, n_inputs))
+            # stack out: [q, .., x_(chunk_start + rate), .., x_(n_inputs-1), s_0, .., s_(t-1)], where
+            # s_(capacity + i) += x_(chunk_start + i)
+            for index in range(width):
+                out += roll(position=width - 1, n_elements=1)
+                if index - self.capacity + chunk_start in chunk:
+                    out += roll(position=width + n_inputs - 1 - (index - self.capacity + chunk_start), n_elements=1)
+                    out += Script.parse_string("OP_ADD")
+            out += self.permutation()
+
+        # stack out: [q, .., s_capacity]
+        out += roll(position=width - 1 - self.capacity, n_elements=1)
+        out += Script.parse_string("OP_TOALTSTACK")
+        out += Script.parse_string(" ".join(["OP_2DROP"] * ((width - 1) // 2) + ["OP_DROP"] * ((width - 1) % 2)))
+        out += Script.parse_string("OP_FROMALTSTACK")
+
+        # stack out: [{q}, .., poseidon(x_0, .., x_(n_inputs-1))]
+        out += roll(position=-1, n_elements=1) if clean_constant else pick(position=-1, n_elements=1)
+        out += mod(stack_preparation="", is_positive=True, is_constant_reused=False)
+
+        return out
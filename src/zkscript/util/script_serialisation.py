@@ -0,0 +1,110 @@
+"""Save and load generated scripts to and from a versioned binary file format.
+
+The file format is:
+    - `MAGIC_BYTES` (4 bytes)
+    - the format version (2 bytes, big-endian)
+    - the length of the metadata (4 bytes, big-endian), followed by the metadata encoded as UTF-8 JSON
+    - the number of tokens of the script (4 bytes, big-endian)
+    - for each token of the script (as returned by `Script.to_string`), its length (4 bytes, big-endian) followed by
+        the token encoded as UTF-8
+"""
+
+import json
+from pathlib import Path
+
+from tx_engine import Script
+
+MAGIC_BYTES = b"ZKSC"
+VERSION = 1
+
+VERSION_BYTES = 2
+LENGTH_BYTES = 4
+
+
+def script_to_bytes(script: Script, metadata: dict | None = None) -> bytes:
+    """Serialise `script` and `metadata` in the zkscript file format.
+
+    Args:
+        script (Script): The script to serialise.
+        metadata (dict | None): JSON-serialisable metadata stored alongside the script (e.g., the curve and the
+            parameters used to generate it). Defaults to `None`, meaning that no metadata is stored.
+
+    Returns:
+        The serialisation of `script` and `metadata`.
+    """
+    encoded_metadata = json.dumps(metadata if metadata is not None else {}, sort_keys=True).encode()
+    tokens = [token.encode() for token in script.to_string().split()]
+
+    out = MAGIC_BYTES + VERSION.to_bytes(VERSION_BYTES, "big")
+    out += len(encoded_metadata).to_bytes(LENGTH_BYTES, "big") + encoded_metadata
+    out += len(tokens).to_bytes(LENGTH_BYTES, "big")
+    out += b"".join(len(token).to_bytes(LENGTH_BYTES, "big") + token for token in tokens)
+    return out
+
+
+def script_from_bytes(data: bytes) -> tuple[Script, dict]:
+    """Deserialise a script and its metadata from the zkscript file format.
+
+    Args:
+        data (bytes): The serialisation of the script, as returned by `script_to_bytes`.
+
+    Returns:
+        The pair `(script, metadata)`.
+
+    Raises:
+        ValueError: If `data` does not start with `MAGIC_BYTES`, if its version is not supported, or if it is
+            truncated or malformed.
+    """
+    if data[: len(MAGIC_BYTES)] != MAGIC_BYTES:
+        msg = "The data is not a zkscript script file: missing magic bytes"
+        raise ValueError(msg)
+    offset = len(MAGIC_BYTES)
+
+    def read(n_bytes: int) -> bytes:
+        nonlocal offset
+        if offset + n_bytes > len(data):
+            msg = "The zkscript script file is truncated"
+            raise ValueError(msg)
+        out = data[offset : offset + n_bytes]
+        offset += n_bytes
+        return out
+
+    version = int.from_bytes(read(VERSION_BYTES), "big")
+    if version > VERSION:
+        msg = f"Unsupported zkscript script file version: {version}. The latest supported version is {VERSION}"
+        raise ValueError(msg)
+
+    metadata = json.loads(read(int.from_bytes(read(LENGTH_BYTES), "big")).decode())
+    n_tokens = int.from_bytes(read(LENGTH_BYTES), "big")
+    tokens = [read(int.from_bytes(read(LENGTH_BYTES), "big")).decode() for _ in range(n_tokens)]
+    if offset != len(data):
+        msg = f"The zkscript script file has {len(data) - offset} unexpected trailing bytes"
+        raise ValueError(msg)
+
+    return Script.parse_string(" ".join(tokens)), metadata
+
+
+def save_script(script: Script, path: str | Path, metadata: dict | None = None) -> None:
+    """Save `script` and `metadata` to the file `path` in the zkscript file format.
+
+    Args:
+        script (Script): The script to save.
+        path (str | Path): The file to which the script is saved.
+        metadata (dict | None): JSON-serialisable metadata stored alongside the script. Defaults to `None`.
+    """
+    Path(path).write_bytes(script_to_bytes(script, metadata))
+
+
+def load_script(path: str | Path) -> tuple[Script, dict]:
+    """Load a script and its metadata from the file `path`, saved with `save_script`.
+
+    Args:
+        path (str | Path): The file from which the script is loaded.
+
+    Returns:
+        The pair `(script, metadata)`.
+
+    Raises:
+        ValueError: If the file is not a zkscript script file, or if its version is not supported.
+    """
+    return script_from_bytes(Path(path).read_bytes())
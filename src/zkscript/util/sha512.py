@@ -0,0 +1,289 @@
+"""Bitcoin script computing the SHA-512 digest of a stack element.
+
+Bitcoin script has no opcode for SHA-512, so the hash function is implemented with bitwise and arithmetic opcodes,
+following FIPS 180-4. The 64-bit words are represented on the stack as 8-byte big-endian strings, on which the
+bitwise operations (`OP_AND`, `OP_XOR`, `OP_LSHIFT`, `OP_RSHIFT`, ...) are executed. Additions modulo 2^64 are
+executed on the corresponding script numbers, and the result is converted back to an 8-byte string.
+"""
+
+from math import isqrt
+
+from tx_engine import Script
+
+from src.zkscript.util.utility_scripts import bytes_to_unsigned, nums_to_script, pick, reverse_endianness_fixed_length
+
+WORD_BYTES = 8
+WORD_BITS = 64
+BLOCK_BYTES = 128
+N_ROUNDS = 80
+LENGTH_BYTES = 16
+
+
+def _first_primes(n: int) -> list[int]:
+    """Return the first `n` prime numbers."""
+    primes = []
+    candidate = 2
+    while len(primes) < n:
+        if all(candidate % p != 0 for p in primes):
+            primes.append(candidate)
+        candidate += 1
+    return primes
+
+
+def _icbrt(n: int) -> int:
+    """Return the integer cube root of `n`."""
+    low, high = 0, 1 << (n.bit_length() // 3 + 1)
+    while low < high:
+        mid = (low + high + 1) // 2
+        if mid**3 <= n:
+            low = mid
+        else:
+            high = mid - 1
+    return low
+
+
+# First 64 bits of the fractional parts of the square roots of the first 8 primes
+SHA512_INITIAL_STATE = [isqrt(p << 128) % (1 << WORD_BITS) for p in _first_primes(8)]
+# First 64 bits of the fractional parts of the cube roots of the first 80 primes
+SHA512_ROUND_CONSTANTS = [_icbrt(p << 192) % (1 << WORD_BITS) for p in _first_primes(N_ROUNDS)]
+
+
+def sha512_padding(message_length: int) -> bytes:
+    """Return the padding appended to a message of `message_length` bytes before hashing it with SHA-512."""
+    n_zeros = (BLOCK_BYTES - LENGTH_BYTES - 1 - message_length) % BLOCK_BYTES
+    return b"\x80" + b"\x00" * n_zeros + (8 * message_length).to_bytes(LENGTH_BYTES, "big")
+
+
+def _word_to_num() -> Script:
+    """Convert the 8-byte big-endian word on top of the stack to a (non-negative) script number."""
+    return bytes_to_unsigned(WORD_BYTES)
+
+
+def _num_to_word() -> Script:
+    """Reduce the non-negative number on top of the stack modulo 2^64 and convert it to an 8-byte big-endian word."""
+    out = nums_to_script([1 << WORD_BITS])
+    out += Script.parse_string("OP_MOD")
+    out += nums_to_script([WORD_BYTES + 1])
+    out += Script.parse_string("OP_NUM2BIN")
+    out += nums_to_script([WORD_BYTES])
+    out += Script.parse_string("OP_SPLIT OP_DROP")
+    out += reverse_endianness_fixed_length(WORD_BYTES)
+    return out
+
+
+def _rotr(n: int) -> Script:
+    """Rotate the word on top of the stack right by `n` bits."""
+    if n % 8 == 0:
+        out = nums_to_script([WORD_BYTES - n // 8])
+        out += Script.parse_string("OP_SPLIT OP_SWAP OP_CAT")
+        return out
+    out = Script.parse_string("OP_DUP")
+    out += nums_to_script([n])
+    out += Script.parse_string("OP_RSHIFT OP_SWAP")
+    out += nums_to_script([WORD_BITS - n])
+    out += Script.parse_string("OP_LSHIFT OP_OR")
+    return out
+
+
+def _sigma(rotations: list[int], shift: int | None = None) -> Script:
+    """Compute the XOR of the rotations (and of the shift, if given) of the word on top of the stack.
+
+    The functions `Sigma0`, `Sigma1`, `sigma0` and `sigma1` of FIPS 180-4 are:
+        - `Sigma0 = _sigma([28, 34, 39])`
+        - `Sigma1 = _sigma([14, 18, 41])`
+        - `sigma0 = _sigma([1, 8], 7)`
+        - `sigma1 = _sigma([19, 61], 6)`
+    """
+    out = Script()
+    terms = [_rotr(n) for n in rotations]
+    if shift is not None:
+        terms.append(nums_to_script([shift]) + Script.parse_string("OP_RSHIFT"))
+    for term in terms[:-1]:
+        out += Script.parse_string("OP_DUP")
+        out += term
+        out += Script.parse_string("OP_SWAP")
+    out += terms[-1]
+    out += Script.parse_string(" ".join(["OP_XOR"] * (len(terms) - 1)))
+    return out
+
+
+def _message_schedule() -> Script:
+    """Expand the 16 words of a block into the 80 words of the message schedule.
+
+    Stack input:
+        - stack:    [.., W_0, .., W_15]
+        - altstack: []
+
+    Stack output:
+        - stack:    [.., W_0, .., W_79]
+        - altstack: []
+    """
+    out = Script()
+    for _ in range(16, N_ROUNDS):
+        # W_t = sigma1(W_(t-2)) + W_(t-7) + sigma0(W_(t-15)) + W_(t-16)
+        out += pick(position=1, n_elements=1)
+        out += _sigma([19, 61], 6)
+        out += _word_to_num()
+        out += pick(position=7, n_elements=1)
+        out += _word_to_num()
+        out += Script.parse_string("OP_ADD")
+        out += pick(position=15, n_elements=1)
+        out += _sigma([1, 8], 7)
+        out += _word_to_num()
+        out += Script.parse_string("OP_ADD")
+        out += pick(position=16, n_elements=1)
+        out += _word_to_num()
+        out += Script.parse_string("OP_ADD")
+        out += _num_to_word()
+    return out
+
+
+def _compression_round(t: int) -> Script:
+    """Execute the `t`-th round of the SHA-512 compression function.
+
+    Stack input:
+        - stack:    [.., h, g, f, e, d, c, b, a]
+        - altstack: [.., W_t]
+
+    Stack output:
+        - stack:    [.., g, f, e, d + T1, c, b, a, T1 + T2]
+        - altstack: [..]
+
+    where `T1 = h + Sigma1(e) + Ch(e,f,g) + K_t + W_t` and `T2 = Sigma0(a) + Maj(a,b,c)`.
+    """
+    # stack out: [.., h, g, f, e, d, c, b, a, Sigma1(e)]
+    out = pick(position=4, n_elements=1)
+    out += _sigma([14, 18, 41])
+    out += _word_to_num()
+    # Ch(e,f,g) = g ^ (e & (f ^ g))
+    # stack out: [.., h, g, f, e, d, c, b, a, Sigma1(e) + Ch(e,f,g)]
+    out += pick(position=6, n_elements=1)
+    out += pick(position=8, n_elements=1)
+    out += Script.parse_string("OP_XOR")
+    out += pick(position=6, n_elements=1)
+    out += Script.parse_string("OP_AND")
+    out += pick(position=8, n_elements=1)
+    out += Script.parse_string("OP_XOR")
+    out += _word_to_num()
+    out += Script.parse_string("OP_ADD")
+    # stack out: [.., g, f, e, d, c, b, a, T1]
+    out += Script.parse_string("OP_8 OP_ROLL")
+    out += _word_to_num()
+    out += Script.parse_string("OP_ADD")
+    out += nums_to_script([SHA512_ROUND_CONSTANTS[t]])
+    out += Script.parse_string("OP_ADD OP_FROMALTSTACK")
+    out += _word_to_num()
+    out += Script.parse_string("OP_ADD")
+    # stack out: [.., g, f, e, d, c, b, a, T1, Sigma0(a)]
+    out += pick(position=1, n_elements=1)
+    out += _sigma([28, 34, 39])
+    out += _word_to_num()
+    # Maj(a,b,c) = (a & b) ^ (c & (a ^ b))
+    # stack out: [.., g, f, e, d, c, b, a, T1, T2]
+    out += pick(position=2, n_elements=1)
+    out += pick(position=4, n_elements=1)
+    out += Script.parse_string("OP_2DUP OP_AND OP_TOALTSTACK OP_XOR")
+    out += pick(position=5, n_elements=1)
+    out += Script.parse_string("OP_AND OP_FROMALTSTACK OP_XOR")
+    out += _word_to_num()
+    out += Script.parse_string("OP_ADD")
+    # stack out: [.., g, f, e, c, b, a, T1 + T2, d + T1]
+    out += Script.parse_string("OP_OVER OP_ADD")
+    out += _num_to_word()
+    out += Script.parse_string("OP_SWAP OP_5 OP_ROLL")
+    out += _word_to_num()
+    out += Script.parse_string("OP_ADD")
+    out += _num_to_word()
+    # stack out: [.., g, f, e, d + T1, c, b, a, T1 + T2]
+    out += Script.parse_string(" ".join(["OP_4 OP_ROLL"] * 4))
+    return out
+
+
+def _compression() -> Script:
+    """Compress a block into the intermediate hash value.
+
+    Stack input:
+        - stack:    [.., H_0, .., H_7]
+        - altstack: [.., W_15, .., W_0]
+
+    Stack output:
+        - stack:    [.., H'_0, .., H'_7]
+        - altstack: [..]
+    """
+    # stack out:    [.., H_0, .., H_7]
+    # altstack out: [.., W_79, .., W_0]
+    out = Script.parse_string(" ".join(["OP_FROMALTSTACK"] * 16))
+    out += _message_schedule()
+    out += Script.parse_string(" ".join(["OP_TOALTSTACK"] * N_ROUNDS))
+
+    # stack out: [.., H_0, .., H_7, H_7, .., H_0]
+    for i in range(8):
+        out += pick(position=2 * i, n_elements=1)
+
+    for t in range(N_ROUNDS):
+        out += _compression_round(t)
+
+    # stack out: [.., H_0 + a, .., H_7 + h]
+    for n in range(8, 0, -1):
+        out += nums_to_script([n])
+        out += Script.parse_string("OP_ROLL")
+        out += _word_to_num()
+        out += nums_to_script([n])
+        out += Script.parse_string("OP_ROLL")
+        out += _word_to_num()
+        out += Script.parse_string("OP_ADD")
+        out += _num_to_word()
+        out += Script.parse_string("OP_TOALTSTACK")
+    out += Script.parse_string(" ".join(["OP_FROMALTSTACK"] * 8))
+
+    return out
+
+
+def sha512(message_length: int) -> Script:
+    """Compute the SHA-512 digest of the message on top of the stack.
+
+    Stack input:
+        - stack:    [.., message]
+        - altstack: []
+
+    Stack output:
+        - stack:    [.., sha512(message)]
+        - altstack: []
+
+    Args:
+        message_length (int): The length in bytes of `message`, which must be known when generating the script.
+
+    Returns:
+        Script to compute the SHA-512 digest of `message`.
+
+    Raises:
+        ValueError: If `message_length` is negative.
+
+    Notes:
+        The script is large: each block of 128 bytes of the padded message requires roughly 45 thousand opcodes.
+    """
+    if message_length < 0:
+        msg = f"The message length must be non-negative: message_length = {message_length}"
+        raise ValueError(msg)
+
+    padding = sha512_padding(message_length)
+    n_words = (message_length + len(padding)) // WORD_BYTES
+
+    # stack out:    [..]
+    # altstack out: [W_(n_words-1), .., W_0]
+    out = Script()
+    out.append_pushdata(padding)
+    out += Script.parse_string("OP_CAT")
+    out += Script.parse_string(" ".join(["OP_8 OP_SPLIT"] * (n_words - 1)))
+    out += Script.parse_string(" ".join(["OP_TOALTSTACK"] * n_words))
+
+    # stack out: [.., H_0, .., H_7]
+    for word in SHA512_INITIAL_STATE:
+        out.append_pushdata(word.to_bytes(WORD_BYTES, "big"))
+    for _ in range(n_words // 16):
+        out += _compression()
+
+    # stack out: [.., H_0 || .. || H_7]
+    out += Script.parse_string(" ".join(["OP_CAT"] * 7))
+
+    return out
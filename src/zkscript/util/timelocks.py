@@ -0,0 +1,190 @@
+"""Timelock guards to combine the scripts of the library with absolute and relative timelocks.
+
+A guard is the sequence `<n> OP_CHECKLOCKTIMEVERIFY OP_DROP` (absolute timelock, see BIP65) or
+`<n> OP_CHECKSEQUENCEVERIFY OP_DROP` (relative timelock, see BIP112). It leaves the stack untouched, so it can be
+prepended to any locking script (e.g., a Groth16 verifier) without modifying its stack input.
+
+Notes:
+    After the Genesis upgrade, `OP_CHECKLOCKTIMEVERIFY` and `OP_CHECKSEQUENCEVERIFY` are executed as `OP_NOP2` and
+    `OP_NOP3`, so script evaluation alone does not enforce the guards. They must be checked against the nLockTime and
+    nSequence of the spending transaction with `check_timelocks`, alongside the evaluation of the script.
+"""
+
+from tx_engine import Script, Tx
+from tx_engine.engine.op_codes import OP_CHECKLOCKTIMEVERIFY, OP_CHECKSEQUENCEVERIFY, OP_DROP
+
+from src.zkscript.util.utility_functions import push_token_to_element
+from src.zkscript.util.utility_scripts import nums_to_script
+
+LOCKTIME_THRESHOLD = 500_000_000  # nLockTime values below the threshold are block heights, the others are timestamps
+SEQUENCE_FINAL = 0xFFFFFFFF
+SEQUENCE_DISABLE_FLAG = 1 << 31
+SEQUENCE_TYPE_FLAG = 1 << 22  # If set, the relative timelock is in units of 512 seconds, else in blocks
+SEQUENCE_MASK = 0xFFFF
+MIN_VERSION_RELATIVE_TIMELOCK = 2  # Relative timelocks are only enforced for transactions of version at least 2
+
+CHECKLOCKTIMEVERIFY_TOKENS = {"OP_CHECKLOCKTIMEVERIFY", "OP_NOP2"}
+CHECKSEQUENCEVERIFY_TOKENS = {"OP_CHECKSEQUENCEVERIFY", "OP_NOP3"}
+
+
+def check_locktime_verify(locktime: int) -> Script:
+    """Guard requiring the nLockTime of the spending transaction to be at least `locktime`.
+
+    Stack input:
+        - stack:    [...]
+        - altstack: []
+
+    Stack output:
+        - stack:    [...]
+        - altstack: []
+
+    Args:
+        locktime (int): The required nLockTime, either a block height (if `locktime < 500_000_000`) or a
+            timestamp.
+
+    Returns:
+        Script enforcing the absolute timelock.
+
+    Raises:
+        ValueError: If `locktime` is not in `[0, 2^32)`.
+    """
+    if not 0 <= locktime < 2**32:
+        msg = f"The locktime must be in [0, 2^32): locktime = {locktime}"
+        raise ValueError(msg)
+    return nums_to_script([locktime]) + Script([OP_CHECKLOCKTIMEVERIFY, OP_DROP])
+
+
+def check_sequence_verify(sequence: int) -> Script:
+    """Guard requiring the nSequence of the spending input to encode a relative timelock of at least `sequence`.
+
+    Stack input:
+        - stack:    [...]
+        - altstack: []
+
+    Stack output:
+        - stack:    [...]
+        - altstack: []
+
+    Args:
+        sequence (int): The required relative timelock, encoded as in BIP68: the lowest 16 bits are the timelock, in
+            blocks or in units of 512 seconds if the type flag `1 << 22` is set.
+
+    Returns:
+        Script enforcing the relative timelock.
+
+    Raises:
+        ValueError: If `sequence` is not in `[0, 2^31)`. Values with the disable flag `1 << 31` set would make the
+            guard a no-op.
+    """
+    if not 0 <= sequence < SEQUENCE_DISABLE_FLAG:
+        msg = f"The sequence must be in [0, 2^31): sequence = {sequence}"
+        raise ValueError(msg)
+    return nums_to_script([sequence]) + Script([OP_CHECKSEQUENCEVERIFY, OP_DROP])
+
+
+def prepend_timelocks(script: Script, locktime: int | None = None, sequence: int | None = None) -> Script:
+    """Prepend the timelock guards to `script`.
+
+    Args:
+        script (Script): The script to guard.
+        locktime (int | None): If not `None`, the nLockTime required by `check_locktime_verify`. Defaults to `None`.
+        sequence (int | None): If not `None`, the relative timelock required by `check_sequence_verify`. Defaults to
+            `None`.
+
+    Returns:
+        The guarded script.
+    """
+    out = Script()
+    if locktime is not None:
+        out += check_locktime_verify(locktime)
+    if sequence is not None:
+        out += check_sequence_verify(sequence)
+    out += script
+    return out
+
+
+def timelock_guards(script: Script) -> list[tuple[str, int]]:
+    """Return the timelock guards of `script`.
+
+    Args:
+        script (Script): The script to inspect.
+
+    Returns:
+        The list of the guards of `script`, in order, as pairs `("locktime", n)` or `("sequence", n)`.
+
+    Raises:
+        ValueError: If a timelock opcode is not preceded by the push of a number.
+    """
+    guards = []
+    tokens = script.to_string().split()
+    for position, token in enumerate(tokens):
+        if token in CHECKLOCKTIMEVERIFY_TOKENS or token in CHECKSEQUENCEVERIFY_TOKENS:
+            pushed = push_token_to_element(tokens[position - 1]) if position > 0 else None
+            if pushed is None or pushed[1] is None:
+                msg = f"The timelock opcode at position {position} is not preceded by the push of a number"
+                raise ValueError(msg)
+            guards.append(("locktime" if token in CHECKLOCKTIMEVERIFY_TOKENS else "sequence", pushed[1]))
+    return guards
+
+
+def check_timelocks(script: Script, tx: Tx, index: int) -> bool:
+    """Check the timelock guards of `script` against the input `index` of the spending transaction `tx`.
+
+    The guards are checked with the rules of BIP65 (`OP_CHECKLOCKTIMEVERIFY`) and BIP112 (`OP_CHECKSEQUENCEVERIFY`).
+
+    Args:
+        script (Script): The locking script of the output spent by the input `index` of `tx`.
+        tx (Tx): The spending transaction.
+        index (int): The index of the input spending the output locked by `script`.
+
+    Returns:
+        `True` if all the guards of `script` are satisfied by `tx`, `False` otherwise.
+    """
+    tx_sequence = tx.tx_ins[index].sequence
+    for kind, value in timelock_guards(script):
+        is_satisfied = (
+            is_locktime_satisfied(value, tx.locktime, tx_sequence)
+            if kind == "locktime"
+            else is_sequence_satisfied(value, tx.version, tx_sequence)
+        )
+        if not is_satisfied:
+            return False
+    return True
+
+
+def is_locktime_satisfied(locktime: int, tx_locktime: int, tx_sequence: int) -> bool:
+    """Check the rules of BIP65 for the guard `<locktime> OP_CHECKLOCKTIMEVERIFY`.
+
+    Args:
+        locktime (int): The nLockTime required by the guard.
+        tx_locktime (int): The nLockTime of the spending transaction.
+        tx_sequence (int): The nSequence of the spending input.
+
+    Returns:
+        `True` if the spending transaction satisfies the guard, `False` otherwise.
+    """
+    if locktime < 0 or tx_sequence == SEQUENCE_FINAL:
+        return False
+    return (locktime < LOCKTIME_THRESHOLD) == (tx_locktime < LOCKTIME_THRESHOLD) and locktime <= tx_locktime
+
+
+def is_sequence_satisfied(sequence: int, tx_version: int, tx_sequence: int) -> bool:
+    """Check the rules of BIP112 for the guard `<sequence> OP_CHECKSEQUENCEVERIFY`.
+
+    Args:
+        sequence (int): The relative timelock required by the guard.
+        tx_version (int): The version of the spending transaction.
+        tx_sequence (int): The nSequence of the spending input.
+
+    Returns:
+        `True` if the spending transaction satisfies the guard, `False` otherwise.
+    """
+    if sequence < 0:
+        return False
+    if sequence & SEQUENCE_DISABLE_FLAG:
+        return True
+    if tx_version < MIN_VERSION_RELATIVE_TIMELOCK or tx_sequence & SEQUENCE_DISABLE_FLAG:
+        return False
+    return (sequence & SEQUENCE_TYPE_FLAG) == (tx_sequence & SEQUENCE_TYPE_FLAG) and (
+        sequence & SEQUENCE_MASK
+    ) <= (tx_sequence & SEQUENCE_MASK)
@@ -7,64 +7,67 @@ from tx_engine import Script
 from src.zkscript.script_types.stack_elements import StackElements
 
 
-def optimise_script(script: Script) -> Script:
-    """Optimise a script by simplifying certain operations.
-
-    This function simplifies certain operations, such as `OP_TOALTSTACK OP_FROMALTSTACK` and
-    `OP_FROMALTSTACK OP_TOALTSTACK`, which cancel each other out and are therefore removed.
-    The function iterates over the script until no further operations can be simplified.
+OPTIMISATION_PATTERNS = {
+    (
+        "OP_TOALTSTACK",
+        "OP_FROMALTSTACK",
+    ): [],
+    (
+        "OP_FROMALTSTACK",
+        "OP_TOALTSTACK",
+    ): [],
+    (
+        "OP_ROT",
+        "OP_ROT",
+        "OP_ROT",
+    ): [],
+    (
+        "OP_SWAP",
+        "OP_ADD",
+    ): ["OP_ADD"],
+    (
+        "OP_SWAP",
+        "OP_MUL",
+    ): ["OP_MUL"],
+    (
+        "OP_SWAP",
+        "OP_SUB",
+        "OP_NEGATE",
+    ): ["OP_SUB"],
+    (
+        "OP_0",
+        "OP_EQUAL",
+        "OP_NOT",
+    ): ["OP_0NOTEQUAL"],
+    (
+        "OP_SWAP",
+        "OP_TUCK",
+    ): ["OP_OVER"],
+}
+
+
+def _optimise_tokens(tokens: list[str], marks: list[int]) -> tuple[list[str], list[int]]:
+    """Simplify the operations in `tokens`, keeping track of the positions in `marks`.
 
     Args:
-        script (Script): The script to be optimised.
+        tokens (list[str]): The operations of the script to be optimised.
+        marks (list[int]): Positions in `tokens`, in increasing order.
 
     Returns:
-        The optimised script with redundant operations removed.
+        The optimised operations and, for each position in `marks`, the number of optimised operations which come from
+        the operations before it. If a simplification involves operations on both sides of a position, the position
+        is moved back to the first operation which is simplified.
     """
-    patterns = {
-        (
-            "OP_TOALTSTACK",
-            "OP_FROMALTSTACK",
-        ): [],
-        (
-            "OP_FROMALTSTACK",
-            "OP_TOALTSTACK",
-        ): [],
-        (
-            "OP_ROT",
-            "OP_ROT",
-            "OP_ROT",
-        ): [],
-        (
-            "OP_SWAP",
-            "OP_ADD",
-        ): ["OP_ADD"],
-        (
-            "OP_SWAP",
-            "OP_MUL",
-        ): ["OP_MUL"],
-        (
-            "OP_SWAP",
-            "OP_SUB",
-            "OP_NEGATE",
-        ): ["OP_SUB"],
-        (
-            "OP_0",
-            "OP_EQUAL",
-            "OP_NOT",
-        ): ["OP_0NOTEQUAL"],
-        (
-            "OP_SWAP",
-            "OP_TUCK",
-        ): ["OP_OVER"],
-    }
-
-    script_list = script.to_string().split()
     stack = []
+    positions = []
+
+    for i, op in enumerate(tokens):
+        while len(positions) < len(marks) and marks[len(positions)] == i:
+            positions.append(len(stack))
 
-    for op in script_list:
         stack.append(op)
 
-        for pattern, replacement in patterns.items():
+        for pattern, replacement in OPTIMISATION_PATTERNS.items():
             pattern_length = len(pattern)
 
             if len(stack) >= pattern_length:
@@ -73,12 +76,97 @@ def optimise_script(script: Script) -> Script:
                 if last_elements == pattern:
                     for _ in range(pattern_length):
                         stack.pop()
+                    if positions and positions[-1] > len(stack):
+                        positions = [min(position, len(stack)) for position in positions]
                     stack.extend(replacement)
                     break
 
+    positions += [len(stack)] * (len(marks) - len(positions))
+
+    return stack, positions
+
+
+def optimise_script(script: Script) -> Script:
+    """Optimise a script by simplifying certain operations.
+
+    This function simplifies certain operations, such as `OP_TOALTSTACK OP_FROMALTSTACK` and
+    `OP_FROMALTSTACK OP_TOALTSTACK`, which cancel each other out and are therefore removed.
+    The function iterates over the script until no further operations can be simplified.
+
+    Args:
+        script (Script): The script to be optimised.
+
+    Returns:
+        The optimised script with redundant operations removed.
+    """
+    stack, _ = _optimise_tokens(script.to_string().split(), [])
+
     return Script.parse_string(" ".join(stack))
 
 
+def optimise_labelled_scripts(
+    regions: list[tuple[Script, list[tuple[int, str]]]],
+) -> tuple[Script, list[tuple[int, str]]]:
+    """Concatenate and optimise script regions, keeping track of where their labels end up.
+
+    Each region is a script together with a list of `(byte_offset, label)` pairs, where `byte_offset` is relative to
+    the beginning of the region and falls on the boundary of an operation. The returned script is the same as
+    `optimise_script` applied to the concatenation of the regions, and the labels are mapped onto it.
+
+    Args:
+        regions (list[tuple[Script, list[tuple[int, str]]]]): The regions to concatenate, in order.
+
+    Returns:
+        The optimised script and the list of `(byte_offset, label)` pairs, where `byte_offset` is relative to
+        the beginning of the optimised script.
+
+    Notes:
+        If a simplification involves operations on both sides of a label, the label is moved back to the first
+        operation which is simplified, so that the operations after the label include all the operations of the
+        labelled region.
+    """
+    tokens = []
+    marks = []
+    labels = []
+    for script, region_labels in regions:
+        region_tokens = script.to_string().split()
+        for byte_offset, label in region_labels:
+            marks.append(len(tokens) + _token_index(region_tokens, byte_offset))
+            labels.append(label)
+        tokens += region_tokens
+
+    order = sorted(range(len(marks)), key=lambda i: marks[i])
+    stack, positions = _optimise_tokens(tokens, [marks[i] for i in order])
+
+    return Script.parse_string(" ".join(stack)), [
+        (_byte_length(stack[:position]), labels[i]) for i, position in zip(order, positions)
+    ]
+
+
+def _byte_length(tokens: list[str]) -> int:
+    """Return the length in bytes of the script made of the operations in `tokens`."""
+    return len(Script.parse_string(" ".join(tokens)).raw_serialize()) if tokens else 0
+
+
+def _token_index(tokens: list[str], byte_offset: int) -> int:
+    """Return the number of operations in `tokens` which come before `byte_offset`.
+
+    Raises:
+        ValueError: If `byte_offset` does not fall on the boundary of an operation.
+    """
+    low, high = 0, len(tokens)
+    while low < high:
+        middle = (low + high) // 2
+        if _byte_length(tokens[:middle]) < byte_offset:
+            low = middle + 1
+        else:
+            high = middle
+    if _byte_length(tokens[:low]) != byte_offset:
+        msg = f"The byte offset {byte_offset} does not fall on the boundary of an operation"
+        raise ValueError(msg)
+    return low
+
+
 def check_order(stack_elements: list[StackElements]) -> ValueError | None:
     """Check that the elements in `stack_elements` do not overlap and are in the right order.
 
@@ -141,6 +229,67 @@ def bitmask_to_boolean_list(bitmask: int, list_length: int) -> list[bool]:
     return [*out, *[False] * (list_length - len(out))]
 
 
+def naf_expansion(n: int) -> list[int]:
+    """Compute the non-adjacent form (NAF) of `n`, from the least to the most significant digit.
+
+    The NAF is the signed base two expansion of `n` with digits in {-1, 0, 1} in which no two consecutive digits are
+    non-zero. It is the signed expansion of `n` with the fewest non-zero digits, i.e., when used as the expansion of
+    the Miller loop, the one requiring the fewest additions (and line evaluations).
+
+    Example:
+        >>> naf_expansion(7)
+        [-1, 0, 0, 1]
+        >>> naf_expansion(-7)
+        [1, 0, 0, -1]
+        >>> naf_expansion(6)
+        [0, -1, 0, 1]
+    """
+    if n < 0:
+        return [-digit for digit in naf_expansion(-n)]
+
+    out = []
+    while n > 0:
+        digit = 2 - (n % 4) if n % 2 == 1 else 0
+        out.append(digit)
+        n = (n - digit) // 2
+    return out
+
+
+def sliding_window_expansion(n: int, window_size: int) -> list[tuple[int, int]]:
+    """Compute the sliding window expansion of the positive integer `n`, from the most to the least significant window.
+
+    The expansion is a list of pairs `(n_squarings, digit)`, where `digit` is either `0` or an odd number smaller than
+    `2^window_size`, such that `n` is obtained by starting from `0` and, for each pair, multiplying by
+    `2^n_squarings` and adding `digit`. The first pair is `(window length, first window)`.
+
+    Example:
+        >>> sliding_window_expansion(5, 1)
+        [(1, 1), (2, 1)]
+        >>> sliding_window_expansion(5, 3)
+        [(3, 5)]
+        >>> sliding_window_expansion(22, 2)
+        [(1, 1), (3, 3), (1, 0)]
+    """
+    bits = bin(n)[2:]
+    out = []
+    n_squarings = 0
+    i = 0
+    while i < len(bits):
+        if bits[i] == "0":
+            n_squarings += 1
+            i += 1
+            continue
+        j = min(i + window_size, len(bits))
+        while bits[j - 1] == "0":
+            j -= 1
+        out.append((n_squarings + j - i, int(bits[i:j], 2)))
+        n_squarings = 0
+        i = j
+    if n_squarings > 0:
+        out.append((n_squarings, 0))
+    return out
+
+
 def base_function_size_estimation_miller_loop(
     modulus: int,
     modulo_threshold: int,
@@ -214,3 +363,317 @@ def base_function_size_estimation_miller_loop(
         out_size_miller_loop,
         out_size_point_miller_loop,
     )
+
+
+STACK_LIMITS_NO_EFFECT = {"OP_NOP", "OP_CODESEPARATOR", *[f"OP_NOP{i}" for i in range(1, 11)]}
+STACK_LIMITS_HASHES = {"OP_SHA256": 32, "OP_HASH256": 32, "OP_RIPEMD160": 20, "OP_HASH160": 20, "OP_SHA1": 20}
+STACK_LIMITS_UNARY = {"OP_1ADD", "OP_1SUB", "OP_NEGATE", "OP_ABS", "OP_2MUL", "OP_2DIV"}
+STACK_LIMITS_BOOLEAN = {"OP_NOT": 1, "OP_0NOTEQUAL": 1, "OP_WITHIN": 3, "OP_CHECKSIG": 2}
+STACK_LIMITS_BINARY_BOOLEAN = {
+    "OP_EQUAL",
+    "OP_BOOLAND",
+    "OP_BOOLOR",
+    "OP_NUMEQUAL",
+    "OP_NUMNOTEQUAL",
+    "OP_LESSTHAN",
+    "OP_GREATERTHAN",
+    "OP_LESSTHANOREQUAL",
+    "OP_GREATERTHANOREQUAL",
+}
+STACK_LIMITS_VERIFY = {"OP_VERIFY": 1, "OP_EQUALVERIFY": 2, "OP_NUMEQUALVERIFY": 2, "OP_CHECKSIGVERIFY": 2}
+STACK_LIMITS_NO_NEW_ELEMENTS = {
+    "OP_IF",
+    "OP_NOTIF",
+    "OP_ELSE",
+    "OP_ENDIF",
+    "OP_DROP",
+    "OP_2DROP",
+    "OP_NIP",
+    "OP_SWAP",
+    "OP_2SWAP",
+    "OP_ROT",
+    "OP_2ROT",
+    "OP_ROLL",
+    "OP_TOALTSTACK",
+    "OP_FROMALTSTACK",
+    *STACK_LIMITS_NO_EFFECT,
+    *STACK_LIMITS_VERIFY,
+}
+
+
+def script_num_size(n: int) -> int:
+    """Return the size in bytes of the minimal encoding of `n` as a script number."""
+    return 0 if n == 0 else abs(n).bit_length() // 8 + 1
+
+
+def push_token_to_element(token: str) -> tuple[int, int | None] | None:
+    """Return the (size, value) of the element pushed by `token`, or `None` if `token` is not a push."""
+    if token in {"OP_0", "OP_FALSE"}:
+        return 0, 0
+    if token == "OP_1NEGATE":
+        return 1, -1
+    if token == "OP_TRUE":
+        return 1, 1
+    if token.startswith("OP_") and token[3:].isdigit():
+        return 1, int(token[3:])
+    if token.startswith("0x"):
+        data = bytes.fromhex(token[2:])
+        if not data:
+            return 0, 0
+        value = int.from_bytes(data[:-1] + bytes([data[-1] & 0x7F]), "little")
+        return len(data), -value if data[-1] & 0x80 else value
+    if token.lstrip("-").isdigit():
+        return script_num_size(int(token)), int(token)
+    return None
+
+
+def check_stack_limits(
+    script: Script,
+    max_elements: int,
+    max_element_bytes: int,
+    initial_stack: list[int] | None = None,
+    unknown_element_bytes: int = 0,
+    max_violations: int | None = None,
+) -> list[str]:
+    """Statically check that the execution of `script` stays within the stack limits.
+
+    The script is walked tracking the worst-case number of elements on the stack and altstack, and an upper bound on
+    the size of each element. The sizes of data pushes are known, while the sizes of the outputs of arithmetic,
+    splicing and hashing operations are bounded from the sizes of their inputs. For `OP_IF ... OP_ELSE ... OP_ENDIF`,
+    both branches are walked and the worst case is kept. Elements accessed below `initial_stack` (e.g., those
+    supplied by an unlocking script which is not known) are assumed to be of size `unknown_element_bytes`. The values
+    of numbers pushed on the stack are tracked through `OP_DEPTH` and additions, so that the positions of `OP_PICK`
+    and `OP_ROLL` are usually known.
+
+    Args:
+        script (Script): The script to check.
+        max_elements (int): The maximum number of elements on the stack and altstack combined.
+        max_element_bytes (int): The maximum size of a stack element, in bytes.
+        initial_stack (list[int] | None): The sizes, in bytes, of the elements on the stack before the execution of
+            `script`, from bottom to top. Defaults to `None`, meaning that the stack is empty.
+        unknown_element_bytes (int): The size, in bytes, assumed for the elements accessed below `initial_stack`.
+            Defaults to `0`.
+        max_violations (int | None): If not `None`, the walk stops after `max_violations` violations are found.
+            Defaults to `None`.
+
+    Returns:
+        The list of violations, each describing the position of the operation in the script and the limit it
+        exceeds. The list is empty if the script stays within the limits.
+
+    Raises:
+        ValueError: If `script` contains an unsupported opcode or unbalanced conditionals.
+    """
+    # Each element is a pair (size, value), where value is None if not known statically
+    stack = [(size, None) for size in (initial_stack if initial_stack is not None else [])]
+    altstack = []
+    # Each entry is [stack and altstack before the branches, stack and altstack at the end of the first branch]
+    branches = []
+    violations = []
+    exceeds_max_elements = False
+
+    def ensure(n: int) -> None:
+        while len(stack) < n:
+            stack.insert(0, (unknown_element_bytes, None))
+
+    def pop() -> tuple[int, int | None]:
+        ensure(1)
+        return stack.pop()
+
+    def merge(first: tuple[list, list], second: tuple[list, list]) -> tuple[list, list]:
+        if len(first[0]) != len(second[0]) or len(first[1]) != len(second[1]):
+            return max(first, second, key=lambda state: len(state[0]) + len(state[1]))
+        return tuple(
+            [(max(x[0], y[0]), x[1] if x[1] == y[1] else None) for x, y in zip(a, b, strict=True)]
+            for a, b in zip(first, second, strict=True)
+        )
+
+    for position, token in enumerate(script.to_string().split()):
+        pushed = push_token_to_element(token)
+        if pushed is not None:
+            stack.append(pushed)
+        elif token in STACK_LIMITS_NO_EFFECT:
+            pass
+        elif token in {"OP_IF", "OP_NOTIF"}:
+            pop()
+            branches.append([(list(stack), list(altstack)), None])
+        elif token == "OP_ELSE":
+            if not branches:
+                msg = f"Unbalanced OP_ELSE at position {position}"
+                raise ValueError(msg)
+            branches[-1][1] = (stack, altstack)
+            stack, altstack = list(branches[-1][0][0]), list(branches[-1][0][1])
+        elif token == "OP_ENDIF":
+            if not branches:
+                msg = f"Unbalanced OP_ENDIF at position {position}"
+                raise ValueError(msg)
+            before, first_branch = branches.pop()
+            stack, altstack = merge(first_branch if first_branch is not None else before, (stack, altstack))
+        elif token == "OP_RETURN":
+            break
+        elif token in {"OP_DUP", "OP_IFDUP"}:
+            ensure(1)
+            stack.append(stack[-1])
+        elif token in {"OP_2DUP", "OP_3DUP"}:
+            n = 2 if token == "OP_2DUP" else 3
+            ensure(n)
+            stack.extend(stack[-n:])
+        elif token == "OP_OVER":
+            ensure(2)
+            stack.append(stack[-2])
+        elif token == "OP_2OVER":
+            ensure(4)
+            stack.extend(stack[-4:-2])
+        elif token == "OP_TUCK":
+            ensure(2)
+            stack.insert(-2, stack[-1])
+        elif token in {"OP_PICK", "OP_ROLL"}:
+            _, n = pop()
+            if n is not None and n >= 0:
+                ensure(n + 1)
+                element = stack[-1 - n]
+                if token == "OP_ROLL":
+                    del stack[-1 - n]
+                stack.append(element)
+            elif token == "OP_PICK":
+                # Worst case: the largest element is picked
+                stack.append((max((size for size, _ in stack), default=0), None))
+            else:
+                ensure(1)
+                stack.append((max(size for size, _ in stack), None))
+                stack.pop(-2)
+        elif token == "OP_DROP":
+            pop()
+        elif token == "OP_2DROP":
+            pop()
+            pop()
+        elif token == "OP_NIP":
+            ensure(2)
+            del stack[-2]
+        elif token == "OP_SWAP":
+            ensure(2)
+            stack[-2:] = stack[-1:-3:-1]
+        elif token == "OP_2SWAP":
+            ensure(4)
+            stack[-4:] = stack[-2:] + stack[-4:-2]
+        elif token == "OP_ROT":
+            ensure(3)
+            stack.append(stack.pop(-3))
+        elif token == "OP_2ROT":
+            ensure(6)
+            stack.extend([stack.pop(-6), stack.pop(-5)])
+        elif token == "OP_DEPTH":
+            stack.append((script_num_size(len(stack)), len(stack)))
+        elif token == "OP_SIZE":
+            ensure(1)
+            stack.append((script_num_size(stack[-1][0]), stack[-1][0]))
+        elif token == "OP_TOALTSTACK":
+            altstack.append(pop())
+        elif token == "OP_FROMALTSTACK":
+            stack.append(altstack.pop() if altstack else (0, None))
+        elif token in STACK_LIMITS_HASHES:
+            pop()
+            stack.append((STACK_LIMITS_HASHES[token], None))
+        elif token in {"OP_1ADD", "OP_1SUB"}:
+            size, value = pop()
+            value = None if value is None else value + (1 if token == "OP_1ADD" else -1)
+            stack.append((size + 1 if value is None else script_num_size(value), value))
+        elif token in STACK_LIMITS_UNARY:
+            size, _ = pop()
+            stack.append((size + 1, None))
+        elif token in STACK_LIMITS_BOOLEAN:
+            for _ in range(STACK_LIMITS_BOOLEAN[token]):
+                pop()
+            stack.append((1, None))
+        elif token in STACK_LIMITS_BINARY_BOOLEAN:
+            pop()
+            pop()
+            stack.append((1, None))
+        elif token in STACK_LIMITS_VERIFY:
+            for _ in range(STACK_LIMITS_VERIFY[token]):
+                pop()
+        elif token in {"OP_ADD", "OP_SUB"} and len(stack) >= 2 and None not in (stack[-1][1], stack[-2][1]):
+            (_, b), (_, a) = pop(), pop()
+            value = a + b if token == "OP_ADD" else a - b
+            stack.append((script_num_size(value), value))
+        elif token in {"OP_ADD", "OP_SUB", "OP_MIN", "OP_MAX", "OP_AND", "OP_OR", "OP_XOR"}:
+            (b, _), (a, _) = pop(), pop()
+            stack.append((max(a, b) + (1 if token in {"OP_ADD", "OP_SUB"} else 0), None))
+        elif token in {"OP_MUL", "OP_CAT"}:
+            (b, _), (a, _) = pop(), pop()
+            stack.append((a + b, None))
+        elif token in {"OP_DIV", "OP_MOD"}:
+            (b, _), (a, _) = pop(), pop()
+            stack.append((min(a, b) if token == "OP_MOD" else a, None))
+        elif token in {"OP_LSHIFT", "OP_RSHIFT", "OP_BIN2NUM", "OP_INVERT"}:
+            if token in {"OP_LSHIFT", "OP_RSHIFT"}:
+                pop()
+            size, _ = pop()
+            stack.append((size, None))
+        elif token == "OP_SPLIT":
+            (_, n), (size, _) = pop(), pop()
+            stack.extend([(n, None), (size - n, None)] if n is not None and 0 <= n <= size else [(size, None)] * 2)
+        elif token == "OP_NUM2BIN":
+            (_, n), (size, _) = pop(), pop()
+            # If the length is not known statically, assume that no padding is added
+            stack.append((n if n is not None else size, None))
+        else:
+            msg = f"Unsupported opcode {token} at position {position}"
+            raise ValueError(msg)
+
+        n_elements = len(stack) + len(altstack)
+        name = token if len(token) <= 20 else token[:17] + "..."
+        if n_elements > max_elements and not exceeds_max_elements:
+            violations.append(
+                f"Position {position} ({name}): {n_elements} elements on the stack and altstack, "
+                f"exceeding max_elements = {max_elements}"
+            )
+        exceeds_max_elements = n_elements > max_elements
+        if token not in STACK_LIMITS_NO_NEW_ELEMENTS and stack and stack[-1][0] > max_element_bytes:
+            violations.append(
+                f"Position {position} ({name}): element of {stack[-1][0]} bytes, "
+                f"exceeding max_element_bytes = {max_element_bytes}"
+            )
+        if max_violations is not None and len(violations) >= max_violations:
+            return violations[:max_violations]
+
+    if branches:
+        msg = "Unbalanced OP_IF/OP_NOTIF"
+        raise ValueError(msg)
+
+    return violations
+
+
+def diff_scripts(a: Script, b: Script, context: int = 3) -> tuple[int, list[str], list[str]] | None:
+    """Find the first operation at which two scripts differ.
+
+    This function is meant to debug optimisations: given two versions of the same script, it reports where they
+    start to diverge.
+
+    Args:
+        a (Script): The first script.
+        b (Script): The second script.
+        context (int): The number of operations to include in the context window before and after the first
+            difference. Defaults to `3`.
+
+    Returns:
+        `None` if the scripts are identical. Otherwise, a tuple `(index, context_a, context_b)`, where `index` is the
+        position of the first operation at which `a` and `b` differ (equal to the length of the shorter script if it
+        is a prefix of the other one), and `context_a`, `context_b` are the operations of `a` and `b` in the window
+        `[index - context, index + context]`.
+
+    Raises:
+        ValueError: If `context` is negative.
+    """
+    if context < 0:
+        msg = f"The context must be non-negative: context = {context}"
+        raise ValueError(msg)
+
+    ops_a = a.to_string().split()
+    ops_b = b.to_string().split()
+
+    index = next((i for i, (op_a, op_b) in enumerate(zip(ops_a, ops_b)) if op_a != op_b), min(len(ops_a), len(ops_b)))
+    if index == len(ops_a) == len(ops_b):
+        return None
+
+    start, end = max(index - context, 0), index + context + 1
+    return index, ops_a[start:end], ops_b[start:end]
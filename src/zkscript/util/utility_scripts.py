@@ -8,6 +8,7 @@ from tx_engine.engine.op_codes import (
     OP_1,
     OP_1NEGATE,
     OP_2,
+    OP_2DROP,
     OP_2DUP,
     OP_2OVER,
     OP_2ROT,
@@ -29,9 +30,11 @@ from tx_engine.engine.op_codes import (
     OP_16,
     OP_ADD,
     OP_CAT,
+    OP_DROP,
     OP_DUP,
     OP_EQUAL,
     OP_EQUALVERIFY,
+    OP_FROMALTSTACK,
     OP_HASH256,
     OP_MOD,
     OP_MUL,
@@ -40,6 +43,7 @@ from tx_engine.engine.op_codes import (
     OP_ROLL,
     OP_ROT,
     OP_SWAP,
+    OP_TOALTSTACK,
     OP_TUCK,
 )
 
@@ -51,7 +55,7 @@ from src.zkscript.script_types.stack_elements import (
     StackFiniteFieldElement,
     StackNumber,
 )
-from src.zkscript.util.utility_functions import bitmask_to_boolean_list, check_order
+from src.zkscript.util.utility_functions import bitmask_to_boolean_list, check_order, sliding_window_expansion
 
 patterns_to_pick = {
     (0, 1): [OP_DUP],
@@ -735,3 +739,97 @@ def unsigned_from_bits(
         shift += -list_rolling_options[i]
 
     return out
+
+
+def pow_fixed_exponent(
+    exp: int,
+    clean_constant: bool = False,
+    is_positive: bool = True,
+    modulus: StackNumber = StackNumber(-1, False),  # noqa: B008
+    window_size: int | None = None,
+) -> Script:
+    """Compute `a^exp % modulus` for an exponent `exp` known when generating the script.
+
+    Stack input:
+        - stack:    [.., modulus, .., a]
+        - altstack: []
+
+    Stack output:
+        - stack:    [.., {modulus}, .., a^exp % modulus]
+        - altstack: []
+
+    The script implements sliding window exponentiation: the odd powers `a, a^3, .., a^(2^window_size - 1)` are
+    computed once and kept on the stack, and the squarings are shared between the windows of the expansion of `exp`
+    (see `sliding_window_expansion`). Every intermediate result is reduced modulo `modulus`.
+
+    Args:
+        exp (int): The exponent.
+        clean_constant (bool): If `True`, `modulus` is removed from the stack. Defaults to `False`.
+        is_positive (bool): If `True`, the result is the positive representative modulo `modulus`. Defaults to
+            `True`.
+        modulus (StackNumber): The position of the modulus in the stack. Defaults to `StackNumber(-1,False)`.
+        window_size (int | None): The size of the windows. If `None`, the size minimising the number of
+            multiplications is used. Defaults to `None`.
+
+    Returns:
+        The script to compute `a^exp % modulus`.
+
+    Raises:
+        ValueError: If `exp` or `window_size` are not positive.
+
+    Example:
+        The inverse of `a` modulo the prime `p` is computed by `pow_fixed_exponent(p - 2)`, and, if `p = 3 mod 4`, a
+        square root of the quadratic residue `a` is computed by `pow_fixed_exponent((p + 1) // 4)`.
+    """
+    if exp < 1:
+        msg = f"The exponent must be positive: exp = {exp}"
+        raise ValueError(msg)
+    if window_size is None:
+        window_size = min(
+            range(1, 7),
+            key=lambda w: 2 ** (w - 1) * (w > 1) + sum(digit != 0 for _, digit in sliding_window_expansion(exp, w)),
+        )
+    if window_size < 1:
+        msg = f"The window size must be positive: window_size = {window_size}"
+        raise ValueError(msg)
+
+    expansion = sliding_window_expansion(exp, window_size)
+    n_powers = 2 ** (window_size - 1) if max(digit for _, digit in expansion) > 1 else 1
+
+    # stack out: [.., modulus, a]
+    out = move(modulus, bool_to_moving_function(clean_constant))
+    out += Script([OP_SWAP])
+
+    # stack out: [.., modulus, a, a^3, .., a^(2 * n_powers - 1)]
+    if n_powers > 1:
+        out += Script([OP_DUP, OP_DUP, OP_MUL])
+        out += pick(position=2, n_elements=1)
+        out += Script([OP_MOD, OP_TOALTSTACK])
+        for i in range(1, n_powers):
+            out += Script([OP_DUP, OP_FROMALTSTACK, OP_TUCK, OP_MUL])
+            out += pick(position=i + 2, n_elements=1)
+            out += Script([OP_MOD, OP_SWAP, OP_TOALTSTACK])
+        out += Script([OP_FROMALTSTACK, OP_DROP])
+
+    # stack out: [.., modulus, a, a^3, .., a^(2 * n_powers - 1), a^exp]
+    out += pick(position=n_powers - 1 - (expansion[0][1] - 1) // 2, n_elements=1)
+    operations = []
+    for n_squarings, digit in expansion[1:]:
+        operations += [0] * n_squarings + ([digit] if digit != 0 else [])
+    for i, digit in enumerate(operations):
+        if digit == 0:
+            out += Script([OP_DUP, OP_MUL])
+        else:
+            out += pick(position=n_powers - (digit - 1) // 2, n_elements=1)
+            out += Script([OP_MUL])
+        if i != len(operations) - 1:
+            out += pick(position=n_powers + 1, n_elements=1)
+            out += Script([OP_MOD])
+
+    # stack out: [.., {modulus}, a^exp % modulus]
+    out += Script([OP_TOALTSTACK])
+    out += Script([OP_2DROP] * (n_powers // 2) + [OP_DROP] * (n_powers % 2))
+    out += Script([OP_FROMALTSTACK])
+    out += mod(stack_preparation="", is_mod_on_top=False, is_positive=is_positive, is_constant_reused=False)
+
+    return out
@@ -0,0 +1,146 @@
+"""Split a script into stages executed in different spends (a verification chain).
+
+When a script (e.g., a Groth16 verifier) is too large to fit in a single locking script, its execution can be split
+into stages, each executed when spending a different output. The state passed from one stage to the next is the
+content of the stack at the boundary between the two stages, which the prover supplies in the unlocking script of
+the next stage.
+
+The stages are bound together by the state commitment: the commitment to the state `[x_0, .., x_(n-1)]`
+(`x_(n-1)` on top of the stack) is defined recursively as:
+    - h_n = b""
+    - h_i = hash256(encode_num(x_i) || h_(i+1))
+    - commitment = h_0
+where `encode_num` is the minimal encoding of a script number. A stage which is not the last one ends by replacing
+the state it computed with its commitment, while a stage which is not the first one is generated for the commitment
+computed by the previous stage, and starts by checking that the state supplied in the unlocking script matches it.
+
+Notes:
+    The scripts generated here do not enforce that the output spent to execute a stage is locked with the locking
+    script of that stage for the commitment computed by the previous stage. This must be enforced by the
+    application, e.g., with transaction introspection.
+"""
+
+from tx_engine import Script, encode_num, hash256d
+
+from src.zkscript.util.utility_scripts import nums_to_script, pick
+
+
+def state_commitment(state: list[int]) -> bytes:
+    """Compute the commitment to `state`.
+
+    Args:
+        state (list[int]): The state, from the bottom to the top of the stack.
+
+    Returns:
+        The commitment to `state`, as computed by `commit_to_state`.
+    """
+    commitment = b""
+    for element in state[::-1]:
+        commitment = hash256d(encode_num(element) + commitment)
+    return commitment
+
+
+def commit_to_state(n_elements: int) -> Script:
+    """Replace the state on top of the stack with its commitment.
+
+    Stack input:
+        - stack:    [..., x_0, .., x_(n_elements-1)]
+        - altstack: []
+
+    Stack output:
+        - stack:    [..., state_commitment([x_0, .., x_(n_elements-1)])]
+        - altstack: []
+
+    Args:
+        n_elements (int): The number of elements of the state.
+
+    Returns:
+        Script to replace the state with its commitment.
+
+    Raises:
+        ValueError: If `n_elements` is not positive.
+    """
+    if n_elements < 1:
+        msg = f"The state must contain at least one element: n_elements = {n_elements}"
+        raise ValueError(msg)
+    return Script.parse_string(" ".join(["OP_HASH256", *["OP_CAT", "OP_HASH256"] * (n_elements - 1)]))
+
+
+def verify_state_commitment(n_elements: int, commitment: bytes) -> Script:
+    """Check that the state on top of the stack matches `commitment`.
+
+    Stack input:
+        - stack:    [..., x_0, .., x_(n_elements-1)]
+        - altstack: []
+
+    Stack output:
+        - stack:    [..., x_0, .., x_(n_elements-1)] or fail
+        - altstack: []
+
+    Args:
+        n_elements (int): The number of elements of the state.
+        commitment (bytes): The commitment the state must match.
+
+    Returns:
+        Script to check the state against `commitment`.
+
+    Raises:
+        ValueError: If `n_elements` is not positive.
+    """
+    out = pick(position=n_elements - 1, n_elements=n_elements)
+    out += commit_to_state(n_elements)
+    out.append_pushdata(commitment)
+    out += Script.parse_string("OP_EQUALVERIFY")
+    return out
+
+
+def chain_stage(
+    script: Script, n_elements_in: int = 0, n_elements_out: int = 0, input_commitment: bytes | None = None
+) -> Script:
+    """Construct the locking script of a stage of a verification chain.
+
+    Stack input:
+        - stack:    [x_0, .., x_(n_elements_in-1)], where the `x_i` are the state computed by the previous stage, or
+            the input of `script` if the stage is the first one
+        - altstack: []
+
+    Stack output:
+        - stack:    [commitment] if `n_elements_out > 0`, else the output of `script`
+        - altstack: []
+
+    Args:
+        script (Script): The part of the computation executed by the stage.
+        n_elements_in (int): The number of elements of the state computed by the previous stage. Must be `0` for the
+            first stage. Defaults to `0`.
+        n_elements_out (int): The number of elements of the state computed by the stage, i.e., the number of
+            elements left on the stack by `script`. Must be `0` for the last stage. Defaults to `0`.
+        input_commitment (bytes | None): The commitment computed by the previous stage. Must be `None` if and only
+            if `n_elements_in == 0`. Defaults to `None`.
+
+    Returns:
+        The locking script of the stage.
+
+    Raises:
+        ValueError: If `input_commitment` is `None` but `n_elements_in > 0`, or vice versa.
+
+    Notes:
+        The number of elements on the stack is checked at the beginning (if `n_elements_in > 0`) and at the end (if
+        `n_elements_out > 0`) of the stage, so that the state cannot be padded with extra elements.
+    """
+    if (input_commitment is None) != (n_elements_in == 0):
+        msg = "The input commitment must be given if and only if the stage takes a state as input"
+        raise ValueError(msg)
+
+    out = Script()
+    if input_commitment is not None:
+        out += Script.parse_string("OP_DEPTH")
+        out += nums_to_script([n_elements_in])
+        out += Script.parse_string("OP_EQUALVERIFY")
+        out += verify_state_commitment(n_elements_in, input_commitment)
+    out += script
+    if n_elements_out > 0:
+        out += Script.parse_string("OP_DEPTH")
+        out += nums_to_script([n_elements_out])
+        out += Script.parse_string("OP_EQUALVERIFY")
+        out += commit_to_state(n_elements_out)
+    return out
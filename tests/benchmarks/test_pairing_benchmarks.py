@@ -0,0 +1,34 @@
+import json
+from dataclasses import asdict
+
+import pytest
+
+from script_examples.benchmarks.pairing_benchmarks import BENCHMARKS, run_benchmark, run_benchmarks
+
+
+@pytest.mark.parametrize("name", list(BENCHMARKS))
+def test_run_benchmark(name):
+    result = run_benchmark(name)
+
+    assert result.name == name
+    assert result.repetitions == 1
+    assert 0 <= result.min_generation_time <= result.mean_generation_time
+    assert result.n_opcodes > 0
+    assert result.script_size >= result.n_opcodes
+
+
+def test_run_benchmarks_summary_is_json_serialisable():
+    results = run_benchmarks(names=["fq2_mul", "fq12_mul"], repetitions=2)
+    summary = json.loads(json.dumps([asdict(result) for result in results]))
+
+    assert [entry["name"] for entry in summary] == ["fq2_mul", "fq12_mul"]
+    assert all(entry["repetitions"] == 2 for entry in summary)
+
+
+@pytest.mark.parametrize(
+    ("name", "repetitions", "msg"),
+    [("unknown", 1, "Unknown benchmark: unknown"), ("fq2_mul", 0, "The number of repetitions must be positive")],
+)
+def test_run_benchmark_invalid_arguments(name, repetitions, msg):
+    with pytest.raises(ValueError, match=msg):
+        run_benchmark(name, repetitions)
@@ -0,0 +1,62 @@
+import pytest
+from tx_engine import Context, Script
+
+from script_examples.benchmarks.unrolled_multiplication_benchmarks import (
+    DEFAULT_SCALAR,
+    FQ,
+    GENERATOR,
+    SECP256K1,
+    affine_unrolled_multiplication,
+    compare_unrolled_multiplications,
+    format_table,
+    projective_unrolled_multiplication,
+)
+from src.zkscript.util.utility_scripts import nums_to_script
+from tests.elliptic_curves.util_projective_ec import multiply, proj_to_list, to_proj
+
+
+def verify_points(points: list[list[int]]) -> Script:
+    out = Script()
+    for point in points[::-1]:
+        for el in point[::-1]:
+            out += nums_to_script([el])
+            out += Script.parse_string("OP_EQUALVERIFY")
+    return out + Script.parse_string("OP_1")
+
+
+@pytest.mark.parametrize("scalar", [3, DEFAULT_SCALAR])
+def test_unrolled_multiplications_verify(scalar):
+    lock, unlock, n_inversions = affine_unrolled_multiplication(scalar)
+    lock += verify_points([GENERATOR.to_list(), GENERATOR.multiply(scalar).to_list()])
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert n_inversions > 0
+
+    lock, unlock, n_inversions = projective_unrolled_multiplication(scalar)
+    generator = to_proj(GENERATOR, FQ)
+    lock += verify_points([proj_to_list(generator), proj_to_list(multiply(generator, scalar, SECP256K1, FQ))])
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert n_inversions == 0
+
+
+def test_compare_unrolled_multiplications():
+    results = compare_unrolled_multiplications()
+
+    assert [result.coordinates for result in results] == ["affine", "projective"]
+    assert all(result.locking_script_size > 0 and result.unlocking_script_size > 0 for result in results)
+    # The gradients of the affine multiplication are in the unlocking script
+    assert results[0].unlocking_script_size > results[1].unlocking_script_size
+    assert results[0].n_inversions >= DEFAULT_SCALAR.bit_length() - 1
+    assert results[1].n_inversions == 0
+
+    table = format_table(results).splitlines()
+    assert len(table) == 4
+    assert table[0].split(" | ")[0].strip() == "coordinates"
+
+
+def test_compare_unrolled_multiplications_invalid_scalar():
+    with pytest.raises(ValueError, match="The scalar must be in the range"):
+        compare_unrolled_multiplications(0)
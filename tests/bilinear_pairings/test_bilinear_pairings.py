@@ -1,3 +1,4 @@
+from copy import copy
 from dataclasses import dataclass
 
 import pytest
@@ -48,6 +49,7 @@ from src.zkscript.script_types.unlocking_keys.miller_loops import (
     TripleMillerLoopUnlockingKey,
 )
 from src.zkscript.script_types.unlocking_keys.pairings import SinglePairingUnlockingKey, TriplePairingUnlockingKey
+from src.zkscript.util.utility_functions import naf_expansion
 from src.zkscript.util.utility_scripts import bitmask_to_boolean_list, nums_to_script
 from tests.bilinear_pairings.util import (
     check_constant,
@@ -87,6 +89,7 @@ class Bls12381:
     # Parameters of the curve
     exp_miller_loop = BLS12_381.miller_loop_engine.exp_miller_loop
     val_miller_loop = BLS12_381.miller_loop_engine.val_miller_loop
+    pairing_curve = BLS12_381
     # Define filename for saving scripts
 
     test_data = {
@@ -2222,6 +2225,10 @@ class Bls12381:
                 ),
             }
         ],
+        "test_triple_miller_loop_opcode_count": [
+            {"is_precomputed_gradients_on_stack": True, "baseline_n_opcodes": 173974, "n_opcodes": 173899},
+            {"is_precomputed_gradients_on_stack": False, "baseline_n_opcodes": 166900, "n_opcodes": 166701},
+        ],
     }
 
 
@@ -2250,6 +2257,7 @@ class Mnt4753:
     # Parameters of the curve
     exp_miller_loop = MNT4_753.miller_loop_engine.exp_miller_loop
     val_miller_loop = MNT4_753.miller_loop_engine.val_miller_loop
+    pairing_curve = MNT4_753
     # Define filename for saving scripts
     filename = "mnt4_753"
 
@@ -3118,6 +3126,10 @@ class Mnt4753:
                 ),
             }
         ],
+        "test_triple_miller_loop_opcode_count": [
+            {"is_precomputed_gradients_on_stack": True, "baseline_n_opcodes": 422147, "n_opcodes": 422072},
+            {"is_precomputed_gradients_on_stack": False, "baseline_n_opcodes": 363969, "n_opcodes": 363144},
+        ],
     }
 
 
@@ -3194,6 +3206,15 @@ def generate_test_cases(test_name):
                         )
                     case "test_triple_miller_loop":
                         out.append((config, test_data["point_p"], test_data["point_q"], test_data["expected"]))
+                    case "test_triple_miller_loop_opcode_count":
+                        out.append(
+                            (
+                                config,
+                                test_data["is_precomputed_gradients_on_stack"],
+                                test_data["baseline_n_opcodes"],
+                                test_data["n_opcodes"],
+                            )
+                        )
                     case "test_triple_pairing":
                         out.append(
                             (
@@ -3589,6 +3610,46 @@ def test_miller_loop(config, point_p, point_q, q_times_val_miller_loop, expected
         save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "test_miller_loop")
 
 
+@pytest.mark.parametrize("clean_constant", [True, False])
+@pytest.mark.parametrize("step", ["first", "middle", "last"])
+@pytest.mark.parametrize(
+    ("config", "point_p", "point_q", "q_times_val_miller_loop", "expected"), generate_test_cases("test_miller_loop")
+)
+def test_miller_loop_resumed_from_accumulator(
+    config, point_p, point_q, q_times_val_miller_loop, expected, step, clean_constant
+):
+    gradients = [[s.to_list() for s in el] for el in point_q.gradients(config.exp_miller_loop)]
+
+    unlocking_key = MillerLoopUnlockingKey(point_p.to_list(), point_q.to_list(), gradients)
+
+    unlock = unlocking_key.to_unlocking_script(config.test_script_pairing)
+
+    step = {"first": len(config.exp_miller_loop) - 3, "middle": len(config.exp_miller_loop) // 2, "last": 1}[step]
+
+    # Check that splitting the Miller loop after `step` gives the same output as the monolithic Miller loop
+    lock = config.test_script_pairing.save_accumulator(step=step, modulo_threshold=1, check_constant=True)
+    lock += config.test_script_pairing.resume_from_accumulator(
+        step=step, modulo_threshold=1, check_constant=False, clean_constant=False
+    )
+
+    lock += modify_verify_modulo_check(generate_verify(expected, config.ix_miller_output), False)
+    lock += modify_verify_modulo_check(Script.parse_string("OP_VERIFY"), False)
+    lock += modify_verify_modulo_check(generate_verify(q_times_val_miller_loop), clean_constant)
+
+    verify_script(lock, unlock, clean_constant)
+
+
+@pytest.mark.parametrize("step", [0, -1])
+@pytest.mark.parametrize("config", [Bls12381, Mnt4753])
+def test_miller_loop_accumulator_invalid_step(config, step):
+    step = step if step >= 0 else len(config.exp_miller_loop) - 2
+
+    with pytest.raises(ValueError, match="The step must be between"):
+        config.test_script_pairing.save_accumulator(step=step, modulo_threshold=1)
+    with pytest.raises(ValueError, match="The step must be between"):
+        config.test_script_pairing.resume_from_accumulator(step=step, modulo_threshold=1)
+
+
 @pytest.mark.parametrize("clean_constant", [True, False])
 @pytest.mark.parametrize(
     ("config", "point_p", "point_q", "miller_output_inverse", "expected"), generate_test_cases("test_single_pairing")
@@ -3617,6 +3678,68 @@ def test_single_pairing(config, point_p, point_q, miller_output_inverse, expecte
         save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "test_single_pairing")
 
 
+@pytest.mark.parametrize(
+    ("config", "point_p", "point_q", "miller_output_inverse", "expected"), generate_test_cases("test_single_pairing")
+)
+def test_single_pairing_negative_miller_loop_parameter(config, point_p, point_q, miller_output_inverse, expected):
+    if point_p.is_infinity() or point_q.is_infinity():
+        pytest.skip("The sign of the Miller loop parameter is irrelevant for trivial pairings")
+
+    # The Miller loop parameter is negative, and its sign is carried by exp_miller_loop
+    assert config.test_script_pairing.is_miller_loop_parameter_negative
+    assert list(config.test_script_pairing.exp_miller_loop) == list(config.exp_miller_loop)
+    assert sum(digit * 2**i for i, digit in enumerate(config.exp_miller_loop)) < 0
+
+    # Ignoring the sign would return e(P,Q)^(-1) = e(-P,Q)
+    assert expected == config.pairing_curve.pairing([point_p], [point_q])
+    assert expected != config.pairing_curve.pairing([-point_p], [point_q])
+
+    gradients = [[s.to_list() for s in el] for el in point_q.gradients(config.exp_miller_loop)]
+    unlocking_key = SinglePairingUnlockingKey(point_p.to_list(), point_q.to_list(), gradients, miller_output_inverse)
+    unlock = unlocking_key.to_unlocking_script(config.test_script_pairing)
+
+    lock = config.test_script_pairing.single_pairing(modulo_threshold=1, check_constant=True, clean_constant=False)
+    lock += modify_verify_modulo_check(generate_verify(expected), True)
+
+    verify_script(lock, unlock, True)
+
+
+@pytest.mark.parametrize(
+    ("config", "point_p", "point_q", "q_times_val_miller_loop", "expected"), generate_test_cases("test_miller_loop")
+)
+def test_miller_loop_naf_and_binary_expansions(config, point_p, point_q, q_times_val_miller_loop, expected):
+    u = sum(digit * 2**i for i, digit in enumerate(config.test_script_pairing.exp_miller_loop))
+    naf_exp = naf_expansion(u)
+    binary_exp = [-int(bit) if u < 0 else int(bit) for bit in bin(abs(u))[2:]][::-1]
+    if sum(digit != 0 for digit in naf_exp) >= sum(digit != 0 for digit in binary_exp):
+        pytest.skip("The non-adjacent form of the Miller loop parameter has no fewer additions than its binary form")
+
+    locks = []
+    for exp_miller_loop in [naf_exp, binary_exp]:
+        pairing_model = copy(config.test_script_pairing)
+        pairing_model.exp_miller_loop = exp_miller_loop
+
+        gradients = [[s.to_list() for s in el] for el in point_q.gradients(exp_miller_loop)]
+        unlocking_key = MillerLoopUnlockingKey(point_p.to_list(), point_q.to_list(), gradients)
+        unlock = unlocking_key.to_unlocking_script(pairing_model)
+
+        # The gradients, including those of the subtraction steps, are verified while executing the loop
+        lock = pairing_model.miller_loop(modulo_threshold=1, check_constant=True, clean_constant=False)
+        locks.append(lock)
+        if exp_miller_loop == list(config.exp_miller_loop):
+            lock += modify_verify_modulo_check(generate_verify(expected, config.ix_miller_output), False)
+        else:
+            lock += Script.parse_string(" ".join(["OP_DROP"] * config.test_script_pairing.N_ELEMENTS_MILLER_OUTPUT))
+            lock += Script.parse_string("OP_1")
+        lock += modify_verify_modulo_check(Script.parse_string("OP_VERIFY"), False)
+        lock += modify_verify_modulo_check(generate_verify(q_times_val_miller_loop), True)
+
+        verify_script(lock, unlock, True)
+
+    # The NAF Miller loop has fewer additions, hence fewer line evaluations
+    assert len(locks[0].raw_serialize()) < len(locks[1].raw_serialize())
+
+
 @pytest.mark.parametrize("clean_constant", [True, False])
 @pytest.mark.parametrize(("config", "point_p", "point_q", "expected"), generate_test_cases("test_triple_miller_loop"))
 @pytest.mark.parametrize("is_precomputed_gradients_in_unlock", [True, False])
@@ -3650,6 +3773,28 @@ def test_triple_miller_loop(
         save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "test_triple_miller_loop")
 
 
+@pytest.mark.parametrize(
+    ("config", "is_precomputed_gradients_on_stack", "baseline_n_opcodes", "n_opcodes"),
+    generate_test_cases("test_triple_miller_loop_opcode_count"),
+)
+def test_triple_miller_loop_opcode_count(config, is_precomputed_gradients_on_stack, baseline_n_opcodes, n_opcodes):
+    point_q = config.test_data["test_triple_miller_loop"][0]["point_q"]
+    gradients = [[[s.to_list() for s in el] for el in point_q[i].gradients(config.exp_miller_loop)] for i in range(3)]
+
+    lock = config.test_script_pairing.triple_miller_loop(
+        modulo_threshold=1,
+        check_constant=True,
+        clean_constant=False,
+        is_precomputed_gradients_on_stack=is_precomputed_gradients_on_stack,
+        precomputed_gradients=gradients[1:],
+    )
+
+    # `baseline_n_opcodes` is the size of the loop before the accumulator was squared after the injection of the
+    # gradients and parked on the altstack at the end of the loop
+    assert len(str(lock).split()) == n_opcodes
+    assert n_opcodes < baseline_n_opcodes
+
+
 @pytest.mark.parametrize("clean_constant", [True, False])
 @pytest.mark.parametrize("is_precomputed_gradients_in_unlock", [True, False])
 @pytest.mark.parametrize("is_miller_loop_proj", [True, False])
@@ -0,0 +1,151 @@
+from dataclasses import dataclass
+
+import pytest
+from elliptic_curves.instantiations.bls12_381.bls12_381 import BLS12_381
+from elliptic_curves.instantiations.bls12_381.bls12_381 import Fq as Fq_bls12_381
+from elliptic_curves.instantiations.bls12_381.bls12_381 import Fq2 as Fq2_bls12_381
+from elliptic_curves.instantiations.mnt4_753.mnt4_753 import MNT4_753
+from tx_engine import Context
+
+from src.zkscript.bilinear_pairings.bls12_381.parameters import u as u_bls12_381
+from src.zkscript.bilinear_pairings.bls12_381.subgroup_membership import (
+    subgroup_membership as subgroup_membership_bls12_381,
+)
+from src.zkscript.bilinear_pairings.mnt4_753.subgroup_membership import (
+    subgroup_membership as subgroup_membership_mnt4_753,
+)
+from src.zkscript.script_types.unlocking_keys.subgroup_membership import SubgroupMembershipUnlockingKey
+from tests.bilinear_pairings.util import multiplication_of_negated_point_gradients, save_scripts
+
+
+@dataclass
+class Bls12381:
+    Fq = Fq_bls12_381
+    Fq2 = Fq2_bls12_381
+    g1_curve = BLS12_381.g1_curve
+    g2_curve = BLS12_381.g2_curve
+    g1_generator = g1_curve.get_generator()
+    g2_generator = g2_curve.get_generator()
+    # Scalars used in the subgroup membership checks
+    g1_scalar = u_bls12_381**2
+    g2_scalar = abs(u_bls12_381)
+    test_script = subgroup_membership_bls12_381
+    # Point on the curve which is not in G1
+    g1_off_subgroup_point = g1_curve(
+        Fq(4),
+        Fq(
+            1630892974828014537729259858097113969650871260980656934049590190201941782487224876496582135785777461178964897591404
+        ),
+        infinity=False,
+    )
+    # Point on the twisted curve which is not in G2
+    g2_off_subgroup_point = g2_curve(
+        Fq2(Fq(2), Fq(0)),
+        Fq2(
+            Fq(
+                3813414062821088896965879244443358096636228247329175415943186029072982909461945441384695595240360445618611812101176
+            ),
+            Fq(
+                3568027680765585585945490907042741669558639753778547462314760963815399658271727325750766584361357481230047117262172
+            ),
+        ),
+        infinity=False,
+    )
+    filename = "bls12_381"
+
+    test_data = {
+        "test_g1_in_subgroup": [
+            {"point": g1_generator, "expected": True},
+            {"point": g1_generator.multiply(0x1234567890ABCDEF), "expected": True},
+            {"point": g1_off_subgroup_point, "expected": False},
+        ],
+        "test_g2_in_subgroup": [
+            {"point": g2_generator, "expected": True},
+            {"point": g2_generator.multiply(0x1234567890ABCDEF), "expected": True},
+            {"point": g2_off_subgroup_point, "expected": False},
+        ],
+    }
+
+
+@dataclass
+class Mnt4753:
+    g1_curve = MNT4_753.g1_curve
+    g1_generator = g1_curve.get_generator()
+    # E(F_q) has prime order: the check of G1 requires no scalar multiplication, and no point on the curve is
+    # outside of G1
+    g1_scalar = None
+    test_script = subgroup_membership_mnt4_753
+    filename = "mnt4_753"
+
+    test_data = {
+        "test_g1_in_subgroup": [
+            {"point": g1_generator, "expected": True},
+            {"point": g1_generator.multiply(0x1234567890ABCDEF), "expected": True},
+        ],
+    }
+
+
+def generate_test_cases(test_name):
+    configurations = [Bls12381, Mnt4753]
+
+    out = []
+    for config in configurations:
+        if test_name in config.test_data:
+            for test_data in config.test_data[test_name]:
+                match test_name:
+                    case "test_g1_in_subgroup" | "test_g2_in_subgroup":
+                        out.append((config, test_data["point"], test_data["expected"]))
+                    case _:
+                        raise ValueError
+    return out
+
+
+@pytest.mark.parametrize("rolling_option", [0, 1])
+@pytest.mark.parametrize("clean_constant", [True, False])
+@pytest.mark.parametrize(("config", "point", "expected"), generate_test_cases("test_g1_in_subgroup"))
+def test_g1_in_subgroup(config, point, expected, clean_constant, rolling_option, save_to_json_folder):
+    gradients = (
+        multiplication_of_negated_point_gradients(config.g1_scalar, point) if config.g1_scalar is not None else []
+    )
+    unlocking_key = SubgroupMembershipUnlockingKey(P=point.to_list(), gradients=gradients)
+    unlock = unlocking_key.to_unlocking_script(config.test_script)
+
+    lock = config.test_script.g1_in_subgroup(
+        check_constant=True, clean_constant=clean_constant, rolling_option=rolling_option
+    )
+
+    context = Context(script=unlock + lock)
+    if expected:
+        assert context.evaluate()
+        assert context.get_stack().size() == (1 if clean_constant else 2) + (2 if rolling_option == 0 else 0)
+        assert context.get_altstack().size() == 0
+    else:
+        assert not context.evaluate()
+
+    if save_to_json_folder and expected:
+        save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "g1 in subgroup")
+
+
+@pytest.mark.parametrize("rolling_option", [0, 1])
+@pytest.mark.parametrize("clean_constant", [True, False])
+@pytest.mark.parametrize(("config", "point", "expected"), generate_test_cases("test_g2_in_subgroup"))
+def test_g2_in_subgroup(config, point, expected, clean_constant, rolling_option, save_to_json_folder):
+    unlocking_key = SubgroupMembershipUnlockingKey(
+        P=point.to_list(), gradients=multiplication_of_negated_point_gradients(config.g2_scalar, point)
+    )
+    unlock = unlocking_key.to_unlocking_script(config.test_script)
+
+    lock = config.test_script.g2_in_subgroup(
+        check_constant=True, clean_constant=clean_constant, rolling_option=rolling_option
+    )
+
+    context = Context(script=unlock + lock)
+    if expected:
+        assert context.evaluate()
+        assert context.get_stack().size() == (1 if clean_constant else 2) + (4 if rolling_option == 0 else 0)
+        assert context.get_altstack().size() == 0
+    else:
+        assert not context.evaluate()
+
+    if save_to_json_folder and expected:
+        save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "g2 in subgroup")
@@ -73,3 +73,17 @@ def save_scripts(lock, unlock, save_to_json_folder, filename, test_name):
 
         with json_file.open("w") as f:
             json.dump(data, f, indent=4)
+
+
+def multiplication_of_negated_point_gradients(scalar, P) -> list[list[int]]:
+    """Compute the gradients required to compute [scalar](-P) as in the subgroup membership checks."""
+    gradients = []
+    T = -P
+    for bit in [int(bit) for bit in bin(scalar)[3:]]:
+        gradients.append(T.gradient(T).to_list())
+        T = T + T
+        if bit == 1:
+            gradients.append(T.gradient(-P).to_list())
+            T = T + (-P)
+
+    return gradients
@@ -0,0 +1,188 @@
+import pytest
+from tx_engine import Context, Script
+
+from src.zkscript.elliptic_curves.edwards25519.edwards25519 import Edwards25519
+from src.zkscript.elliptic_curves.edwards25519.parameters import GROUP_ORDER, Bx, By, q
+from src.zkscript.elliptic_curves.edwards25519.util import (
+    compress_point,
+    decompress_point,
+    edwards_addition,
+    edwards_scalar_multiplication,
+)
+from src.zkscript.script_types.unlocking_keys.edwards25519 import EdDSAUnlockingKey
+from src.zkscript.util.utility_scripts import nums_to_script
+
+edwards25519 = Edwards25519()
+generator = [Bx, By]
+identity = [0, 1]
+
+# Test vectors from RFC 8032, Section 7.1: (public key, message, signature)
+rfc8032_test_vectors = [
+    (
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        "",
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155"
+        "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+    ),
+    (
+        "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+        "72",
+        "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da"
+        "085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+    ),
+    (
+        "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+        "af82",
+        "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac"
+        "18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+    ),
+]
+
+
+def to_extended(P: list[int], z: int) -> list[int]:  # noqa: N803
+    return [P[0] * z % q, P[1] * z % q, z, P[0] * P[1] * z % q]
+
+
+def to_affine(P: list[int]) -> list[int]:  # noqa: N803
+    z_inverse = pow(P[2], -1, q)
+    return [P[0] * z_inverse % q, P[1] * z_inverse % q]
+
+
+def extended_output(e: int, f: int, g: int, h: int) -> list[int]:
+    return [e * f % q, g * h % q, f * g % q, e * h % q]
+
+
+def extended_addition(P: list[int], Q: list[int]) -> list[int]:  # noqa: N803
+    x1, y1, z1, t1 = P
+    x2, y2, z2, t2 = Q
+    a, b = (y1 - x1) * (y2 - x2), (y1 + x1) * (y2 + x2)
+    c, d = 2 * edwards25519.D * t1 * t2, 2 * z1 * z2
+    return extended_output(b - a, d - c, d + c, b + a)
+
+
+def extended_doubling(P: list[int]) -> list[int]:  # noqa: N803
+    x1, y1, z1, _ = P
+    a, b, c = x1 * x1, y1 * y1, 2 * z1 * z1
+    e, g, h = (x1 + y1) ** 2 - a - b, b - a, -a - b
+    return extended_output(e, g - c, g, h)
+
+
+points = [
+    identity,
+    generator,
+    edwards_scalar_multiplication(2, generator),
+    edwards_scalar_multiplication(GROUP_ORDER - 1, generator),
+    edwards_scalar_multiplication(123456789, generator),
+]
+
+
+def verify_extended_point(expected: list[int]) -> Script:
+    out = Script()
+    for el in expected[::-1]:
+        out += nums_to_script([el])
+        out += Script.parse_string("OP_EQUALVERIFY")
+    out += Script.parse_string("OP_1")
+    return out
+
+
+@pytest.mark.parametrize("P", points)
+@pytest.mark.parametrize("Q", points)
+def test_point_addition(P, Q):  # noqa: N803
+    P_extended, Q_extended = to_extended(P, 3), to_extended(Q, 5)  # noqa: N806
+    expected = extended_addition(P_extended, Q_extended)
+    assert to_affine(expected) == edwards_addition(P, Q)
+
+    unlock = nums_to_script([q, *P_extended, *Q_extended])
+    lock = edwards25519.point_addition(take_modulo=True, check_constant=True, clean_constant=True)
+    lock += verify_extended_point(expected)
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize("P", points)
+@pytest.mark.parametrize("Q", points[1:])
+def test_point_addition_with_fixed_point(P, Q):  # noqa: N803
+    P_extended = to_extended(P, 7)  # noqa: N806
+    expected = extended_addition(P_extended, to_extended(Q, 1))
+    assert to_affine(expected) == edwards_addition(P, Q)
+
+    unlock = nums_to_script([q, *P_extended])
+    lock = edwards25519.point_addition_with_fixed_point(
+        Q=Q, take_modulo=True, check_constant=True, clean_constant=True
+    )
+    lock += verify_extended_point(expected)
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize("P", points)
+def test_point_doubling(P):  # noqa: N803
+    P_extended = to_extended(P, 11)  # noqa: N806
+    expected = extended_doubling(P_extended)
+    assert to_affine(expected) == edwards_addition(P, P)
+
+    unlock = nums_to_script([q, *P_extended])
+    lock = edwards25519.point_doubling(take_modulo=True, check_constant=True, clean_constant=True)
+    lock += verify_extended_point(expected)
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize(("pubkey", "msg", "signature"), rfc8032_test_vectors)
+def test_verify_eddsa(pubkey, msg, signature):
+    pubkey, msg, signature = bytes.fromhex(pubkey), bytes.fromhex(msg), bytes.fromhex(signature)
+
+    unlock = EdDSAUnlockingKey(pubkey=pubkey, msg=msg, signature=signature).to_unlocking_script()
+    lock = edwards25519.verify_eddsa(pubkey=pubkey, msg=msg, check_constant=True, clean_constant=True)
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize(("pubkey", "msg", "signature"), rfc8032_test_vectors[:1])
+def test_verify_eddsa_invalid_signature(pubkey, msg, signature):
+    pubkey, msg, signature = bytes.fromhex(pubkey), bytes.fromhex(msg), bytes.fromhex(signature)
+    lock = edwards25519.verify_eddsa(pubkey=pubkey, msg=msg, check_constant=True, clean_constant=True)
+
+    # The verification equation does not hold
+    s = (int.from_bytes(signature[32:], "little") + 1) % GROUP_ORDER
+    tampered_signature = signature[:32] + s.to_bytes(32, "little")
+    unlock = EdDSAUnlockingKey(pubkey=pubkey, msg=msg, signature=tampered_signature).to_unlocking_script()
+    context = Context(script=unlock + lock)
+    assert not context.evaluate()
+
+    # The challenge does not match the message
+    unlock = EdDSAUnlockingKey(pubkey=pubkey, msg=msg + b"\x00", signature=signature).to_unlocking_script()
+    context = Context(script=unlock + lock)
+    assert not context.evaluate()
+
+    # The encoding of s is not canonical
+    s = int.from_bytes(signature[32:], "little") + GROUP_ORDER
+    tampered_signature = signature[:32] + s.to_bytes(32, "little")
+    unlock = EdDSAUnlockingKey(pubkey=pubkey, msg=msg, signature=tampered_signature).to_unlocking_script()
+    context = Context(script=unlock + lock)
+    assert not context.evaluate()
+
+
+def test_point_compression():
+    for P in points:
+        assert decompress_point(compress_point(P)) == P
+    assert edwards_scalar_multiplication(GROUP_ORDER, generator) == identity
+
+    with pytest.raises(ValueError, match="The encoding of a point must be 32 bytes long"):
+        decompress_point(bytes(31))
+    with pytest.raises(ValueError, match="The y coordinate of the point is not reduced modulo q"):
+        decompress_point(q.to_bytes(32, "little"))
+    with pytest.raises(ValueError, match="The encoding does not correspond to a point of edwards25519"):
+        decompress_point((1 | (1 << 255)).to_bytes(32, "little"))
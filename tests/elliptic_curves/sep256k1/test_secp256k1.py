@@ -1,16 +1,21 @@
 import pytest
 from elliptic_curves.fields.prime_field import PrimeField
 from elliptic_curves.models.ec import ShortWeierstrassEllipticCurve
-from tx_engine import Context, Script, hash256d
+from tx_engine import SIGHASH, Context, Script, Tx, TxIn, TxOut, hash256d, sig_hash_preimage
 
+from src.zkscript.elliptic_curves.curves import get_curve
 from src.zkscript.elliptic_curves.secp256k1.secp256k1 import Secp256k1
+from src.zkscript.elliptic_curves.secp256k1.util import decompress_point, lift_x, pubkey_to_point
 from src.zkscript.script_types.stack_elements import StackBaseElement, StackEllipticCurvePoint, StackFiniteFieldElement
 from src.zkscript.script_types.unlocking_keys.secp256k1 import (
     Secp256k1BasePointMultiplicationUnlockingKey,
+    Secp256k1MultisigUnlockingKey,
     Secp256k1PointMultiplicationUnlockingKey,
     Secp256k1PointMultiplicationUpToSignUnlockingKey,
+    Secp256k1PointMultiplicationWithSignUnlockingKey,
 )
 from src.zkscript.util.utility_scripts import nums_to_script
+from tests.elliptic_curves.util import mutated_witnesses
 
 modulus = 115792089237316195423570985008687907853269984665640564039457584007908834671663
 order = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
@@ -30,6 +35,9 @@ dummy_pre_sig_hash = int.to_bytes(5345875014124133193336817693138659206178434870
 dummy_sighash = hash256d(dummy_pre_sig_hash)
 h = int.from_bytes(dummy_sighash)
 
+prev_txid = int.to_bytes(34060536512648028283387372577505466741680559421950955299118826044926210663733, length=32).hex()
+prev_amount = 100
+
 
 def compress(P) -> bytes:
     point_as_list = P.to_list()
@@ -40,10 +48,51 @@ def compress(P) -> bytes:
     return out
 
 
+def der_integer(n: int) -> bytes:
+    n_bytes = n.to_bytes((n.bit_length() + 8) // 8)
+    return bytes.fromhex("02") + len(n_bytes).to_bytes() + n_bytes
+
+
+def sign(private_key: int, k: int) -> bytes:
+    """Sign `dummy_sighash` with `private_key` and nonce `k`, returning the DER encoded low-s signature."""
+    r = generator.multiply(k).to_list()[0] % order
+    s = pow(k, -1, order) * (h + r * private_key) % order
+    s = min(s, order - s)
+    signature = der_integer(r) + der_integer(s)
+    return bytes.fromhex("30") + len(signature).to_bytes() + signature + SIGHASH.ALL_FORKID.to_bytes()
+
+
 def tweak_private_key(a: int) -> int:
     return (-2 * h * pow(generator.x.x, -1, order) - a) % order
 
 
+def multi_output_tx(second_output_amount: int) -> Tx:
+    return Tx(
+        version=1,
+        tx_ins=[TxIn(prev_tx=prev_txid, prev_index=0, sequence=0)],
+        tx_outs=[
+            TxOut(amount=1, script_pubkey=Script.parse_string("OP_1")),
+            TxOut(amount=second_output_amount, script_pubkey=Script.parse_string("OP_2")),
+        ],
+        locktime=0,
+    )
+
+
+def multi_input_tx(second_input_prev_index: int) -> Tx:
+    return Tx(
+        version=1,
+        tx_ins=[
+            TxIn(prev_tx=prev_txid, prev_index=0, sequence=0),
+            TxIn(prev_tx=prev_txid, prev_index=second_input_prev_index, sequence=0),
+        ],
+        tx_outs=[
+            TxOut(amount=1, script_pubkey=Script.parse_string("OP_1")),
+            TxOut(amount=2, script_pubkey=Script.parse_string("OP_2")),
+        ],
+        locktime=0,
+    )
+
+
 @pytest.mark.parametrize(
     ("a", "A", "additional_constant"),
     [
@@ -85,14 +134,19 @@ def test_verify_base_point_multiplication_up_to_epsilon(a, A, additional_constan
 
 
 @pytest.mark.parametrize(("a", "A"), [(2, generator.multiply(2)), (10, generator.multiply(10))])
-def test_verify_base_point(a, A):
+@pytest.mark.parametrize("is_compressed_pubkey", [True, False])
+def test_verify_base_point(a, A, is_compressed_pubkey):
     lock = Secp256k1.verify_base_point_multiplication(
         True,
         True,
     )
 
     unlocking_key = Secp256k1BasePointMultiplicationUnlockingKey(
-        sig_hash_preimage=dummy_pre_sig_hash, h=dummy_sighash, a=a, A=A.to_list()
+        sig_hash_preimage=dummy_pre_sig_hash,
+        h=dummy_sighash,
+        a=a,
+        A=compress(A) if is_compressed_pubkey else A.to_list(),
+        is_compressed_pubkey=is_compressed_pubkey,
     )
     unlock = unlocking_key.to_unlocking_script()
 
@@ -101,6 +155,85 @@ def test_verify_base_point(a, A):
     assert context.get_stack().size() == 1
 
 
+@pytest.mark.parametrize(("a", "A"), [(2, generator.multiply(2)), (10, generator.multiply(10))])
+def test_verify_base_point_sighash_single(a, A):
+    lock = Secp256k1.verify_base_point_multiplication(True, True, sighash_flags=SIGHASH.SINGLE_FORKID)
+
+    preimages = {
+        sighash_flags: [
+            sig_hash_preimage(
+                tx=multi_output_tx(second_output_amount),
+                index=0,
+                script_pubkey=lock,
+                prev_amount=prev_amount,
+                sighash_flags=sighash_flags,
+            )
+            for second_output_amount in [2, 3]
+        ]
+        for sighash_flags in [SIGHASH.ALL_FORKID, SIGHASH.SINGLE_FORKID]
+    }
+    # SIGHASH_SINGLE only commits to the output at the index of the input
+    assert preimages[SIGHASH.ALL_FORKID][0] != preimages[SIGHASH.ALL_FORKID][1]
+    assert preimages[SIGHASH.SINGLE_FORKID][0] == preimages[SIGHASH.SINGLE_FORKID][1]
+
+    preimage = preimages[SIGHASH.SINGLE_FORKID][0]
+    sighash = hash256d(preimage)
+
+    unlocking_key = Secp256k1BasePointMultiplicationUnlockingKey(
+        sig_hash_preimage=preimage, h=sighash, a=a, A=A.to_list()
+    )
+    unlock = unlocking_key.to_unlocking_script()
+
+    context = Context(unlock + lock, z=sighash)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+
+
+@pytest.mark.parametrize(
+    "sighash_flags",
+    [
+        SIGHASH.ALL_FORKID,
+        SIGHASH.SINGLE_FORKID,
+        SIGHASH.NONE_FORKID,
+        SIGHASH.ALL_ANYONECANPAY_FORKID,
+        SIGHASH.NONE_ANYONECANPAY_FORKID,
+        SIGHASH.SINGLE_ANYONECANPAY_FORKID,
+    ],
+)
+def test_verify_base_point_sighash_flags(sighash_flags):
+    a, A = 2, generator.multiply(2)
+    lock = Secp256k1.verify_base_point_multiplication(True, True, sighash_flags=sighash_flags)
+
+    preimages = [
+        sig_hash_preimage(
+            tx=multi_input_tx(second_input_prev_index),
+            index=0,
+            script_pubkey=lock,
+            prev_amount=prev_amount,
+            sighash_flags=sighash_flags,
+        )
+        for second_input_prev_index in [1, 2]
+    ]
+    # ANYONECANPAY only commits to the input being signed
+    is_anyonecanpay = sighash_flags in [
+        SIGHASH.ALL_ANYONECANPAY_FORKID,
+        SIGHASH.NONE_ANYONECANPAY_FORKID,
+        SIGHASH.SINGLE_ANYONECANPAY_FORKID,
+    ]
+    assert (preimages[0] == preimages[1]) == is_anyonecanpay
+
+    sighash = hash256d(preimages[0])
+
+    unlocking_key = Secp256k1BasePointMultiplicationUnlockingKey(
+        sig_hash_preimage=preimages[0], h=sighash, a=a, A=A.to_list()
+    )
+    unlock = unlocking_key.to_unlocking_script()
+
+    context = Context(unlock + lock, z=sighash)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+
+
 @pytest.mark.parametrize("negate", [True, False])
 @pytest.mark.parametrize(
     ("b", "P"),
@@ -140,7 +273,8 @@ def test_verify_point_multiplication_up_to_sign(b, P, negate):
 
 
 @pytest.mark.parametrize(("b", "P"), [(3, generator.multiply(10)), (110, generator.multiply(547))])
-def test_verify_point_multiplication(b, P):
+@pytest.mark.parametrize("is_compressed_pubkey", [True, False])
+def test_verify_point_multiplication(b, P, is_compressed_pubkey):
     Q = P.multiply(b)
 
     d = []
@@ -175,11 +309,181 @@ def test_verify_point_multiplication(b, P):
         D=[el.to_list() for el in D],
         Q=Q.to_list(),
         b=b,
-        P=P.to_list(),
+        P=compress(P) if is_compressed_pubkey else P.to_list(),
+        is_compressed_pubkey=is_compressed_pubkey,
+    )
+
+    unlock = unlocking_key.to_unlocking_script()
+    # The compressed public key is decompressed to the same point as the uncompressed path
+    unlocking_key.P, unlocking_key.is_compressed_pubkey = P.to_list(), False
+    assert unlock.raw_serialize() == unlocking_key.to_unlocking_script().raw_serialize()
+
+    context = Context(unlock + lock, z=dummy_sighash)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+
+    # The script rejects the minimally-mutated witnesses
+    for description, mutated_key in mutated_witnesses(unlocking_key, ["Q", "P"], get_curve("secp256k1")):
+        context = Context(mutated_key.to_unlocking_script() + lock, z=dummy_sighash)
+        assert not context.evaluate(), description
+
+
+@pytest.mark.parametrize("negate", [True, False])
+@pytest.mark.parametrize(("b", "P"), [(3, generator.multiply(10)), (110, generator.multiply(547))])
+def test_verify_point_multiplication_with_sign(b, P, negate):
+    # The hints are computed for bP, the target is Q = ± bP
+    bP = P.multiply(b)
+
+    d = []
+    d.append(Fr_k1(h) * Fr_k1(bP.x.x).invert())
+    d.append(Fr_k1(h) * Fr_k1((bP + generator.multiply(b)).x.x).invert())
+
+    s = []
+    s.append(Fr_k1(bP.x.x) * Fr_k1(b).invert())
+    s.append(Fr_k1((bP + generator.multiply(b)).x.x) * Fr_k1(b).invert())
+
+    D = []
+    D.append(generator.multiply(d[0].x))
+    D.append(generator.multiply(d[1].x - 1))
+    D.append(generator.multiply(b))
+
+    gradients = []
+    gradients.append(P.gradient(-D[0]))
+    gradients.append(P.gradient(-D[1]))
+    gradients.append(bP.gradient(D[2]))
+
+    sign = 1 if negate else 0
+
+    lock = Secp256k1.verify_point_multiplication_with_sign(
+        True,
+        True,
     )
+    # Consume the result of the verification and check the sign
+    lock += Script.parse_string("OP_VERIFY")
+    lock += nums_to_script([sign])
+    lock += Script.parse_string("OP_EQUAL")
 
+    unlocking_key = Secp256k1PointMultiplicationWithSignUnlockingKey(
+        sig_hash_preimage=dummy_pre_sig_hash,
+        h=dummy_sighash,
+        s=[el.to_list()[0] for el in s],
+        gradients=[el.to_list()[0] for el in gradients],
+        d=[el.to_list()[0] for el in d],
+        D=[el.to_list() for el in D],
+        Q=bP.multiply(-1 if negate else 1).to_list(),
+        b=b,
+        P=P.to_list(),
+        sign=sign,
+    )
     unlock = unlocking_key.to_unlocking_script()
 
     context = Context(unlock + lock, z=dummy_sighash)
     assert context.evaluate()
     assert context.get_stack().size() == 1
+
+    # The wrong sign makes the script fail
+    unlocking_key.sign = 1 - sign
+    context = Context(unlocking_key.to_unlocking_script() + lock, z=dummy_sighash)
+    assert not context.evaluate()
+
+
+private_keys = [3, 5, 7]
+pubkeys = [compress(generator.multiply(private_key)) for private_key in private_keys]
+
+
+@pytest.mark.parametrize(
+    ("m", "n", "signers", "is_valid"),
+    [
+        (2, 3, [0, 2], True),
+        (2, 3, [0, 1], True),
+        (2, 3, [1, 2], True),
+        # Signatures in a different order than the public keys
+        (2, 3, [2, 0], False),
+        (2, 3, [0, 0], False),
+        (1, 1, [0], True),
+    ],
+)
+def test_verify_multisig(m, n, signers, is_valid):
+    lock = Secp256k1.verify_multisig(m, pubkeys[:n])
+
+    unlocking_key = Secp256k1MultisigUnlockingKey(
+        signatures=[sign(private_keys[i], k=11 + i) for i in signers],
+    )
+    unlock = unlocking_key.to_unlocking_script()
+
+    context = Context(unlock + lock, z=dummy_sighash)
+    assert context.evaluate() == is_valid
+    if is_valid:
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+
+
+def test_verify_multisig_without_dummy_element():
+    lock = Secp256k1.verify_multisig(1, pubkeys[:1])
+
+    unlock = Script()
+    unlock.append_pushdata(sign(private_keys[0], k=11))
+
+    context = Context(unlock + lock, z=dummy_sighash)
+    assert not context.evaluate()
+
+
+@pytest.mark.parametrize(
+    ("m", "keys", "message"),
+    [
+        (1, [], "The list of public keys must not be empty"),
+        (0, pubkeys, "The number of required signatures must be between 1 and 3"),
+        (4, pubkeys, "The number of required signatures must be between 1 and 3"),
+        (1, [pubkeys[0][:32]], "The public key at index 0 must be 33 or 65 bytes long"),
+    ],
+)
+def test_verify_multisig_errors(m, keys, message):
+    with pytest.raises(ValueError, match=message):
+        Secp256k1.verify_multisig(m, keys)
+
+
+@pytest.mark.parametrize("private_key", [1, 2, 10, 547, order - 1])
+def test_decompress_point(private_key):
+    P = generator.multiply(private_key)
+    assert decompress_point(compress(P)) == P.to_list()
+
+
+@pytest.mark.parametrize(
+    "pubkey",
+    [
+        compress(generator)[:32],
+        bytes.fromhex("04") + compress(generator)[1:],
+        # x = 5 is not the x coordinate of a point of secp256k1
+        bytes.fromhex("02") + (5).to_bytes(32),
+    ],
+)
+def test_decompress_point_errors(pubkey):
+    with pytest.raises(ValueError, match="compressed public key"):
+        decompress_point(pubkey)
+
+
+@pytest.mark.parametrize("private_key", [1, 2, 10, 547, order - 1])
+def test_lift_x(private_key):
+    P = generator.multiply(private_key)
+    x_only = compress(P)[1:]
+    # BIP340 lifts to the point with even y coordinate
+    even_P = P if P.to_list()[1] % 2 == 0 else -P
+    assert lift_x(x_only) == even_P.to_list()
+    assert lift_x(x_only, even_y=False) == (-even_P).to_list()
+    assert pubkey_to_point(x_only, is_compressed=True) == even_P.to_list()
+    assert pubkey_to_point(compress(P), is_compressed=True) == P.to_list()
+
+
+@pytest.mark.parametrize(
+    "x_only",
+    [
+        compress(generator)[:31],
+        compress(generator),
+        # x = 5 is not the x coordinate of a point of secp256k1
+        (5).to_bytes(32),
+        modulus.to_bytes(32),
+    ],
+)
+def test_lift_x_errors(x_only):
+    with pytest.raises(ValueError, match="x-only public key"):
+        lift_x(x_only)
@@ -0,0 +1,54 @@
+import pytest
+
+from src.zkscript.elliptic_curves.curves import CURVES, get_curve
+
+
+def is_probable_prime(n: int, bases: tuple[int, ...] = (2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37)) -> bool:
+    if n < 2:
+        return False
+    for p in bases:
+        if n % p == 0:
+            return n == p
+    d, s = n - 1, 0
+    while d % 2 == 0:
+        d, s = d // 2, s + 1
+    for base in bases:
+        x = pow(base, d, n)
+        if x in {1, n - 1}:
+            continue
+        for _ in range(s - 1):
+            x = pow(x, 2, n)
+            if x == n - 1:
+                break
+        else:
+            return False
+    return True
+
+
+@pytest.mark.parametrize("name", list(CURVES))
+def test_curve_parameters(name):
+    curve = CURVES[name]
+    assert curve.name == name
+    assert curve.form in {"short_weierstrass", "twisted_edwards"}
+    assert curve.modulus.bit_length() >= 255
+    assert is_probable_prime(curve.modulus)
+    assert is_probable_prime(curve.group_order)
+    assert -curve.modulus < curve.a < curve.modulus
+    assert -curve.modulus < curve.b < curve.modulus
+
+
+def test_get_curve():
+    assert get_curve("secp256k1") is CURVES["secp256k1"]
+    assert get_curve("BLS12-381") is CURVES["bls12_381"]
+
+    with pytest.raises(ValueError, match="the supported curves are: secp256k1, edwards25519, bls12_381, mnt4_753"):
+        get_curve("p256")
+
+
+@pytest.mark.parametrize(
+    ("name", "expected"), [("secp256k1", 32), ("edwards25519", 32), ("bls12_381", 48), ("mnt4_753", 95)]
+)
+def test_field_byte_width(name, expected):
+    curve = CURVES[name]
+    assert curve.field_byte_width() == expected
+    assert curve.modulus < 2 ** (8 * curve.field_byte_width())
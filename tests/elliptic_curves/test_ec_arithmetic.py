@@ -1,4 +1,5 @@
 from dataclasses import dataclass
+from math import log2
 
 import pytest
 from elliptic_curves.fields.prime_field import PrimeField
@@ -15,6 +16,9 @@ from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
 from src.zkscript.elliptic_curves.ec_operations_fq2 import EllipticCurveFq2
 from src.zkscript.fields.fq2 import Fq2 as Fq2Script
 from src.zkscript.script_types.stack_elements import StackEllipticCurvePoint, StackFiniteFieldElement
+from src.zkscript.script_types.unlocking_keys.known_base_ec_multiplication import (
+    EllipticCurveFqKnownBaseLsbUnlockingKey,
+)
 from src.zkscript.script_types.unlocking_keys.msm_with_fixed_bases import MsmWithFixedBasesUnlockingKey
 from src.zkscript.script_types.unlocking_keys.unrolled_ec_multiplication import EllipticCurveFqUnrolledUnlockingKey
 from src.zkscript.util.utility_scripts import nums_to_script
@@ -145,6 +149,29 @@ class Secp256k1:
             {"P": P, "Q": point_at_infinity, "expected": P},
             {"P": point_at_infinity, "Q": Q, "expected": Q},
         ],
+        "test_point_equal": [
+            {"P": point_at_infinity, "Q": point_at_infinity, "expected": True},
+            {"P": P, "Q": point_at_infinity, "expected": False},
+            {"P": point_at_infinity, "Q": P, "expected": False},
+            {"P": P, "Q": P, "expected": True},
+            {"P": P, "Q": Q, "expected": False},
+            {"P": P, "Q": -P, "expected": False},
+        ],
+        "test_verify_point_addition": [
+            {"P": P, "Q": Q, "R": P + Q, "is_valid": True},
+            {"P": P, "Q": P, "R": P + P, "is_valid": True},
+            {"P": P, "Q": -P, "R": point_at_infinity, "is_valid": True},
+            {"P": P, "Q": Q, "R": P + P, "is_valid": False},
+            {"P": P, "Q": Q, "R": -(P + Q), "is_valid": False},
+            {"P": P, "Q": P, "R": P + Q, "is_valid": False},
+            {"P": P, "Q": -P, "R": P, "is_valid": False},
+        ],
+        "test_verify_point_doubling": [
+            {"P": P, "R": P + P, "is_valid": True},
+            {"P": -P, "R": -(P + P), "is_valid": True},
+            {"P": P, "R": -(P + P), "is_valid": False},
+            {"P": P, "R": P + Q, "is_valid": False},
+        ],
         "test_multiplication_unrolled": [
             {"P": P, "a": a, "expected": P.multiply(a), "max_multiplier": order},
             {"P": P, "a": 0, "expected": P.multiply(0), "max_multiplier": order},
@@ -289,6 +316,29 @@ class Secp256r1:
             {"P": P, "Q": point_at_infinity, "expected": P},
             {"P": point_at_infinity, "Q": Q, "expected": Q},
         ],
+        "test_point_equal": [
+            {"P": point_at_infinity, "Q": point_at_infinity, "expected": True},
+            {"P": P, "Q": point_at_infinity, "expected": False},
+            {"P": point_at_infinity, "Q": P, "expected": False},
+            {"P": P, "Q": P, "expected": True},
+            {"P": P, "Q": Q, "expected": False},
+            {"P": P, "Q": -P, "expected": False},
+        ],
+        "test_verify_point_addition": [
+            {"P": P, "Q": Q, "R": P + Q, "is_valid": True},
+            {"P": P, "Q": P, "R": P + P, "is_valid": True},
+            {"P": P, "Q": -P, "R": point_at_infinity, "is_valid": True},
+            {"P": P, "Q": Q, "R": P + P, "is_valid": False},
+            {"P": P, "Q": Q, "R": -(P + Q), "is_valid": False},
+            {"P": P, "Q": P, "R": P + Q, "is_valid": False},
+            {"P": P, "Q": -P, "R": P, "is_valid": False},
+        ],
+        "test_verify_point_doubling": [
+            {"P": P, "R": P + P, "is_valid": True},
+            {"P": -P, "R": -(P + P), "is_valid": True},
+            {"P": P, "R": -(P + P), "is_valid": False},
+            {"P": P, "R": P + Q, "is_valid": False},
+        ],
         "test_multiplication_unrolled": [
             {"P": P, "a": a, "expected": P.multiply(a), "max_multiplier": order},
             {"P": P, "a": 0, "expected": P.multiply(0), "max_multiplier": order},
@@ -698,6 +748,12 @@ def generate_test_cases(test_name):
                         )
                     case "test_addition_unknown_points":
                         out.append((config, test_data["P"], test_data["Q"], test_data["expected"]))
+                    case "test_point_equal":
+                        out.append((config, test_data["P"], test_data["Q"], test_data["expected"]))
+                    case "test_verify_point_addition":
+                        out.append((config, test_data["P"], test_data["Q"], test_data["R"], test_data["is_valid"]))
+                    case "test_verify_point_doubling":
+                        out.append((config, test_data["P"], test_data["R"], test_data["is_valid"]))
                     case "test_multiplication_unrolled":
                         out.append(
                             (config, test_data["P"], test_data["a"], test_data["expected"], test_data["max_multiplier"])
@@ -908,6 +964,92 @@ def test_addition_unknown_points(config, P, Q, positive_modulo, expected, save_t
         save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "point addition with unknown points")
 
 
+@pytest.mark.parametrize(("config", "P", "Q", "expected"), generate_test_cases("test_point_equal"))
+@pytest.mark.parametrize("is_verify", [True, False])
+def test_point_equal(config, P, Q, expected, is_verify):
+    unlock = nums_to_script([config.modulus])
+    unlock += generate_unlock(P, degree=config.degree)
+    unlock += generate_unlock(Q, degree=config.degree)
+
+    lock = config.test_script.point_equal(is_verify=is_verify, check_constant=True, clean_constant=True)
+    if is_verify:
+        lock += Script.parse_string("OP_1")
+    elif not expected:
+        lock += Script.parse_string("OP_NOT")
+
+    context = Context(script=unlock + lock)
+    if expected or not is_verify:
+        assert context.evaluate()
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+    else:
+        assert not context.evaluate()
+
+
+@pytest.mark.parametrize(("config", "P", "Q", "R", "is_valid"), generate_test_cases("test_verify_point_addition"))
+def test_verify_point_addition(config, P, Q, R, is_valid, save_to_json_folder):
+    unlock = nums_to_script([config.modulus])
+    if P != -Q:
+        unlock += nums_to_script(P.gradient(Q).to_list())
+    unlock += generate_unlock(P, degree=config.degree)
+    unlock += generate_unlock(Q, degree=config.degree)
+    unlock += generate_unlock(R, degree=config.degree)
+
+    lock = config.test_script.verify_point_addition(check_constant=True, clean_constant=True)
+    lock += Script.parse_string("OP_1")
+
+    context = Context(script=unlock + lock)
+    if is_valid:
+        assert context.evaluate()
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+    else:
+        assert not context.evaluate()
+
+    if save_to_json_folder and is_valid:
+        save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "verify point addition")
+
+
+@pytest.mark.parametrize(("config", "P", "R", "is_valid"), generate_test_cases("test_verify_point_doubling"))
+def test_verify_point_doubling(config, P, R, is_valid, save_to_json_folder):
+    unlock = nums_to_script([config.modulus])
+    unlock += nums_to_script(P.gradient(P).to_list())
+    unlock += generate_unlock(P, degree=config.degree)
+    unlock += generate_unlock(R, degree=config.degree)
+
+    lock = config.test_script.verify_point_doubling(check_constant=True, clean_constant=True)
+    lock += Script.parse_string("OP_1")
+
+    context = Context(script=unlock + lock)
+    if is_valid:
+        assert context.evaluate()
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+    else:
+        assert not context.evaluate()
+
+    if save_to_json_folder and is_valid:
+        save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "verify point doubling")
+
+
+@pytest.mark.parametrize(("R", "is_valid"), [("0x00 0x00", True), ("OP_1 OP_0", False)])
+def test_verify_point_doubling_two_torsion(R, is_valid):
+    # The point (1,0) has order two on the curve y^2 = x^3 - 1, so its double is the point at infinity
+    modulus = Secp256k1.modulus
+    unlock = nums_to_script([modulus, 1, 0]) + Script.parse_string(R)
+
+    lock = EllipticCurveFq(q=modulus, curve_a=0, curve_b=-1).verify_point_doubling(
+        check_constant=True, clean_constant=True
+    )
+    lock += Script.parse_string("OP_1")
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate() == is_valid
+    if is_valid:
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+
+
 @pytest.mark.parametrize("fixed_length_unlock", [True, False])
 @pytest.mark.parametrize(
     ("config", "P", "a", "expected", "max_multiplier"), generate_test_cases("test_multiplication_unrolled")
@@ -1041,3 +1183,86 @@ def test_msm_with_fixed_bases(config, scalars, bases, expected, positive_modulo,
         save_scripts(
             str(lock), str(unlock), save_to_json_folder, config.filename, "multi scalar multiplication with fixed bases"
         )
+
+
+def known_base_lsb_gradients(a, base, point_at_infinity, max_multiplier):
+    gradients = []
+    T, multiple = point_at_infinity, base
+    for i in range(int(log2(max_multiplier)) + 1):
+        if (a >> i) & 1 == 1:
+            gradients.append([] if T.is_infinity() else T.get_gradient(multiple).to_list())
+            T = T + multiple
+        else:
+            gradients.append([])
+        multiple = multiple + multiple
+    return gradients
+
+
+@pytest.mark.parametrize("strategy", ["msb", "lsb"])
+@pytest.mark.parametrize(
+    ("a", "max_multiplier"),
+    [(0, Secp256k1.order), (1, Secp256k1.order), (Secp256k1.order // 4, Secp256k1.order), (0xDEADBEEF, 2**32)],
+)
+def test_multiply_known_base(a, max_multiplier, strategy, save_to_json_folder):
+    config = Secp256k1
+    base = config.generator
+
+    if strategy == "msb":
+        unlocking_key = EllipticCurveFqUnrolledUnlockingKey(
+            P=None, a=a, gradients=unrolled_multiplication_gradients(a, base).as_data(), max_multiplier=max_multiplier
+        )
+        unlock = unlocking_key.to_unlocking_script(config.test_script, load_modulus=True, load_P=False)
+        lock = config.test_script.multiply_known_base_msb(
+            base=base.to_list(),
+            max_multiplier=max_multiplier,
+            modulo_threshold=1,
+            check_constant=True,
+            clean_constant=True,
+        )
+    else:
+        unlocking_key = EllipticCurveFqKnownBaseLsbUnlockingKey(
+            a=a,
+            gradients=known_base_lsb_gradients(a, base, config.point_at_infinity, max_multiplier),
+            max_multiplier=max_multiplier,
+        )
+        unlock = unlocking_key.to_unlocking_script(config.test_script, load_modulus=True)
+        lock = config.test_script.multiply_known_base_lsb(
+            base=base.to_list(),
+            max_multiplier=max_multiplier,
+            modulo_threshold=1,
+            check_constant=True,
+            clean_constant=True,
+        )
+    lock += generate_verify_point(base.multiply(a), degree=config.degree)
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+    if save_to_json_folder:
+        save_scripts(
+            str(lock), str(unlock), save_to_json_folder, config.filename, f"known base multiplication {strategy}"
+        )
+
+
+@pytest.mark.parametrize("modulo_threshold", [1, 200 * 8])
+@pytest.mark.parametrize("max_multiplier", [2, 2**8, 2**64, Secp256k1.order])
+@pytest.mark.parametrize("base", [Secp256k1.generator, Secp256k1.P])
+def test_multiply_known_base_auto_is_smallest(base, max_multiplier, modulo_threshold):
+    test_script = Secp256k1.test_script
+    arguments = {
+        "base": base.to_list(),
+        "max_multiplier": max_multiplier,
+        "modulo_threshold": modulo_threshold,
+        "check_constant": True,
+        "clean_constant": True,
+    }
+
+    strategy, auto = test_script.multiply_known_base_auto(**arguments)
+    msb = test_script.multiply_known_base_msb(**arguments)
+    lsb = test_script.multiply_known_base_lsb(**arguments)
+
+    assert len(auto.raw_serialize()) <= len(msb.raw_serialize())
+    assert len(auto.raw_serialize()) <= len(lsb.raw_serialize())
+    assert auto.raw_serialize() == (lsb if strategy == "lsb" else msb).raw_serialize()
@@ -1,10 +1,13 @@
 import json
-from itertools import product
+from collections.abc import Iterator
+from dataclasses import replace
+from itertools import combinations, product
 from pathlib import Path
-from typing import Union
+from typing import Any, Union
 
 from tx_engine import Script
 
+from src.zkscript.elliptic_curves.curves import CurveParameters
 from src.zkscript.script_types.stack_elements import StackEllipticCurvePoint, StackFiniteFieldElement
 from src.zkscript.util.utility_functions import boolean_list_to_bitmask
 from src.zkscript.util.utility_scripts import nums_to_script
@@ -405,3 +408,52 @@ def save_scripts(lock, unlock, save_to_json_folder, filename, test_name):
 
         with json_file.open("w") as f:
             json.dump(data, f, indent=4)
+
+
+def is_on_curve(point: list[int], curve: CurveParameters) -> bool:
+    """Check whether the affine point `point = [x, y]` is on `curve`."""
+    x, y = point
+    if curve.form == "twisted_edwards":
+        return (curve.a * x * x + y * y - 1 - curve.b * x * x * y * y) % curve.modulus == 0
+    return (y * y - x**3 - curve.a * x - curve.b) % curve.modulus == 0
+
+
+def off_curve_point(point: list[int], curve: CurveParameters) -> list[int]:
+    """Return the first point `[x, y + k]`, `k > 0`, that is not on `curve`."""
+    x, y = point
+    k = 1
+    while is_on_curve([x, (y + k) % curve.modulus], curve):
+        k += 1
+    return [x, (y + k) % curve.modulus]
+
+
+def mutated_witnesses(unlocking_key: Any, point_fields: list[str], curve: CurveParameters) -> Iterator[tuple[str, Any]]:
+    """Generate minimally-mutated copies of a valid unlocking key for a point-operation script.
+
+    The mutations are:
+        - the least significant bit of a coordinate of a point is flipped
+        - a point is replaced by a point not on the curve
+        - two (distinct) points are swapped
+
+    Args:
+        unlocking_key (Any): A valid unlocking key, a dataclass.
+        point_fields (list[str]): The names of the fields of `unlocking_key` holding affine points `[x, y]`. Fields
+            whose value is not a list (e.g., compressed public keys) are skipped.
+        curve (CurveParameters): The curve of the points.
+
+    Yields:
+        Pairs `(description, mutated_key)`, where `mutated_key` is an invalid copy of `unlocking_key`.
+    """
+    points = {name: getattr(unlocking_key, name) for name in point_fields}
+    points = {name: point for name, point in points.items() if isinstance(point, list)}
+
+    for name, point in points.items():
+        for coordinate in range(2):
+            flipped = point.copy()
+            flipped[coordinate] = (flipped[coordinate] ^ 1) % curve.modulus
+            yield f"flip bit of coordinate {coordinate} of {name}", replace(unlocking_key, **{name: flipped})
+        yield f"{name} off curve", replace(unlocking_key, **{name: off_curve_point(point, curve)})
+
+    for first, second in combinations(points, 2):
+        if points[first] != points[second]:
+            yield f"swap {first} and {second}", replace(unlocking_key, **{first: points[second], second: points[first]})
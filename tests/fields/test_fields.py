@@ -14,6 +14,7 @@ from src.zkscript.fields.fq4 import Fq4 as Fq4Script
 from src.zkscript.fields.fq6_3_over_2 import Fq6 as Fq6Script
 from src.zkscript.fields.fq12_2_over_3_over_2 import Fq12 as Fq12Script
 from src.zkscript.fields.fq12_3_over_2_over_2 import Fq12Cubic as Fq12CubicScript
+from src.zkscript.fields.util import fq2_sqrt
 from src.zkscript.script_types.stack_elements import StackFiniteFieldElement
 from src.zkscript.util.utility_scripts import nums_to_script
 from tests.fields.util import check_constant, generate_unlock, generate_verify, save_scripts
@@ -86,6 +87,12 @@ class Fq2ResidueMinusOne:
             {"x": [5, 1], "expected": [7], "positive_modulo": False},
             {"x": [5, 1], "expected": [7], "positive_modulo": True},
         ],
+        "test_sqrt": [
+            {"x": [5, 10], "y": [-5, -1], "expected": [14, 18], "positive_modulo": True},
+            {"x": [5, 10], "y": [-5, -1], "expected": [-5, -1], "positive_modulo": False},
+            {"x": [7, -3], "y": [17, 15], "expected": [17, 15], "positive_modulo": True},
+            {"x": [-1, 0], "y": [0, 20], "expected": [0, 1], "positive_modulo": True},
+        ],
     }
 
 
@@ -154,6 +161,11 @@ class Fq2ResidueNotMinusOne:
             {"x": [5, 3], "expected": [-2], "positive_modulo": False},
             {"x": [5, 3], "expected": [17], "positive_modulo": True},
         ],
+        "test_sqrt": [
+            {"x": [1, 1], "y": [11, 13], "expected": [11, 13], "positive_modulo": True},
+            {"x": [1, 1], "y": [-11, -13], "expected": [-11, -13], "positive_modulo": False},
+            {"x": [2, 0], "y": [0, -11], "expected": [0, 8], "positive_modulo": True},
+        ],
     }
 
 
@@ -950,6 +962,57 @@ def test_norm(config, positive_modulo, x, expected, clean_constant, is_constant_
         save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "norm")
 
 
+@pytest.mark.parametrize("clean_constant", [True, False])
+@pytest.mark.parametrize("is_constant_reused", [True, False])
+@pytest.mark.parametrize(("config", "positive_modulo", "x", "y", "expected"), generate_test_cases("test_sqrt"))
+def test_sqrt(config, positive_modulo, x, y, expected, clean_constant, is_constant_reused, save_to_json_folder):
+    # The witness computed off-chain is a square root of x
+    root = fq2_sqrt(x, config.q, config.non_residue.to_int())
+    assert config.Fq2(config.Fq(root[0]), config.Fq(root[1])).power(2) == config.Fq2(config.Fq(x[0]), config.Fq(x[1]))
+
+    unlock = nums_to_script([config.q])
+    unlock += generate_unlock(x)
+    unlock += generate_unlock(y)
+
+    lock = config.test_script.sqrt(
+        take_modulo=True,
+        positive_modulo=positive_modulo,
+        check_constant=True,
+        clean_constant=clean_constant,
+        is_constant_reused=is_constant_reused,
+    )
+    if is_constant_reused:
+        lock += check_constant(config.q)
+    lock += generate_verify(expected)
+
+    verify_script(lock, unlock, clean_constant)
+
+    if save_to_json_folder and clean_constant and not is_constant_reused:
+        save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "sqrt")
+
+
+@pytest.mark.parametrize(
+    ("config", "x"),
+    [
+        (Fq2ResidueMinusOne, [3, 5]),
+        (Fq2ResidueMinusOne, [1, 1]),
+        (Fq2ResidueNotMinusOne, [5, 10]),
+        (Fq2ResidueNotMinusOne, [4, 1]),
+    ],
+)
+def test_sqrt_of_non_square(config, x):
+    with pytest.raises(ValueError, match="is not a square"):
+        fq2_sqrt(x, config.q, config.non_residue.to_int())
+
+    lock = config.test_script.sqrt(take_modulo=True, check_constant=True, clean_constant=True, is_constant_reused=False)
+    # No element of F_q^2 is a square root of x
+    for y0 in range(config.q):
+        for y1 in range(config.q):
+            unlock = nums_to_script([config.q]) + generate_unlock(x) + generate_unlock([y0, y1])
+            context = Context(script=unlock + lock)
+            assert not context.evaluate()
+
+
 @pytest.mark.parametrize("clean_constant", [True, False])
 @pytest.mark.parametrize("is_constant_reused", [True, False])
 @pytest.mark.parametrize(
@@ -1230,3 +1293,36 @@ def fq_inverse(save_to_json_folder, q, x, mod_frequency, positive_modulo, negate
 
     if save_to_json_folder:
         save_scripts(str(lock), str(unlock), save_to_json_folder, "fq", "inverse")
+
+
+@pytest.mark.parametrize("is_verify", [True, False])
+@pytest.mark.parametrize("config", [Fq12TwoOverThreeOverTwo, Fq12ThreeOverTwoOverTwo])
+@pytest.mark.parametrize(
+    ("x", "expected"),
+    [
+        ([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], True),
+        ([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], False),
+        ([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5, 0], False),
+        ([2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], False),
+        ([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], False),
+    ],
+)
+def test_is_one(config, x, expected, is_verify, save_to_json_folder):
+    unlock = nums_to_script([config.q])
+    unlock += generate_unlock(x)
+
+    lock = config.test_script.is_one(is_verify=is_verify)
+    if is_verify:
+        lock += Script.parse_string("OP_1")
+    else:
+        lock += nums_to_script([int(expected)])
+        lock += Script.parse_string("OP_EQUAL")
+
+    if is_verify and not expected:
+        context = Context(script=unlock + lock)
+        assert not context.evaluate()
+    else:
+        verify_script(lock, unlock, clean_constant=False)
+
+    if save_to_json_folder and is_verify and expected:
+        save_scripts(str(lock), str(unlock), save_to_json_folder, config.filename, "is_one")
@@ -1,26 +1,42 @@
 import json
+import logging
 import sys
 from dataclasses import dataclass
+from math import log2
 from pathlib import Path
 from random import randint, seed
 
 import pytest
 from elliptic_curves.instantiations.bls12_381.bls12_381 import BLS12_381, ProofBls12381, VerifyingKeyBls12381
 from elliptic_curves.instantiations.mnt4_753.mnt4_753 import MNT4_753, ProofMnt4753, VerifyingKeyMnt4753
-from tx_engine import Context
+from tx_engine import Context, Script
 
+from src.zkscript.bilinear_pairings.bls12_381.parameters import u as u_bls12_381
+from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
 from src.zkscript.groth16.bls12_381.bls12_381 import bls12_381
 from src.zkscript.groth16.mnt4_753.mnt4_753 import mnt4_753
+from src.zkscript.groth16.model.groth16 import Groth16
 from src.zkscript.script_types.locking_keys.groth16 import Groth16LockingKey, Groth16LockingKeyWithPrecomputedMsm
 from src.zkscript.script_types.locking_keys.groth16_proj import (
     Groth16ProjLockingKey,
     Groth16ProjLockingKeyWithPrecomputedMsm,
 )
-from src.zkscript.script_types.unlocking_keys.groth16 import Groth16UnlockingKey, Groth16UnlockingKeyWithPrecomputedMsm
+from src.zkscript.script_types.unlocking_keys.groth16 import (
+    Groth16GradientHints,
+    Groth16UnlockingKey,
+    Groth16UnlockingKeyWithPrecomputedMsm,
+    Groth16Witness,
+)
 from src.zkscript.script_types.unlocking_keys.groth16_proj import (
     Groth16ProjUnlockingKey,
     Groth16ProjUnlockingKeyWithPrecomputedMsm,
 )
+from src.zkscript.script_types.unlocking_keys.msm_with_fixed_bases import MsmWithFixedBasesUnlockingKey
+from src.zkscript.util.ark_serialisation import ark_field_elements_to_ints
+from src.zkscript.util.utility_functions import push_token_to_element
+from src.zkscript.util.utility_scripts import nums_to_script, pick
+from src.zkscript.util.verification_chain import state_commitment
+from tests.bilinear_pairings.util import multiplication_of_negated_point_gradients
 
 
 @dataclass
@@ -512,6 +528,830 @@ def test_groth16_with_precomputed_msm(
         save_scripts(str(lock), str(unlock), save_to_json_folder, filename, "groth16")
 
 
+@pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
+@pytest.mark.parametrize("is_precomputed_msm", [True, False])
+def test_groth16_with_subgroup_membership(
+    precomputed_gradients_in_unlocking,
+    is_precomputed_msm,
+    save_to_json_folder,
+):
+    test_script = Bls12381.test_script
+    prepared_vk = Bls12381.prepared_vk
+    prepared_proof = Bls12381.prepared_proofs[0]
+
+    gradients_subgroup_a = multiplication_of_negated_point_gradients(u_bls12_381**2, Bls12381.A)
+    gradients_subgroup_c = multiplication_of_negated_point_gradients(u_bls12_381**2, Bls12381.C)
+    gradients_pairings = [
+        prepared_proof.gradients_b,
+        prepared_proof.gradients_minus_gamma,
+        prepared_proof.gradients_minus_delta,
+    ]
+    locking_gradients_pairings = [prepared_vk.gradients_minus_gamma, prepared_vk.gradients_minus_delta]
+
+    if is_precomputed_msm:
+        unlocking_key = Groth16UnlockingKeyWithPrecomputedMsm(
+            A=prepared_proof.a,
+            B=prepared_proof.b,
+            C=prepared_proof.c,
+            gradients_pairings=gradients_pairings,
+            inverse_miller_output=prepared_proof.inverse_miller_loop,
+            precomputed_msm=Bls12381.sum_gamma_abc[0].to_list(),
+            has_precomputed_gradients=precomputed_gradients_in_unlocking,
+            gradients_subgroup_a=gradients_subgroup_a,
+            gradients_subgroup_c=gradients_subgroup_c,
+        )
+        unlock = unlocking_key.to_unlocking_script(test_script, True)
+
+        locking_key = Groth16LockingKeyWithPrecomputedMsm(
+            alpha_beta=Bls12381.alpha_beta[0].to_list(),
+            minus_gamma=prepared_vk.minus_gamma,
+            minus_delta=prepared_vk.minus_delta,
+            gradients_pairings=locking_gradients_pairings,
+            has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+        )
+        lock = test_script.groth16_verifier_with_precomputed_msm(
+            locking_key,
+            modulo_threshold=1,
+            check_constant=True,
+            clean_constant=True,
+            check_subgroup_membership=True,
+        )
+    else:
+        unlocking_key = Groth16UnlockingKey.from_data(
+            groth16_model=test_script,
+            pub=prepared_proof.public_statements,
+            A=prepared_proof.a,
+            B=prepared_proof.b,
+            C=prepared_proof.c,
+            gradients_pairings=gradients_pairings,
+            gradients_multiplications=prepared_proof.gradients_multiplications,
+            max_multipliers=Bls12381.max_multipliers[0],
+            gradients_additions=prepared_proof.gradients_additions,
+            inverse_miller_output=prepared_proof.inverse_miller_loop,
+            gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+            has_precomputed_gradients=precomputed_gradients_in_unlocking,
+            gradients_subgroup_a=gradients_subgroup_a,
+            gradients_subgroup_c=gradients_subgroup_c,
+        )
+        unlock = unlocking_key.to_unlocking_script(test_script, True)
+
+        locking_key = Groth16LockingKey(
+            alpha_beta=Bls12381.alpha_beta[0].to_list(),
+            minus_gamma=prepared_vk.minus_gamma,
+            minus_delta=prepared_vk.minus_delta,
+            gamma_abc=prepared_vk.gamma_abc,
+            gradients_pairings=locking_gradients_pairings,
+            has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+        )
+        lock = test_script.groth16_verifier(
+            locking_key,
+            modulo_threshold=1,
+            max_multipliers=Bls12381.max_multipliers[0],
+            check_constant=True,
+            clean_constant=True,
+            check_subgroup_membership=True,
+        )
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+    if save_to_json_folder:
+        save_scripts(str(lock), str(unlock), save_to_json_folder, Bls12381.filename, "groth16 with subgroup membership")
+
+
+def test_groth16_subgroup_membership_mnt4_753():
+    prepared_vk = Mnt4753.prepared_vk
+    prepared_proof = Mnt4753.prepared_proofs[0]
+    locking_key = Groth16LockingKeyWithPrecomputedMsm(
+        alpha_beta=Mnt4753.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gradients_pairings=[prepared_vk.gradients_minus_gamma, prepared_vk.gradients_minus_delta],
+    )
+
+    # E(F_q) has prime order: the subgroup membership checks of A and C require no gradients
+    unlocking_key = Groth16UnlockingKeyWithPrecomputedMsm(
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        gradients_pairings=[
+            prepared_proof.gradients_b,
+            prepared_proof.gradients_minus_gamma,
+            prepared_proof.gradients_minus_delta,
+        ],
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+        precomputed_msm=Mnt4753.sum_gamma_abc[0].to_list(),
+        has_precomputed_gradients=True,
+        gradients_subgroup_a=[],
+        gradients_subgroup_c=[],
+    )
+    lock = Mnt4753.test_script.groth16_verifier_with_precomputed_msm(
+        locking_key, modulo_threshold=1, check_constant=True, clean_constant=True, check_subgroup_membership=True
+    )
+
+    context = Context(script=unlocking_key.to_unlocking_script(Mnt4753.test_script, True) + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+    # The checks are not available if the model is instantiated without them
+    groth16_model = Groth16(
+        pairing_model=Mnt4753.test_script.pairing_model,
+        curve_a=Mnt4753.test_script.curve_a,
+        curve_b=Mnt4753.test_script.curve_b,
+        r=Mnt4753.test_script.r,
+    )
+    with pytest.raises(ValueError, match="Subgroup membership checks are not available for this curve"):
+        groth16_model.groth16_verifier_with_precomputed_msm(
+            locking_key, modulo_threshold=1, check_subgroup_membership=True
+        )
+
+
+def test_groth16_skip_subgroup_checks(caplog):
+    test_script = Bls12381.test_script
+    prepared_vk = Bls12381.prepared_vk
+    prepared_proof = Bls12381.prepared_proofs[0]
+
+    locking_key = Groth16LockingKeyWithPrecomputedMsm(
+        alpha_beta=Bls12381.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gradients_pairings=[prepared_vk.gradients_minus_gamma, prepared_vk.gradients_minus_delta],
+    )
+
+    def unlocking_script(with_subgroup_gradients):
+        return Groth16UnlockingKeyWithPrecomputedMsm(
+            A=prepared_proof.a,
+            B=prepared_proof.b,
+            C=prepared_proof.c,
+            gradients_pairings=[
+                prepared_proof.gradients_b,
+                prepared_proof.gradients_minus_gamma,
+                prepared_proof.gradients_minus_delta,
+            ],
+            inverse_miller_output=prepared_proof.inverse_miller_loop,
+            precomputed_msm=Bls12381.sum_gamma_abc[0].to_list(),
+            has_precomputed_gradients=True,
+            gradients_subgroup_a=multiplication_of_negated_point_gradients(u_bls12_381**2, Bls12381.A)
+            if with_subgroup_gradients
+            else None,
+            gradients_subgroup_c=multiplication_of_negated_point_gradients(u_bls12_381**2, Bls12381.C)
+            if with_subgroup_gradients
+            else None,
+        ).to_unlocking_script(test_script, True)
+
+    def verifier(**kwargs):
+        return test_script.groth16_verifier_with_precomputed_msm(
+            locking_key, modulo_threshold=1, check_constant=True, clean_constant=True, **kwargs
+        )
+
+    with caplog.at_level(logging.WARNING):
+        lock_with_checks = verifier(check_subgroup_membership=True)
+        assert caplog.text == ""
+        lock_without_checks = verifier(check_subgroup_membership=True, skip_subgroup_checks=True)
+    assert "without the requested subgroup checks" in caplog.text
+
+    # Skipping the checks produces a smaller verifier
+    assert len(lock_without_checks.raw_serialize()) < len(lock_with_checks.raw_serialize())
+    # The flag does not change the default verifier
+    assert verifier(skip_subgroup_checks=True).raw_serialize() == verifier().raw_serialize()
+
+    # Both verifiers accept a valid proof
+    for lock, with_subgroup_gradients in [(lock_with_checks, True), (lock_without_checks, False)]:
+        context = Context(script=unlocking_script(with_subgroup_gradients) + lock)
+        assert context.evaluate()
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize("commitment_chunk_size", [None, 1, 8])
+def test_groth16_chunked_hash_commitment(commitment_chunk_size):
+    prepared_vk = Bls12381.prepared_vk
+    prepared_proof = Bls12381.prepared_proofs[0]
+
+    unlocking_key = Groth16UnlockingKey.from_data(
+        groth16_model=Bls12381.test_script,
+        pub=prepared_proof.public_statements,
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        gradients_pairings=[
+            prepared_proof.gradients_b,
+            prepared_proof.gradients_minus_gamma,
+            prepared_proof.gradients_minus_delta,
+        ],
+        gradients_multiplications=prepared_proof.gradients_multiplications,
+        max_multipliers=Bls12381.max_multipliers[0],
+        gradients_additions=prepared_proof.gradients_additions,
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+        gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+        has_precomputed_gradients=True,
+    )
+    unlock = unlocking_key.to_unlocking_script(Bls12381.test_script, True)
+
+    locking_key = Groth16LockingKey(
+        alpha_beta=Bls12381.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=False,
+    )
+    lock = Bls12381.test_script.groth16_verifier(
+        locking_key,
+        modulo_threshold=1,
+        max_multipliers=Bls12381.max_multipliers[0],
+        check_constant=True,
+        clean_constant=True,
+        commitment_chunk_size=commitment_chunk_size,
+    )
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+    # A tampered gradient only changes the commitment of the chunk containing it
+    tampered_iteration = 10
+    tampered_gradients = [list(gradients) for gradients in locking_key.gradients_pairings]
+    tampered_gradients[1][tampered_iteration] = [
+        [gradient[0] + 1, *gradient[1:]] for gradient in tampered_gradients[1][tampered_iteration]
+    ]
+    tampered_locking_key = Groth16LockingKey(
+        alpha_beta=locking_key.alpha_beta,
+        minus_gamma=locking_key.minus_gamma,
+        minus_delta=locking_key.minus_delta,
+        gamma_abc=locking_key.gamma_abc,
+        gradients_pairings=tampered_gradients,
+        has_precomputed_gradients=False,
+    )
+    commitments = Bls12381.test_script.gradients_to_hash_commitments(locking_key, commitment_chunk_size)
+    tampered_commitments = Bls12381.test_script.gradients_to_hash_commitments(
+        tampered_locking_key, commitment_chunk_size
+    )
+    expected_chunk = 0 if commitment_chunk_size is None else tampered_iteration // commitment_chunk_size
+    assert [i for i, (x, y) in enumerate(zip(commitments, tampered_commitments)) if x != y] == [expected_chunk]
+
+
+def test_groth16_invalid_commitment_chunk_size():
+    prepared_vk = Bls12381.prepared_vk
+    locking_key = Groth16LockingKeyWithPrecomputedMsm(
+        alpha_beta=Bls12381.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gradients_pairings=[prepared_vk.gradients_minus_gamma, prepared_vk.gradients_minus_delta],
+        has_precomputed_gradients=False,
+    )
+
+    with pytest.raises(ValueError, match="The commitment chunk size must be positive"):
+        Bls12381.test_script.groth16_verifier_with_precomputed_msm(
+            locking_key, modulo_threshold=1, commitment_chunk_size=0
+        )
+
+
+@pytest.mark.parametrize("is_preimage_valid", [True, False])
+def test_groth16_public_inputs_commitment(is_preimage_valid):
+    seed(42)
+    n_public_inputs = 8
+    A = Bls12381.pairing_curve.g1_curve.generate_random_point()
+    B = Bls12381.pairing_curve.g2_curve.generate_random_point()
+    C = Bls12381.pairing_curve.g1_curve.generate_random_point()
+    alpha = Bls12381.pairing_curve.g1_curve.generate_random_point()
+    beta = Bls12381.pairing_curve.g2_curve.generate_random_point()
+    gamma = Bls12381.pairing_curve.g2_curve.generate_random_point()
+    delta = Bls12381.pairing_curve.g2_curve.generate_random_point()
+
+    dlog_gamma_abc = [randint(1, Bls12381.r - 1) for _ in range(n_public_inputs + 1)]  # noqa: S311
+    # Include the edge cases 0 and 1
+    pub_statement = [1, 0, 1] + [randint(2, Bls12381.r - 1) for _ in range(n_public_inputs - 2)]  # noqa: S311
+
+    gamma_abc = [Bls12381.g1.multiply(dlog) for dlog in dlog_gamma_abc]
+    sum_gamma_abc = Bls12381.g1.multiply(0)
+    for i in range(len(gamma_abc)):
+        sum_gamma_abc += gamma_abc[i].multiply(pub_statement[i])
+
+    vk = VerifyingKeyBls12381(alpha, beta, gamma, delta, gamma_abc)
+    proof = ProofBls12381(A, B, C)
+    cache_vk = vk.prepare()
+    prepared_vk = vk.prepare_for_zkscript(cache_vk)
+    prepared_proof = proof.prepare_for_zkscript(cache_vk, pub_statement[1:])
+    alpha_beta = Bls12381.pairing_curve.pairing([A, sum_gamma_abc, C], [B, -gamma, -delta])
+
+    unlocking_key = Groth16UnlockingKey.from_data(
+        groth16_model=Bls12381.test_script,
+        pub=prepared_proof.public_statements,
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        gradients_pairings=[
+            prepared_proof.gradients_b,
+            prepared_proof.gradients_minus_gamma,
+            prepared_proof.gradients_minus_delta,
+        ],
+        gradients_multiplications=prepared_proof.gradients_multiplications,
+        max_multipliers=None,
+        gradients_additions=prepared_proof.gradients_additions,
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+        gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+        has_precomputed_gradients=True,
+    )
+    unlock = unlocking_key.to_unlocking_script(Bls12381.test_script, True, n_public_inputs)
+
+    locking_key = Groth16LockingKey(
+        alpha_beta=alpha_beta.to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=False,
+    )
+    # A mismatched preimage is modelled by committing to different public inputs
+    committed_pub = pub_statement[1:] if is_preimage_valid else [*pub_statement[1:-1], pub_statement[-1] + 1]
+    lock = Bls12381.test_script.groth16_verifier(
+        locking_key,
+        modulo_threshold=1,
+        check_constant=True,
+        clean_constant=True,
+        public_inputs_commitment=Bls12381.test_script.public_inputs_to_hash_commitment(committed_pub),
+    )
+    context = Context(script=unlock + lock)
+    assert context.evaluate() == is_preimage_valid
+    if is_preimage_valid:
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+
+
+
+def test_groth16_public_inputs_commitment_marker():
+    # The gradients are not checked when extracting the scalars, so dummy values are used
+    scalars = [0, 1, 6, 3, 0]
+    max_multipliers = [8, 4, 16, 4, 2]
+    gradients_multiplications = [[], [], [[[11], [12]], [[13], [14]]], [[[15], [16]]], []]
+    unlocking_key = MsmWithFixedBasesUnlockingKey.from_data(
+        scalars=scalars,
+        gradients_multiplications=gradients_multiplications,
+        max_multipliers=max_multipliers,
+        gradients_additions=[[7]] * (len(scalars) - 1),
+    )
+    unlock = unlocking_key.to_unlocking_script(
+        EllipticCurveFq(q=Bls12381.q, curve_a=0, curve_b=4), load_modulus=True, extractable_scalars=len(scalars)
+    )
+
+    # After extracting a_i, the marker `marker_a_is_zero` of a_i is at position n_blocks * 4 + i + 1, where
+    # n_blocks = sum_(j=0)^i log2(max_multipliers[j]). The verifier moves the scalars extracted before a_i to the
+    # altstack, so they do not shift the position
+    for i, scalar in enumerate(scalars):
+        n_blocks = sum(int(log2(max_multipliers[j])) for j in range(i + 1))
+        lock = MsmWithFixedBasesUnlockingKey.extract_scalar_as_unsigned(
+            max_multipliers=max_multipliers, index=i, rolling_option=False
+        )
+        lock += pick(position=n_blocks * 4 + i + 1, n_elements=1)
+        lock += Script.parse_string("OP_1" if scalar == 0 else "OP_0")
+        lock += Script.parse_string("OP_EQUALVERIFY")
+        assert Context(script=unlock + lock).evaluate(), i
+
+    # The verifier replaces the extracted scalar by 0 if the marker is set: reading the marker at the wrong position
+    # would commit to 1 in place of 0
+    verify_commitment = Bls12381.test_script._Groth16__verify_public_inputs_commitment  # noqa: SLF001
+    lock = verify_commitment(len(scalars), max_multipliers, state_commitment(scalars))
+    assert Context(script=unlock + lock).evaluate()
+    lock = verify_commitment(len(scalars), max_multipliers, state_commitment([max(a, 1) for a in scalars]))
+    assert not Context(script=unlock + lock).evaluate()
+
+
+def test_groth16_stack_limits():
+    prepared_vk = Bls12381.prepared_vk
+    locking_key = Groth16LockingKey(
+        alpha_beta=Bls12381.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=True,
+    )
+    verifier_arguments = {
+        "locking_key": locking_key,
+        "modulo_threshold": 200 * 8,
+        "max_multipliers": Bls12381.max_multipliers[0],
+        "check_constant": True,
+        "clean_constant": True,
+    }
+
+    lock = Bls12381.test_script.groth16_verifier(**verifier_arguments)
+    guarded_lock = Bls12381.test_script.groth16_verifier(**verifier_arguments, stack_limits=(10**6, 10**6))
+    assert guarded_lock == lock
+
+    with pytest.raises(ValueError, match="The Groth16 verifier exceeds the stack limits"):
+        Bls12381.test_script.groth16_verifier(**verifier_arguments, stack_limits=(10, 520))
+
+
+@pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
+def test_groth16_terminal(precomputed_gradients_in_unlocking):
+    prepared_vk = Bls12381.prepared_vk
+    prepared_proof = Bls12381.prepared_proofs[0]
+
+    unlocking_key = Groth16UnlockingKey.from_data(
+        groth16_model=Bls12381.test_script,
+        pub=prepared_proof.public_statements,
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        gradients_pairings=[
+            prepared_proof.gradients_b,
+            prepared_proof.gradients_minus_gamma,
+            prepared_proof.gradients_minus_delta,
+        ],
+        gradients_multiplications=prepared_proof.gradients_multiplications,
+        max_multipliers=Bls12381.max_multipliers[0],
+        gradients_additions=prepared_proof.gradients_additions,
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+        gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+        has_precomputed_gradients=precomputed_gradients_in_unlocking,
+    )
+    unlock = unlocking_key.to_unlocking_script(Bls12381.test_script, True)
+
+    def verifier(alpha_beta: list[int], terminal: bool) -> Script:
+        locking_key = Groth16LockingKey(
+            alpha_beta=alpha_beta,
+            minus_gamma=prepared_vk.minus_gamma,
+            minus_delta=prepared_vk.minus_delta,
+            gamma_abc=prepared_vk.gamma_abc,
+            gradients_pairings=[
+                prepared_vk.gradients_minus_gamma,
+                prepared_vk.gradients_minus_delta,
+            ],
+            has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+        )
+        return Bls12381.test_script.groth16_verifier(
+            locking_key,
+            modulo_threshold=1,
+            max_multipliers=Bls12381.max_multipliers[0],
+            check_constant=True,
+            clean_constant=True,
+            terminal=terminal,
+        )
+
+    alpha_beta = Bls12381.alpha_beta[0].to_list()
+    lock = verifier(alpha_beta, terminal=False)
+    terminal_lock = verifier(alpha_beta, terminal=True)
+    assert terminal_lock.to_string().split()[-1] == "OP_EQUALVERIFY"
+    assert len(terminal_lock.raw_serialize()) == len(lock.raw_serialize())
+
+    # Standalone: the verifier leaves the result of the verification on the stack
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+
+    # Composition with a boolean check: the result of the verifier is combined with the one of the check
+    context = Context(script=unlock + lock + Script.parse_string("OP_1 OP_BOOLAND"))
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+
+    # Composition with a check following the verifier: the verifier leaves nothing on the stack
+    context = Context(script=unlock + terminal_lock + Script.parse_string("OP_DEPTH OP_NOT"))
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+
+    if not precomputed_gradients_in_unlocking:
+        # Only the last element of alpha_beta is checked by the final check of the verifier
+        wrong_alpha_beta = [alpha_beta[0] + 1, *alpha_beta[1:]]
+        context = Context(script=unlock + verifier(wrong_alpha_beta, terminal=False) + Script.parse_string("OP_NOT"))
+        assert context.evaluate()
+        context = Context(script=unlock + verifier(wrong_alpha_beta, terminal=True) + Script.parse_string("OP_1"))
+        assert not context.evaluate()
+
+
+@pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
+def test_groth16_witness_binding(precomputed_gradients_in_unlocking):
+    prepared_vk = Bls12381.prepared_vk
+    # A different valid proof of the same statement: e(2A, B/2) = e(A, B)
+    other_proof = ProofBls12381(Bls12381.A.multiply(2), Bls12381.B.multiply(pow(2, -1, Bls12381.r)), Bls12381.C)
+    prepared_proofs = [
+        Bls12381.prepared_proofs[0],
+        other_proof.prepare_for_zkscript(Bls12381.cache_vk, Bls12381.pub_statements[0][1:]),
+    ]
+
+    def unlocking_key(proof, hints) -> Groth16UnlockingKeyWithPrecomputedMsm:
+        return Groth16UnlockingKeyWithPrecomputedMsm(
+            A=proof.a,
+            B=proof.b,
+            C=proof.c,
+            gradients_pairings=[hints.gradients_b, hints.gradients_minus_gamma, hints.gradients_minus_delta],
+            inverse_miller_output=hints.inverse_miller_loop,
+            precomputed_msm=Bls12381.sum_gamma_abc[0].to_list(),
+            has_precomputed_gradients=precomputed_gradients_in_unlocking,
+        )
+
+    locking_key = Groth16LockingKeyWithPrecomputedMsm(
+        alpha_beta=Bls12381.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+    )
+
+    # The verifier is generated without knowing which proof will be used to spend it
+    lock = Bls12381.test_script.groth16_verifier_with_precomputed_msm(
+        locking_key, modulo_threshold=1, check_constant=True, clean_constant=True, bind_witness=True
+    )
+
+    def unlocking_script(key, witness_commitment) -> Script:
+        out = nums_to_script([Bls12381.q])
+        out.append_pushdata(witness_commitment)
+        return out + key.to_unlocking_script(Bls12381.test_script, False)
+
+    keys = [unlocking_key(proof, proof) for proof in prepared_proofs]
+    assert keys[0].witness_commitment() != keys[1].witness_commitment()
+
+    # Any valid proof is accepted together with its own hints, and its commitment is left below the result
+    for key in keys:
+        check_commitment = Script.parse_string("OP_SWAP")
+        check_commitment.append_pushdata(key.witness_commitment())
+        check_commitment += Script.parse_string("OP_EQUALVERIFY")
+        unlock = key.to_unlocking_script(Bls12381.test_script, True, bind_witness=True)
+        assert unlock.raw_serialize() == unlocking_script(key, key.witness_commitment()).raw_serialize()
+        context = Context(script=unlock + lock + check_commitment)
+        assert context.evaluate()
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+
+    # Swapping in the hints of the other valid proof is rejected, both against the commitment of the proof and
+    # against the commitment of the swapped hints
+    for proof, hints in [prepared_proofs, prepared_proofs[::-1]]:
+        swapped_key = unlocking_key(proof, hints)
+        for witness_commitment in [unlocking_key(proof, proof).witness_commitment(), swapped_key.witness_commitment()]:
+            context = Context(script=unlocking_script(swapped_key, witness_commitment) + lock)
+            assert not context.evaluate()
+
+    # The commitment of the other proof is rejected
+    context = Context(script=unlocking_script(keys[0], keys[1].witness_commitment()) + lock)
+    assert not context.evaluate()
+
+    # Non-reduced representatives of the hints or of the proof are rejected, even with a matching commitment
+    key = unlocking_key(prepared_proofs[0], prepared_proofs[0])
+    key.inverse_miller_output = [key.inverse_miller_output[0] + Bls12381.q, *key.inverse_miller_output[1:]]
+    context = Context(script=key.to_unlocking_script(Bls12381.test_script, True, bind_witness=True) + lock)
+    assert not context.evaluate()
+
+    key = unlocking_key(prepared_proofs[0], prepared_proofs[0])
+    key.C = [key.C[0] + Bls12381.q, *key.C[1:]]
+    context = Context(script=key.to_unlocking_script(Bls12381.test_script, True, bind_witness=True) + lock)
+    assert not context.evaluate()
+
+
+@pytest.mark.parametrize("config", [Bls12381, Mnt4753])
+def test_groth16_baked_bases(config):
+    prepared_vk = config.prepared_vk
+    expected_bases = [point.to_list() for point in config.gamma_abc]
+
+    locking_key = Groth16LockingKey(
+        alpha_beta=config.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[prepared_vk.gradients_minus_gamma, prepared_vk.gradients_minus_delta],
+        has_precomputed_gradients=False,
+    )
+    assert config.test_script.baked_bases(locking_key) == expected_bases
+
+    # Points in projective coordinates are mapped back to affine ones
+    z = 7
+    proj_locking_key = Groth16ProjLockingKey(
+        alpha_beta=config.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=[[x * z % config.q, y * z % config.q, z] for x, y in prepared_vk.gamma_abc],
+    )
+    assert config.test_script.baked_bases(proj_locking_key) == expected_bases
+
+
+@pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
+@pytest.mark.parametrize("config", [Bls12381, Mnt4753])
+def test_groth16_debug_labels(config, precomputed_gradients_in_unlocking):
+    prepared_vk = config.prepared_vk
+    prepared_proof = config.prepared_proofs[0]
+
+    unlocking_key = Groth16UnlockingKey.from_data(
+        groth16_model=config.test_script,
+        pub=prepared_proof.public_statements,
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        gradients_pairings=[
+            prepared_proof.gradients_b,
+            prepared_proof.gradients_minus_gamma,
+            prepared_proof.gradients_minus_delta,
+        ],
+        gradients_multiplications=prepared_proof.gradients_multiplications,
+        max_multipliers=config.max_multipliers[0],
+        gradients_additions=prepared_proof.gradients_additions,
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+        gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+        has_precomputed_gradients=precomputed_gradients_in_unlocking,
+    )
+    unlock = unlocking_key.to_unlocking_script(config.test_script, True)
+
+    locking_key = Groth16LockingKey(
+        alpha_beta=config.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+    )
+    lock, labels = config.test_script.groth16_verifier(
+        locking_key,
+        modulo_threshold=1,
+        max_multipliers=config.max_multipliers[0],
+        check_constant=True,
+        clean_constant=True,
+        debug_labels=True,
+    )
+
+    assert [label for _, label in labels] == ["gamma_abc_msm", "miller_loop_start", "final_exp"]
+    offsets = [offset for offset, _ in labels]
+    assert offsets == sorted(offsets)
+    assert offsets[-1] < len(lock.raw_serialize())
+
+    # The labels mark the script generated without them
+    unlabelled_lock = config.test_script.groth16_verifier(
+        locking_key,
+        modulo_threshold=1,
+        max_multipliers=config.max_multipliers[0],
+        check_constant=True,
+        clean_constant=True,
+    )
+    assert lock.raw_serialize() == unlabelled_lock.raw_serialize()
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize("config", [Bls12381, Mnt4753])
+def test_groth16_proj_debug_labels(config):
+    prepared_proof = config.prepared_proofs[0]
+
+    unlocking_key = Groth16ProjUnlockingKey.from_data(
+        groth16_model=config.test_script,
+        pub=prepared_proof.public_statements,
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        max_multipliers=config.max_multipliers[0],
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+    )
+    unlock = unlocking_key.to_unlocking_script(config.test_script, True, 0)
+
+    locking_key = Groth16ProjLockingKey(
+        alpha_beta=config.alpha_beta[0].to_list(),
+        minus_gamma=config.prepared_vk.minus_gamma,
+        minus_delta=config.prepared_vk.minus_delta,
+        gamma_abc=config.prepared_vk.gamma_abc,
+    )
+    verifier_args = {
+        "modulo_threshold": 1,
+        "max_multipliers": config.max_multipliers[0],
+        "check_constant": True,
+        "clean_constant": True,
+    }
+    lock, labels = config.test_script.groth16_verifier_proj(locking_key, debug_labels=True, **verifier_args)
+
+    assert [label for _, label in labels] == ["gamma_abc_msm", "miller_loop_start", "final_exp"]
+    offsets = [offset for offset, _ in labels]
+    assert offsets == sorted(offsets)
+    assert offsets[-1] < len(lock.raw_serialize())
+    unlabelled_lock = config.test_script.groth16_verifier_proj(locking_key, **verifier_args)
+    assert lock.raw_serialize() == unlabelled_lock.raw_serialize()
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
+@pytest.mark.parametrize("config", [Bls12381, Mnt4753])
+def test_groth16_verification_chain(config, precomputed_gradients_in_unlocking):
+    test_script = config.test_script
+    prepared_vk = config.prepared_vk
+    prepared_proof = config.prepared_proofs[0]
+    gradients_pairings = [
+        prepared_proof.gradients_b,
+        prepared_proof.gradients_minus_gamma,
+        prepared_proof.gradients_minus_delta,
+    ]
+
+    unlocking_key = Groth16UnlockingKey.from_data(
+        groth16_model=test_script,
+        pub=prepared_proof.public_statements,
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        gradients_pairings=gradients_pairings,
+        gradients_multiplications=prepared_proof.gradients_multiplications,
+        max_multipliers=config.max_multipliers[0],
+        gradients_additions=prepared_proof.gradients_additions,
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+        gradient_gamma_abc_zero=prepared_proof.gradient_gamma_abc_zero,
+        has_precomputed_gradients=precomputed_gradients_in_unlocking,
+    )
+    unlock = unlocking_key.to_unlocking_script(test_script, True)
+
+    locking_key = Groth16LockingKey(
+        alpha_beta=config.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+    )
+    locking_key_with_precomputed_msm = Groth16LockingKeyWithPrecomputedMsm(
+        alpha_beta=locking_key.alpha_beta,
+        minus_gamma=locking_key.minus_gamma,
+        minus_delta=locking_key.minus_delta,
+        gradients_pairings=locking_key.gradients_pairings,
+        has_precomputed_gradients=locking_key.has_precomputed_gradients,
+    )
+
+    # Monolithic verifier
+    lock = test_script.groth16_verifier(
+        locking_key,
+        modulo_threshold=1,
+        max_multipliers=config.max_multipliers[0],
+        check_constant=True,
+        clean_constant=True,
+    )
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+
+    # The state at the end of the first stage is the input of the verifier with precomputed msm
+    state_unlocking_key = Groth16UnlockingKeyWithPrecomputedMsm(
+        A=prepared_proof.a,
+        B=prepared_proof.b,
+        C=prepared_proof.c,
+        gradients_pairings=gradients_pairings,
+        inverse_miller_output=prepared_proof.inverse_miller_loop,
+        precomputed_msm=config.sum_gamma_abc[0].to_list(),
+        has_precomputed_gradients=precomputed_gradients_in_unlocking,
+    )
+    state = [
+        push_token_to_element(token)[1]
+        for token in state_unlocking_key.to_unlocking_script(test_script, True).to_string().split()
+    ]
+    assert len(state) == test_script.verification_chain_state_size(locking_key)
+    commitment = state_commitment(state)
+
+    # First stage: compute the msm and commit to the state
+    first_stage = test_script.groth16_verifier_msm_stage(
+        locking_key,
+        modulo_threshold=1,
+        max_multipliers=config.max_multipliers[0],
+        check_constant=True,
+    )
+    first_stage.append_pushdata(commitment)
+    first_stage += Script.parse_string("OP_EQUALVERIFY OP_1")
+    context = Context(script=unlock + first_stage)
+    assert context.evaluate()
+
+    # Second stage: verify the proof starting from the committed state
+    second_stage = test_script.groth16_verifier_pairing_stage(
+        locking_key_with_precomputed_msm,
+        modulo_threshold=1,
+        input_commitment=commitment,
+        clean_constant=True,
+    )
+    context = Context(script=nums_to_script(state) + second_stage)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+    # A state which does not match the commitment is rejected, even if it represents the same values
+    tampered_state = [*state[:-1], state[-1] + test_script.pairing_model.modulus]
+    context = Context(script=nums_to_script(tampered_state) + second_stage)
+    assert not context.evaluate()
+
+
 @pytest.mark.slow
 @pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
 @pytest.mark.parametrize("extractable_inputs", [1, 0])
@@ -823,3 +1663,156 @@ def test_groth16_proj_slow(
 
     if save_to_json_folder:
         save_scripts(str(lock), str(unlock), save_to_json_folder, filename, "groth16")
+
+
+@pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
+@pytest.mark.parametrize("config", [Bls12381, Mnt4753])
+def test_groth16_witness(config, precomputed_gradients_in_unlocking):
+    prepared_proof = config.prepared_proofs[1]
+
+    witness = Groth16Witness(has_precomputed_gradients=precomputed_gradients_in_unlocking)
+    witness.public_inputs = prepared_proof.public_statements
+    witness.gradient_hints = Groth16GradientHints.from_prepared_proof(prepared_proof)
+    witness.proof_c = prepared_proof.c
+    witness.proof_b = prepared_proof.b
+    witness.proof_a = prepared_proof.a
+    witness.max_multipliers = config.max_multipliers[1]
+    unlock = witness.build(config.test_script)
+
+    locking_key = Groth16LockingKey(
+        alpha_beta=config.alpha_beta[1].to_list(),
+        minus_gamma=config.prepared_vk.minus_gamma,
+        minus_delta=config.prepared_vk.minus_delta,
+        gamma_abc=config.prepared_vk.gamma_abc,
+        gradients_pairings=[
+            config.prepared_vk.gradients_minus_gamma,
+            config.prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+    )
+    lock = config.test_script.groth16_verifier(
+        locking_key,
+        modulo_threshold=1,
+        max_multipliers=config.max_multipliers[1],
+        check_constant=True,
+        clean_constant=True,
+    )
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+def test_groth16_witness_missing_fields():
+    prepared_proof = Bls12381.prepared_proofs[0]
+
+    witness = Groth16Witness(proof_a=prepared_proof.a, proof_c=prepared_proof.c)
+    assert witness.missing_fields() == ["proof_b", "public_inputs", "gradient_hints"]
+    with pytest.raises(ValueError, match="proof_b, public_inputs, gradient_hints"):
+        witness.build(Bls12381.test_script)
+
+    witness.proof_b = prepared_proof.b
+    witness.gradient_hints = Groth16GradientHints.from_prepared_proof(prepared_proof)
+    witness.public_inputs = prepared_proof.public_statements[1:]
+    with pytest.raises(ValueError, match="public inputs"):
+        witness.build(Bls12381.test_script)
+
+
+@pytest.mark.parametrize(
+    ("example", "test_script", "pairing_curve", "vk_type", "proof_type"),
+    [
+        ("sha256", bls12_381, BLS12_381, VerifyingKeyBls12381, ProofBls12381),
+        ("key_ownership", bls12_381, BLS12_381, VerifyingKeyBls12381, ProofBls12381),
+        ("square_root", mnt4_753, MNT4_753, VerifyingKeyMnt4753, ProofMnt4753),
+    ],
+)
+@pytest.mark.parametrize("precomputed_gradients_in_unlocking", [True, False])
+def test_groth16_from_ark_proof(
+    example, test_script, pairing_curve, vk_type, proof_type, precomputed_gradients_in_unlocking
+):
+    # Proofs generated with ark-groth16 by the Rust examples
+    proof_dir = Path(__file__).resolve().parents[2] / "examples" / example / "proof"
+    with (proof_dir / "proof.json").open() as f:
+        serialised_proof = json.load(f)["proof"]
+    with (proof_dir / "verifying_key.json").open() as f:
+        vk = vk_type.deserialise(json.load(f)["verifying_key"])
+    with (proof_dir / "public_inputs.json").open() as f:
+        pub = ark_field_elements_to_ints(json.load(f)["public_inputs"], pairing_curve.scalar_field.get_modulus())
+
+    cache_vk = vk.prepare()
+    prepared_vk = vk.prepare_for_zkscript(cache_vk)
+
+    unlocking_key = Groth16UnlockingKey.from_ark_proof(
+        groth16_model=test_script,
+        proof_type=proof_type,
+        serialised_proof=serialised_proof,
+        pub=pub,
+        cache_vk=cache_vk,
+        has_precomputed_gradients=precomputed_gradients_in_unlocking,
+    )
+    unlock = unlocking_key.to_unlocking_script(test_script)
+
+    locking_key = Groth16LockingKey(
+        alpha_beta=prepared_vk.alpha_beta,
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=not precomputed_gradients_in_unlocking,
+    )
+    lock = test_script.groth16_verifier(
+        locking_key,
+        modulo_threshold=200 * 8,
+        check_constant=True,
+        clean_constant=True,
+    )
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+    # A proof for different public inputs is rejected
+    unlocking_key = Groth16UnlockingKey.from_ark_proof(
+        groth16_model=test_script,
+        proof_type=proof_type,
+        serialised_proof=serialised_proof,
+        pub=[(pub[0] + 1) % pairing_curve.scalar_field.get_modulus(), *pub[1:]],
+        cache_vk=cache_vk,
+        has_precomputed_gradients=precomputed_gradients_in_unlocking,
+    )
+    context = Context(script=unlocking_key.to_unlocking_script(test_script) + lock)
+    assert not context.evaluate(quiet=True)
+
+
+@pytest.mark.parametrize(
+    ("commitment_chunk_size", "expected_opcode_count"),
+    [
+        (None, 545),
+        (16, 548),
+    ],
+)
+def test_commitment_opcode_count(commitment_chunk_size, expected_opcode_count):
+    # Regression guard: the BLS12-381 triple Miller loop has 63 iterations, 5 of which with an addition, hence the
+    # gradients of -gamma and -delta consist of 2 * (63 + 5) elements of F_q^2, each hashed with OP_CAT OP_HASH256
+    prepared_vk = Bls12381.prepared_vk
+    locking_key = Groth16LockingKey(
+        alpha_beta=Bls12381.alpha_beta[0].to_list(),
+        minus_gamma=prepared_vk.minus_gamma,
+        minus_delta=prepared_vk.minus_delta,
+        gamma_abc=prepared_vk.gamma_abc,
+        gradients_pairings=[
+            prepared_vk.gradients_minus_gamma,
+            prepared_vk.gradients_minus_delta,
+        ],
+        has_precomputed_gradients=False,
+    )
+
+    assert bls12_381.commitment_opcode_count(locking_key, commitment_chunk_size) == expected_opcode_count
+
+    locking_key.has_precomputed_gradients = True
+    assert bls12_381.commitment_opcode_count(locking_key, commitment_chunk_size) == 0
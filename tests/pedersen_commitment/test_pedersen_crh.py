@@ -0,0 +1,97 @@
+from hashlib import sha256
+
+import pytest
+from elliptic_curves.fields.prime_field import PrimeField
+from elliptic_curves.models.ec import ShortWeierstrassEllipticCurve
+from elliptic_curves.util.zkscript import multi_scalar_multiplication_with_fixed_bases_gradients
+from tx_engine import Context
+
+from script_examples.pedersen_commitment.pedersen_crh import PedersenCRHCommitment
+from src.zkscript.elliptic_curves.ec_operations_fq import EllipticCurveFq
+from src.zkscript.script_types.unlocking_keys.msm_with_fixed_bases import MsmWithFixedBasesUnlockingKey
+
+modulus = 115792089237316195423570985008687907853269984665640564039457584007908834671663
+order = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
+Fq_k1 = PrimeField(modulus)
+secp256k1 = ShortWeierstrassEllipticCurve(a=Fq_k1(0), b=Fq_k1(7))
+generator = secp256k1(
+    x=Fq_k1(0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798),
+    y=Fq_k1(0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8),
+    infinity=False,
+)
+ec_fq = EllipticCurveFq(q=modulus, curve_a=0, curve_b=7)
+
+# Deterministic parameters: 8 windows of 4 bits, i.e., messages of up to 4 bytes
+window_size = 4
+n_windows = 8
+generators = [
+    generator.multiply(int.from_bytes(sha256(b"pedersen window" + bytes([i])).digest()) % order)
+    for i in range(n_windows)
+]
+randomness_generator = generator.multiply(int.from_bytes(sha256(b"pedersen randomness").digest()) % order)
+bases = [*generators, randomness_generator]
+
+commitment_scheme = PedersenCRHCommitment(
+    ec_fq=ec_fq,
+    generators=[G.to_list() for G in generators],
+    randomness_generator=randomness_generator.to_list(),
+    window_size=window_size,
+    max_randomness=order,
+)
+
+
+def commit(message: bytes, randomness: int):
+    commitment = secp256k1.infinity()
+    for base, scalar in zip(bases, commitment_scheme.opening_scalars(message, randomness)):
+        commitment += base.multiply(scalar)
+    return commitment
+
+
+def generate_unlock(message: bytes, randomness: int):
+    scalars = commitment_scheme.opening_scalars(message, randomness)
+    gradients_multiplications, gradients_additions = multi_scalar_multiplication_with_fixed_bases_gradients(
+        scalars, bases
+    ).as_data()
+    unlocking_key = MsmWithFixedBasesUnlockingKey.from_data(
+        scalars=scalars,
+        gradients_multiplications=gradients_multiplications,
+        max_multipliers=commitment_scheme.max_multipliers,
+        gradients_additions=gradients_additions,
+    )
+    return unlocking_key.to_unlocking_script(ec_fq, load_modulus=True)
+
+
+def test_message_to_scalars():
+    # Little-endian bits, byte by byte
+    assert commitment_scheme.message_to_scalars(b"\x21\xf0") == [1, 2, 0, 15, 0, 0, 0, 0]
+    assert commitment_scheme.message_to_scalars(b"") == [0] * n_windows
+    with pytest.raises(ValueError, match="The message does not fit in the windows"):
+        commitment_scheme.message_to_scalars(b"\x00" * 5)
+    with pytest.raises(ValueError, match="The randomness must be in the range"):
+        commitment_scheme.opening_scalars(b"", order + 1)
+
+
+@pytest.mark.parametrize(
+    ("message", "randomness"),
+    [
+        (b"\xde\xad\xbe\xef", 91983874018876379023889961993961681946728348573401297229445648499213712679245),
+        (b"\x01", 5),
+        (b"\x00\x00\x10\x00", 0),
+    ],
+)
+@pytest.mark.parametrize("x_only", [False, True])
+def test_verify_pedersen_opening(message, randomness, x_only):
+    commitment = commit(message, randomness)
+    lock = commitment_scheme.verify_pedersen_opening(
+        commitment=commitment.to_list(), modulo_threshold=1, check_constant=True, clean_constant=True, x_only=x_only
+    )
+
+    context = Context(script=generate_unlock(message, randomness) + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+    # Opening with a tampered message fails
+    tampered_message = bytes([message[0] ^ 0x01]) + message[1:]
+    context = Context(script=generate_unlock(tampered_message, randomness) + lock)
+    assert not context.evaluate()
@@ -2,13 +2,22 @@ import json
 from pathlib import Path
 
 import pytest
-from tx_engine import SIGHASH, Context, Tx, TxIn, hash256d, sig_hash_preimage
+from elliptic_curves.fields.prime_field import PrimeField
+from elliptic_curves.models.ec import ShortWeierstrassEllipticCurve
+from tx_engine import SIGHASH, Context, Script, Tx, TxIn, TxOut, hash256d, sig_hash_preimage
 
 from src.zkscript.script_types.stack_elements import StackBaseElement
 from src.zkscript.script_types.unlocking_keys.transaction_introspection import (
     PushTxBitShiftUnlockingKey,
     PushTxUnlockingKey,
+    SighashPreimageUnlockingKey,
 )
+from src.zkscript.transaction_introspection.shared_sighash import (
+    InputSignature,
+    SharedSighashPreimage,
+    verify_input_signatures,
+)
+from src.zkscript.transaction_introspection.sighash_midstates import precompute_sighash_midstates
 from src.zkscript.transaction_introspection.transaction_introspection import TransactionIntrospection
 
 prev_txid = int.to_bytes(34060536512648028283387372577505466741680559421950955299118826044926210663733, length=32).hex()
@@ -127,3 +136,150 @@ def test_pushtx(sighash_flags, is_sig_hash_preimage, is_opcodeseparator, save_to
 
     if save_to_json_folder:
         save_scripts(str(lock), str(tx_in.script_sig), save_to_json_folder, "transaction_introspection", "pushtx")
+
+
+def generate_tx(prev_indices):
+    tx_ins = [TxIn(prev_tx=prev_txid, prev_index=prev_index, sequence=prev_index) for prev_index in prev_indices]
+    tx_outs = [
+        TxOut(amount=10, script_pubkey=Script.parse_string("OP_1")),
+        TxOut(amount=20, script_pubkey=Script.parse_string("OP_2 OP_DROP OP_1")),
+    ]
+    return Tx(version=1, tx_ins=tx_ins, tx_outs=tx_outs, locktime=0)
+
+
+@pytest.mark.parametrize(
+    "sighash_flags",
+    [
+        SIGHASH.ALL_FORKID,
+        SIGHASH.SINGLE_FORKID,
+        SIGHASH.NONE_FORKID,
+        SIGHASH.ALL_ANYONECANPAY_FORKID,
+        SIGHASH.NONE_ANYONECANPAY_FORKID,
+        SIGHASH.SINGLE_ANYONECANPAY_FORKID,
+    ],
+)
+@pytest.mark.parametrize("index", [0, 1])
+def test_sighash_preimage_from_midstates(sighash_flags, index):
+    script_pubkey = Script.parse_string("OP_1")
+    tx = generate_tx([0, 1])
+    expected = sig_hash_preimage(
+        tx=tx, index=index, script_pubkey=script_pubkey, prev_amount=prev_amount, sighash_flags=sighash_flags
+    )
+
+    # All the digests are computed on the stack
+    on_stack = precompute_sighash_midstates(
+        tx, index, sighash_flags, fixed_prevouts=False, fixed_sequences=False, fixed_outputs=False
+    )
+    # All the digests are precomputed
+    precomputed = precompute_sighash_midstates(tx, index, sighash_flags)
+    assert precomputed.hash_prevouts == expected[4:36]
+    assert precomputed.hash_sequence == expected[36:68]
+    assert precomputed.hash_outputs == expected[-40:-8]
+    # Only the outputs are fixed at build time: the template spends different outpoints
+    outputs_only = precompute_sighash_midstates(
+        generate_tx([2, 3]), index, sighash_flags, fixed_prevouts=False, fixed_sequences=False
+    )
+
+    unlocking_key = SighashPreimageUnlockingKey(
+        tx=tx, index=index, script_pubkey=script_pubkey, prev_amount=prev_amount
+    )
+    unlocks = []
+    for midstates in [on_stack, precomputed, outputs_only]:
+        unlock = unlocking_key.to_unlocking_script(sighash_flags=sighash_flags, midstates=midstates)
+        lock = TransactionIntrospection.sighash_preimage_from_midstates(
+            sighash_flags=sighash_flags, midstates=midstates
+        )
+        lock.append_pushdata(expected)
+        lock += Script.parse_string("OP_EQUAL")
+
+        context = Context(script=unlock + lock)
+        assert context.evaluate()
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+        unlocks.append(unlock)
+
+    # Precomputing the midstates only leaves the variable portion of the transaction in the unlocking script
+    assert len(unlocks[1].raw_serialize()) <= len(unlocks[2].raw_serialize()) <= len(unlocks[0].raw_serialize())
+
+
+order = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
+Fq_k1 = PrimeField(0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F)
+secp256k1 = ShortWeierstrassEllipticCurve(a=Fq_k1(0), b=Fq_k1(7))
+generator = secp256k1(
+    x=Fq_k1(0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798),
+    y=Fq_k1(0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8),
+    infinity=False,
+)
+
+
+def compressed_pubkey(private_key: int) -> bytes:
+    x, y = generator.multiply(private_key).to_list()
+    return bytes([3 if y % 2 else 2]) + x.to_bytes(32)
+
+
+def sign(sighash: bytes, private_key: int, k: int, sighash_flags: SIGHASH) -> bytes:
+    """Sign `sighash` with `private_key` and nonce `k`, returning the DER encoded low-s signature."""
+
+    def der_integer(n: int) -> bytes:
+        n_bytes = n.to_bytes((n.bit_length() + 8) // 8)
+        return bytes.fromhex("02") + len(n_bytes).to_bytes() + n_bytes
+
+    r = generator.multiply(k).to_list()[0] % order
+    s = pow(k, -1, order) * (int.from_bytes(sighash) + r * private_key) % order
+    s = min(s, order - s)
+    signature = der_integer(r) + der_integer(s)
+    return bytes.fromhex("30") + len(signature).to_bytes() + signature + sighash_flags.to_bytes()
+
+
+@pytest.mark.parametrize("sighash_flags", [SIGHASH.ALL_FORKID, SIGHASH.ALL_ANYONECANPAY_FORKID])
+def test_verify_input_signatures_shared_preimage(sighash_flags):
+    tx = generate_tx([0, 1])
+    private_keys = [3, 5]
+    script_pubkeys = [Script.parse_string("OP_1"), Script.parse_string("OP_2 OP_DROP OP_1")]
+    prev_amounts = [prev_amount, 2 * prev_amount]
+
+    # The shared section is computed once for both inputs
+    shared = SharedSighashPreimage.from_tx(tx, sighash_flags)
+    signatures = []
+    for index in range(2):
+        expected = sig_hash_preimage(
+            tx=tx,
+            index=index,
+            script_pubkey=script_pubkeys[index],
+            prev_amount=prev_amounts[index],
+            sighash_flags=sighash_flags,
+        )
+        assert shared.preimage(index, script_pubkeys[index], prev_amounts[index]) == expected
+
+        sighash = shared.sighash(index, script_pubkeys[index], prev_amounts[index])
+        signature = sign(sighash, private_keys[index], k=11 + index, sighash_flags=sighash_flags)
+        pubkey = compressed_pubkey(private_keys[index])
+        signatures.append(InputSignature(index, script_pubkeys[index], prev_amounts[index], signature, pubkey))
+
+        # The signature is valid for the script interpreter
+        unlock = Script()
+        unlock.append_pushdata(signature)
+        lock = Script()
+        lock.append_pushdata(pubkey)
+        lock += Script.parse_string("OP_CHECKSIG")
+        context = Context(script=unlock + lock, z=sighash)
+        assert context.evaluate()
+        assert context.get_stack().size() == 1
+
+    assert verify_input_signatures(tx, sighash_flags, signatures)
+
+    # Swapping the public keys invalidates the signatures
+    swapped = [
+        InputSignature(sig.index, sig.script_pubkey, sig.prev_amount, sig.signature, other.pubkey)
+        for sig, other in zip(signatures, signatures[::-1], strict=True)
+    ]
+    assert not verify_input_signatures(tx, sighash_flags, swapped)
+
+    # A signature made with different sighash flags is rejected
+    signatures[1].signature = signatures[1].signature[:-1] + SIGHASH.NONE_FORKID.to_bytes()
+    assert not verify_input_signatures(tx, sighash_flags, signatures)
+
+
+def test_shared_sighash_preimage_single():
+    with pytest.raises(ValueError, match="SIGHASH_SINGLE"):
+        SharedSighashPreimage.from_tx(generate_tx([0, 1]), SIGHASH.SINGLE_FORKID)
@@ -0,0 +1,78 @@
+import pytest
+
+from src.zkscript.util.ark_serialisation import (
+    Endianness,
+    ark_field_element_size,
+    ark_field_elements_to_ints,
+    ark_tower_field_element_to_ints,
+    ints_to_ark_field_elements,
+    ints_to_ark_tower_field_element,
+)
+
+R_BLS12_381 = 0x73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001
+Q_BLS12_381 = 0x1A0111EA397FE69A4B1BA7B6434BACD764774B84F38512BF6730D2A0F6B0F6241EABFFFEB153FFFFB9FEFFFFFFFFAAAB
+
+
+def serialise(elements, modulus):
+    size = ark_field_element_size(modulus)
+    return len(elements).to_bytes(8, "little") + b"".join(el.to_bytes(size, "little") for el in elements)
+
+
+@pytest.mark.parametrize("elements", [[], [0], [1, 2**200, R_BLS12_381 - 1]])
+def test_ark_field_elements_to_ints(elements):
+    assert ark_field_elements_to_ints(serialise(elements, R_BLS12_381), R_BLS12_381) == elements
+    assert ark_field_elements_to_ints(list(serialise(elements, R_BLS12_381)), R_BLS12_381) == elements
+
+
+@pytest.mark.parametrize(
+    ("data", "message"),
+    [
+        (bytes(7), "too short"),
+        (serialise([1, 2], R_BLS12_381)[:-1], "Expected 2 field elements of 32 bytes, got 63 bytes"),
+        (serialise([R_BLS12_381], R_BLS12_381), "The field element at index 0 is not reduced"),
+    ],
+)
+def test_ark_field_elements_to_ints_invalid(data, message):
+    with pytest.raises(ValueError, match=message):
+        ark_field_elements_to_ints(data, R_BLS12_381)
+
+
+@pytest.mark.parametrize("endianness", [Endianness.LITTLE, Endianness.BIG])
+@pytest.mark.parametrize("elements", [[], [0], [1, 2**200, R_BLS12_381 - 1]])
+def test_field_elements_round_trip(elements, endianness):
+    serialised = ints_to_ark_field_elements(elements, R_BLS12_381, endianness)
+    assert ark_field_elements_to_ints(serialised, R_BLS12_381, endianness) == elements
+
+
+def test_field_elements_endianness():
+    serialised_big_endian = bytes.fromhex("0100000000000000") + bytes(31) + bytes.fromhex("01")
+    assert ints_to_ark_field_elements([1], R_BLS12_381) == serialise([1], R_BLS12_381)
+    assert ints_to_ark_field_elements([1], R_BLS12_381, Endianness.BIG) == serialised_big_endian
+    assert ark_field_elements_to_ints(serialised_big_endian, R_BLS12_381) == [2**248]
+
+
+@pytest.mark.parametrize("endianness", [Endianness.LITTLE, Endianness.BIG])
+@pytest.mark.parametrize(
+    "element",
+    [
+        [5],
+        [1, Q_BLS12_381 - 1],
+        [i * 2**300 + 7 for i in range(12)],
+    ],
+)
+def test_tower_field_element_round_trip(element, endianness):
+    serialised = ints_to_ark_tower_field_element(element, Q_BLS12_381, endianness)
+    assert len(serialised) == len(element) * 48
+    assert ark_tower_field_element_to_ints(serialised, Q_BLS12_381, len(element), endianness) == element
+
+
+@pytest.mark.parametrize(
+    ("data", "extension_degree", "message"),
+    [
+        (bytes(95), 2, "Expected 2 coefficients of 48 bytes, got 95 bytes"),
+        (bytes(48) + Q_BLS12_381.to_bytes(48, "little"), 2, "The coefficient at index 1 is not reduced"),
+    ],
+)
+def test_ark_tower_field_element_to_ints_invalid(data, extension_degree, message):
+    with pytest.raises(ValueError, match=message):
+        ark_tower_field_element_to_ints(data, Q_BLS12_381, extension_degree)
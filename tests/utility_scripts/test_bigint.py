@@ -0,0 +1,133 @@
+import pytest
+from tx_engine import Context, Script
+
+from src.zkscript.util.bigint import bigint_add, bigint_le, bigint_lt, bigint_sub, from_limbs, to_limbs
+from src.zkscript.util.utility_scripts import nums_to_script
+
+LIMB_BITS = 32
+N_LIMBS = 4
+LIMB_MAX = 2**LIMB_BITS - 1
+
+
+@pytest.mark.parametrize(
+    ("a", "b"),
+    [
+        # Equal operands
+        (0, 0),
+        (LIMB_MAX, LIMB_MAX),
+        (2**127 + 5, 2**127 + 5),
+        # Operands differing in the least significant limb only
+        (7, 8),
+        (2**96 + 8, 2**96 + 7),
+        # Across limb boundaries
+        (LIMB_MAX, LIMB_MAX + 1),
+        (2**64, 2**64 - 1),
+        (2**96 - 1, 2**96),
+        # A larger least significant limb does not compensate a smaller more significant limb
+        (2**32 + LIMB_MAX, 2**33),
+        (2**128 - 1, 2**127),
+        (0, 2**128 - 1),
+    ],
+)
+@pytest.mark.parametrize("or_equal", [False, True])
+def test_bigint_comparison(a, b, or_equal):
+    unlock = nums_to_script(to_limbs(a, N_LIMBS, LIMB_BITS) + to_limbs(b, N_LIMBS, LIMB_BITS))
+    lock = bigint_le(N_LIMBS) if or_equal else bigint_lt(N_LIMBS)
+    lock += nums_to_script([int(a <= b if or_equal else a < b)])
+    lock += Script.parse_string("OP_NUMEQUAL")
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize(("a", "b"), [(3, 5), (5, 3), (4, 4)])
+def test_bigint_comparison_single_limb(a, b):
+    for lock, expected in [(bigint_lt(1), a < b), (bigint_le(1), a <= b)]:
+        context = Context(script=nums_to_script([a, b]) + lock)
+        assert context.evaluate() == expected
+
+
+def generate_verify(z) -> Script:
+    out = Script()
+    for ix, el in enumerate(z[::-1]):
+        out += nums_to_script([el])
+        if ix != len(z) - 1:
+            out += Script.parse_string("OP_EQUALVERIFY")
+        else:
+            out += Script.parse_string("OP_EQUAL")
+
+    return out
+
+
+@pytest.mark.parametrize(
+    ("a", "b"),
+    [
+        (0, 0),
+        (3, 4),
+        # Carry from the least significant limb to the next one
+        (LIMB_MAX, 1),
+        # Carry across every limb boundary
+        (2**128 - 1, 1),
+        (2**96 - 1, 2**96 - 1),
+        # The sum overflows the limbs: the carry is exposed
+        (2**128 - 1, 2**128 - 1),
+        (2**127, 2**127),
+    ],
+)
+def test_bigint_add(a, b):
+    unlock = nums_to_script(to_limbs(a, N_LIMBS, LIMB_BITS) + to_limbs(b, N_LIMBS, LIMB_BITS))
+    lock = bigint_add(N_LIMBS, LIMB_BITS)
+    lock += generate_verify(to_limbs(a + b, N_LIMBS + 1, LIMB_BITS))
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize(
+    ("a", "b"),
+    [
+        (0, 0),
+        (4, 3),
+        (2**127 + 5, 2**127 + 5),
+        # Borrow from the second limb
+        (2**32, 1),
+        # Borrow across every limb boundary
+        (2**96, 1),
+        (2**128 - 2**96, 2**96 - 1),
+        # a < b: the borrow is exposed
+        (0, 1),
+        (2**64, 2**128 - 1),
+    ],
+)
+def test_bigint_sub(a, b):
+    unlock = nums_to_script(to_limbs(a, N_LIMBS, LIMB_BITS) + to_limbs(b, N_LIMBS, LIMB_BITS))
+    lock = bigint_sub(N_LIMBS, LIMB_BITS)
+    lock += generate_verify([int(a < b), *to_limbs((a - b) % 2**128, N_LIMBS, LIMB_BITS)])
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize("x", [0, 1, LIMB_MAX, 2**64 + 3, 2**128 - 1])
+def test_limbs_round_trip(x):
+    limbs = to_limbs(x, N_LIMBS, LIMB_BITS)
+    assert len(limbs) == N_LIMBS
+    assert all(0 <= limb <= LIMB_MAX for limb in limbs)
+    assert from_limbs(limbs, LIMB_BITS) == x
+
+
+def test_bigint_errors():
+    with pytest.raises(ValueError, match="The integer does not fit in 4 limbs of 32 bits"):
+        to_limbs(2**128, N_LIMBS, LIMB_BITS)
+    with pytest.raises(ValueError, match="The integer does not fit in 4 limbs of 32 bits"):
+        to_limbs(-1, N_LIMBS, LIMB_BITS)
+    with pytest.raises(ValueError, match="The big integers must have at least one limb"):
+        bigint_lt(0)
+    with pytest.raises(ValueError, match="The big integers must have at least one limb"):
+        bigint_add(0, LIMB_BITS)
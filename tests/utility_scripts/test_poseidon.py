@@ -0,0 +1,111 @@
+from random import randint, seed
+
+import pytest
+from tx_engine import Context, Script
+
+from src.zkscript.util.poseidon import Poseidon
+from src.zkscript.util.utility_scripts import nums_to_script
+
+# Scalar field of BLS12-381
+MODULUS = 0x73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001
+
+# Parameters with the shape of `poseidon_parameters_for_test` in ark-crypto-primitives, the round constants and the
+# MDS matrix are generated with `randint`
+seed(42)
+PARAMETERS = {
+    "q": MODULUS,
+    "full_rounds": 8,
+    "partial_rounds": 31,
+    "alpha": 17,
+    "mds": [[randint(0, MODULUS - 1) for _ in range(3)] for _ in range(3)],  # noqa: S311
+    "ark": [[randint(0, MODULUS - 1) for _ in range(3)] for _ in range(39)],  # noqa: S311
+    "rate": 2,
+    "capacity": 1,
+}
+
+
+def ark_poseidon_hash(inputs, q, full_rounds, partial_rounds, alpha, mds, ark, rate, capacity):
+    """Absorb `inputs` and squeeze one field element, as `PoseidonSponge` of ark-crypto-primitives does."""
+    width = rate + capacity
+
+    def permute(state):
+        for round_number in range(full_rounds + partial_rounds):
+            state = [(x + c) % q for x, c in zip(state, ark[round_number])]
+            is_full_round = round_number < full_rounds // 2 or round_number >= full_rounds // 2 + partial_rounds
+            state = [pow(x, alpha, q) if is_full_round or i == 0 else x for i, x in enumerate(state)]
+            state = [sum(mds[i][j] * state[j] for j in range(width)) % q for i in range(width)]
+        return state
+
+    state = [0] * width
+    rate_start_index = 0
+    remaining = list(inputs)
+    while True:
+        if rate_start_index + len(remaining) <= rate:
+            for i, x in enumerate(remaining):
+                state[capacity + rate_start_index + i] = (state[capacity + rate_start_index + i] + x) % q
+            break
+        for i in range(rate - rate_start_index):
+            state[capacity + rate_start_index + i] = (state[capacity + rate_start_index + i] + remaining[i]) % q
+        state = permute(state)
+        remaining = remaining[rate - rate_start_index :]
+        rate_start_index = 0
+
+    return permute(state)[capacity]
+
+
+@pytest.mark.parametrize(
+    "inputs",
+    [
+        [0],
+        [1, 2],
+        [MODULUS - 1, 0, 5],
+        [randint(0, MODULUS - 1) for _ in range(4)],  # noqa: S311
+        [randint(0, MODULUS - 1) for _ in range(5)],  # noqa: S311
+    ],
+)
+def test_poseidon_hash(inputs):
+    poseidon = Poseidon(**PARAMETERS)
+
+    unlock = nums_to_script([MODULUS, *inputs])
+
+    lock = poseidon.poseidon_hash(len(inputs), check_constant=True, clean_constant=True)
+    lock += nums_to_script([ark_poseidon_hash(inputs, **PARAMETERS)])
+    lock += Script.parse_string("OP_EQUAL")
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+def test_poseidon_hash_rejects_wrong_digest():
+    poseidon = Poseidon(**PARAMETERS)
+    inputs = [1, 2]
+
+    unlock = nums_to_script([MODULUS, *inputs])
+
+    lock = poseidon.poseidon_hash(len(inputs), check_constant=True, clean_constant=True)
+    lock += nums_to_script([ark_poseidon_hash([2, 1], **PARAMETERS)])
+    lock += Script.parse_string("OP_EQUALVERIFY")
+
+    context = Context(script=unlock + lock)
+    assert not context.evaluate()
+
+
+@pytest.mark.parametrize(
+    ("parameters", "error"),
+    [
+        ({"full_rounds": 7}, "The number of full rounds must be even"),
+        ({"alpha": 1}, "The exponent of the S-box must be at least 2"),
+        ({"mds": PARAMETERS["mds"][:2]}, "The MDS matrix must be of size 3 x 3"),
+        ({"partial_rounds": 30}, "Expected 38 lists of 3 round constants"),
+    ],
+)
+def test_poseidon_invalid_parameters(parameters, error):
+    with pytest.raises(ValueError, match=error):
+        Poseidon(**{**PARAMETERS, **parameters})
+
+
+def test_poseidon_hash_invalid_n_inputs():
+    with pytest.raises(ValueError, match="The number of inputs must be positive"):
+        Poseidon(**PARAMETERS).poseidon_hash(0)
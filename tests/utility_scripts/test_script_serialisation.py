@@ -0,0 +1,44 @@
+import pytest
+from tx_engine import Script
+
+from src.zkscript.util.script_serialisation import (
+    MAGIC_BYTES,
+    VERSION,
+    load_script,
+    save_script,
+    script_from_bytes,
+    script_to_bytes,
+)
+from src.zkscript.util.utility_scripts import mod, nums_to_script
+
+
+@pytest.mark.parametrize(
+    ("script", "metadata"),
+    [
+        (Script(), None),
+        (nums_to_script([0, -1, 16, 17, 2**255]) + mod(), {"curve": "bls12_381", "modulo_threshold": 1600}),
+        (Script.parse_string("OP_1 OP_IF OP_DUP OP_ELSE OP_DROP OP_ENDIF"), {"note": "conditionals"}),
+    ],
+)
+def test_save_and_load_script(tmp_path, script, metadata):
+    path = tmp_path / "script.zks"
+    save_script(script, path, metadata)
+    loaded_script, loaded_metadata = load_script(path)
+
+    assert loaded_script.to_string() == script.to_string()
+    assert loaded_script.raw_serialize() == script.raw_serialize()
+    assert loaded_metadata == (metadata if metadata is not None else {})
+
+
+@pytest.mark.parametrize(
+    ("data", "message"),
+    [
+        (bytes.fromhex("51"), "The data is not a zkscript script file"),
+        (MAGIC_BYTES + (VERSION + 1).to_bytes(2, "big"), f"Unsupported zkscript script file version: {VERSION + 1}"),
+        (script_to_bytes(Script.parse_string("OP_1 OP_2"))[:-1], "The zkscript script file is truncated"),
+        (script_to_bytes(Script.parse_string("OP_1 OP_2")) + b"\x00", "1 unexpected trailing bytes"),
+    ],
+)
+def test_load_invalid_script(data, message):
+    with pytest.raises(ValueError, match=message):
+        script_from_bytes(data)
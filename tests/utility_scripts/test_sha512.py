@@ -0,0 +1,48 @@
+import hashlib
+
+import pytest
+from tx_engine import Context, Script
+
+from src.zkscript.util.sha512 import SHA512_INITIAL_STATE, SHA512_ROUND_CONSTANTS, sha512, sha512_padding
+
+
+def test_sha512_constants():
+    assert SHA512_INITIAL_STATE[0] == 0x6A09E667F3BCC908
+    assert SHA512_INITIAL_STATE[7] == 0x5BE0CD19137E2179
+    assert SHA512_ROUND_CONSTANTS[0] == 0x428A2F98D728AE22
+    assert SHA512_ROUND_CONSTANTS[79] == 0x6C44198C4A475817
+
+
+@pytest.mark.parametrize("message_length", [0, 1, 64, 111, 112, 128, 200])
+def test_sha512_padding(message_length):
+    assert (message_length + len(sha512_padding(message_length))) % 128 == 0
+
+
+@pytest.mark.parametrize(
+    "message",
+    [
+        b"",
+        b"abc",
+        bytes(range(64)),
+        bytes(range(111)),
+        bytes(range(112)),
+        bytes(range(200)),
+    ],
+)
+def test_sha512(message):
+    unlock = Script()
+    unlock.append_pushdata(message)
+
+    lock = sha512(len(message))
+    lock.append_pushdata(hashlib.sha512(message).digest())
+    lock += Script.parse_string("OP_EQUAL")
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+def test_sha512_errors():
+    with pytest.raises(ValueError, match="The message length must be non-negative"):
+        sha512(-1)
@@ -0,0 +1,83 @@
+import pytest
+from tx_engine import Script, Tx, TxIn
+
+from src.zkscript.util.timelocks import (
+    SEQUENCE_FINAL,
+    SEQUENCE_TYPE_FLAG,
+    check_locktime_verify,
+    check_sequence_verify,
+    check_timelocks,
+    prepend_timelocks,
+    timelock_guards,
+)
+from src.zkscript.util.utility_scripts import nums_to_script
+
+prev_txid = "00" * 32
+
+# Stand-in for a generated verifier: checks that the unlocking script pushes 3
+verifier = nums_to_script([3]) + Script.parse_string("OP_EQUAL")
+
+
+def spending_tx(sequence: int, locktime: int = 0, version: int = 2) -> Tx:
+    tx_in = TxIn(prev_tx=prev_txid, prev_index=0, sequence=sequence)
+    return Tx(version=version, tx_ins=[tx_in], tx_outs=[], locktime=locktime)
+
+
+@pytest.mark.parametrize(
+    ("locktime", "sequence", "expected"),
+    [
+        (None, None, []),
+        (100, None, [("locktime", 100)]),
+        (None, 10, [("sequence", 10)]),
+        (600_000_000, SEQUENCE_TYPE_FLAG | 5, [("locktime", 600_000_000), ("sequence", SEQUENCE_TYPE_FLAG | 5)]),
+    ],
+)
+def test_prepend_timelocks(locktime, sequence, expected):
+    lock = prepend_timelocks(verifier, locktime=locktime, sequence=sequence)
+    assert timelock_guards(lock) == expected
+
+
+@pytest.mark.parametrize(
+    ("required_sequence", "tx_sequence", "version", "is_valid"),
+    [
+        (10, 10, 2, True),
+        (10, 11, 2, True),
+        (10, 9, 2, False),
+        (10, 10, 1, False),
+        (10, SEQUENCE_FINAL, 2, False),
+        (SEQUENCE_TYPE_FLAG | 10, 10, 2, False),
+        (SEQUENCE_TYPE_FLAG | 10, SEQUENCE_TYPE_FLAG | 10, 2, True),
+    ],
+)
+def test_check_sequence_verify(required_sequence, tx_sequence, version, is_valid):
+    lock = prepend_timelocks(verifier, sequence=required_sequence)
+    assert check_timelocks(lock, spending_tx(tx_sequence, version=version), 0) == is_valid
+
+
+@pytest.mark.parametrize(
+    ("required_locktime", "tx_locktime", "tx_sequence", "is_valid"),
+    [
+        (100, 100, 0, True),
+        (100, 99, 0, False),
+        (100, 100, SEQUENCE_FINAL, False),
+        (100, 600_000_000, 0, False),
+        (600_000_000, 600_000_001, 0, True),
+    ],
+)
+def test_check_locktime_verify(required_locktime, tx_locktime, tx_sequence, is_valid):
+    lock = prepend_timelocks(verifier, locktime=required_locktime)
+    assert check_timelocks(lock, spending_tx(tx_sequence, locktime=tx_locktime), 0) == is_valid
+
+
+@pytest.mark.parametrize(
+    ("function", "value"),
+    [
+        (check_locktime_verify, -1),
+        (check_locktime_verify, 2**32),
+        (check_sequence_verify, -1),
+        (check_sequence_verify, 2**31),
+    ],
+)
+def test_timelock_out_of_range(function, value):
+    with pytest.raises(ValueError, match="must be in"):
+        function(value)
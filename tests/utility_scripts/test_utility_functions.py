@@ -1,7 +1,16 @@
 import pytest
 from tx_engine import Script
 
-from src.zkscript.util.utility_functions import bitmask_to_boolean_list, boolean_list_to_bitmask, optimise_script
+from src.zkscript.util.utility_functions import (
+    bitmask_to_boolean_list,
+    boolean_list_to_bitmask,
+    check_stack_limits,
+    diff_scripts,
+    naf_expansion,
+    optimise_labelled_scripts,
+    optimise_script,
+    sliding_window_expansion,
+)
 
 
 @pytest.mark.parametrize(
@@ -31,6 +40,27 @@ def test_optimise_script(script, expected):
     assert optimised_script.to_string().split() == expected
 
 
+@pytest.mark.parametrize(
+    ("regions", "expected", "expected_labels"),
+    [
+        ([("OP_1 OP_2", [(0, "a"), (1, "b")]), ("OP_3", [(0, "c")])], "OP_1 OP_2 OP_3", [(0, "a"), (1, "b"), (2, "c")]),
+        # The simplification across the label moves it back to the first simplified operation
+        ([("OP_1 OP_TOALTSTACK", []), ("OP_FROMALTSTACK OP_2", [(0, "a")])], "OP_1 OP_2", [(1, "a")]),
+        ([("OP_1 OP_SWAP", []), ("OP_ADD OP_2", [(0, "a")])], "OP_1 OP_ADD OP_2", [(1, "a")]),
+        # The labels are given in bytes
+        ([("0x0102 OP_ROT", []), ("OP_ROT OP_ROT 0x03", [(2, "a")])], "0x0102 0x03", [(3, "a")]),
+        ([("OP_1", [(1, "end")])], "OP_1", [(1, "end")]),
+    ],
+)
+def test_optimise_labelled_scripts(regions, expected, expected_labels):
+    regions = [(Script.parse_string(script), labels) for script, labels in regions]
+    script, labels = optimise_labelled_scripts(regions)
+
+    assert script.to_string() == Script.parse_string(expected).to_string()
+    assert script.raw_serialize() == optimise_script(sum((script for script, _ in regions), Script())).raw_serialize()
+    assert labels == expected_labels
+
+
 @pytest.mark.parametrize(
     ("function", "inputs", "expected"),
     [
@@ -45,3 +75,103 @@ def test_optimise_script(script, expected):
 )
 def test_bitmask_to_boolean_list_and_reverse(function, inputs, expected):
     assert function(**inputs) == expected
+
+
+@pytest.mark.parametrize(
+    ("script", "max_elements", "max_element_bytes", "expected"),
+    [
+        ("OP_1 OP_2 OP_ADD", 2, 520, []),
+        ("0x" + "01" * 521, 10, 520, ["Position 0 (0x010101010101010...): element of 521 bytes"]),
+        ("OP_1 OP_2 OP_3", 2, 520, ["Position 2 (OP_3): 3 elements on the stack and altstack"]),
+        ("OP_1 OP_TOALTSTACK OP_2 OP_3", 2, 520, ["Position 3 (OP_3): 3 elements on the stack and altstack"]),
+        ("0x" + "01" * 300 + " OP_DUP OP_MUL", 10, 520, ["Position 2 (OP_MUL): element of 600 bytes"]),
+        ("0x" + "01" * 300 + " OP_DUP OP_MUL OP_DUP OP_SHA256 OP_MOD", 10, 600, []),
+        (
+            "0x" + "01" * 300 + " OP_DUP OP_DEPTH OP_1SUB OP_PICK OP_CAT",
+            10,
+            520,
+            ["Position 5 (OP_CAT): element of 600 bytes"],
+        ),
+        ("OP_1 OP_IF OP_1 OP_2 OP_ELSE OP_1 OP_ENDIF", 1, 520, ["Position 3 (OP_2)", "Position 6 (OP_ENDIF)"]),
+        ("OP_0 OP_IF 0x" + "01" * 521 + " OP_ENDIF", 10, 520, ["Position 2 (0x010101010101010...)"]),
+    ],
+)
+def test_check_stack_limits(script, max_elements, max_element_bytes, expected):
+    violations = check_stack_limits(Script.parse_string(script), max_elements, max_element_bytes)
+
+    assert len(violations) == len(expected)
+    for violation, prefix in zip(violations, expected, strict=True):
+        assert violation.startswith(prefix)
+
+
+def test_check_stack_limits_unknown_elements():
+    script = Script.parse_string("OP_DUP OP_MUL")
+
+    assert check_stack_limits(script, 10, 520) == []
+    assert check_stack_limits(script, 10, 520, initial_stack=[300]) != []
+    assert check_stack_limits(script, 10, 520, unknown_element_bytes=300) != []
+    assert len(check_stack_limits(Script.parse_string("OP_1 " * 10), 1, 0, max_violations=2)) == 2
+
+
+@pytest.mark.parametrize(
+    ("script", "message"),
+    [
+        ("OP_1 OP_CHECKMULTISIG", "Unsupported opcode OP_CHECKMULTISIG at position 1"),
+        ("OP_1 OP_IF OP_1", "Unbalanced OP_IF/OP_NOTIF"),
+        ("OP_1 OP_ENDIF", "Unbalanced OP_ENDIF at position 1"),
+    ],
+)
+def test_check_stack_limits_errors(script, message):
+    with pytest.raises(ValueError, match=message):
+        check_stack_limits(Script.parse_string(script), 10, 520)
+
+
+@pytest.mark.parametrize(
+    "n",
+    [0, 1, -1, 7, -7, 0xD201000000010000, -0xD201000000010000, 2**753 - 1, 3**200],
+)
+def test_naf_expansion(n):
+    expansion = naf_expansion(n)
+
+    assert sum(digit * 2**i for i, digit in enumerate(expansion)) == n
+    assert all(digit in {-1, 0, 1} for digit in expansion)
+    assert all(expansion[i] == 0 or expansion[i + 1] == 0 for i in range(len(expansion) - 1))
+    assert sum(digit != 0 for digit in expansion) <= bin(abs(n)).count("1")
+
+
+@pytest.mark.parametrize("n", [1, 5, 22, 2**255 - 21, 3**200])
+@pytest.mark.parametrize("window_size", [1, 2, 4, 6])
+def test_sliding_window_expansion(n, window_size):
+    expansion = sliding_window_expansion(n, window_size)
+
+    value = 0
+    for n_squarings, digit in expansion:
+        value = value * 2**n_squarings + digit
+    assert value == n
+    assert all(digit == 0 or (digit % 2 == 1 and digit < 2**window_size) for _, digit in expansion)
+    assert expansion[0][1] != 0
+
+
+
+@pytest.mark.parametrize("position", [0, 3, 7])
+def test_diff_scripts(position):
+    ops = ["OP_1", "OP_2", "OP_ADD", "OP_DUP", "OP_MUL", "OP_3", "OP_SUB"]
+    a = Script.parse_string(" ".join(ops))
+    b = Script.parse_string(" ".join([*ops[:position], "OP_NOP", *ops[position:]]))
+
+    index, context_a, context_b = diff_scripts(a, b, context=1)
+
+    assert index == position
+    assert context_a == ops[max(position - 1, 0) : position + 2]
+    assert context_b == [*ops[max(position - 1, 0) : position], "OP_NOP", *ops[position : position + 1]]
+    assert diff_scripts(b, a, context=1)[0] == position
+
+
+def test_diff_scripts_identical():
+    script = Script.parse_string("OP_1 OP_2 OP_ADD")
+    assert diff_scripts(script, Script.parse_string("OP_1 OP_2 OP_ADD")) is None
+
+
+def test_diff_scripts_errors():
+    with pytest.raises(ValueError, match="The context must be non-negative"):
+        diff_scripts(Script(), Script(), context=-1)
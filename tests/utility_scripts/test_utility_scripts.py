@@ -1,6 +1,6 @@
 import pytest
 from tx_engine import Context, Script, encode_num
-from tx_engine.engine.util import GROUP_ORDER_INT
+from tx_engine.engine.util import GROUP_ORDER_INT, PRIME_INT
 
 from src.zkscript.script_types.stack_elements import StackBaseElement, StackFiniteFieldElement, StackNumber
 from src.zkscript.util.utility_functions import boolean_list_to_bitmask
@@ -12,6 +12,7 @@ from src.zkscript.util.utility_scripts import (
     move,
     nums_to_script,
     pick,
+    pow_fixed_exponent,
     reverse_endianness_bounded_length,
     reverse_endianness_fixed_length,
     roll,
@@ -511,3 +512,55 @@ def test_unsigned_from_bits(stack, elements_positions, rolling_option, expected)
     context = Context(unlock + lock)
     assert context.evaluate()
     assert context.get_stack().size() == 1
+
+
+@pytest.mark.parametrize("modulus", [PRIME_INT, GROUP_ORDER_INT, 2**255 - 19])
+@pytest.mark.parametrize("a", [1, 2, 123456789, -987654321])
+@pytest.mark.parametrize("window_size", [None, 1, 4])
+@pytest.mark.parametrize("clean_constant", [True, False])
+def test_pow_fixed_exponent_inverse(modulus, a, window_size, clean_constant):
+    expected = pow(a, -1, modulus)
+    assert expected == pow(a, modulus - 2, modulus)
+
+    unlock = nums_to_script([modulus, a])
+    lock = pow_fixed_exponent(exp=modulus - 2, clean_constant=clean_constant, window_size=window_size)
+    lock += nums_to_script([expected])
+    lock += Script.parse_string("OP_EQUALVERIFY")
+    lock += Script.parse_string("OP_DROP OP_1" if not clean_constant else "OP_1")
+
+    context = Context(unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize("a", [1, 4, 123456789**2, (2**200 + 7) ** 2])
+@pytest.mark.parametrize("is_positive", [True, False])
+def test_pow_fixed_exponent_square_root(a, is_positive):
+    # PRIME_INT = 3 mod 4, so a^((PRIME_INT + 1)/4) is a square root of the quadratic residue a
+    expected = pow(a, (PRIME_INT + 1) // 4, PRIME_INT)
+    assert expected**2 % PRIME_INT == a % PRIME_INT
+
+    unlock = nums_to_script([PRIME_INT, a])
+    lock = pow_fixed_exponent(exp=(PRIME_INT + 1) // 4, clean_constant=True, is_positive=is_positive)
+    lock += nums_to_script([expected])
+    lock += Script.parse_string("OP_EQUAL")
+
+    context = Context(unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize(
+    ("exp", "window_size", "msg"),
+    [
+        (0, None, "The exponent must be positive"),
+        (-3, None, "The exponent must be positive"),
+        (5, 0, "The window size must be positive"),
+    ],
+)
+def test_errors_pow_fixed_exponent(exp, window_size, msg):
+    with pytest.raises(ValueError, match=msg):
+        pow_fixed_exponent(exp=exp, window_size=window_size)
+
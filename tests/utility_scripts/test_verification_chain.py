@@ -0,0 +1,100 @@
+import pytest
+from tx_engine import Context, Script
+
+from src.zkscript.util.utility_scripts import nums_to_script
+from src.zkscript.util.verification_chain import (
+    chain_stage,
+    commit_to_state,
+    state_commitment,
+    verify_state_commitment,
+)
+
+
+@pytest.mark.parametrize(
+    "state",
+    [
+        [0],
+        [1, 2, 3],
+        [-5, 0, 17, 2**300, -(2**255)],
+    ],
+)
+def test_commit_to_state(state):
+    unlock = nums_to_script(state)
+
+    lock = commit_to_state(len(state))
+    lock.append_pushdata(state_commitment(state))
+    lock += Script.parse_string("OP_EQUAL")
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate()
+    assert context.get_stack().size() == 1
+    assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize(
+    ("state", "committed_state", "is_valid"),
+    [
+        ([1, 2, 3], [1, 2, 3], True),
+        ([1, 2, 3], [1, 2, 4], False),
+        ([1, 2, 3], [2, 1, 3], False),
+        ([7, 1, 2, 3], [1, 2, 3], True),
+    ],
+)
+def test_verify_state_commitment(state, committed_state, is_valid):
+    unlock = nums_to_script(state)
+
+    lock = verify_state_commitment(len(committed_state), state_commitment(committed_state))
+    lock += Script.parse_string(" ".join(["OP_DROP"] * (len(state) - 1)))
+
+    context = Context(script=unlock + lock)
+    assert context.evaluate() == is_valid
+    if is_valid:
+        assert context.get_stack().size() == 1
+
+
+@pytest.mark.parametrize(
+    ("inputs", "is_valid"),
+    [
+        ([2, 3], True),
+        ([2, 4], False),
+    ],
+)
+def test_chain_stage_first_stage(inputs, is_valid):
+    # [a, b] -> [a + b, a + b]
+    lock = chain_stage(script=Script.parse_string("OP_ADD OP_DUP"), n_elements_out=2)
+    lock.append_pushdata(state_commitment([5, 5]))
+    lock += Script.parse_string("OP_EQUALVERIFY OP_1")
+
+    context = Context(script=nums_to_script(inputs) + lock)
+    assert context.evaluate() == is_valid
+    if is_valid:
+        assert context.get_stack().size() == 1
+        assert context.get_altstack().size() == 0
+
+
+@pytest.mark.parametrize(
+    ("supplied_state", "is_valid"),
+    [
+        ([5, 5], True),
+        ([5, 6], False),
+        ([5, 5, 5], False),
+        ([5], False),
+    ],
+)
+def test_chain_stage_next_stage(supplied_state, is_valid):
+    # [x, y] -> x == y
+    lock = chain_stage(
+        script=Script.parse_string("OP_EQUAL"), n_elements_in=2, input_commitment=state_commitment([5, 5])
+    )
+
+    context = Context(script=nums_to_script(supplied_state) + lock)
+    assert context.evaluate() == is_valid
+
+
+def test_chain_stage_errors():
+    with pytest.raises(ValueError, match="The input commitment must be given if and only if"):
+        chain_stage(script=Script(), n_elements_in=2)
+    with pytest.raises(ValueError, match="The input commitment must be given if and only if"):
+        chain_stage(script=Script(), input_commitment=state_commitment([1]))
+    with pytest.raises(ValueError, match="The state must contain at least one element"):
+        commit_to_state(0)
@@ -1,7 +1,7 @@
 use clap::Parser;
 
 /// CLI of the application
-/// It can be run in either `setup`, `process`, `prove`, `verify` mode
+/// It can be run in either `setup`, `process`, `prove`, `verify`, `fingerprint`, `export-vk` mode
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub(crate) struct Cli {
@@ -21,7 +21,35 @@ pub(crate) struct Cli {
     #[arg(short, long)]
     pub verify: bool,
 
+    // In verification mode, reject the proof if its genesis txid is not this one
+    #[arg(long, requires = "verify")]
+    pub expected_genesis: Option<String>,
+
+    // Print the fingerprint of the verifying key
+    #[arg(long)]
+    pub fingerprint: bool,
+
+    // Export the keys required to verify (without the proving keys) to the directory, to distribute them to verifiers
+    #[arg(long, value_name = "DIR")]
+    pub export_vk: Option<String>,
+
+    // In proving mode, prove every `*.toml` proving data file in the directory, loading the proving key once
+    #[arg(long, requires = "prove", conflicts_with = "file")]
+    pub batch: Option<String>,
+
+    // In batch proving mode, the number of proofs generated in parallel
+    #[arg(long, requires = "batch", default_value_t = 1)]
+    pub jobs: usize,
+
+    // In proving mode, the maximum number of proofs in the chain (overrides `max_depth` in the proving data)
+    #[arg(long, requires = "prove")]
+    pub max_depth: Option<u32>,
+
+    // In proving mode, overwrite the proofs already saved with the same name
+    #[arg(long, requires = "prove")]
+    pub force: bool,
+
     // File path
-    #[arg(short, long)]
-    pub file: String,
+    #[arg(short, long, required_unless_present_any = ["fingerprint", "batch", "export_vk"])]
+    pub file: Option<String>,
 }
@@ -1,10 +1,11 @@
-use anyhow::{Result, anyhow};
 use std::fs;
 
 use chain_gang::{messages::OutPoint, util::Hash256};
 use serde::Deserialize;
 use transaction_chain_proof::snarks::universal_tcp_snark::UniversalTransactionChainProofPublicInput;
 
+use crate::error::TokenError;
+
 /// Data required to generate a Transaction Chain Proof
 #[derive(Clone, Deserialize)]
 pub struct ProvingData {
@@ -18,6 +19,9 @@ pub struct ProvingData {
 #[derive(Clone, Deserialize)]
 pub struct ChainParameters {
     pub chain_index: u32,
+    /// If set, the maximum number of proofs in the chain, genesis proof included
+    #[serde(default)]
+    pub max_depth: Option<u32>,
 }
 
 /// Public inputs of the proof
@@ -35,11 +39,15 @@ pub struct Witness {
 }
 
 impl ProvingData {
-    pub fn load(file_path: String) -> Result<Self> {
-        let file_data = fs::read_to_string(file_path)
-            .map_err(|e| anyhow!("Failed to read proving data. Error: {}", e))?;
-        toml::from_str::<ProvingData>(&file_data)
-            .map_err(|e| anyhow!("Failed to parse proving data. Error {}", e))
+    pub fn load(file_path: String) -> Result<Self, TokenError> {
+        let file_data = fs::read_to_string(file_path).map_err(|e| TokenError::DataLoad {
+            name: "proving data".to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str::<ProvingData>(&file_data).map_err(|e| TokenError::DataLoad {
+            name: "proving data".to_string(),
+            reason: e.to_string(),
+        })
     }
 }
 
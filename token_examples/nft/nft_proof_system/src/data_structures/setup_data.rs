@@ -1,19 +1,28 @@
 use std::fs;
 
-use anyhow::{Result, anyhow};
 use serde::Deserialize;
 
+use crate::error::TokenError;
+use crate::util::KeyFormat;
+
 /// Data required to setup a Ttransaction Chain Proof circuit
 #[derive(Clone, Deserialize)]
 pub struct SetupData {
     pub chain_index: u32,
+    /// Serialisation format of the proving keys: "uncompressed" (default) or "compressed"
+    #[serde(default)]
+    pub key_format: KeyFormat,
 }
 
 impl SetupData {
-    pub fn load(file_path: String) -> Result<Self> {
-        let file_data = fs::read_to_string(file_path)
-            .map_err(|e| anyhow!("Failed to read setup data. Error: {}", e))?;
-        toml::from_str::<SetupData>(&file_data)
-            .map_err(|e| anyhow!("Failed to parse setup data. Error: {}", e))
+    pub fn load(file_path: String) -> Result<Self, TokenError> {
+        let file_data = fs::read_to_string(file_path).map_err(|e| TokenError::DataLoad {
+            name: "setup data".to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str::<SetupData>(&file_data).map_err(|e| TokenError::DataLoad {
+            name: "setup data".to_string(),
+            reason: e.to_string(),
+        })
     }
 }
@@ -1,16 +1,20 @@
 use std::fs;
 
-use anyhow::{Result, anyhow};
 use chain_gang::{messages::OutPoint, util::Hash256};
 use serde::Deserialize;
 use transaction_chain_proof::snarks::universal_tcp_snark::UniversalTransactionChainProofPublicInput;
 
+use crate::error::TokenError;
+
 /// Data required to verify a Transaction Chain Proof
 #[derive(Deserialize)]
 pub struct VerifyingData {
     pub chain_parameters: ChainParameters,
     pub public_inputs: PublicInputs,
     pub proof_path: String,
+    /// If set, the verification fails when the genesis txid of the proof is not equal to this one
+    #[serde(default)]
+    pub expected_genesis_txid: Option<String>,
 }
 
 /// Parameters of the Transaction Chain
@@ -27,11 +31,38 @@ pub struct PublicInputs {
 }
 
 impl VerifyingData {
-    pub fn load(file_path: String) -> Result<Self> {
-        let file_data = fs::read_to_string(file_path)
-            .map_err(|e| anyhow!("Failed to read verifying data. Error: {}", e))?;
-        toml::from_str::<VerifyingData>(&file_data)
-            .map_err(|e| anyhow!("Failed to parse verifying data. Error: {}", e))
+    pub fn load(file_path: String) -> Result<Self, TokenError> {
+        let file_data = fs::read_to_string(file_path).map_err(|e| TokenError::DataLoad {
+            name: "verifying data".to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str::<VerifyingData>(&file_data).map_err(|e| TokenError::DataLoad {
+            name: "verifying data".to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Check that the genesis txid of the public inputs is equal to `expected_genesis_txid` (if set), so that a valid
+    /// proof for a token of a different lineage is not accepted
+    pub fn check_genesis(&self) -> Result<(), TokenError> {
+        let Some(expected_genesis_txid) = &self.expected_genesis_txid else {
+            return Ok(());
+        };
+        let decode = |name: &str, txid: &str| {
+            Hash256::decode(txid).map_err(|e| TokenError::DataLoad {
+                name: name.to_string(),
+                reason: e.to_string(),
+            })
+        };
+        if decode("expected genesis txid", expected_genesis_txid)?
+            != decode("genesis txid", &self.public_inputs.genesis_txid)?
+        {
+            return Err(TokenError::GenesisMismatch {
+                expected: expected_genesis_txid.clone(),
+                found: self.public_inputs.genesis_txid.clone(),
+            });
+        }
+        Ok(())
     }
 }
 
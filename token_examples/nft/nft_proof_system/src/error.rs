@@ -0,0 +1,43 @@
+use thiserror::Error;
+
+/// Errors returned by the NFT application
+/// Each variant carries the description of the underlying error, so that callers can match on the kind of failure
+#[derive(Debug, Error)]
+pub enum TokenError {
+    /// Failed to read or parse the data (setup, proving or verifying data) passed to the application
+    #[error("Failed to load {name}. Error: {reason}")]
+    DataLoad { name: String, reason: String },
+    /// Failed to read or deserialize a key
+    #[error("Failed to load {key}. Error: {reason}")]
+    KeyLoad { key: String, reason: String },
+    /// Failed to save a key, a proof or a processed input
+    #[error("Failed to save {name}. Error: {reason}")]
+    Save { name: String, reason: String },
+    /// Failed to read or deserialize a proof
+    #[error("Failed to load {name}. Error: {reason}")]
+    ProofDeserialize { name: String, reason: String },
+    /// Failed to decode or parse a transaction
+    #[error("Failed to parse {name}. Error: {reason}")]
+    TxParse { name: String, reason: String },
+    /// Failed to generate the keys
+    #[error("Failed to perform the setup. Error: {0}")]
+    Setup(String),
+    /// Failed to process the public input
+    #[error("Failed to process the public input. Error: {0}")]
+    InputProcessing(String),
+    /// The name of the proof is already used by a saved proof or by another proof of the batch
+    #[error("The proof name {name} is already in use: {reason}")]
+    ProofNameCollision { name: String, reason: String },
+    /// The genesis txid of the proof is not the one expected by the verifier
+    #[error("The proof is for the genesis txid {found}, expected {expected}")]
+    GenesisMismatch { expected: String, found: String },
+    /// The proof would make the chain deeper than the maximum depth
+    #[error("The chain would have depth {depth}, exceeding the maximum depth {max_depth}")]
+    MaxDepthExceeded { depth: u32, max_depth: u32 },
+    /// Failed to generate a proof
+    #[error("Failed to generate the proof. Error: {0}")]
+    Proving(String),
+    /// The verification algorithm failed (note: an invalid proof is not an error)
+    #[error("Failed to verify the proof. Error: {0}")]
+    Verification(String),
+}
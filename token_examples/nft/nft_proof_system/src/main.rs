@@ -1,34 +1,84 @@
+use std::sync::Arc;
+
 use clap::Parser;
 use cli::Cli;
+use tracing_subscriber::EnvFilter;
 
-pub mod cli;
-pub mod data_structures;
-pub mod nft;
-pub mod util;
-
-use data_structures::{
+use nft_proof_system::data_structures::{
     proving_data::ProvingData, setup_data::SetupData, verifying_data::VerifyingData,
 };
-use nft::{NFT, groth16_nft::UniversalTCPSnark};
+use nft_proof_system::nft::{NFT, ProofNameGuard, groth16_nft::UniversalTCPSnark, prove_batch};
+
+mod cli;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
+    // Logs (spans and events of setup, prove and verify) are written to stderr, filtered by `RUST_LOG`
+    // (e.g., `RUST_LOG=info`)
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
     let cli = Cli::parse();
+    // The file is required in every mode except `fingerprint`, `export-vk` and batch proving
+    let file = cli.file.clone().unwrap_or_default();
 
     if cli.setup {
-        let setup_data = SetupData::load(cli.file).unwrap();
-        <UniversalTCPSnark as NFT>::setup(setup_data).unwrap();
+        let setup_data = SetupData::load(file)?;
+        <UniversalTCPSnark as NFT>::setup(setup_data)?;
     } else if cli.process {
-        let proving_data = ProvingData::load(cli.file).unwrap();
-        <UniversalTCPSnark as NFT>::process_input(proving_data).unwrap();
+        let proving_data = ProvingData::load(file)?;
+        <UniversalTCPSnark as NFT>::process_input(proving_data)?;
+    } else if let (true, Some(dir)) = (cli.prove, &cli.batch) {
+        // The proving key is loaded once and shared by the threads generating the proofs
+        let pk = Arc::new(<UniversalTCPSnark as NFT>::load_pk()?);
+        let guard = ProofNameGuard::new(<UniversalTCPSnark as NFT>::PROOFS_PATH, cli.force);
+        let outcomes = prove_batch(dir, cli.jobs, |mut proving_data| {
+            guard.claim(&proving_data)?;
+            if cli.max_depth.is_some() {
+                proving_data.chain_parameters.max_depth = cli.max_depth;
+            }
+            <UniversalTCPSnark as NFT>::prove_with_pk(&pk, proving_data)
+        })?;
+
+        println!();
+        for (file_path, outcome) in &outcomes {
+            match outcome {
+                Ok(()) => println!("{}: proof generated", file_path),
+                Err(e) => println!("{}: failed. {}", file_path, e),
+            }
+        }
+        let n_failed = outcomes.iter().filter(|(_, outcome)| outcome.is_err()).count();
+        println!("\n{} of {} proofs generated.\n", outcomes.len() - n_failed, outcomes.len());
+        if n_failed != 0 {
+            std::process::exit(1);
+        }
     } else if cli.prove {
-        let proving_data = ProvingData::load(cli.file).unwrap();
-        <UniversalTCPSnark as NFT>::prove(proving_data).unwrap();
+        let mut proving_data = ProvingData::load(file)?;
+        if cli.max_depth.is_some() {
+            proving_data.chain_parameters.max_depth = cli.max_depth;
+        }
+        ProofNameGuard::new(<UniversalTCPSnark as NFT>::PROOFS_PATH, cli.force).claim(&proving_data)?;
+        <UniversalTCPSnark as NFT>::prove(proving_data)?;
     } else if cli.verify {
-        let verifying_data = VerifyingData::load(cli.file).unwrap();
+        let mut verifying_data = VerifyingData::load(file)?;
+        if cli.expected_genesis.is_some() {
+            verifying_data.expected_genesis_txid = cli.expected_genesis.clone();
+        }
         assert!(
-            <UniversalTCPSnark as NFT>::verify(verifying_data).unwrap(),
+            <UniversalTCPSnark as NFT>::verify(verifying_data)?,
             "\nProof not valid.\n"
         );
         println!("\nValid proof.\n")
+    } else if cli.fingerprint {
+        let vk = <UniversalTCPSnark as NFT>::load_vk()?;
+        println!(
+            "\nVerifying key fingerprint: {}\n",
+            <UniversalTCPSnark as NFT>::vk_fingerprint(&vk)
+        );
+    } else if let Some(export_path) = &cli.export_vk {
+        <UniversalTCPSnark as NFT>::export_vk(<UniversalTCPSnark as NFT>::KEYS_PATH, export_path)?;
+        println!("\nVerifying key exported to {}\n", export_path);
     }
+
+    Ok(())
 }
@@ -1,5 +1,5 @@
-use anyhow::{Result, anyhow};
 use std::io::Cursor;
+use std::path::Path;
 
 use ark_mnt4_753::{
     Fq as ScalarFieldMNT6, Fr as ScalarFieldMNT4, g1::Parameters as ShortWeierstrassParameters,
@@ -11,6 +11,7 @@ use ark_pcd::variable_length_crh::pedersen::VariableLengthPedersenParameters;
 
 use ark_groth16::{
     Groth16, PreparedVerifyingKey, ProvingKey, VerifyingKey, constraints::Groth16VerifierGadget,
+    prepare_verifying_key, verify_proof,
 };
 use ark_mnt4_753::{MNT4_753, constraints::PairingVar as MNT4PairingVar};
 use ark_mnt6_753::{MNT6_753, constraints::PairingVar as MNT6PairingVar};
@@ -20,6 +21,7 @@ use bitcoin_r1cs::constraints::tx::TxVarConfig;
 use chain_gang::messages::Tx;
 use chain_gang::util::Serializable;
 use rand_chacha::ChaChaRng;
+use tracing::{info, instrument};
 
 use transaction_chain_proof::predicates::universal_tcp::UniversalTransactionChainProofPredicate;
 use transaction_chain_proof::snarks::universal_tcp_snark::{
@@ -27,12 +29,17 @@ use transaction_chain_proof::snarks::universal_tcp_snark::{
     UniversalTransactionChainProofSNARK, UniversalTransactionChainProofWitness,
 };
 
+use crate::data_structures::proof_bundle::ProofBundle;
 use crate::data_structures::proving_data::ProvingData;
 use crate::data_structures::setup_data::SetupData;
 use crate::data_structures::verifying_data::VerifyingData;
+use crate::error::TokenError;
 
-use crate::nft::NFT;
-use crate::util::{data_to_serialisation, read_from_file, save_to_file};
+use crate::nft::{NFT, chain_depth};
+use crate::util::{
+    data_to_serialisation, fingerprint, key_from_serialisation, key_to_serialisation, read_from_file,
+    save_to_file,
+};
 
 /// PCD with Groth16 as MainSNARK and HelpSNARK
 /// Over the MNT4_753 - MNT6_753 cycle, with MNT4_753 as the HelpSNARK curve
@@ -53,7 +60,7 @@ type PCD = ECCyclePCD<ScalarFieldMNT6, ScalarFieldMNT4, PCDGroth16Mnt4>;
 /// Unlocking scripts are <Sig> w/ r.len = 33, s.len = 32
 /// Outputs are P2PK
 #[derive(Clone)]
-pub(crate) struct Config;
+pub struct Config;
 impl TxVarConfig for Config {
     const N_INPUTS: usize = 2;
     const N_OUTPUTS: usize = 2;
@@ -63,9 +70,17 @@ impl TxVarConfig for Config {
     const PRE_SIGHASH_N_INPUT: Option<usize> = None;
 }
 
-pub(crate) type UniversalTCPSnark =
+pub type UniversalTCPSnark =
     UniversalTransactionChainProofSNARK<ScalarFieldMNT6, Config, PCD, ChaChaRng>;
 
+/// Verifying key of the NFT with the verifying key of the help circuit prepared
+/// A TCP proof is a proof for the help circuit, so verifying it with the prepared key skips the pairing
+/// precomputations done at every call to `verify`
+pub struct PreparedNFTVerifyingKey {
+    pub vk: <UniversalTCPSnark as UniversalTransactionChainProofData>::VerifyingKey,
+    pub help_pvk: PreparedVerifyingKey<MNT4_753>,
+}
+
 impl NFT for UniversalTCPSnark {
     type ProvingKeyMainCircuit = ProvingKey<MNT6_753>;
     type ProvingKeyHelpCircuit = ProvingKey<MNT4_753>;
@@ -73,51 +88,40 @@ impl NFT for UniversalTCPSnark {
     type VerifyingKeyMainCircuit = VerifyingKey<MNT6_753>;
     type VerifyingKeyHelpCircuit = VerifyingKey<MNT4_753>;
     type VerifyingKey = <UniversalTCPSnark as UniversalTransactionChainProofData>::VerifyingKey;
+    type PreparedVerifyingKey = PreparedNFTVerifyingKey;
     type Proof = <UniversalTCPSnark as UniversalTransactionChainProofData>::Proof;
     const KEYS_PATH: &str = "data/keys/";
     const PROOFS_PATH: &str = "data/proofs/";
 
     /// Perform the setup based on the provided `chain_index`
-    fn setup(setup_data: SetupData) -> Result<()> {
-        let (pk, _vk) = Self::setup(&setup_data.chain_index).unwrap();
-        save_to_file(
-            &pk.crh_pp.seed,
-            &(Self::KEYS_PATH.to_owned() + "crh_pp_seed.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save crh_pp. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.main_pk),
-            &(Self::KEYS_PATH.to_owned() + "main_pk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save main_pk. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.help_pk),
-            &(Self::KEYS_PATH.to_owned() + "help_pk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save help_pk. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.help_vk),
-            &(Self::KEYS_PATH.to_owned() + "help_vk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save help_vk. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.main_pvk.vk),
-            &(Self::KEYS_PATH.to_owned() + "main_vk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save main_vk. Error: {}", e))?;
+    #[instrument(skip_all, fields(chain_index = setup_data.chain_index))]
+    fn setup(setup_data: SetupData) -> Result<(), TokenError> {
+        let (pk, _vk) = Self::setup(&setup_data.chain_index)
+            .map_err(|e| TokenError::Setup(format!("{:?}", e)))?;
+        let keys_path = <Self as NFT>::KEYS_PATH;
+        save_key(&pk.crh_pp.seed, keys_path, "crh_pp_seed")?;
+        save_key(&key_to_serialisation(&pk.main_pk, setup_data.key_format), keys_path, "main_pk")?;
+        save_key(&key_to_serialisation(&pk.help_pk, setup_data.key_format), keys_path, "help_pk")?;
+        save_key(&data_to_serialisation(&pk.help_vk), keys_path, "help_vk")?;
+        save_key(&data_to_serialisation(&pk.main_pvk.vk), keys_path, "main_vk")?;
+        info!(keys_path = <Self as NFT>::KEYS_PATH, "keys saved");
 
         Ok(())
     }
 
     /// Process the input contained in `ProvingData` (i.e., compute the Pedersen hash)
-    fn process_input(proving_data: ProvingData) -> Result<()> {
+    #[instrument(
+        skip_all,
+        fields(chain_index = proving_data.chain_parameters.chain_index, proof_name = %proving_data.proof_name)
+    )]
+    fn process_input(proving_data: ProvingData) -> Result<(), TokenError> {
         // Generate processed input
-        let vk = Self::load_vk().map_err(|e| anyhow!("Failed to load vk. Error: {}", e))?;
+        let vk = Self::load_vk()?;
         let public_input: UniversalTransactionChainProofPublicInput = proving_data.clone().into();
         let processed_input = PCD::msg_to_input_hash::<
             UniversalTransactionChainProofPredicate<Config>,
         >(&vk, &public_input.into())
-        .map_err(|e| anyhow!("Failed to process the public input. Error: {}", e))?;
+        .map_err(|e| TokenError::InputProcessing(format!("{:?}", e)))?;
 
         // Save processed input to file
         let processed_input_path =
@@ -126,81 +130,137 @@ impl NFT for UniversalTCPSnark {
             &data_to_serialisation(&processed_input),
             &processed_input_path,
         )
-        .map_err(|e| anyhow!("Failed to save the processed public input. Error: {}", e))?;
+        .map_err(|e| TokenError::Save {
+            name: "the processed public input".to_string(),
+            reason: e.to_string(),
+        })?;
+        info!(%processed_input_path, "processed input saved");
 
         Ok(())
     }
 
     /// Generate a proof for the provided `ProvingData`
-    fn prove(proving_data: ProvingData) -> Result<()> {
-        let pk = Self::load_pk().map_err(|e| anyhow!("Failed to load pk. Error: {}", e))?;
+    #[instrument(
+        skip_all,
+        fields(chain_index = proving_data.chain_parameters.chain_index, proof_name = %proving_data.proof_name)
+    )]
+    fn prove(proving_data: ProvingData) -> Result<(), TokenError> {
+        let pk = Self::load_pk()?;
+        <Self as NFT>::prove_with_pk(&pk, proving_data)
+    }
 
+    /// Generate a proof for the provided `ProvingData` with the proving key `pk`
+    #[instrument(
+        skip_all,
+        fields(chain_index = proving_data.chain_parameters.chain_index, proof_name = %proving_data.proof_name)
+    )]
+    fn prove_with_pk(pk: &Self::ProvingKey, proving_data: ProvingData) -> Result<(), TokenError> {
         // Proving data
         let chain_index = proving_data.chain_parameters.chain_index;
+        let depth = chain_depth(Self::PROOFS_PATH, &proving_data)?;
         let tx = match proving_data.witness.tx.is_empty() {
             true => None,
-            false => Some(
-                Tx::read(&mut Cursor::new(
-                    hex::decode(proving_data.witness.tx.clone())
-                        .map_err(|e| anyhow!("Failed to hex decode witness tx. Error: {}", e))?,
-                ))
-                .map_err(|e| anyhow!("Failed to read witness tx. Error: {}", e))?,
-            ),
+            false => {
+                let tx_bytes = hex::decode(proving_data.witness.tx.clone()).map_err(|e| {
+                    TokenError::TxParse {
+                        name: "witness tx".to_string(),
+                        reason: e.to_string(),
+                    }
+                })?;
+                Some(
+                    Tx::read(&mut Cursor::new(tx_bytes)).map_err(|e| TokenError::TxParse {
+                        name: "witness tx".to_string(),
+                        reason: e.to_string(),
+                    })?,
+                )
+            }
         };
         let prior_proof = match proving_data.witness.prior_proof_path.is_empty() {
             true => None,
             false => {
                 let prior_proof_path =
                     Self::PROOFS_PATH.to_owned() + &proving_data.witness.prior_proof_path + ".bin";
-                Self::Proof::deserialize_unchecked(Cursor::new(
-                    read_from_file(&prior_proof_path)
-                        .map_err(|e| anyhow!("Failed to read prior proof. Error: {}", e))?,
-                ))
-                .ok()
+                Some(load_proof::<Self::Proof>(&prior_proof_path, "prior proof")?)
             }
         };
 
         // Proof generation
         let public_input: UniversalTransactionChainProofPublicInput = proving_data.clone().into();
         let witness = UniversalTransactionChainProofWitness::<Self::Proof> { tx, prior_proof };
-        let proof = Self::prove(&chain_index, &pk, &public_input, &witness).unwrap();
+        let proof = Self::prove(&chain_index, pk, &public_input, &witness)
+            .map_err(|e| TokenError::Proving(format!("{:?}", e)))?;
 
         // Save proof to file
         let proof_path = Self::PROOFS_PATH.to_owned() + &proving_data.proof_name + ".bin";
-        save_to_file(&data_to_serialisation(&proof), &proof_path)
-            .map_err(|e| anyhow!("Failed to save proof. Error: {}", e))?;
+        let proof_serialised = data_to_serialisation(&proof);
+        save_to_file(&proof_serialised, &proof_path).map_err(|e| TokenError::Save {
+            name: "proof".to_string(),
+            reason: e.to_string(),
+        })?;
+        info!(%proof_path, "proof saved");
+
+        // Save the proof bundled with its public input
+        let bundle_path = Self::PROOFS_PATH.to_owned() + &proving_data.proof_name + "_bundle.toml";
+        ProofBundle::new(&proving_data, depth, &proof_serialised).save(&bundle_path)?;
+        info!(%bundle_path, "proof bundle saved");
 
         Ok(())
     }
 
     /// Verify the proof contained in `VerifyingData`
-    fn verify(verifying_data: VerifyingData) -> Result<bool> {
-        let vk = Self::load_vk().map_err(|e| anyhow!("Failed to load vk. Error: {}", e))?;
+    #[instrument(
+        skip_all,
+        fields(chain_index = verifying_data.chain_parameters.chain_index, proof_name = %verifying_data.proof_path)
+    )]
+    fn verify(verifying_data: VerifyingData) -> Result<bool, TokenError> {
+        verifying_data.check_genesis()?;
+        let vk = Self::load_vk()?;
         let proof_path = Self::PROOFS_PATH.to_owned() + &verifying_data.proof_path + ".bin";
-        let proof =
-            Self::Proof::deserialize_unchecked(Cursor::new(read_from_file(&proof_path).unwrap()))
-                .map_err(|e| anyhow!("Failed to deserialize proof. Error: {}", e))?;
+        let proof = load_proof::<Self::Proof>(&proof_path, "proof")?;
         let public_input: UniversalTransactionChainProofPublicInput = verifying_data.into();
-        Self::verify(&vk, &public_input, &proof)
-            .map_err(|e| anyhow!("Failed to verify the proof. Error: {:?}", e))
+        let is_valid = Self::verify(&vk, &public_input, &proof)
+            .map_err(|e| TokenError::Verification(format!("{:?}", e)))?;
+        info!(is_valid, "proof verified");
+
+        Ok(is_valid)
     }
 
-    /// Load the proving key
-    fn load_pk() -> Result<Self::ProvingKey> {
-        let crh_pp_seed_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/crh_pp_seed.bin"))
-            .map_err(|e| anyhow!("Failed to read crh_pp. Error: {}", e))?;
-        let main_pk_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/main_pk.bin"))
-            .map_err(|e| anyhow!("Failed to read main_pk. Error: {}", e))?;
-        let help_pk_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/help_pk.bin"))
-            .map_err(|e| anyhow!("Failed to read help_pk. Error: {}", e))?;
+    /// Prepare the verifying key of the help circuit
+    fn prepare(vk: Self::VerifyingKey) -> Self::PreparedVerifyingKey {
+        let help_pvk = prepare_verifying_key(&vk.help_vk);
+        PreparedNFTVerifyingKey { vk, help_pvk }
+    }
+
+    /// Verify the proof contained in `VerifyingData` with the prepared verifying key `pvk`
+    #[instrument(
+        skip_all,
+        fields(chain_index = verifying_data.chain_parameters.chain_index, proof_name = %verifying_data.proof_path)
+    )]
+    fn verify_with_pvk(pvk: &Self::PreparedVerifyingKey, verifying_data: VerifyingData) -> Result<bool, TokenError> {
+        verifying_data.check_genesis()?;
+        let proof_path = Self::PROOFS_PATH.to_owned() + &verifying_data.proof_path + ".bin";
+        let proof = load_proof::<Self::Proof>(&proof_path, "proof")?;
+        // The public input of the help circuit is the processed input (i.e., the Pedersen hash) of the public input
+        let public_input: UniversalTransactionChainProofPublicInput = verifying_data.into();
+        let processed_input = PCD::msg_to_input_hash::<
+            UniversalTransactionChainProofPredicate<Config>,
+        >(&pvk.vk, &public_input.into())
+        .map_err(|e| TokenError::InputProcessing(format!("{:?}", e)))?;
+        verify_proof(&pvk.help_pvk, &proof, &processed_input)
+            .map_err(|e| TokenError::Verification(format!("{:?}", e)))
+    }
 
+    /// Load the proving key
+    #[instrument]
+    fn load_pk() -> Result<Self::ProvingKey, TokenError> {
+        let keys_path = <Self as NFT>::KEYS_PATH;
         let crh_pp = VariableLengthPedersenParameters {
-            seed: crh_pp_seed_bytes,
+            seed: read_key(keys_path, "crh_pp_seed")?,
         };
-        let main_pk = Self::ProvingKeyMainCircuit::deserialize_unchecked(main_pk_bytes.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize main_pk. Error: {}", e))?;
-        let help_pk = Self::ProvingKeyHelpCircuit::deserialize_unchecked(help_pk_bytes.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize help_pk. Error: {}", e))?;
+        let main_pk: Self::ProvingKeyMainCircuit =
+            deserialize_proving_key(&read_key(keys_path, "main_pk")?, "main_pk")?;
+        let help_pk: Self::ProvingKeyHelpCircuit =
+            deserialize_proving_key(&read_key(keys_path, "help_pk")?, "help_pk")?;
         let help_vk = help_pk.vk.clone();
         let main_pvk: PreparedVerifyingKey<MNT6_753> = main_pk.vk.clone().into();
         Ok(Self::ProvingKey {
@@ -213,19 +273,320 @@ impl NFT for UniversalTCPSnark {
     }
 
     /// Load the verifying key
-    fn load_vk() -> Result<Self::VerifyingKey> {
-        let crh_pp_seed_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/crh_pp_seed.bin"))
-            .map_err(|e| anyhow!("Failed to read crh_pp. Error: {}", e))?;
-        let help_vk_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/help_vk.bin"))
-            .map_err(|e: std::io::Error| anyhow!("Failed to read help_vk. Error: {}", e))?;
+    #[instrument]
+    fn load_vk() -> Result<Self::VerifyingKey, TokenError> {
+        <Self as NFT>::load_vk_from(<Self as NFT>::KEYS_PATH)
+    }
 
+    /// Load the verifying key from `keys_path`
+    fn load_vk_from(keys_path: &str) -> Result<Self::VerifyingKey, TokenError> {
         let crh_pp = VariableLengthPedersenParameters {
-            seed: crh_pp_seed_bytes,
+            seed: read_key(keys_path, "crh_pp_seed")?,
         };
-        let help_vk =
-            Self::VerifyingKeyHelpCircuit::deserialize_unchecked(help_vk_bytes.as_slice())
-                .map_err(|e| anyhow!("Failed to deserialize help_vk. Error: {}", e))?;
+        let help_vk: Self::VerifyingKeyHelpCircuit = deserialize_key(&read_key(keys_path, "help_vk")?, "help_vk")?;
 
         Ok(Self::VerifyingKey { crh_pp, help_vk })
     }
+
+    /// Save the verifying key bundle (the Pedersen parameters and the help verifying key) loaded from `keys_path`
+    /// to `export_path`
+    #[instrument]
+    fn export_vk(keys_path: &str, export_path: &str) -> Result<(), TokenError> {
+        let vk = <Self as NFT>::load_vk_from(keys_path)?;
+        save_key(&vk.crh_pp.seed, export_path, "crh_pp_seed")?;
+        save_key(&data_to_serialisation(&vk.help_vk), export_path, "help_vk")?;
+        info!(export_path, "verifying key exported");
+
+        Ok(())
+    }
+
+    /// Compute the SHA256 fingerprint of the verifying key (the Pedersen parameters and the help verifying key)
+    fn vk_fingerprint(vk: &Self::VerifyingKey) -> String {
+        fingerprint(&[vk.crh_pp.seed.clone(), data_to_serialisation(&vk.help_vk)])
+    }
+}
+
+/// Path of the file of the key `key` in the directory `keys_path`
+fn key_path(keys_path: &str, key: &str) -> String {
+    Path::new(keys_path).join(format!("{}.bin", key)).to_string_lossy().to_string()
+}
+
+/// Save the key `key` to `<keys_path>/<key>.bin`
+fn save_key(data: &[u8], keys_path: &str, key: &str) -> Result<(), TokenError> {
+    save_to_file(data, &key_path(keys_path, key)).map_err(|e| TokenError::Save {
+        name: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Read the key `key` from `<keys_path>/<key>.bin`
+pub(crate) fn read_key(keys_path: &str, key: &str) -> Result<Vec<u8>, TokenError> {
+    read_from_file(&key_path(keys_path, key)).map_err(|e| TokenError::KeyLoad {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Deserialize the key `key` from `bytes`
+fn deserialize_key<T: CanonicalDeserialize>(bytes: &[u8], key: &str) -> Result<T, TokenError> {
+    T::deserialize_unchecked(bytes).map_err(|e| TokenError::KeyLoad {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Deserialize the proving key `key` from `bytes`, saved with [key_to_serialisation] in either format
+fn deserialize_proving_key<T: CanonicalDeserialize>(bytes: &[u8], key: &str) -> Result<T, TokenError> {
+    key_from_serialisation(bytes).map_err(|e| TokenError::KeyLoad {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Read and deserialize the proof `name` from `proof_path`
+fn load_proof<T: CanonicalDeserialize>(proof_path: &str, name: &str) -> Result<T, TokenError> {
+    let proof_bytes = read_from_file(proof_path).map_err(|e| TokenError::ProofDeserialize {
+        name: name.to_string(),
+        reason: e.to_string(),
+    })?;
+    T::deserialize_unchecked(Cursor::new(proof_bytes)).map_err(|e| TokenError::ProofDeserialize {
+        name: name.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::UniformRand;
+    use ark_groth16::{create_random_proof, generate_random_parameters};
+    use ark_mnt4_753::{G1Projective, G2Projective};
+    use ark_relations::lc;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use crate::data_structures::proving_data::{ChainParameters, PublicInputs, Witness};
+    use crate::data_structures::verifying_data;
+    use crate::util::KeyFormat;
+    use rand_chacha::rand_core::SeedableRng;
+    use std::fmt::{Debug, Write};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    /// Layer recording the name and the fields of every span created, as `name field=value ...`
+    #[derive(Clone, Default)]
+    struct SpanRecorder(Arc<Mutex<Vec<String>>>);
+
+    struct FieldRecorder(String);
+
+    impl Visit for FieldRecorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            write!(self.0, " {}={:?}", field.name(), value).unwrap();
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for SpanRecorder {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = FieldRecorder(attrs.metadata().name().to_string());
+            attrs.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    fn random_help_vk(rng: &mut ChaChaRng) -> VerifyingKey<MNT4_753> {
+        VerifyingKey {
+            alpha_g1: G1Projective::rand(rng).into_affine(),
+            beta_g2: G2Projective::rand(rng).into_affine(),
+            gamma_g2: G2Projective::rand(rng).into_affine(),
+            delta_g2: G2Projective::rand(rng).into_affine(),
+            gamma_abc_g1: vec![G1Projective::rand(rng).into_affine(), G1Projective::rand(rng).into_affine()],
+        }
+    }
+
+    #[test]
+    fn test_missing_key_file() {
+        let missing_key = "missing_key_for_test";
+        assert!(matches!(
+            read_key(<UniversalTCPSnark as NFT>::KEYS_PATH, missing_key),
+            Err(TokenError::KeyLoad { key, .. }) if key == missing_key
+        ));
+    }
+
+    #[test]
+    fn test_verify_expected_genesis() {
+        let data = |expected_genesis_txid: Option<&str>| VerifyingData {
+            chain_parameters: verifying_data::ChainParameters { chain_index: 0 },
+            public_inputs: verifying_data::PublicInputs {
+                outpoint_txid: "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa".to_string(),
+                genesis_txid: "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c".to_string(),
+            },
+            proof_path: "proof_for_genesis_test".to_string(),
+            expected_genesis_txid: expected_genesis_txid.map(str::to_string),
+        };
+
+        // The genesis txid is checked before loading the keys and the proof, so a proof for the wrong lineage is
+        // rejected whatever its validity
+        let wrong_genesis = "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa";
+        assert!(matches!(
+            <UniversalTCPSnark as NFT>::verify(data(Some(wrong_genesis))),
+            Err(TokenError::GenesisMismatch { expected, .. }) if expected == wrong_genesis
+        ));
+        assert!(matches!(
+            <UniversalTCPSnark as NFT>::verify(data(Some("zz"))),
+            Err(TokenError::DataLoad { .. })
+        ));
+
+        // With the right (or no) expected genesis, the verification goes on and stops at the missing keys
+        let genesis = "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c";
+        for expected_genesis_txid in [Some(genesis), None] {
+            assert!(data(expected_genesis_txid).check_genesis().is_ok());
+            assert!(matches!(
+                <UniversalTCPSnark as NFT>::verify(data(expected_genesis_txid)),
+                Err(TokenError::KeyLoad { .. })
+            ));
+        }
+    }
+
+    fn random_help_pk(rng: &mut ChaChaRng) -> ProvingKey<MNT4_753> {
+        let random_g1 = |rng: &mut ChaChaRng, n: usize| {
+            (0..n).map(|_| G1Projective::rand(rng).into_affine()).collect::<Vec<_>>()
+        };
+        ProvingKey {
+            vk: random_help_vk(rng),
+            beta_g1: G1Projective::rand(rng).into_affine(),
+            delta_g1: G1Projective::rand(rng).into_affine(),
+            a_query: random_g1(rng, 4),
+            b_g1_query: random_g1(rng, 4),
+            b_g2_query: (0..4).map(|_| G2Projective::rand(rng).into_affine()).collect(),
+            h_query: random_g1(rng, 3),
+            l_query: random_g1(rng, 2),
+        }
+    }
+
+    #[test]
+    fn test_proving_key_formats_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let help_pk = random_help_pk(&mut rng);
+
+        let uncompressed = key_to_serialisation(&help_pk, KeyFormat::Uncompressed);
+        let compressed = key_to_serialisation(&help_pk, KeyFormat::Compressed);
+        assert!(compressed.len() < uncompressed.len());
+
+        for bytes in [uncompressed, compressed] {
+            let deserialized_help_pk: ProvingKey<MNT4_753> = deserialize_proving_key(&bytes, "help_pk").unwrap();
+            assert_eq!(deserialized_help_pk, help_pk);
+        }
+
+        assert!(matches!(
+            deserialize_proving_key::<ProvingKey<MNT4_753>>(&[2u8, 0u8], "help_pk"),
+            Err(TokenError::KeyLoad { .. })
+        ));
+    }
+
+    #[test]
+    fn test_vk_fingerprint_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let seed = vec![1u8; 32];
+        let help_vk = random_help_vk(&mut rng);
+        let vk_fingerprint = fingerprint(&[seed.clone(), data_to_serialisation(&help_vk)]);
+
+        let deserialized_help_vk: VerifyingKey<MNT4_753> =
+            deserialize_key(&data_to_serialisation(&help_vk), "help_vk").unwrap();
+        assert_eq!(
+            vk_fingerprint,
+            fingerprint(&[seed.clone(), data_to_serialisation(&deserialized_help_vk)])
+        );
+
+        let other_help_vk = random_help_vk(&mut rng);
+        assert_ne!(vk_fingerprint, fingerprint(&[seed, data_to_serialisation(&other_help_vk)]));
+    }
+
+    /// Circuit proving knowledge of a square root of the public input
+    #[derive(Clone)]
+    struct SquareCircuit {
+        root: Option<ScalarFieldMNT4>,
+        square: Option<ScalarFieldMNT4>,
+    }
+
+    impl ConstraintSynthesizer<ScalarFieldMNT4> for SquareCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<ScalarFieldMNT4>) -> Result<(), SynthesisError> {
+            let square = cs.new_input_variable(|| self.square.ok_or(SynthesisError::AssignmentMissing))?;
+            let root = cs.new_witness_variable(|| self.root.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(lc!() + root, lc!() + root, lc!() + square)
+        }
+    }
+
+    #[test]
+    fn test_export_vk() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let keys_path = std::env::temp_dir().join("nft_test_export_vk_keys");
+        let export_path = std::env::temp_dir().join("nft_test_export_vk_export");
+        let (keys_path, export_path) = (keys_path.to_str().unwrap(), export_path.to_str().unwrap());
+
+        // Full keys, as saved by the setup
+        let root = ScalarFieldMNT4::from(3u64);
+        let square = root * root;
+        let circuit = SquareCircuit {
+            root: Some(root),
+            square: Some(square),
+        };
+        let help_pk = generate_random_parameters::<MNT4_753, _, _>(circuit.clone(), &mut rng).unwrap();
+        let seed = vec![1u8; 32];
+        save_key(&seed, keys_path, "crh_pp_seed").unwrap();
+        save_key(&key_to_serialisation(&help_pk, KeyFormat::Uncompressed), keys_path, "help_pk").unwrap();
+        save_key(&data_to_serialisation(&help_pk.vk), keys_path, "help_vk").unwrap();
+        let proof = create_random_proof(circuit, &help_pk, &mut rng).unwrap();
+
+        // The export only contains the keys required to verify
+        <UniversalTCPSnark as NFT>::export_vk(keys_path, export_path).unwrap();
+        assert!(Path::new(&key_path(export_path, "help_vk")).exists());
+        assert!(!Path::new(&key_path(export_path, "help_pk")).exists());
+
+        let vk = <UniversalTCPSnark as NFT>::load_vk_from(export_path).unwrap();
+        let full_vk = <UniversalTCPSnark as NFT>::load_vk_from(keys_path).unwrap();
+        assert_eq!(
+            <UniversalTCPSnark as NFT>::vk_fingerprint(&vk),
+            <UniversalTCPSnark as NFT>::vk_fingerprint(&full_vk)
+        );
+        let pvk = prepare_verifying_key(&vk.help_vk);
+        assert!(verify_proof(&pvk, &proof, &[square]).unwrap());
+        assert!(!verify_proof(&pvk, &proof, &[root]).unwrap());
+
+        std::fs::remove_dir_all(keys_path).unwrap();
+        std::fs::remove_dir_all(export_path).unwrap();
+    }
+
+    #[test]
+    fn test_prove_spans() {
+        let proving_data = ProvingData {
+            chain_parameters: ChainParameters {
+                chain_index: 0,
+                max_depth: None,
+            },
+            public_inputs: PublicInputs {
+                outpoint_txid: "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa".to_string(),
+                genesis_txid: "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c".to_string(),
+            },
+            witness: Witness {
+                tx: "".to_string(),
+                prior_proof_path: "".to_string(),
+            },
+            proof_name: "proof_for_span_test".to_string(),
+        };
+
+        // There are no keys in the test environment, so the prove stops when loading the proving key
+        let recorder = SpanRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let result = tracing::subscriber::with_default(subscriber, || {
+            <UniversalTCPSnark as NFT>::prove(proving_data)
+        });
+
+        assert!(matches!(result, Err(TokenError::KeyLoad { .. })));
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec!["prove chain_index=0 proof_name=proof_for_span_test", "load_pk"]
+        );
+    }
 }
@@ -1,9 +1,18 @@
-use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rayon::ThreadPoolBuilder;
+use rayon::prelude::*;
+use tracing::{Span, info_span, instrument};
 
+use crate::data_structures::proof_bundle::ProofBundle;
 use crate::data_structures::proving_data::ProvingData;
 use crate::data_structures::setup_data::SetupData;
 use crate::data_structures::verifying_data::VerifyingData;
+use crate::error::TokenError;
 
 pub mod groth16_nft;
 
@@ -15,25 +24,287 @@ pub trait NFT {
     type VerifyingKeyMainCircuit: Clone + CanonicalSerialize + CanonicalDeserialize;
     type VerifyingKeyHelpCircuit: Clone + CanonicalSerialize + CanonicalDeserialize;
     type VerifyingKey;
+    type PreparedVerifyingKey;
     type Proof: Clone + CanonicalSerialize + CanonicalDeserialize;
     const KEYS_PATH: &str;
     const PROOFS_PATH: &str;
 
     // Perform the setup of the NFT and save the keys to file
-    fn setup(setup_data: SetupData) -> Result<()>;
+    fn setup(setup_data: SetupData) -> Result<(), TokenError>;
 
     // Process the public input contained in `ProvingData` and save it to file
-    fn process_input(proving_data: ProvingData) -> Result<()>;
+    fn process_input(proving_data: ProvingData) -> Result<(), TokenError>;
 
     // Prove that an NFT is held in the output of a given tx and save the proof to file
-    fn prove(proving_data: ProvingData) -> Result<()>;
+    fn prove(proving_data: ProvingData) -> Result<(), TokenError>;
+
+    // Same as `prove`, with a proving key already loaded, so that it can be reused across proofs
+    fn prove_with_pk(pk: &Self::ProvingKey, proving_data: ProvingData) -> Result<(), TokenError>;
 
     // Verify that an NFT is held in the output of a given tx
-    fn verify(verifying_data: VerifyingData) -> Result<bool>;
+    fn verify(verifying_data: VerifyingData) -> Result<bool, TokenError>;
+
+    // Precompute the parts of the verification that only depend on the verifying key, so that they can be reused
+    // across verifications
+    fn prepare(vk: Self::VerifyingKey) -> Self::PreparedVerifyingKey;
+
+    // Same as `verify`, with a prepared verifying key, so that a long-running verifier prepares the key only once
+    fn verify_with_pvk(pvk: &Self::PreparedVerifyingKey, verifying_data: VerifyingData) -> Result<bool, TokenError>;
 
     // Load the proving key of the NFT scheme
-    fn load_pk() -> Result<Self::ProvingKey>;
+    fn load_pk() -> Result<Self::ProvingKey, TokenError>;
 
     // Load the verifying key of the NFT scheme
-    fn load_vk() -> Result<Self::VerifyingKey>;
+    fn load_vk() -> Result<Self::VerifyingKey, TokenError>;
+
+    // Load the verifying key of the NFT scheme from the directory `keys_path`
+    fn load_vk_from(keys_path: &str) -> Result<Self::VerifyingKey, TokenError>;
+
+    // Save the keys required to verify (without the large proving keys) loaded from the directory `keys_path` to the
+    // directory `export_path`, so that they can be shipped to the verifiers
+    fn export_vk(keys_path: &str, export_path: &str) -> Result<(), TokenError>;
+
+    // Compute the fingerprint of the verifying key, to check that prover and verifier share the same key
+    fn vk_fingerprint(vk: &Self::VerifyingKey) -> String;
+}
+
+/// Run `prove` on every `*.toml` proving data file in the directory `dir`, in alphabetical order, over `jobs` threads
+/// A file that fails to load or to be proven does not stop the batch: the outcome of each file is returned, together
+/// with its path and in the same order, so that it can be reported at the end
+/// Note: the proofs are not reproducible, as `UniversalTCPSnark` samples the randomness of each proof itself
+#[instrument(skip(prove))]
+pub fn prove_batch(
+    dir: &str,
+    jobs: usize,
+    prove: impl Fn(ProvingData) -> Result<(), TokenError> + Sync,
+) -> Result<Vec<(String, Result<(), TokenError>)>, TokenError> {
+    let dir_error = |reason: String| TokenError::DataLoad {
+        name: format!("the batch directory {}", dir),
+        reason,
+    };
+    let mut file_paths = fs::read_dir(Path::new(dir))
+        .map_err(|e| dir_error(e.to_string()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| dir_error(e.to_string()))?;
+    file_paths.retain(|path| path.is_file() && path.extension().is_some_and(|extension| extension == "toml"));
+    file_paths.sort();
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|e| TokenError::Proving(e.to_string()))?;
+
+    // The spans of the files are created in the threads of the pool, so their parent is set explicitly
+    let batch_span = Span::current();
+    Ok(pool.install(|| {
+        file_paths
+            .par_iter()
+            .map(|path| {
+                let file_path = path.to_string_lossy().to_string();
+                let _file_span = info_span!(parent: &batch_span, "prove_file", file_path = %file_path).entered();
+                let outcome = ProvingData::load(file_path.clone()).and_then(&prove);
+                (file_path, outcome)
+            })
+            .collect()
+    }))
+}
+
+/// Guard against proofs silently overwriting each other
+/// Each proof claims its name before being generated: the claim fails if another proof (e.g., of the same batch) has
+/// already claimed the name or, unless `force` is set, if a proof with the same name is saved in `proofs_path`
+pub struct ProofNameGuard {
+    proofs_path: String,
+    force: bool,
+    claimed: Mutex<HashSet<String>>,
+}
+
+impl ProofNameGuard {
+    pub fn new(proofs_path: &str, force: bool) -> Self {
+        Self {
+            proofs_path: proofs_path.to_string(),
+            force,
+            claimed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Claim the name of the proof generated from `proving_data`
+    pub fn claim(&self, proving_data: &ProvingData) -> Result<(), TokenError> {
+        let name = &proving_data.proof_name;
+        let collision = |reason: String| TokenError::ProofNameCollision {
+            name: name.clone(),
+            reason,
+        };
+
+        let proof_path = format!("{}{}.bin", self.proofs_path, name);
+        if !self.force && Path::new(&proof_path).exists() {
+            return Err(collision(format!(
+                "a proof is already saved at {} (pass --force to overwrite it)",
+                proof_path
+            )));
+        }
+        if !self.claimed.lock().unwrap().insert(name.clone()) {
+            return Err(collision("another proof of the batch has the same name".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Compute the depth of the proof generated from `proving_data`, i.e., the number of proofs in the chain up to
+/// this one, and check it against `max_depth` in the chain parameters
+/// The depth of the prior proof is read from its bundle in `proofs_path`
+pub fn chain_depth(proofs_path: &str, proving_data: &ProvingData) -> Result<u32, TokenError> {
+    let prior_proof_path = &proving_data.witness.prior_proof_path;
+    let depth = match prior_proof_path.is_empty() {
+        true => 1,
+        false => {
+            let prior_bundle = ProofBundle::load(&format!("{}{}_bundle.toml", proofs_path, prior_proof_path))?;
+            prior_bundle.depth.saturating_add(1)
+        }
+    };
+    match proving_data.chain_parameters.max_depth {
+        Some(max_depth) if depth > max_depth => Err(TokenError::MaxDepthExceeded { depth, max_depth }),
+        _ => Ok(depth),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROVING_DATA: &str = r#"proof_name = "PROOF_NAME"
+
+[chain_parameters]
+chain_index = 0
+
+[public_inputs]
+outpoint_txid = "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa"
+genesis_txid = "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c"
+
+[witness]
+tx = ""
+prior_proof_path = ""
+"#;
+
+    /// Write the proving data files of a batch in a fresh directory `name`: `a.toml`, `c.toml` and `d.toml` are valid,
+    /// `b.toml` is malformed
+    fn write_batch(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for proof_name in ["a", "c", "d"] {
+            fs::write(
+                dir.join(format!("{}.toml", proof_name)),
+                PROVING_DATA.replace("PROOF_NAME", &format!("proof_{}", proof_name)),
+            )
+            .unwrap();
+        }
+        fs::write(dir.join("b.toml"), "proof_name = \"proof_b\"\n[chain_parameters]\n").unwrap();
+        // Not a proving data file
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_prove_batch() {
+        let dir = write_batch("nft_prove_batch_test");
+
+        let proven = std::sync::Mutex::new(vec![]);
+        let outcomes = prove_batch(dir.to_str().unwrap(), 1, |proving_data| {
+            proven.lock().unwrap().push(proving_data.proof_name);
+            Ok(())
+        })
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(proven.into_inner().unwrap(), vec!["proof_a", "proof_c", "proof_d"]);
+        assert_eq!(outcomes.len(), 4);
+        assert!(outcomes[0].0.ends_with("a.toml") && outcomes[0].1.is_ok());
+        assert!(outcomes[1].0.ends_with("b.toml") && matches!(outcomes[1].1, Err(TokenError::DataLoad { .. })));
+        assert!(outcomes[2].0.ends_with("c.toml") && outcomes[2].1.is_ok());
+        assert!(outcomes[3].0.ends_with("d.toml") && outcomes[3].1.is_ok());
+    }
+
+    #[test]
+    fn test_proof_name_guard() {
+        let dir = write_batch("nft_proof_name_guard_test");
+        // `c.toml` has the same proof name as `a.toml`
+        fs::write(dir.join("c.toml"), PROVING_DATA.replace("PROOF_NAME", "proof_a")).unwrap();
+        let proofs_path = format!("{}/", dir.join("proofs").to_str().unwrap());
+
+        let prove_with_guard = |guard: &ProofNameGuard| {
+            prove_batch(dir.to_str().unwrap(), 1, |proving_data| {
+                guard.claim(&proving_data)?;
+                // Mock proof
+                fs::create_dir_all(&proofs_path).unwrap();
+                fs::write(format!("{}{}.bin", proofs_path, proving_data.proof_name), "").unwrap();
+                Ok(())
+            })
+            .unwrap()
+        };
+
+        // The second proof named `proof_a` is not generated
+        let outcomes = prove_with_guard(&ProofNameGuard::new(&proofs_path, false));
+        assert!(outcomes[0].1.is_ok());
+        assert!(matches!(&outcomes[2].1, Err(TokenError::ProofNameCollision { name, .. }) if name == "proof_a"));
+        assert!(outcomes[3].1.is_ok());
+
+        // Proving again does not overwrite the saved proofs, unless forced
+        let outcomes = prove_with_guard(&ProofNameGuard::new(&proofs_path, false));
+        assert!(matches!(outcomes[0].1, Err(TokenError::ProofNameCollision { .. })));
+        assert!(matches!(outcomes[3].1, Err(TokenError::ProofNameCollision { .. })));
+        let outcomes = prove_with_guard(&ProofNameGuard::new(&proofs_path, true));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(outcomes[0].1.is_ok());
+        assert!(matches!(outcomes[2].1, Err(TokenError::ProofNameCollision { .. })));
+        assert!(outcomes[3].1.is_ok());
+    }
+
+    #[test]
+    fn test_prove_batch_missing_dir() {
+        assert!(matches!(
+            prove_batch("missing_dir_for_test", 1, |_| Ok(())),
+            Err(TokenError::DataLoad { .. })
+        ));
+    }
+
+    #[test]
+    fn test_chain_depth() {
+        let dir = std::env::temp_dir().join("nft_chain_depth_test");
+        let _ = fs::remove_dir_all(&dir);
+        let proofs_path = format!("{}/", dir.to_str().unwrap());
+        let proving_data = |proof_name: &str, prior_proof_path: &str| {
+            let mut proving_data: ProvingData = toml::from_str(
+                &PROVING_DATA
+                    .replace("PROOF_NAME", proof_name)
+                    .replace("prior_proof_path = \"\"", &format!("prior_proof_path = \"{}\"", prior_proof_path)),
+            )
+            .unwrap();
+            proving_data.chain_parameters.max_depth = Some(3);
+            proving_data
+        };
+        // Mock proof: only the bundle, which records the depth, is saved
+        let prove = |proving_data: &ProvingData| {
+            let depth = chain_depth(&proofs_path, proving_data)?;
+            ProofBundle::new(proving_data, depth, &[])
+                .save(&format!("{}{}_bundle.toml", proofs_path, proving_data.proof_name))?;
+            Ok::<u32, TokenError>(depth)
+        };
+
+        // Proving up to the maximum depth succeeds
+        assert_eq!(prove(&proving_data("proof_1", "")).unwrap(), 1);
+        assert_eq!(prove(&proving_data("proof_2", "proof_1")).unwrap(), 2);
+        assert_eq!(prove(&proving_data("proof_3", "proof_2")).unwrap(), 3);
+        // One step beyond fails
+        let outcome = prove(&proving_data("proof_4", "proof_3"));
+        assert!(matches!(outcome, Err(TokenError::MaxDepthExceeded { depth: 4, max_depth: 3 })));
+        // Without a maximum depth, the chain can grow
+        let mut unbounded = proving_data("proof_4", "proof_3");
+        unbounded.chain_parameters.max_depth = None;
+        assert_eq!(prove(&unbounded).unwrap(), 4);
+        // The depth of the prior proof is read from its bundle
+        let outcome = prove(&proving_data("proof_5", "missing_proof"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(outcome, Err(TokenError::DataLoad { .. })));
+    }
 }
@@ -0,0 +1,120 @@
+//! Commitment to the token burnt by the burning transaction
+//! The burnt token is identified by the public input of its TCP proof: the genesis txid of the token and the outpoint
+//! holding it. The commitment is computed from these, so that the prover (from the proving data) and the verifier
+//! (from the burning transaction) derive the same bytes
+//! Note: the commitment is not embedded in the burning transaction, as the circuit of the PoB only accepts burnt token
+//! outputs locked by `OP_0 OP_RETURN` (see [crate::pob::Config] and [crate::pob::check_spending_tx])
+
+use chain_gang::messages::{OutPoint, Tx};
+use chain_gang::util::{Hash256, Serializable};
+use sha2::{Digest, Sha256};
+
+use crate::error::TokenError;
+
+/// Length of the burn commitment
+pub const BURN_COMMITMENT_LEN: usize = 32;
+
+/// Preimage of the burn commitment: the genesis txid of the token followed by the serialisation of the outpoint
+/// holding it (txid and index, as in a tx input)
+pub fn burn_commitment_preimage(genesis_txid: &Hash256, outpoint: &OutPoint) -> Vec<u8> {
+    let mut preimage = genesis_txid.0.to_vec();
+    outpoint.write(&mut preimage).unwrap();
+    preimage
+}
+
+/// Burn commitment of the token with genesis `genesis_txid` held by `outpoint`: the SHA256 of
+/// [burn_commitment_preimage]
+pub fn burn_commitment(genesis_txid: &Hash256, outpoint: &OutPoint) -> [u8; BURN_COMMITMENT_LEN] {
+    Sha256::digest(burn_commitment_preimage(genesis_txid, outpoint)).into()
+}
+
+/// Burn commitment computed by the prover from the public input of the TCP proof: the genesis txid, the txid of the
+/// outpoint (both hex encoded, as in the proving data of the TCP) and the index of the outpoint
+pub fn burn_commitment_from_public_input(
+    genesis_txid: &str,
+    outpoint_txid: &str,
+    outpoint_index: u32,
+) -> Result<[u8; BURN_COMMITMENT_LEN], TokenError> {
+    let decode = |name: &str, txid: &str| {
+        Hash256::decode(txid).map_err(|e| TokenError::TxParse {
+            name: name.to_string(),
+            reason: e.to_string(),
+        })
+    };
+    let outpoint = OutPoint {
+        hash: decode("outpoint txid", outpoint_txid)?,
+        index: outpoint_index,
+    };
+    Ok(burn_commitment(&decode("genesis txid", genesis_txid)?, &outpoint))
+}
+
+/// Burn commitment computed by the verifier from the burning transaction `spending_tx`: the token is the one spent
+/// by its first input
+pub fn burn_commitment_from_spending_tx(
+    genesis_txid: &str,
+    spending_tx: &Tx,
+) -> Result<[u8; BURN_COMMITMENT_LEN], TokenError> {
+    let genesis_txid = Hash256::decode(genesis_txid).map_err(|e| TokenError::TxParse {
+        name: "genesis txid".to_string(),
+        reason: e.to_string(),
+    })?;
+    let token_input = spending_tx
+        .inputs
+        .first()
+        .ok_or(TokenError::InvalidSpendingTx("the tx has no inputs".to_string()))?;
+    Ok(burn_commitment(&genesis_txid, &token_input.prev_output))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Spending tx of `proving_data.toml`
+    const SPENDING_TX: &str = "010000000340fd94950241edad515321861661a1a6f0717dbfbaefb8d2d3f7f3fa8c46ad45000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d112000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d11201000000000000000002000000000000000002006aecef0200000000001976a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac00000000";
+    const GENESIS_TXID: &str = "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c";
+
+    #[test]
+    fn test_burn_commitment() {
+        let spending_tx = Tx::read(&mut Cursor::new(hex::decode(SPENDING_TX).unwrap())).unwrap();
+        let token_outpoint = &spending_tx.inputs[0].prev_output;
+
+        // The prover derives the commitment from the public input of the TCP proof, the verifier from the tx
+        let prover_commitment =
+            burn_commitment_from_public_input(GENESIS_TXID, &token_outpoint.hash.encode(), token_outpoint.index)
+                .unwrap();
+        let verifier_commitment = burn_commitment_from_spending_tx(GENESIS_TXID, &spending_tx).unwrap();
+        assert_eq!(prover_commitment, verifier_commitment);
+
+        // The commitment is deterministic
+        assert_eq!(
+            prover_commitment,
+            burn_commitment_from_public_input(GENESIS_TXID, &token_outpoint.hash.encode(), token_outpoint.index)
+                .unwrap()
+        );
+
+        // The preimage is the genesis txid followed by the outpoint
+        let preimage = burn_commitment_preimage(&Hash256::decode(GENESIS_TXID).unwrap(), token_outpoint);
+        assert_eq!(preimage.len(), 32 + 36);
+        assert_eq!(hex::encode(&preimage[32..]), &SPENDING_TX[10..82]);
+
+        // The commitment depends on the token
+        let other_index =
+            burn_commitment_from_public_input(GENESIS_TXID, &token_outpoint.hash.encode(), token_outpoint.index + 1)
+                .unwrap();
+        assert_ne!(prover_commitment, other_index);
+        let other_genesis = burn_commitment_from_public_input(
+            "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa",
+            &token_outpoint.hash.encode(),
+            token_outpoint.index,
+        )
+        .unwrap();
+        assert_ne!(prover_commitment, other_genesis);
+
+        assert!(matches!(
+            burn_commitment_from_public_input("zz", &token_outpoint.hash.encode(), 0),
+            Err(TokenError::TxParse { .. })
+        ));
+    }
+}
@@ -0,0 +1,120 @@
+//! Memoization of the verification of PoBs
+//! A verifier service re-verifying the same proof only pays for the pairings once: the result of the verification is
+//! stored under the fingerprints of the verifying key, the proof and the public input
+
+use std::collections::{HashMap, VecDeque};
+
+use ark_groth16::{Proof, VerifyingKey};
+use ark_mnt4_753::{Fr as ScalarFieldMNT4, MNT4_753};
+use tracing::debug;
+
+use crate::error::TokenError;
+use crate::utils::{data_to_serialisation, fingerprint};
+use crate::verify;
+
+/// Key of a cached verification: `(vk_fingerprint, proof_hash, public_input_hash)`
+pub type CacheKey = (String, String, String);
+
+/// Bounded cache of verification results
+/// When the cache is full, the oldest entry is evicted to make room for the new one (FIFO eviction)
+/// Only the outcome of successful verifications is stored: errors are returned and not cached
+#[derive(Debug)]
+pub struct VerificationCache {
+    capacity: usize,
+    results: HashMap<CacheKey, bool>,
+    insertion_order: VecDeque<CacheKey>,
+    hits: usize,
+    misses: usize,
+}
+
+impl VerificationCache {
+    /// Create an empty cache holding at most `capacity` results
+    /// A cache with `capacity` equal to zero never stores anything
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            results: HashMap::with_capacity(capacity),
+            insertion_order: VecDeque::with_capacity(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Compute the key under which the verification of `proof` against `public_input` with `vk` is stored
+    pub fn key(vk: &VerifyingKey<MNT4_753>, public_input: &[ScalarFieldMNT4], proof: &Proof<MNT4_753>) -> CacheKey {
+        (
+            fingerprint(&[data_to_serialisation(vk)]),
+            fingerprint(&[data_to_serialisation(proof)]),
+            fingerprint(&[data_to_serialisation(&public_input.to_vec())]),
+        )
+    }
+
+    /// Verify a PoB against its public input, returning the cached result if the same triple
+    /// `(vk, proof, public_input)` was already verified
+    pub fn verify(
+        &mut self,
+        vk: &VerifyingKey<MNT4_753>,
+        public_input: &[ScalarFieldMNT4],
+        proof: &Proof<MNT4_753>,
+    ) -> Result<bool, TokenError> {
+        let key = Self::key(vk, public_input, proof);
+        if let Some(&is_valid) = self.results.get(&key) {
+            self.hits += 1;
+            debug!(is_valid, "verification cache hit");
+            return Ok(is_valid);
+        }
+
+        self.misses += 1;
+        let is_valid = verify(vk, public_input, proof)?;
+        self.insert(key, is_valid);
+        Ok(is_valid)
+    }
+
+    /// Store `is_valid` under `key`, evicting the oldest entries if the cache is full
+    fn insert(&mut self, key: CacheKey, is_valid: bool) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.results.len() >= self.capacity {
+            match self.insertion_order.pop_front() {
+                Some(oldest) => {
+                    self.results.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        self.insertion_order.push_back(key.clone());
+        self.results.insert(key, is_valid);
+    }
+
+    /// Number of verifications answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of verifications which required running the verification algorithm
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Number of results currently stored
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Whether the cache stores no result
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Maximum number of results stored
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Remove all the stored results, keeping the hit and miss counters
+    pub fn clear(&mut self) {
+        self.results.clear();
+        self.insertion_order.clear();
+    }
+}
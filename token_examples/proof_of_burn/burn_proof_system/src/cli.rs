@@ -1,7 +1,8 @@
 use clap::{Parser, Subcommand};
 
 /// CLI of the application
-/// It can be run in either `setup`, `prove`, or `verify` mode
+/// It can be run in either `setup`, `prove`, `verify`, `verify-tcp`, `fingerprint`, `check-keys`, `tag`, or `funding`
+/// mode
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub(crate) struct Cli {
@@ -12,9 +13,52 @@ pub(crate) struct Cli {
 #[derive(Subcommand, Debug)]
 pub(crate) enum Commands {
     /// Setup mode
-    Setup,
+    Setup {
+        /// Save the proving key compressed: smaller on disk, but slower to load
+        #[arg(long)]
+        compressed: bool,
+        /// Generate the keys for a RefTx input spending a P2PKH output, instead of an output locked by `OP_CHECKSIG`
+        #[arg(long)]
+        p2pkh: bool,
+        /// Read the shape of the RefTx from the `prev_lock_script` entry of a TOML file (e.g., `proving_data.toml`),
+        /// so that the keys match the RefTx that will be proved
+        #[arg(long, conflicts_with = "p2pkh")]
+        config: Option<String>,
+    },
     /// Verification mode
-    Verify,
+    Verify {
+        /// Read the vk, the proof and the public input from stdin (JSON, see `VerifyingBundle`) instead of the files
+        /// saved by the application, and print the result
+        #[arg(long)]
+        stdin: bool,
+    },
     /// Proving mode
-    Prove
+    Prove,
+    /// Verification mode for the TCP proof (the help proof over MNT6_753) used to generate the PoB
+    VerifyTcp,
+    /// Print the fingerprint of the verifying key of the PoB
+    Fingerprint,
+    /// Check that the proving key and the verifying key saved by the setup are a matching pair
+    CheckKeys,
+    /// Print the integrity tag (the sighash of the RefTx input) committed to by the PoB for a spending tx
+    Tag {
+        /// The spending tx, hex encoded
+        #[arg(long)]
+        tx: String,
+        /// The locking script of the output spent by the RefTx input, hex encoded
+        #[arg(long, default_value = "ac")]
+        prev_lock_script: String,
+        /// The amount of the output spent by the RefTx input
+        #[arg(long)]
+        prev_amount: u64,
+    },
+    /// Print the minimum amount of the funding input of the burning tx, so that it covers the fee of the tx
+    Funding {
+        /// File containing the unlocking script of the RefTx input (the spending script of the PoB), hex encoded
+        #[arg(long)]
+        script: String,
+        /// Fee rate of the burning tx, in satoshis per kB
+        #[arg(long, default_value_t = 10)]
+        fee_rate: u64,
+    },
 }
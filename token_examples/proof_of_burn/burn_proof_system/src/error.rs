@@ -0,0 +1,40 @@
+use thiserror::Error;
+
+/// Errors returned by the PoB application
+/// Each variant carries the description of the underlying error, so that callers can match on the kind of failure
+#[derive(Debug, Error)]
+pub enum TokenError {
+    /// Failed to read or parse the proving data passed to the application
+    #[error("Failed to load {name}. Error: {reason}")]
+    DataLoad { name: String, reason: String },
+    /// Failed to read or deserialize a key
+    #[error("Failed to load {key}. Error: {reason}")]
+    KeyLoad { key: String, reason: String },
+    /// Failed to read or deserialize a proof or its public input
+    #[error("Failed to load {name}. Error: {reason}")]
+    ProofDeserialize { name: String, reason: String },
+    /// Failed to decode or parse a transaction
+    #[error("Failed to parse {name}. Error: {reason}")]
+    TxParse { name: String, reason: String },
+    /// Failed to save a key, a proof or its public input
+    #[error("Failed to save {name}. Error: {reason}")]
+    Save { name: String, reason: String },
+    /// Failed to generate the keys
+    #[error("Failed to perform the setup. Error: {0}")]
+    Setup(String),
+    /// Failed to generate a proof
+    #[error("Failed to generate the proof. Error: {0}")]
+    Proving(String),
+    /// The spending tx does not have the shape expected by the PoB
+    #[error("Invalid spending tx: {0}")]
+    InvalidSpendingTx(String),
+    /// The transaction does not have the shape described by the configuration of the circuit
+    #[error("The tx does not match the circuit configuration: {0}")]
+    TxConfigMismatch(String),
+    /// The proving key and the verifying key were not generated by the same setup
+    #[error("The proving key and the verifying key do not match")]
+    KeyMismatch,
+    /// The verification algorithm failed (note: an invalid proof is not an error)
+    #[error("Failed to verify the proof. Error: {0}")]
+    Verification(String),
+}
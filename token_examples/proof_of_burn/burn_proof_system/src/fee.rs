@@ -0,0 +1,86 @@
+//! Estimate of the funds required by the burning transaction
+//! The unlocking script of the RefTx input (the Groth16 verifier of the PoB) is large, so the fee of the burning
+//! transaction is dominated by it. The funding input must cover the fee, otherwise the burning transaction is rejected
+
+use bitcoin_r1cs::constraints::tx::TxVarConfig;
+
+use crate::pob::Config;
+use crate::tx_field_array::var_int_len;
+
+/// Length of the unlocking script of a P2PKH input: `<signature> <compressed public key>`, with a signature of at most
+/// 72 bytes (plus the sighash flag)
+pub const P2PKH_UNLOCK_SCRIPT_LEN: usize = 1 + 73 + 1 + 33;
+
+/// Length of the serialisation of the burning transaction (with the shape described by [Config]), if the unlocking
+/// script of the RefTx input has length `len_reftx_unlock_script`
+/// The token and the funding inputs are assumed to spend P2PKH outputs
+pub fn burn_tx_len(len_reftx_unlock_script: usize) -> usize {
+    let inputs_len: usize = [P2PKH_UNLOCK_SCRIPT_LEN, len_reftx_unlock_script, P2PKH_UNLOCK_SCRIPT_LEN]
+        .iter()
+        .map(|len| 32 + 4 + var_int_len(*len) + len + 4) // Outpoint, unlocking script, sequence
+        .sum();
+    let outputs_len: usize = <Config as TxVarConfig>::LEN_LOCK_SCRIPTS
+        .iter()
+        .map(|len| 8 + var_int_len(*len) + len) // Amount, locking script
+        .sum();
+    4 + var_int_len(<Config as TxVarConfig>::N_INPUTS)
+        + inputs_len
+        + var_int_len(<Config as TxVarConfig>::N_OUTPUTS)
+        + outputs_len
+        + 4 // Version, locktime
+}
+
+/// Fee (in satoshis) of the burning transaction, if the unlocking script of the RefTx input has length
+/// `len_reftx_unlock_script` and the transaction pays `fee_rate` satoshis per kB
+/// The fee is computed as in `update_tx_balance` of `tx_engine_utils.py`
+pub fn burn_tx_fee(len_reftx_unlock_script: usize, fee_rate: u64) -> u64 {
+    burn_tx_len(len_reftx_unlock_script) as u64 * fee_rate / 1024 + 1
+}
+
+/// Minimum amount (in satoshis) of the funding input of the burning transaction, if the unlocking script of the
+/// RefTx input has length `len_reftx_unlock_script` and the transaction pays `fee_rate` satoshis per kB
+/// `update_tx_balance` of `tx_engine_utils.py` requires the change output to be strictly larger than the fee, so the
+/// funding input must cover the fee plus one satoshi
+pub fn min_funding_amount(len_reftx_unlock_script: usize, fee_rate: u64) -> u64 {
+    burn_tx_fee(len_reftx_unlock_script, fee_rate) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_funding_amount_scales_with_script() {
+        // Without the unlocking script of the RefTx input, the tx only contains the P2PKH inputs and the outputs
+        assert_eq!(burn_tx_len(0), 4 + 1 + 3 * 41 + 2 * P2PKH_UNLOCK_SCRIPT_LEN + 1 + 11 + 34 + 4);
+        assert_eq!(burn_tx_len(300_000) - burn_tx_len(0), 300_000 + 4);
+
+        // The estimate grows with the length of the script
+        let small = min_funding_amount(1_000, 10);
+        let large = min_funding_amount(300_000, 10);
+        assert!(large > small);
+        assert_eq!(large, burn_tx_len(300_000) as u64 * 10 / 1024 + 2);
+        assert!(min_funding_amount(600_000, 10) > large);
+
+        // The estimate grows with the fee rate
+        assert_eq!(min_funding_amount(300_000, 0), 2);
+        assert!(min_funding_amount(300_000, 100) >= 10 * (large - 2));
+    }
+
+    #[test]
+    fn test_min_funding_amount_boundary() {
+        // Check of `update_tx_balance` in `tx_engine_utils.py`: `amount > tx_size * fee_rate // 1024 + 1`
+        let is_funded = |amount: u64, tx_size: usize, fee_rate: u64| amount > tx_size as u64 * fee_rate / 1024 + 1;
+
+        // With fee rates 0 and 1024 the size times the fee rate is a multiple of 1024, with the others it is not
+        for len_script in [0, 1_000, 300_000] {
+            for fee_rate in [0, 1, 10, 500, 1024] {
+                let amount = min_funding_amount(len_script, fee_rate);
+                let tx_size = burn_tx_len(len_script);
+                assert!(is_funded(amount, tx_size, fee_rate), "{} {}", len_script, fee_rate);
+                assert!(!is_funded(amount - 1, tx_size, fee_rate), "{} {}", len_script, fee_rate);
+                assert_eq!(amount, burn_tx_fee(len_script, fee_rate) + 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use ark_crypto_primitives::SNARK;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_mnt6_753::{Fr as ScalarFieldMNT6, MNT6_753};
+use ark_pcd::variable_length_crh::pedersen::VariableLengthPedersenParameters;
+use ark_serialize::CanonicalDeserialize;
+
+use crate::error::TokenError;
+use crate::utils::read_from_file;
+
+/// Folder containing the keys of the TCP
+pub const TCP_KEYS_PATH: &str = "../tcp_proof_system/data/keys/";
+/// Folder containing the proofs of the TCP
+pub const TCP_PROOFS_PATH: &str = "../tcp_proof_system/data/proofs/";
+
+/// Read the key `key` of the TCP from `<TCP_KEYS_PATH>/<key>.bin`
+pub fn read_tcp_key(key: &str) -> Result<Vec<u8>, TokenError> {
+    read_from_file(&format!("{}{}.bin", TCP_KEYS_PATH, key)).map_err(|e| TokenError::KeyLoad {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Load the keys of the TCP needed by the PoB: the Pedersen parameters and the verifying key of the help circuit
+pub fn load_tcp_keys() -> Result<(VariableLengthPedersenParameters, VerifyingKey<MNT6_753>), TokenError> {
+    let crh_pp = VariableLengthPedersenParameters {
+        seed: read_tcp_key("crh_pp_seed")?,
+    };
+    let help_vk = VerifyingKey::<MNT6_753>::deserialize_unchecked(read_tcp_key("help_vk")?.as_slice())
+        .map_err(|e| TokenError::KeyLoad {
+            key: "help_vk".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    Ok((crh_pp, help_vk))
+}
+
+/// Load the TCP proof `tcp_proof_name` (a proof for the help circuit, over MNT6_753) and its processed input
+/// (saved by the TCP in `process` mode as `<tcp_proof_name>_processed_input.bin`)
+pub fn load_tcp_proof(
+    tcp_proof_name: &str,
+) -> Result<(Proof<MNT6_753>, Vec<ScalarFieldMNT6>), TokenError> {
+    let proof_name = tcp_proof_name.trim_end_matches(".bin");
+    let proof_error = |name: &str, reason: String| TokenError::ProofDeserialize {
+        name: name.to_string(),
+        reason,
+    };
+
+    let proof_bytes = read_from_file(&format!("{}{}.bin", TCP_PROOFS_PATH, proof_name))
+        .map_err(|e| proof_error("the TCP proof", e.to_string()))?;
+    let processed_input_bytes =
+        read_from_file(&format!("{}{}_processed_input.bin", TCP_PROOFS_PATH, proof_name))
+            .map_err(|e| proof_error("the processed input of the TCP proof", e.to_string()))?;
+
+    let proof = Proof::<MNT6_753>::deserialize_unchecked(proof_bytes.as_slice())
+        .map_err(|e| proof_error("the TCP proof", e.to_string()))?;
+    let processed_input = Vec::<ScalarFieldMNT6>::deserialize_unchecked(processed_input_bytes.as_slice())
+        .map_err(|e| proof_error("the processed input of the TCP proof", e.to_string()))?;
+
+    Ok((proof, processed_input))
+}
+
+/// Verify a standalone proof for the help circuit of the TCP (over MNT6_753) against its processed input
+/// This allows checking the inner half of the cycle independently of the PoB proof (over MNT4_753)
+pub fn verify_help_proof(
+    help_vk: &VerifyingKey<MNT6_753>,
+    processed_input: &[ScalarFieldMNT6],
+    proof: &Proof<MNT6_753>,
+) -> Result<bool, TokenError> {
+    Groth16::<MNT6_753>::verify(help_vk, processed_input, proof)
+        .map_err(|e| TokenError::Verification(format!("{:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+    use rand_chacha::ChaChaRng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    /// Circuit proving knowledge of a square root of the public input
+    #[derive(Clone)]
+    struct SquareRoot {
+        root: ScalarFieldMNT6,
+        square: ScalarFieldMNT6,
+    }
+
+    impl ConstraintSynthesizer<ScalarFieldMNT6> for SquareRoot {
+        fn generate_constraints(self, cs: ConstraintSystemRef<ScalarFieldMNT6>) -> Result<(), SynthesisError> {
+            let square = FpVar::new_input(cs.clone(), || Ok(self.square))?;
+            let root = FpVar::new_witness(cs, || Ok(self.root))?;
+            (&root * &root).enforce_equal(&square)
+        }
+    }
+
+    fn setup_and_prove() -> (VerifyingKey<MNT6_753>, Vec<ScalarFieldMNT6>, Proof<MNT6_753>) {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let circuit = SquareRoot {
+            root: ScalarFieldMNT6::from(3u64),
+            square: ScalarFieldMNT6::from(9u64),
+        };
+        let (pk, vk) = Groth16::<MNT6_753>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<MNT6_753>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+        (vk, vec![circuit.square], proof)
+    }
+
+    #[test]
+    fn test_missing_key_file() {
+        let missing_key = "missing_key_for_test";
+        assert!(matches!(
+            read_tcp_key(missing_key),
+            Err(TokenError::KeyLoad { key, .. }) if key == missing_key
+        ));
+    }
+
+    #[test]
+    fn test_valid_help_proof() {
+        let (vk, processed_input, proof) = setup_and_prove();
+        assert!(verify_help_proof(&vk, &processed_input, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_tampered_help_proof() {
+        let (vk, processed_input, proof) = setup_and_prove();
+
+        let mut tampered_proof = proof.clone();
+        tampered_proof.a = proof.c;
+        assert!(!verify_help_proof(&vk, &processed_input, &tampered_proof).unwrap());
+
+        let tampered_input = vec![processed_input[0] + ScalarFieldMNT6::from(1u64)];
+        assert!(!verify_help_proof(&vk, &tampered_input, &proof).unwrap());
+    }
+}
@@ -0,0 +1,291 @@
+//! Proof of Burn (PoB) as a library
+//! The functions below wire the RefTx circuit of the PoB, so that other Rust programs can generate and verify PoBs
+//! in-process. The CLI of the crate is a thin wrapper around them, which loads and saves the data to file
+
+use std::io::{Cursor, Read};
+
+use ark_crypto_primitives::SNARK;
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::PrimeField;
+use ark_groth16::{
+    Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey, prepare_verifying_key, verify_proof,
+};
+use ark_mnt4_753::{Fr as ScalarFieldMNT4, MNT4_753};
+use ark_mnt6_753::MNT6_753;
+use ark_pcd::variable_length_crh::pedersen::VariableLengthPedersenParameters;
+use bitcoin_r1cs::bitcoin_predicates::data_structures::proof::BitcoinProof;
+use bitcoin_r1cs::bitcoin_predicates::data_structures::unit::BitcoinUnit;
+use bitcoin_r1cs::reftx::RefTxCircuit;
+use bitcoin_r1cs::{
+    bitcoin_predicates::data_structures::field_array::FieldArray,
+    transaction_integrity_gadget::TransactionIntegrityScheme,
+};
+use chain_gang::script::Script;
+use chain_gang::transaction::sighash::SigHashCache;
+use chain_gang::{
+    messages::Tx,
+    util::{Hash256, Serializable},
+};
+use rand_chacha::rand_core::{CryptoRng, RngCore};
+use tracing::{info, instrument};
+
+use error::TokenError;
+use pob::{Config, PoB, check_spending_tx};
+use utils::key_from_serialisation;
+
+pub mod burn_commitment;
+pub mod cache;
+pub mod error;
+pub mod fee;
+pub mod help_proof;
+pub mod pob;
+pub mod proving_data;
+pub mod setup_data;
+pub mod tx_field_array;
+pub mod utils;
+pub mod verifying_data;
+
+/// Decode and parse the hex encoded transaction `tx`
+/// Both the standard serialisation and the witness (segwit-style, BIP144) serialisation are accepted. The witness
+/// data is dropped: the circuit works with the standard serialisation, from which the txid is computed, and the
+/// sighash committed to by the PoB does not depend on the witnesses, so the configuration of the circuit is unchanged
+pub fn read_tx(tx: &str) -> Result<Tx, TokenError> {
+    let tx_error = |reason: String| TokenError::TxParse {
+        name: "spending tx".to_string(),
+        reason,
+    };
+    let tx_bytes = hex::decode(tx).map_err(|e| tx_error(e.to_string()))?;
+    // Witness serialisation: version, marker `0x00`, flag (non-zero), inputs, outputs, witnesses, locktime
+    if tx_bytes.len() > 6 && tx_bytes[4] == 0x00 && tx_bytes[5] != 0x00 {
+        return read_witness_tx(&tx_bytes).map_err(tx_error);
+    }
+    Tx::read(&mut Cursor::new(tx_bytes)).map_err(|e| tx_error(e.to_string()))
+}
+
+/// Parse a transaction in witness serialisation, dropping the witnesses
+fn read_witness_tx(tx_bytes: &[u8]) -> Result<Tx, String> {
+    // Without marker and flag, the tx is parsed as a standard one up to the witnesses, which are read as the locktime
+    let mut stripped = tx_bytes[..4].to_vec();
+    stripped.extend(&tx_bytes[6..]);
+    let mut cursor = Cursor::new(stripped.as_slice());
+    let mut tx = Tx::read(&mut cursor).map_err(|e| e.to_string())?;
+    cursor.set_position(cursor.position() - 4);
+
+    let read_var_int = |cursor: &mut Cursor<&[u8]>| -> Result<u64, String> {
+        let mut prefix = [0u8; 1];
+        cursor.read_exact(&mut prefix).map_err(|e| e.to_string())?;
+        let len = match prefix[0] {
+            0xfd => 2,
+            0xfe => 4,
+            0xff => 8,
+            n => return Ok(n as u64),
+        };
+        let mut bytes = [0u8; 8];
+        cursor.read_exact(&mut bytes[..len]).map_err(|e| e.to_string())?;
+        Ok(u64::from_le_bytes(bytes))
+    };
+    // One stack of witness items per input
+    for _ in 0..tx.inputs.len() {
+        for _ in 0..read_var_int(&mut cursor)? {
+            let item_len = read_var_int(&mut cursor)?;
+            cursor.set_position(cursor.position() + item_len);
+        }
+    }
+
+    let mut lock_time = [0u8; 4];
+    cursor.read_exact(&mut lock_time).map_err(|e| e.to_string())?;
+    if cursor.position() != stripped.len() as u64 {
+        return Err("unexpected data after the locktime".to_string());
+    }
+    tx.lock_time = u32::from_le_bytes(lock_time);
+    Ok(tx)
+}
+
+/// Deserialize the proving key of the PoB, saved compressed or uncompressed
+pub fn load_pk(pk_serialised: &[u8]) -> Result<ProvingKey<MNT4_753>, TokenError> {
+    key_from_serialisation(pk_serialised).map_err(|e| TokenError::KeyLoad {
+        key: "pk".to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// RefTx circuit of the PoB for a RefTx input spending an output whose locking script has length
+/// `LEN_PREV_LOCK_SCRIPT`
+pub type PoBRefTx<const LEN_PREV_LOCK_SCRIPT: usize> =
+    RefTxCircuit<PoB<LEN_PREV_LOCK_SCRIPT>, ScalarFieldMNT4, Config<LEN_PREV_LOCK_SCRIPT>>;
+
+/// Build the dummy RefTx used to generate the keys of the PoB for a RefTx input spending an output whose locking
+/// script has length `LEN_PREV_LOCK_SCRIPT`
+/// The dummy only carries the shape of the circuit: its locking data, unlocking data and witness are defaults
+/// `crh_pp` and `help_vk` are the Pedersen parameters and the verifying key of the help circuit of the TCP
+pub fn dummy_reftx<const LEN_PREV_LOCK_SCRIPT: usize>(
+    crh_pp: &VariableLengthPedersenParameters,
+    help_vk: &VerifyingKey<MNT6_753>,
+) -> PoBRefTx<LEN_PREV_LOCK_SCRIPT> {
+    PoBRefTx::<LEN_PREV_LOCK_SCRIPT> {
+        locking_data: FieldArray::<1, ScalarFieldMNT4, Config<LEN_PREV_LOCK_SCRIPT>>::default(),
+        integrity_tag: None,
+        unlocking_data: BitcoinUnit::default(),
+        witness: BitcoinProof::new(&Proof::<MNT6_753>::default()),
+        spending_data: None,
+        prev_lock_script: None,
+        prev_amount: None,
+        sighash_cache: None,
+        predicate: PoB::<LEN_PREV_LOCK_SCRIPT>::new(crh_pp, help_vk, 0),
+    }
+}
+
+/// Generate the keys of the PoB for a RefTx input spending an output whose locking script has length
+/// `LEN_PREV_LOCK_SCRIPT`
+/// `crh_pp` and `help_vk` are the Pedersen parameters and the verifying key of the help circuit of the TCP
+#[instrument(skip_all, fields(len_prev_lock_script = LEN_PREV_LOCK_SCRIPT))]
+pub fn setup<const LEN_PREV_LOCK_SCRIPT: usize, R: RngCore + CryptoRng>(
+    crh_pp: &VariableLengthPedersenParameters,
+    help_vk: &VerifyingKey<MNT6_753>,
+    rng: &mut R,
+) -> Result<(ProvingKey<MNT4_753>, VerifyingKey<MNT4_753>), TokenError> {
+    Groth16::<MNT4_753>::circuit_specific_setup(dummy_reftx::<LEN_PREV_LOCK_SCRIPT>(crh_pp, help_vk), rng)
+        .map_err(|e| TokenError::Setup(format!("{:?}", e)))
+}
+
+/// Build the RefTx of the PoB for the RefTx input of `spending_tx`, spending an output locked by `prev_lock_script`
+/// (of length `LEN_PREV_LOCK_SCRIPT`) with amount `prev_amount`
+/// `tcp_proof` is the TCP proof of the token with genesis `genesis_txid` (hex encoded), and `crh_pp` and `help_vk`
+/// are the keys of the TCP it was generated with
+#[allow(clippy::too_many_arguments)]
+pub fn reftx<const LEN_PREV_LOCK_SCRIPT: usize>(
+    crh_pp: &VariableLengthPedersenParameters,
+    help_vk: &VerifyingKey<MNT6_753>,
+    tcp_proof: &Proof<MNT6_753>,
+    genesis_txid: &str,
+    spending_tx: Tx,
+    prev_lock_script: Script,
+    prev_amount: u64,
+) -> Result<PoBRefTx<LEN_PREV_LOCK_SCRIPT>, TokenError> {
+    let genesis_txid = Hash256::decode(genesis_txid).map_err(|e| TokenError::TxParse {
+        name: "genesis txid".to_string(),
+        reason: e.to_string(),
+    })?;
+    let genesis_txid = FieldArray::<1, ScalarFieldMNT4, Config<LEN_PREV_LOCK_SCRIPT>>::new([
+        ScalarFieldMNT4::from_le_bytes_mod_order(&genesis_txid.0),
+    ]);
+
+    // PoB
+    let pob = PoB::<LEN_PREV_LOCK_SCRIPT>::new(crh_pp, help_vk, 0);
+
+    // Tag
+    let tag = TransactionIntegrityScheme::<Config<LEN_PREV_LOCK_SCRIPT>>::commit(
+        &spending_tx,
+        &prev_lock_script,
+        prev_amount,
+        &mut SigHashCache::new(),
+    );
+
+    Ok(PoBRefTx::<LEN_PREV_LOCK_SCRIPT> {
+        locking_data: genesis_txid,
+        integrity_tag: Some(tag),
+        unlocking_data: BitcoinUnit::default(),
+        witness: BitcoinProof::new(tcp_proof),
+        spending_data: Some(spending_tx),
+        prev_lock_script: Some(prev_lock_script),
+        prev_amount: Some(prev_amount),
+        sighash_cache: None,
+        predicate: pob,
+    })
+}
+
+/// Generate a PoB for the RefTx input of `spending_tx`, spending an output locked by `prev_lock_script` (of length
+/// `LEN_PREV_LOCK_SCRIPT`) with amount `prev_amount`
+/// `tcp_proof` is the TCP proof of the token with genesis `genesis_txid` (hex encoded), and `crh_pp` and `help_vk`
+/// are the keys of the TCP it was generated with
+/// Return the proof and its public input
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip_all, fields(len_prev_lock_script = LEN_PREV_LOCK_SCRIPT, genesis_txid, prev_amount))]
+pub fn prove<const LEN_PREV_LOCK_SCRIPT: usize, R: RngCore + CryptoRng>(
+    pk: &ProvingKey<MNT4_753>,
+    crh_pp: &VariableLengthPedersenParameters,
+    help_vk: &VerifyingKey<MNT6_753>,
+    tcp_proof: &Proof<MNT6_753>,
+    genesis_txid: &str,
+    spending_tx: Tx,
+    prev_lock_script: Script,
+    prev_amount: u64,
+    rng: &mut R,
+) -> Result<(Proof<MNT4_753>, Vec<ScalarFieldMNT4>), TokenError> {
+    // Catch malformed spending txs before the expensive proof generation
+    check_spending_tx(&spending_tx)?;
+    let reftx = reftx::<LEN_PREV_LOCK_SCRIPT>(
+        crh_pp,
+        help_vk,
+        tcp_proof,
+        genesis_txid,
+        spending_tx,
+        prev_lock_script,
+        prev_amount,
+    )?;
+
+    let public_input = reftx.public_input();
+    let proof =
+        Groth16::<MNT4_753>::prove(pk, reftx, rng).map_err(|e| TokenError::Proving(format!("{:?}", e)))?;
+    info!("proof generated");
+
+    Ok((proof, public_input))
+}
+
+/// Verify a PoB against its public input
+pub fn verify(
+    vk: &VerifyingKey<MNT4_753>,
+    public_input: &[ScalarFieldMNT4],
+    proof: &Proof<MNT4_753>,
+) -> Result<bool, TokenError> {
+    verify_prepared(&prepare(vk), public_input, proof)
+}
+
+/// Prepare the verifying key of the PoB, precomputing the parts of the verification that only depend on the key
+/// A verifier checking many PoBs should prepare the key once and verify them with [verify_prepared]
+pub fn prepare(vk: &VerifyingKey<MNT4_753>) -> PreparedVerifyingKey<MNT4_753> {
+    prepare_verifying_key(vk)
+}
+
+/// Verify a PoB against its public input with the prepared verifying key `pvk`
+#[instrument(skip_all, fields(n_public_inputs = public_input.len()))]
+pub fn verify_prepared(
+    pvk: &PreparedVerifyingKey<MNT4_753>,
+    public_input: &[ScalarFieldMNT4],
+    proof: &Proof<MNT4_753>,
+) -> Result<bool, TokenError> {
+    let is_valid =
+        verify_proof(pvk, proof, public_input).map_err(|e| TokenError::Verification(format!("{:?}", e)))?;
+    info!(is_valid, "proof verified");
+
+    Ok(is_valid)
+}
+
+/// Check that the proving key `pk` and the verifying key `vk` of the PoB were generated by the same setup
+/// A PoB cannot be generated without a valid witness (a TCP proof and a spending tx), so the keys are checked
+/// directly: the verifying key embedded in `pk` must be `vk`, and the elements of `pk` in G1 must match the elements
+/// of `vk` in G2, i.e., `e(beta_g1, g2) = e(g1, beta_g2)` and `e(delta_g1, g2) = e(g1, delta_g2)`
+pub fn check_keys(pk: &ProvingKey<MNT4_753>, vk: &VerifyingKey<MNT4_753>) -> bool {
+    let g1 = <MNT4_753 as PairingEngine>::G1Affine::prime_subgroup_generator();
+    let g2 = <MNT4_753 as PairingEngine>::G2Affine::prime_subgroup_generator();
+
+    pk.vk == *vk
+        && MNT4_753::pairing(pk.beta_g1, g2) == MNT4_753::pairing(g1, vk.beta_g2)
+        && MNT4_753::pairing(pk.delta_g1, g2) == MNT4_753::pairing(g1, vk.delta_g2)
+}
+
+/// Compute the integrity tag committed to by the PoB (the sighash of the RefTx input), hex encoded in the order in
+/// which it is committed to
+pub fn integrity_tag<const LEN_PREV_LOCK_SCRIPT: usize>(
+    spending_tx: &Tx,
+    prev_lock_script: &Script,
+    prev_amount: u64,
+) -> String {
+    let tag = TransactionIntegrityScheme::<Config<LEN_PREV_LOCK_SCRIPT>>::commit(
+        spending_tx,
+        prev_lock_script,
+        prev_amount,
+        &mut SigHashCache::new(),
+    );
+    hex::encode(tag.0)
+}
@@ -1,199 +1,259 @@
 use std::io::Cursor;
 
 use anyhow::anyhow;
-use ark_crypto_primitives::SNARK;
-use ark_ff::PrimeField;
-use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_groth16::{Proof, VerifyingKey};
 use ark_mnt4_753::{Fr as ScalarFieldMNT4, MNT4_753};
 use ark_mnt6_753::MNT6_753;
-use ark_pcd::variable_length_crh::pedersen::VariableLengthPedersenParameters;
 use ark_serialize::CanonicalDeserialize;
-use bitcoin_r1cs::bitcoin_predicates::data_structures::proof::BitcoinProof;
-use bitcoin_r1cs::bitcoin_predicates::data_structures::unit::BitcoinUnit;
-use bitcoin_r1cs::reftx::RefTxCircuit;
-use bitcoin_r1cs::{
-    bitcoin_predicates::data_structures::field_array::FieldArray,
-    transaction_integrity_gadget::TransactionIntegrityScheme,
+use burn_proof_system::error::TokenError;
+use burn_proof_system::fee::{burn_tx_len, min_funding_amount};
+use burn_proof_system::help_proof::{TCP_PROOFS_PATH, load_tcp_keys, load_tcp_proof, verify_help_proof};
+use burn_proof_system::pob::{CHECKSIG_LEN, P2PKH_LEN, check_spending_tx};
+use burn_proof_system::proving_data::ProvingData;
+use burn_proof_system::setup_data::SetupData;
+use burn_proof_system::utils::{
+    KeyFormat, data_to_serialisation, fingerprint, key_to_serialisation, read_from_file, save_to_file,
 };
-use chain_gang::script::op_codes::OP_CHECKSIG;
+use burn_proof_system::verifying_data::VerifyingBundle;
+use burn_proof_system::{check_keys, integrity_tag, load_pk, read_tx, verify};
 use chain_gang::script::Script;
-use chain_gang::transaction::sighash::SigHashCache;
-use chain_gang::{
-    messages::Tx,
-    util::{Hash256, Serializable},
-};
 use clap::Parser;
 use cli::{Cli, Commands};
-use pob::{Config, PoB};
-use proving_data::ProvingData;
 use rand_chacha::ChaChaRng;
 use rand_chacha::rand_core::SeedableRng;
-use utils::{data_to_serialisation, read_from_file, save_to_file};
+use tracing_subscriber::EnvFilter;
 
 mod cli;
-mod pob;
-mod proving_data;
-mod utils;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
+    // Logs (spans and events of setup, prove and verify) are written to stderr, filtered by `RUST_LOG`
+    // (e.g., `RUST_LOG=info`), so that they do not mix with the result printed by `verify --stdin`
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Setup => {
-            // Load the key of the TCP
-            let crh_pp_seed_bytes = read_from_file("../tcp_proof_system/data/keys/crh_pp_seed.bin")
-                .map_err(|e| anyhow!("Failed to read crh_pp. Error: {}", e))
-                .unwrap();
-            let help_vk_bytes = read_from_file("../tcp_proof_system/data/keys/help_vk.bin")
-                .map_err(|e: std::io::Error| anyhow!("Failed to read help_vk. Error: {}", e))
-                .unwrap();
-
-            let crh_pp = VariableLengthPedersenParameters {
-                seed: crh_pp_seed_bytes,
-            };
-            let help_vk = VerifyingKey::<MNT6_753>::deserialize_unchecked(help_vk_bytes.as_slice())
-                .map_err(|e| anyhow!("Failed to deserialize help_vk. Error: {}", e))
-                .unwrap();
-
-            // PoB
-            let pob = PoB::new(&crh_pp, &help_vk, 0);
-            // Dummy RefTx
-            let dummy_reftx = RefTxCircuit::<PoB, ScalarFieldMNT4, Config> {
-                locking_data: FieldArray::<1, ScalarFieldMNT4, Config>::default(),
-                integrity_tag: None,
-                unlocking_data: BitcoinUnit::default(),
-                witness: BitcoinProof::new(&Proof::<MNT6_753>::default()),
-                spending_data: None,
-                prev_lock_script: None,
-                prev_amount: None,
-                sighash_cache: None,
-                predicate: pob,
+        Commands::Setup { compressed, p2pkh, config } => {
+            let len_prev_lock_script = match config {
+                Some(config) => SetupData::load(&config)?.prev_lock_script.to_script()?.0.len(),
+                None if p2pkh => P2PKH_LEN,
+                None => CHECKSIG_LEN,
             };
-
-            // Setup
-            let mut rng = ChaChaRng::from_entropy();
-            let (pk, vk) = Groth16::<MNT4_753>::circuit_specific_setup(dummy_reftx, &mut rng).unwrap();
-
-            // Save keys
-            save_to_file(&data_to_serialisation(&pk), "data/keys/pk.bin").unwrap();
-            save_to_file(&data_to_serialisation(&vk), "data/keys/vk.bin").unwrap();
+            match len_prev_lock_script {
+                CHECKSIG_LEN => setup::<CHECKSIG_LEN>(compressed)?,
+                P2PKH_LEN => setup::<P2PKH_LEN>(compressed)?,
+                len => Err(TokenError::TxParse {
+                    name: "previous locking script".to_string(),
+                    reason: format!("unsupported length {}, expected {} or {}", len, CHECKSIG_LEN, P2PKH_LEN),
+                })?,
+            }
         },
     Commands::Prove => {
-        let proving_data = ProvingData::load("proving_data.toml").unwrap();
-        let genesis_txid = FieldArray::<1, ScalarFieldMNT4, Config>::new([
-            ScalarFieldMNT4::from_le_bytes_mod_order(
-                &Hash256::decode(&proving_data.genesis_txid).unwrap().0,
-            ),
-        ]);
-        let spending_tx = Tx::read(&mut Cursor::new(
-            hex::decode(proving_data.spending_tx)
-                .map_err(|e| anyhow!("Failed to hex decode witness tx. Error: {}", e))
-                .unwrap(),
-        ))
-        .map_err(|e| anyhow!("Failed to read witness tx. Error: {}", e))
-        .unwrap();
-        let tcp_proof = Proof::<MNT6_753>::deserialize_unchecked(Cursor::new(
-            read_from_file(&format!("../tcp_proof_system/data/proofs/{}", proving_data.tcp_proof_name))
-                .map_err(|e| anyhow!("Failed to read prior proof. Error: {}", e))
-                .unwrap(),
-        ))
-        .unwrap();
-
-        // Load the key of the TCP
-        let crh_pp_seed_bytes = read_from_file("../tcp_proof_system/data/keys/crh_pp_seed.bin")
-            .map_err(|e| anyhow!("Failed to read crh_pp. Error: {}", e))
-            .unwrap();
-        let help_vk_bytes = read_from_file("../tcp_proof_system/data/keys/help_vk.bin")
-            .map_err(|e: std::io::Error| anyhow!("Failed to read help_vk. Error: {}", e))
-            .unwrap();
-
-        let crh_pp = VariableLengthPedersenParameters {
-            seed: crh_pp_seed_bytes,
-        };
-        let help_vk = VerifyingKey::<MNT6_753>::deserialize_unchecked(help_vk_bytes.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize help_vk. Error: {}", e))
-            .unwrap();
-
-        // PoB
-        let pob = PoB::new(&crh_pp, &help_vk, 0);
-
-        // Tag
-        let tag = TransactionIntegrityScheme::<Config>::commit(
-            &spending_tx,
-            &Script(vec![OP_CHECKSIG]),
-            proving_data.prev_amount,
-            &mut SigHashCache::new(),
-        );
-
-        // RefTx
-        let reftx = RefTxCircuit::<PoB, ScalarFieldMNT4, Config> {
-            locking_data: genesis_txid,
-            integrity_tag: Some(tag),
-            unlocking_data: BitcoinUnit::default(),
-            witness: BitcoinProof::new(&tcp_proof),
-            spending_data: Some(spending_tx),
-            prev_lock_script: Some(Script(vec![OP_CHECKSIG])),
-            prev_amount: Some(proving_data.prev_amount),
-            sighash_cache: None,
-            predicate: pob,
+        let proving_data = ProvingData::load("proving_data.toml")?;
+        let prev_lock_script = proving_data.prev_lock_script.to_script()?;
+        match prev_lock_script.0.len() {
+            CHECKSIG_LEN => prove::<CHECKSIG_LEN>(proving_data, prev_lock_script)?,
+            P2PKH_LEN => prove::<P2PKH_LEN>(proving_data, prev_lock_script)?,
+            len => Err(TokenError::TxParse {
+                name: "previous locking script".to_string(),
+                reason: format!("unsupported length {}, expected {} or {}", len, CHECKSIG_LEN, P2PKH_LEN),
+            })?,
+        }
+    },
+    Commands::Verify { stdin } => {
+        // Load the serialisations of the vk of RefTx, the public input and the proof
+        let (vk_serialised, public_input_serialised, proof_serialised) = if stdin {
+            let bundle = VerifyingBundle::load(std::io::stdin().lock())?;
+            (
+                bundle.decode("vk", &bundle.vk)?,
+                bundle.decode("public input", &bundle.public_input)?,
+                bundle.decode("proof", &bundle.proof)?,
+            )
+        } else {
+            (
+                read_from_file("data/keys/vk.bin")
+                    .map_err(|e: std::io::Error| anyhow!("Failed to read vk. Error: {}", e))?,
+                read_from_file("data/proofs/input_proof_of_burn.bin")
+                    .map_err(|e: std::io::Error| anyhow!("Failed to read public input. Error: {}", e))?,
+                read_from_file("data/proofs/proof_of_burn.bin")
+                    .map_err(|e: std::io::Error| anyhow!("Failed to read proof. Error: {}", e))?,
+            )
         };
 
-        // Load key of RefTx
-        let pk_serialised = read_from_file("data/keys/pk.bin")
-            .map_err(|e: std::io::Error| anyhow!("Failed to read pk. Error: {}", e))
-            .unwrap();
-        let pk = ProvingKey::<MNT4_753>::deserialize_unchecked(pk_serialised.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize pk. Error: {}", e))
-            .unwrap();
-
-        // Save the public input
-        save_to_file(
-            data_to_serialisation(&reftx.public_input()).as_slice(),
-            "data/proofs/input_proof_of_burn.bin",
-        )
-        .unwrap();
+        let vk = VerifyingKey::<MNT4_753>::deserialize_unchecked(vk_serialised.as_slice())
+            .map_err(|e| anyhow!("Failed to deserialize vk. Error: {}", e))?;
+        let public_input =
+            Vec::<ScalarFieldMNT4>::deserialize_unchecked(public_input_serialised.as_slice())
+                .map_err(|e| anyhow!("Failed to deserialize public input. Error: {}", e))?;
+        let proof = Proof::<MNT4_753>::deserialize_unchecked(proof_serialised.as_slice())
+            .map_err(|e| anyhow!("Failed to deserialize proof. Error: {}", e))?;
 
-        // Proof
-        let mut rng = ChaChaRng::from_entropy();
-        let proof = Groth16::<MNT4_753>::prove(&pk, reftx, &mut rng).unwrap();
+        let is_valid = verify(&vk, &public_input, &proof)?;
 
-        // Save the proof
-        save_to_file(
-            &data_to_serialisation(&proof),
-            "data/proofs/proof_of_burn.bin",
-        )
-        .unwrap();
+        if stdin {
+            // Stateless mode: print the result and report it through the exit code
+            println!("{}", is_valid);
+            std::process::exit(if is_valid { 0 } else { 1 });
+        }
+        assert!(is_valid, "\nProof not valid.\n");
+        println!("\nValid proof.\n")
+    },
+    Commands::VerifyTcp => {
+        // Verify the TCP proof (the help proof, over MNT6_753) on its own, to check the inner half of the cycle
+        let proving_data = ProvingData::load("proving_data.toml")?;
+        let (_crh_pp, help_vk) = load_tcp_keys()?;
+        let (tcp_proof, processed_input) = load_tcp_proof(&proving_data.tcp_proof_name)?;
+
+        let is_valid = verify_help_proof(&help_vk, &processed_input, &tcp_proof)?;
+
+        assert!(is_valid, "\nTCP proof not valid.\n");
+        println!("\nValid TCP proof.\n")
     },
-    Commands::Verify => {
+    Commands::Fingerprint => {
         // Load vk of RefTx
-        let vk_serialised = read_from_file("data/keys/vk.bin")
-            .map_err(|e: std::io::Error| anyhow!("Failed to read vk. Error: {}", e))
-            .unwrap();
+        let key_error = |reason: String| TokenError::KeyLoad {
+            key: "vk".to_string(),
+            reason,
+        };
+        let vk_serialised = read_from_file("data/keys/vk.bin").map_err(|e| key_error(e.to_string()))?;
         let vk = VerifyingKey::<MNT4_753>::deserialize_unchecked(vk_serialised.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize vk. Error: {}", e))
-            .unwrap();
+            .map_err(|e| key_error(e.to_string()))?;
 
-        // Load the public input
-        let public_input_serialised = read_from_file("data/proofs/input_proof_of_burn.bin")
-            .map_err(|e: std::io::Error| anyhow!("Failed to read public input. Error: {}", e))
-            .unwrap();
-        let public_input =
-            Vec::<ScalarFieldMNT4>::deserialize_unchecked(public_input_serialised.as_slice())
-                .map_err(|e| anyhow!("Failed to deserialize public input. Error: {}", e))
-                .unwrap();
+        println!("\nVerifying key fingerprint: {}\n", fingerprint(&[data_to_serialisation(&vk)]))
+    },
+    Commands::CheckKeys => {
+        // Load pk and vk of RefTx
+        let pk = load_pk(&read_from_file("data/keys/pk.bin").map_err(|e| TokenError::KeyLoad {
+            key: "pk".to_string(),
+            reason: e.to_string(),
+        })?)?;
+        let key_error = |reason: String| TokenError::KeyLoad {
+            key: "vk".to_string(),
+            reason,
+        };
+        let vk_serialised = read_from_file("data/keys/vk.bin").map_err(|e| key_error(e.to_string()))?;
+        let vk = VerifyingKey::<MNT4_753>::deserialize_unchecked(vk_serialised.as_slice())
+            .map_err(|e| key_error(e.to_string()))?;
 
-        // Load the proof
-        let proof_serialised = read_from_file("data/proofs/proof_of_burn.bin")
-            .map_err(|e: std::io::Error| anyhow!("Failed to read proof. Error: {}", e))
-            .unwrap();
-        let proof = Proof::<MNT4_753>::deserialize_unchecked(proof_serialised.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize proof. Error: {}", e))
-            .unwrap();
+        if !check_keys(&pk, &vk) {
+            Err(TokenError::KeyMismatch)?
+        }
+        println!("\nThe proving key and the verifying key match.\n")
+    },
+    Commands::Tag { tx, prev_lock_script, prev_amount } => {
+        let spending_tx = read_tx(&tx)?;
+        let prev_lock_script = Script(hex::decode(prev_lock_script).map_err(|e| TokenError::TxParse {
+            name: "previous locking script".to_string(),
+            reason: e.to_string(),
+        })?);
 
-        let is_valid = Groth16::<MNT4_753>::verify(&vk, &public_input, &proof).unwrap();
+        // The circuit supports the locking scripts whose length has a configuration
+        let tag = match prev_lock_script.0.len() {
+            CHECKSIG_LEN => integrity_tag::<CHECKSIG_LEN>(&spending_tx, &prev_lock_script, prev_amount),
+            P2PKH_LEN => integrity_tag::<P2PKH_LEN>(&spending_tx, &prev_lock_script, prev_amount),
+            len => Err(TokenError::TxParse {
+                name: "previous locking script".to_string(),
+                reason: format!("unsupported length {}, expected {} or {}", len, CHECKSIG_LEN, P2PKH_LEN),
+            })?,
+        };
+        println!("{}", tag)
+    },
+    Commands::Funding { script, fee_rate } => {
+        let script_error = |reason: String| TokenError::DataLoad {
+            name: "spending script".to_string(),
+            reason,
+        };
+        let script = std::fs::read_to_string(&script).map_err(|e| script_error(e.to_string()))?;
+        let len_script = hex::decode(script.trim()).map_err(|e| script_error(e.to_string()))?.len();
 
-        assert!(is_valid, "\nProof not valid.\n");
-        println!("\nValid proof.\n")
+        println!(
+            "\nMinimum funding amount: {} satoshis (estimated size of the burning tx: {} bytes)\n",
+            min_funding_amount(len_script, fee_rate),
+            burn_tx_len(len_script)
+        )
     }
 }
-}
\ No newline at end of file
+
+    Ok(())
+}
+
+/// Generate the keys of the PoB for a RefTx input spending an output whose locking script has length
+/// `LEN_PREV_LOCK_SCRIPT`, and save them to file
+fn setup<const LEN_PREV_LOCK_SCRIPT: usize>(compressed: bool) -> anyhow::Result<()> {
+    // Load the key of the TCP
+    let (crh_pp, help_vk) = load_tcp_keys()?;
+
+    // Setup
+    let mut rng = ChaChaRng::from_entropy();
+    let (pk, vk) = burn_proof_system::setup::<LEN_PREV_LOCK_SCRIPT, _>(&crh_pp, &help_vk, &mut rng)?;
+
+    // Save keys
+    // The verifying key is always saved uncompressed, as it is read by the Python scripts
+    let key_format = if compressed { KeyFormat::Compressed } else { KeyFormat::Uncompressed };
+    save_to_file(&key_to_serialisation(&pk, key_format), "data/keys/pk.bin").map_err(|e| TokenError::Save {
+        name: "pk".to_string(),
+        reason: e.to_string(),
+    })?;
+    save_to_file(&data_to_serialisation(&vk), "data/keys/vk.bin").map_err(|e| TokenError::Save {
+        name: "vk".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    Ok(())
+}
+
+/// Generate a PoB for a RefTx input spending an output locked by `prev_lock_script`, of length
+/// `LEN_PREV_LOCK_SCRIPT`, and save the proof and its public input to file
+fn prove<const LEN_PREV_LOCK_SCRIPT: usize>(proving_data: ProvingData, prev_lock_script: Script) -> anyhow::Result<()> {
+    let spending_tx = read_tx(&proving_data.spending_tx)?;
+    // Catch malformed spending txs before loading the keys
+    check_spending_tx(&spending_tx)?;
+    let tcp_proof_error = |reason: String| TokenError::ProofDeserialize {
+        name: "the TCP proof".to_string(),
+        reason,
+    };
+    let tcp_proof = Proof::<MNT6_753>::deserialize_unchecked(Cursor::new(
+        read_from_file(&format!("{}{}", TCP_PROOFS_PATH, proving_data.tcp_proof_name))
+            .map_err(|e| tcp_proof_error(e.to_string()))?,
+    ))
+    .map_err(|e| tcp_proof_error(e.to_string()))?;
+
+    // Load the key of the TCP
+    let (crh_pp, help_vk) = load_tcp_keys()?;
+
+    // Load key of RefTx
+    let pk = load_pk(&read_from_file("data/keys/pk.bin").map_err(|e| TokenError::KeyLoad {
+        key: "pk".to_string(),
+        reason: e.to_string(),
+    })?)?;
+
+    // Proof
+    let mut rng = ChaChaRng::from_entropy();
+    let (proof, public_input) = burn_proof_system::prove::<LEN_PREV_LOCK_SCRIPT, _>(
+        &pk,
+        &crh_pp,
+        &help_vk,
+        &tcp_proof,
+        &proving_data.genesis_txid,
+        spending_tx,
+        prev_lock_script,
+        proving_data.prev_amount,
+        &mut rng,
+    )?;
+
+    // Save the public input and the proof
+    save_to_file(&data_to_serialisation(&public_input), "data/proofs/input_proof_of_burn.bin").map_err(|e| {
+        TokenError::Save {
+            name: "public input".to_string(),
+            reason: e.to_string(),
+        }
+    })?;
+    save_to_file(&data_to_serialisation(&proof), "data/proofs/proof_of_burn.bin").map_err(|e| TokenError::Save {
+        name: "proof".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    Ok(())
+}
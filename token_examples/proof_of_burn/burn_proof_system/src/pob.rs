@@ -14,8 +14,14 @@ use bitcoin_r1cs::{
     bitcoin_predicates::proof_of_burn::ProofOfBurn, constraints::tx::TxVarConfig,
     transaction_integrity_gadget::TransactionIntegrityConfig,
 };
+use chain_gang::messages::Tx;
+use chain_gang::script::Script;
+use chain_gang::script::op_codes::{OP_0, OP_CHECKSIG, OP_DUP, OP_EQUALVERIFY, OP_HASH160, OP_RETURN};
 use chain_gang::transaction::sighash::{SIGHASH_ALL, SIGHASH_FORKID};
 use rand_chacha::ChaChaRng;
+use serde::Deserialize;
+
+use crate::error::TokenError;
 
 pub struct PCDGroth16;
 impl ECCyclePCDConfig<ScalarFieldMNT4, ScalarFieldMNT6> for PCDGroth16 {
@@ -28,20 +34,184 @@ impl ECCyclePCDConfig<ScalarFieldMNT4, ScalarFieldMNT6> for PCDGroth16 {
     type HelpSNARKGadget = Groth16VerifierGadget<MNT6_753, MNT6PairingVar>;
 }
 
+/// Length of the locking script `OP_CHECKSIG`
+pub const CHECKSIG_LEN: usize = 1;
+/// Length of a P2PKH locking script
+pub const P2PKH_LEN: usize = 25;
+
+/// Configuration of the PoB, parameterised by the length of the locking script of the output spent by the RefTx input
+/// The circuit depends on the length, so the keys must be generated for the same configuration used to prove
 #[derive(Clone)]
-pub struct Config;
+pub struct Config<const LEN_PREV_LOCK_SCRIPT: usize = CHECKSIG_LEN>;
 
-impl TxVarConfig for Config {
+impl<const LEN_PREV_LOCK_SCRIPT: usize> TxVarConfig for Config<LEN_PREV_LOCK_SCRIPT> {
     const N_INPUTS: usize = 3; // Token to be burnt,  RefTx input, funds
     const N_OUTPUTS: usize = 2; // Burnt token, change
     const LEN_UNLOCK_SCRIPTS: &[usize] = &[0, 0, 0];
     const LEN_LOCK_SCRIPTS: &[usize] = &[0x02, 0x19]; // OP_0 OP_RETURN, P2PKH
 }
 
-impl TransactionIntegrityConfig for Config {
-    const LEN_PREV_LOCK_SCRIPT: usize = 1; // OP_CHECKSIG
+impl<const LEN_PREV_LOCK_SCRIPT: usize> TransactionIntegrityConfig for Config<LEN_PREV_LOCK_SCRIPT> {
+    const LEN_PREV_LOCK_SCRIPT: usize = LEN_PREV_LOCK_SCRIPT; // e.g., OP_CHECKSIG or P2PKH
     const N_INPUT: usize = 1; // Reftx input is the second one
     const SIGHASH_FLAG: u8 = SIGHASH_ALL | SIGHASH_FORKID;
 }
 
-pub type PoB = ProofOfBurn<ScalarFieldMNT4, ScalarFieldMNT6, PCDGroth16, Config>;
+pub type PoB<const LEN_PREV_LOCK_SCRIPT: usize = CHECKSIG_LEN> =
+    ProofOfBurn<ScalarFieldMNT4, ScalarFieldMNT6, PCDGroth16, Config<LEN_PREV_LOCK_SCRIPT>>;
+
+/// Locking script of the output spent by the RefTx input
+/// In `proving_data.toml`, it is given as `prev_lock_script = "checksig"` (the default),
+/// `prev_lock_script = { p2pkh = "<hex encoded hash of the public key>" }` or
+/// `prev_lock_script = { hex = "<hex encoded locking script>" }`
+/// The circuit only has configurations for locking scripts of length [CHECKSIG_LEN] and [P2PKH_LEN]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrevLockScript {
+    /// `OP_CHECKSIG`
+    #[default]
+    CheckSig,
+    /// `OP_DUP OP_HASH160 <pubkey_hash> OP_EQUALVERIFY OP_CHECKSIG`
+    P2pkh(String),
+    /// Any locking script, hex encoded
+    Hex(String),
+}
+
+impl PrevLockScript {
+    /// Return the locking script
+    pub fn to_script(&self) -> Result<Script, TokenError> {
+        match self {
+            PrevLockScript::CheckSig => Ok(Script(vec![OP_CHECKSIG])),
+            PrevLockScript::P2pkh(pubkey_hash) => {
+                let pubkey_hash_error = |reason: String| TokenError::TxParse {
+                    name: "public key hash".to_string(),
+                    reason,
+                };
+                let pubkey_hash = hex::decode(pubkey_hash).map_err(|e| pubkey_hash_error(e.to_string()))?;
+                if pubkey_hash.len() != 20 {
+                    return Err(pubkey_hash_error(format!("expected 20 bytes, got {}", pubkey_hash.len())));
+                }
+                let mut script = vec![OP_DUP, OP_HASH160, 20];
+                script.extend(pubkey_hash);
+                script.extend([OP_EQUALVERIFY, OP_CHECKSIG]);
+                Ok(Script(script))
+            },
+            PrevLockScript::Hex(script) => Ok(Script(hex::decode(script).map_err(|e| TokenError::TxParse {
+                name: "previous locking script".to_string(),
+                reason: e.to_string(),
+            })?)),
+        }
+    }
+}
+
+/// Check that `spending_tx` has the shape described by [Config]: `N_INPUTS` inputs, the burnt token output locked by
+/// `OP_0 OP_RETURN`, and the change output locked by a P2PKH script
+pub fn check_spending_tx(spending_tx: &Tx) -> Result<(), TokenError> {
+    let n_inputs = <Config as TxVarConfig>::N_INPUTS;
+    let n_outputs = <Config as TxVarConfig>::N_OUTPUTS;
+    if spending_tx.inputs.len() != n_inputs {
+        return Err(TokenError::InvalidSpendingTx(format!(
+            "expected {} inputs, got {}",
+            n_inputs,
+            spending_tx.inputs.len()
+        )));
+    }
+    if spending_tx.outputs.len() != n_outputs {
+        return Err(TokenError::InvalidSpendingTx(format!(
+            "expected {} outputs, got {}",
+            n_outputs,
+            spending_tx.outputs.len()
+        )));
+    }
+
+    let burn_script = &spending_tx.outputs[0].lock_script.0;
+    if burn_script.as_slice() != [OP_0, OP_RETURN] {
+        return Err(TokenError::InvalidSpendingTx(format!(
+            "output 0 must be locked by OP_0 OP_RETURN, got {}",
+            hex::encode(burn_script)
+        )));
+    }
+
+    let change_script = &spending_tx.outputs[1].lock_script.0;
+    let is_p2pkh = change_script.len() == P2PKH_LEN
+        && change_script[..3] == [OP_DUP, OP_HASH160, 20]
+        && change_script[23..] == [OP_EQUALVERIFY, OP_CHECKSIG];
+    if !is_p2pkh {
+        return Err(TokenError::InvalidSpendingTx(format!(
+            "output 1 must be locked by a P2PKH script, got {}",
+            hex::encode(change_script)
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use chain_gang::util::Serializable;
+
+    use super::*;
+
+    /// Spending tx of `proving_data.toml`
+    const SPENDING_TX: &str = "010000000340fd94950241edad515321861661a1a6f0717dbfbaefb8d2d3f7f3fa8c46ad45000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d112000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d11201000000000000000002000000000000000002006aecef0200000000001976a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac00000000";
+
+    fn read_tx(tx: &str) -> Tx {
+        Tx::read(&mut Cursor::new(hex::decode(tx).unwrap())).unwrap()
+    }
+
+    #[derive(Deserialize)]
+    struct Data {
+        #[serde(default)]
+        prev_lock_script: PrevLockScript,
+    }
+
+    #[test]
+    fn test_prev_lock_script() {
+        let data: Data = toml::from_str("").unwrap();
+        assert_eq!(data.prev_lock_script, PrevLockScript::CheckSig);
+        assert_eq!(data.prev_lock_script.to_script().unwrap().0.len(), CHECKSIG_LEN);
+
+        let data: Data =
+            toml::from_str(r#"prev_lock_script = { p2pkh = "cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e" }"#).unwrap();
+        assert_eq!(
+            hex::encode(data.prev_lock_script.to_script().unwrap().0),
+            "76a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac"
+        );
+        assert_eq!(data.prev_lock_script.to_script().unwrap().0.len(), P2PKH_LEN);
+
+        assert!(PrevLockScript::P2pkh("cecd".to_string()).to_script().is_err());
+
+        let data: Data =
+            toml::from_str(r#"prev_lock_script = { hex = "76a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac" }"#)
+                .unwrap();
+        assert_eq!(
+            data.prev_lock_script.to_script().unwrap().0,
+            PrevLockScript::P2pkh("cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e".to_string()).to_script().unwrap().0
+        );
+
+        assert!(PrevLockScript::Hex("76a9zz".to_string()).to_script().is_err());
+    }
+
+    #[test]
+    fn test_check_spending_tx() {
+        assert!(check_spending_tx(&read_tx(SPENDING_TX)).is_ok());
+
+        // The burnt token output is missing: the change output is the only one
+        let mut tx = read_tx(SPENDING_TX);
+        tx.outputs.remove(0);
+        assert!(matches!(check_spending_tx(&tx), Err(TokenError::InvalidSpendingTx(_))));
+
+        // Output 0 is not locked by OP_0 OP_RETURN
+        let tx = read_tx(&SPENDING_TX.replace("02006aecef", "02516aecef"));
+        let error = check_spending_tx(&tx).unwrap_err().to_string();
+        assert!(error.contains("output 0 must be locked by OP_0 OP_RETURN"), "{}", error);
+
+        // The change output is not P2PKH
+        let mut tx = read_tx(SPENDING_TX);
+        tx.outputs[1].lock_script = Script(vec![OP_CHECKSIG]);
+        let error = check_spending_tx(&tx).unwrap_err().to_string();
+        assert!(error.contains("output 1 must be locked by a P2PKH script"), "{}", error);
+    }
+}
@@ -1,8 +1,10 @@
-use anyhow::{Result, anyhow};
 use std::fs;
 
 use serde::Deserialize;
 
+use crate::error::TokenError;
+use crate::pob::PrevLockScript;
+
 /// Data required to generate a Transaction Chain Proof
 #[derive(Clone, Deserialize)]
 pub struct ProvingData {
@@ -10,13 +12,20 @@ pub struct ProvingData {
     pub spending_tx: String,
     pub tcp_proof_name: String,
     pub prev_amount: u64,
+    /// The locking script of the output spent by the RefTx input
+    #[serde(default)]
+    pub prev_lock_script: PrevLockScript,
 }
 
 impl ProvingData {
-    pub fn load(file_path: &str) -> Result<Self> {
-        let file_data = fs::read_to_string(file_path)
-            .map_err(|e| anyhow!("Failed to read proving data. Error: {}", e))?;
-        toml::from_str::<ProvingData>(&file_data)
-            .map_err(|e| anyhow!("Failed to parse proving data. Error {}", e))
+    pub fn load(file_path: &str) -> Result<Self, TokenError> {
+        let file_data = fs::read_to_string(file_path).map_err(|e| TokenError::DataLoad {
+            name: "proving data".to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str::<ProvingData>(&file_data).map_err(|e| TokenError::DataLoad {
+            name: "proving data".to_string(),
+            reason: e.to_string(),
+        })
     }
 }
@@ -0,0 +1,46 @@
+use std::fs;
+
+use serde::Deserialize;
+
+use crate::error::TokenError;
+use crate::pob::PrevLockScript;
+
+/// Data describing the shape of the RefTx for which the keys of the PoB are generated
+/// The fields are a subset of those of [crate::proving_data::ProvingData], so the proving data can be used as setup
+/// data to generate keys matching the RefTx that will be proved
+#[derive(Clone, Deserialize)]
+pub struct SetupData {
+    /// The locking script of the output spent by the RefTx input
+    #[serde(default)]
+    pub prev_lock_script: PrevLockScript,
+}
+
+impl SetupData {
+    pub fn load(file_path: &str) -> Result<Self, TokenError> {
+        let file_data = fs::read_to_string(file_path).map_err(|e| TokenError::DataLoad {
+            name: "setup data".to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str::<SetupData>(&file_data).map_err(|e| TokenError::DataLoad {
+            name: "setup data".to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pob::{CHECKSIG_LEN, P2PKH_LEN};
+
+    #[test]
+    fn test_setup_data_from_proving_data() {
+        // The proving data of the application can be used as setup data
+        let setup_data = SetupData::load("proving_data.toml").unwrap();
+        assert_eq!(setup_data.prev_lock_script.to_script().unwrap().0.len(), CHECKSIG_LEN);
+
+        let setup_data: SetupData =
+            toml::from_str(r#"prev_lock_script = { p2pkh = "cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e" }"#).unwrap();
+        assert_eq!(setup_data.prev_lock_script.to_script().unwrap().0.len(), P2PKH_LEN);
+    }
+}
@@ -0,0 +1,201 @@
+//! Conversion between a transaction and its representation as an array of field elements
+//! The transaction is serialised and the serialisation is packed in chunks of [chunk_len] bytes, each chunk read as
+//! a little-endian integer (as done for the txids in the public input of the PoB). The length of the serialisation
+//! is fixed by the [TxVarConfig] of the circuit, so that the packing can be reverted
+
+use std::io::Cursor;
+
+use ark_ff::{BigInteger, PrimeField};
+use bitcoin_r1cs::{bitcoin_predicates::data_structures::field_array::FieldArray, constraints::tx::TxVarConfig};
+use chain_gang::{messages::Tx, util::Serializable};
+
+use crate::error::TokenError;
+
+/// Number of bytes packed in a field element of `F`: the largest number of bytes that always fits below the modulus
+pub fn chunk_len<F: PrimeField>() -> usize {
+    (F::size_in_bits() - 1) / 8
+}
+
+/// Length of the encoding of `n` as a Bitcoin VarInt
+pub(crate) fn var_int_len(n: usize) -> usize {
+    match n {
+        0..0xfd => 1,
+        0xfd..=0xffff => 3,
+        0x10000..=0xffffffff => 5,
+        _ => 9,
+    }
+}
+
+/// Length of the serialisation of a transaction with the shape described by `C`
+pub fn serialised_tx_len<C: TxVarConfig>() -> usize {
+    let inputs_len: usize = C::LEN_UNLOCK_SCRIPTS
+        .iter()
+        .map(|len| 32 + 4 + var_int_len(*len) + len + 4) // Outpoint, unlocking script, sequence
+        .sum();
+    let outputs_len: usize = C::LEN_LOCK_SCRIPTS
+        .iter()
+        .map(|len| 8 + var_int_len(*len) + len) // Amount, locking script
+        .sum();
+    4 + var_int_len(C::N_INPUTS) + inputs_len + var_int_len(C::N_OUTPUTS) + outputs_len + 4 // Version, locktime
+}
+
+/// Number of field elements of `F` required to pack a transaction with the shape described by `C`
+pub fn n_field_elements<F: PrimeField, C: TxVarConfig>() -> usize {
+    serialised_tx_len::<C>().div_ceil(chunk_len::<F>())
+}
+
+/// Check that `tx` has the shape described by `C`: number of inputs and outputs, and length of their scripts
+pub fn check_tx_shape<C: TxVarConfig>(tx: &Tx) -> Result<(), TokenError> {
+    if tx.inputs.len() != C::N_INPUTS {
+        return Err(TokenError::TxConfigMismatch(format!(
+            "expected {} inputs, got {}",
+            C::N_INPUTS,
+            tx.inputs.len()
+        )));
+    }
+    if tx.outputs.len() != C::N_OUTPUTS {
+        return Err(TokenError::TxConfigMismatch(format!(
+            "expected {} outputs, got {}",
+            C::N_OUTPUTS,
+            tx.outputs.len()
+        )));
+    }
+    for (i, (input, len)) in tx.inputs.iter().zip(C::LEN_UNLOCK_SCRIPTS).enumerate() {
+        if input.unlock_script.0.len() != *len {
+            return Err(TokenError::TxConfigMismatch(format!(
+                "expected an unlocking script of length {} for input {}, got {}",
+                len,
+                i,
+                input.unlock_script.0.len()
+            )));
+        }
+    }
+    for (i, (output, len)) in tx.outputs.iter().zip(C::LEN_LOCK_SCRIPTS).enumerate() {
+        if output.lock_script.0.len() != *len {
+            return Err(TokenError::TxConfigMismatch(format!(
+                "expected a locking script of length {} for output {}, got {}",
+                len,
+                i,
+                output.lock_script.0.len()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Serialise `tx` and pack the serialisation in [n_field_elements] elements of `F`
+/// Fail if `tx` does not have the shape described by `C`
+pub fn tx_to_field_elements<F: PrimeField, C: TxVarConfig>(tx: &Tx) -> Result<Vec<F>, TokenError> {
+    check_tx_shape::<C>(tx)?;
+    let mut tx_bytes = Vec::with_capacity(serialised_tx_len::<C>());
+    tx.write(&mut tx_bytes).map_err(|e| TokenError::TxParse {
+        name: "tx".to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(tx_bytes.chunks(chunk_len::<F>()).map(F::from_le_bytes_mod_order).collect())
+}
+
+/// Serialise `tx` and pack the serialisation in the field array of the circuit described by `C`
+/// Fail if `tx` does not have the shape described by `C`, or if `N` is not [n_field_elements]
+pub fn tx_to_field_array<const N: usize, F: PrimeField, C: TxVarConfig>(
+    tx: &Tx,
+) -> Result<FieldArray<N, F, C>, TokenError> {
+    let elements: [F; N] = tx_to_field_elements::<F, C>(tx)?.try_into().map_err(|elements: Vec<F>| {
+        TokenError::TxConfigMismatch(format!("expected {} field elements, got {}", N, elements.len()))
+    })?;
+    Ok(FieldArray::new(elements))
+}
+
+/// Unpack the serialisation of a transaction with the shape described by `C` from `elements` and parse it
+/// This is the inverse of [tx_to_field_elements]
+pub fn field_elements_to_tx<F: PrimeField, C: TxVarConfig>(elements: &[F]) -> Result<Tx, TokenError> {
+    let expected_n_elements = n_field_elements::<F, C>();
+    if elements.len() != expected_n_elements {
+        return Err(TokenError::TxConfigMismatch(format!(
+            "expected {} field elements, got {}",
+            expected_n_elements,
+            elements.len()
+        )));
+    }
+
+    let tx_len = serialised_tx_len::<C>();
+    let mut tx_bytes = Vec::with_capacity(tx_len);
+    for (i, element) in elements.iter().enumerate() {
+        let element_bytes = element.into_repr().to_bytes_le();
+        let len = chunk_len::<F>().min(tx_len - tx_bytes.len());
+        // Elements not obtained by packing chunks of `len` bytes cannot be unpacked
+        if element_bytes[len..].iter().any(|byte| *byte != 0) {
+            return Err(TokenError::TxParse {
+                name: "tx".to_string(),
+                reason: format!("field element {} does not fit in {} bytes", i, len),
+            });
+        }
+        tx_bytes.extend(&element_bytes[..len]);
+    }
+
+    let tx = Tx::read(&mut Cursor::new(tx_bytes)).map_err(|e| TokenError::TxParse {
+        name: "tx".to_string(),
+        reason: e.to_string(),
+    })?;
+    check_tx_shape::<C>(&tx)?;
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_mnt4_753::Fr as ScalarFieldMNT4;
+
+    use super::*;
+    use crate::pob::{CHECKSIG_LEN, Config};
+    use crate::read_tx;
+
+    /// Spending tx of `proving_data.toml`
+    const SPENDING_TX: &str = "010000000340fd94950241edad515321861661a1a6f0717dbfbaefb8d2d3f7f3fa8c46ad45000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d112000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d11201000000000000000002000000000000000002006aecef0200000000001976a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac00000000";
+
+    type PoBConfig = Config<CHECKSIG_LEN>;
+
+    #[test]
+    fn test_tx_field_elements_round_trip() {
+        let tx = read_tx(SPENDING_TX).unwrap();
+        assert_eq!(serialised_tx_len::<PoBConfig>(), SPENDING_TX.len() / 2);
+
+        let elements = tx_to_field_elements::<ScalarFieldMNT4, PoBConfig>(&tx).unwrap();
+        assert_eq!(elements.len(), n_field_elements::<ScalarFieldMNT4, PoBConfig>());
+        let tx_bytes = hex::decode(SPENDING_TX).unwrap();
+        assert_eq!(
+            elements[0],
+            ScalarFieldMNT4::from_le_bytes_mod_order(&tx_bytes[..chunk_len::<ScalarFieldMNT4>()])
+        );
+
+        let unpacked_tx = field_elements_to_tx::<ScalarFieldMNT4, PoBConfig>(&elements).unwrap();
+        assert_eq!(unpacked_tx, tx);
+    }
+
+    #[test]
+    fn test_tx_field_elements_mismatch() {
+        // The tx does not have the shape of the configuration
+        let mut tx = read_tx(SPENDING_TX).unwrap();
+        tx.outputs.remove(0);
+        assert!(matches!(
+            tx_to_field_elements::<ScalarFieldMNT4, PoBConfig>(&tx),
+            Err(TokenError::TxConfigMismatch(_))
+        ));
+
+        // Wrong number of field elements
+        let tx = read_tx(SPENDING_TX).unwrap();
+        let mut elements = tx_to_field_elements::<ScalarFieldMNT4, PoBConfig>(&tx).unwrap();
+        elements.push(ScalarFieldMNT4::from(0u64));
+        assert!(matches!(
+            field_elements_to_tx::<ScalarFieldMNT4, PoBConfig>(&elements),
+            Err(TokenError::TxConfigMismatch(_))
+        ));
+
+        // A field element larger than a chunk
+        elements.pop();
+        elements[0] = -ScalarFieldMNT4::from(1u64);
+        assert!(matches!(
+            field_elements_to_tx::<ScalarFieldMNT4, PoBConfig>(&elements),
+            Err(TokenError::TxParse { .. })
+        ));
+    }
+}
@@ -2,10 +2,11 @@ use std::fs::{self, File};
 use std::io::{Read, Result as IoResult, Write};
 use std::path::Path;
 
-use ark_serialize::CanonicalSerialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use sha2::{Digest, Sha256};
 
 /// Save a list of bytes to `file_path`
-pub(crate) fn save_to_file(data: &[u8], file_path: &str) -> IoResult<()> {
+pub fn save_to_file(data: &[u8], file_path: &str) -> IoResult<()> {
     let file_path: &Path = Path::new(file_path);
     // Create parent directories if they don't exist
     fs::create_dir_all(file_path.parent().unwrap())?;
@@ -17,7 +18,7 @@ pub(crate) fn save_to_file(data: &[u8], file_path: &str) -> IoResult<()> {
 }
 
 /// Read `file_path` into a vector of bytes
-pub(crate) fn read_from_file(file_path: &str) -> IoResult<Vec<u8>> {
+pub fn read_from_file(file_path: &str) -> IoResult<Vec<u8>> {
     let mut file = File::open(file_path)?;
     let mut len_bytes = [0u8; 8];
     file.read_exact(&mut len_bytes)?;
@@ -31,8 +32,115 @@ pub(crate) fn read_from_file(file_path: &str) -> IoResult<Vec<u8>> {
 
 /// Serialise an item implementing [CanonicalSerialize]
 /// We use unchecked serialisation for speed reasons
-pub(crate) fn data_to_serialisation(item: &impl CanonicalSerialize) -> Vec<u8> {
+pub fn data_to_serialisation(item: &impl CanonicalSerialize) -> Vec<u8> {
     let mut serialized_data: Vec<u8> = vec![0; item.uncompressed_size()];
     item.serialize_unchecked(&mut serialized_data[..]).unwrap();
     serialized_data
 }
+
+/// Serialisation format of the proving key, saved as the first byte of the serialisation
+/// - `Uncompressed`: fast to deserialise, as points are read without any check
+/// - `Compressed`: roughly half the size, but much slower to deserialise, as every point must be decompressed
+///   (which requires a square root) and checked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFormat {
+    Uncompressed = 0,
+    Compressed = 1,
+}
+
+/// Serialise a key in `format`, prefixing the serialisation with the format byte
+pub fn key_to_serialisation(item: &impl CanonicalSerialize, format: KeyFormat) -> Vec<u8> {
+    let mut serialized_data: Vec<u8> = vec![format as u8];
+    match format {
+        KeyFormat::Uncompressed => serialized_data.extend(data_to_serialisation(item)),
+        KeyFormat::Compressed => item.serialize(&mut serialized_data).unwrap(),
+    }
+    serialized_data
+}
+
+/// Deserialise a key serialised with [key_to_serialisation], detecting its format from the first byte
+pub fn key_from_serialisation<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, SerializationError> {
+    match bytes.split_first() {
+        Some((&0, data)) => T::deserialize_unchecked(data),
+        Some((&1, data)) => T::deserialize(data),
+        _ => Err(SerializationError::InvalidData),
+    }
+}
+
+/// Compute the fingerprint of a verifying key from the serialisation of its components:
+/// the hex encoding of the SHA256 of their concatenation
+pub fn fingerprint(serialised_components: &[Vec<u8>]) -> String {
+    let mut hasher = Sha256::new();
+    for component in serialised_components {
+        hasher.update(component);
+    }
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::UniformRand;
+    use ark_groth16::{ProvingKey, VerifyingKey};
+    use ark_mnt4_753::{G1Projective, G2Projective, MNT4_753};
+    use rand_chacha::ChaChaRng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    fn random_vk(rng: &mut ChaChaRng) -> VerifyingKey<MNT4_753> {
+        VerifyingKey {
+            alpha_g1: G1Projective::rand(rng).into_affine(),
+            beta_g2: G2Projective::rand(rng).into_affine(),
+            gamma_g2: G2Projective::rand(rng).into_affine(),
+            delta_g2: G2Projective::rand(rng).into_affine(),
+            gamma_abc_g1: vec![G1Projective::rand(rng).into_affine(), G1Projective::rand(rng).into_affine()],
+        }
+    }
+
+    fn random_pk(rng: &mut ChaChaRng) -> ProvingKey<MNT4_753> {
+        let random_g1 = |rng: &mut ChaChaRng, n: usize| {
+            (0..n).map(|_| G1Projective::rand(rng).into_affine()).collect::<Vec<_>>()
+        };
+        ProvingKey {
+            vk: random_vk(rng),
+            beta_g1: G1Projective::rand(rng).into_affine(),
+            delta_g1: G1Projective::rand(rng).into_affine(),
+            a_query: random_g1(rng, 4),
+            b_g1_query: random_g1(rng, 4),
+            b_g2_query: (0..4).map(|_| G2Projective::rand(rng).into_affine()).collect(),
+            h_query: random_g1(rng, 3),
+            l_query: random_g1(rng, 2),
+        }
+    }
+
+    #[test]
+    fn test_proving_key_formats_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let pk = random_pk(&mut rng);
+
+        let uncompressed = key_to_serialisation(&pk, KeyFormat::Uncompressed);
+        let compressed = key_to_serialisation(&pk, KeyFormat::Compressed);
+        assert!(compressed.len() < uncompressed.len());
+
+        for bytes in [uncompressed, compressed] {
+            let deserialized_pk: ProvingKey<MNT4_753> = key_from_serialisation(&bytes).unwrap();
+            assert_eq!(deserialized_pk, pk);
+        }
+
+        assert!(key_from_serialisation::<ProvingKey<MNT4_753>>(&[2u8, 0u8]).is_err());
+    }
+
+    #[test]
+    fn test_vk_fingerprint_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let vk = random_vk(&mut rng);
+        let vk_fingerprint = fingerprint(&[data_to_serialisation(&vk)]);
+
+        let deserialized_vk =
+            VerifyingKey::<MNT4_753>::deserialize_unchecked(data_to_serialisation(&vk).as_slice()).unwrap();
+        assert_eq!(vk_fingerprint, fingerprint(&[data_to_serialisation(&deserialized_vk)]));
+
+        let other_vk = random_vk(&mut rng);
+        assert_ne!(vk_fingerprint, fingerprint(&[data_to_serialisation(&other_vk)]));
+    }
+}
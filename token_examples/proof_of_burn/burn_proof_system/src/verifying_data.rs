@@ -0,0 +1,72 @@
+use std::io::Read;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Deserialize;
+
+use crate::error::TokenError;
+
+/// Encoding of the byte strings of a [VerifyingBundle]
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// Data required to verify a PoB, read as JSON from stdin in `verify --stdin` mode
+/// `vk`, `proof` and `public_input` are the encodings of the serialisations saved by the application in
+/// `data/keys/vk.bin`, `data/proofs/proof_of_burn.bin` and `data/proofs/input_proof_of_burn.bin` (without the length
+/// prefix written by `save_to_file`)
+#[derive(Clone, Deserialize)]
+pub struct VerifyingBundle {
+    #[serde(default)]
+    pub encoding: Encoding,
+    pub vk: String,
+    pub proof: String,
+    pub public_input: String,
+}
+
+impl VerifyingBundle {
+    pub fn load(reader: impl Read) -> Result<Self, TokenError> {
+        serde_json::from_reader(reader).map_err(|e| TokenError::DataLoad {
+            name: "verifying bundle".to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Decode the field `name` of the bundle, whose content is `data`
+    pub fn decode(&self, name: &str, data: &str) -> Result<Vec<u8>, TokenError> {
+        let decoded = match self.encoding {
+            Encoding::Hex => hex::decode(data).map_err(|e| e.to_string()),
+            Encoding::Base64 => STANDARD.decode(data).map_err(|e| e.to_string()),
+        };
+        decoded.map_err(|reason| TokenError::DataLoad {
+            name: name.to_string(),
+            reason,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_encodings() {
+        let data = vec![0u8, 1, 2, 254, 255];
+        for (encoding, encoded) in [("hex", hex::encode(&data)), ("base64", STANDARD.encode(&data))] {
+            let json = format!(
+                r#"{{"encoding": "{}", "vk": "{}", "proof": "", "public_input": ""}}"#,
+                encoding, encoded
+            );
+            let bundle = VerifyingBundle::load(json.as_bytes()).unwrap();
+            assert_eq!(bundle.decode("vk", &bundle.vk).unwrap(), data);
+        }
+
+        // The encoding defaults to hex
+        let bundle = VerifyingBundle::load(r#"{"vk": "zz", "proof": "", "public_input": ""}"#.as_bytes()).unwrap();
+        assert_eq!(bundle.encoding, Encoding::Hex);
+        assert!(bundle.decode("vk", &bundle.vk).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use ark_crypto_primitives::SNARK;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_mnt4_753::{Fr as ScalarFieldMNT4, MNT4_753};
+use ark_mnt6_753::MNT6_753;
+use ark_pcd::variable_length_crh::pedersen::VariableLengthPedersenParameters;
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use burn_proof_system::cache::VerificationCache;
+use burn_proof_system::error::TokenError;
+use burn_proof_system::help_proof::{load_tcp_keys, load_tcp_proof};
+use burn_proof_system::pob::{CHECKSIG_LEN, P2PKH_LEN, PrevLockScript};
+use burn_proof_system::proving_data::ProvingData;
+use burn_proof_system::{
+    check_keys, dummy_reftx, integrity_tag, prepare, prove, read_tx, reftx, setup, verify, verify_prepared,
+};
+use rand_chacha::ChaChaRng;
+use rand_chacha::rand_core::SeedableRng;
+use std::time::Instant;
+
+/// Spending tx of `proving_data.toml`
+const SPENDING_TX: &str = "010000000340fd94950241edad515321861661a1a6f0717dbfbaefb8d2d3f7f3fa8c46ad45000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d112000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d11201000000000000000002000000000000000002006aecef0200000000001976a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac00000000";
+
+/// Circuit proving knowledge of a square root `x` of the public input `y`
+struct SquareCircuit {
+    x: Option<ScalarFieldMNT4>,
+}
+
+impl ConstraintSynthesizer<ScalarFieldMNT4> for SquareCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<ScalarFieldMNT4>) -> Result<(), SynthesisError> {
+        let x = cs.new_witness_variable(|| self.x.ok_or(SynthesisError::AssignmentMissing))?;
+        let y = cs.new_input_variable(|| self.x.map(|x| x * x).ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + y)
+    }
+}
+
+/// Return the keys of `SquareCircuit` and a proof valid for the public input `[9]`
+fn square_proof() -> (ProvingKey<MNT4_753>, VerifyingKey<MNT4_753>, Proof<MNT4_753>) {
+    let mut rng = ChaChaRng::seed_from_u64(0);
+    let (pk, vk) = Groth16::<MNT4_753>::circuit_specific_setup(SquareCircuit { x: None }, &mut rng).unwrap();
+    let proof = Groth16::<MNT4_753>::prove(
+        &pk,
+        SquareCircuit {
+            x: Some(ScalarFieldMNT4::from(3u64)),
+        },
+        &mut rng,
+    )
+    .unwrap();
+    (pk, vk, proof)
+}
+
+#[test]
+fn test_verify() {
+    let (_pk, vk, proof) = square_proof();
+
+    assert!(verify(&vk, &[ScalarFieldMNT4::from(9u64)], &proof).unwrap());
+    assert!(!verify(&vk, &[ScalarFieldMNT4::from(10u64)], &proof).unwrap());
+}
+
+#[test]
+fn test_check_keys() {
+    let (pk, vk, _proof) = square_proof();
+    assert!(check_keys(&pk, &vk));
+
+    // Keys of a different setup of the same circuit
+    let mut rng = ChaChaRng::seed_from_u64(1);
+    let (other_pk, other_vk) =
+        Groth16::<MNT4_753>::circuit_specific_setup(SquareCircuit { x: None }, &mut rng).unwrap();
+    assert!(check_keys(&other_pk, &other_vk));
+    assert!(!check_keys(&pk, &other_vk));
+    assert!(!check_keys(&other_pk, &vk));
+
+    // A pk whose embedded vk was replaced is caught by the pairing checks
+    let mut tampered_pk = pk.clone();
+    tampered_pk.vk = other_vk.clone();
+    assert!(!check_keys(&tampered_pk, &other_vk));
+}
+
+#[test]
+fn test_verify_prepared() {
+    let (_pk, vk, proof) = square_proof();
+    let pvk = prepare(&vk);
+
+    assert!(verify_prepared(&pvk, &[ScalarFieldMNT4::from(9u64)], &proof).unwrap());
+    assert!(!verify_prepared(&pvk, &[ScalarFieldMNT4::from(10u64)], &proof).unwrap());
+}
+
+#[test]
+fn test_verification_cache() {
+    let (_pk, vk, proof) = square_proof();
+    let mut cache = VerificationCache::new(1);
+
+    assert!(cache.verify(&vk, &[ScalarFieldMNT4::from(9u64)], &proof).unwrap());
+    assert_eq!((cache.hits(), cache.misses()), (0, 1));
+    assert!(cache.verify(&vk, &[ScalarFieldMNT4::from(9u64)], &proof).unwrap());
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+    // A different public input is a different triple: it evicts the previous result from the full cache
+    assert!(!cache.verify(&vk, &[ScalarFieldMNT4::from(10u64)], &proof).unwrap());
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 2, 1));
+    assert!(cache.verify(&vk, &[ScalarFieldMNT4::from(9u64)], &proof).unwrap());
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 3, 1));
+}
+
+/// Report the time of 100 verifications with and without preparing the verifying key once
+/// The timings are only printed, as wall-clock time depends on the machine and its load
+/// Run with `cargo test --release -- --ignored --nocapture`
+#[test]
+#[ignore = "benchmark"]
+fn test_prepared_verify_benchmark() {
+    const N_ITERATIONS: usize = 100;
+    let (_pk, vk, proof) = square_proof();
+    let public_input = [ScalarFieldMNT4::from(9u64)];
+
+    let start = Instant::now();
+    for _ in 0..N_ITERATIONS {
+        assert!(verify(&vk, &public_input, &proof).unwrap());
+    }
+    let unprepared_time = start.elapsed();
+
+    let start = Instant::now();
+    let pvk = prepare(&vk);
+    for _ in 0..N_ITERATIONS {
+        assert!(verify_prepared(&pvk, &public_input, &proof).unwrap());
+    }
+    let prepared_time = start.elapsed();
+
+    println!(
+        "{} verifications: unprepared {:?}, prepared {:?}",
+        N_ITERATIONS, unprepared_time, prepared_time
+    );
+}
+
+#[test]
+fn test_prove_invalid_spending_tx() {
+    // The spending tx is checked before the circuit is built, so the keys do not need to be the ones of the PoB
+    let (pk, _vk, _proof) = square_proof();
+    let crh_pp = VariableLengthPedersenParameters { seed: vec![] };
+    let help_vk = VerifyingKey::<MNT6_753>::default();
+    let tcp_proof = Proof::<MNT6_753>::default();
+
+    // Burnt token output spendable (`OP_1 OP_RETURN`)
+    let spending_tx = read_tx(&SPENDING_TX.replace("02006aecef", "02516aecef")).unwrap();
+    let result = prove::<CHECKSIG_LEN, _>(
+        &pk,
+        &crh_pp,
+        &help_vk,
+        &tcp_proof,
+        &"00".repeat(32),
+        spending_tx,
+        PrevLockScript::CheckSig.to_script().unwrap(),
+        1,
+        &mut ChaChaRng::seed_from_u64(0),
+    );
+    assert!(matches!(result, Err(TokenError::InvalidSpendingTx(_))));
+}
+
+#[test]
+fn test_dummy_reftx_public_input_len() {
+    // The keys generated from the dummy RefTx can only verify proofs of RefTxs with the same public input length
+    let crh_pp = VariableLengthPedersenParameters { seed: vec![0u8; 32] };
+    let help_vk = VerifyingKey::<MNT6_753>::default();
+    let tcp_proof = Proof::<MNT6_753>::default();
+
+    let dummy = dummy_reftx::<CHECKSIG_LEN>(&crh_pp, &help_vk);
+    let reftx = reftx::<CHECKSIG_LEN>(
+        &crh_pp,
+        &help_vk,
+        &tcp_proof,
+        &"00".repeat(32),
+        read_tx(SPENDING_TX).unwrap(),
+        PrevLockScript::CheckSig.to_script().unwrap(),
+        1,
+    )
+    .unwrap();
+    assert_eq!(dummy.public_input().len(), reftx.public_input().len());
+}
+
+#[test]
+fn test_read_witness_tx() {
+    // SPENDING_TX in witness serialisation: marker and flag after the version, one stack of witness items per input
+    // (two items, none, one empty item) before the locktime
+    let (body, lock_time) = SPENDING_TX.split_at(SPENDING_TX.len() - 8);
+    let witness_tx = format!("{}0001{}0201ab02cdef000100{}", &body[..8], &body[8..], lock_time);
+
+    let tx = read_tx(SPENDING_TX).unwrap();
+    let parsed_witness_tx = read_tx(&witness_tx).unwrap();
+    assert_eq!(parsed_witness_tx.hash(), tx.hash());
+    assert_eq!(parsed_witness_tx.lock_time, tx.lock_time);
+
+    // The PoB proves over the same statement
+    let prev_lock_script = PrevLockScript::CheckSig.to_script().unwrap();
+    assert_eq!(
+        integrity_tag::<CHECKSIG_LEN>(&parsed_witness_tx, &prev_lock_script, 1),
+        integrity_tag::<CHECKSIG_LEN>(&tx, &prev_lock_script, 1)
+    );
+    let crh_pp = VariableLengthPedersenParameters { seed: vec![0u8; 32] };
+    let help_vk = VerifyingKey::<MNT6_753>::default();
+    let tcp_proof = Proof::<MNT6_753>::default();
+    let public_input = |spending_tx| {
+        reftx::<CHECKSIG_LEN>(
+            &crh_pp,
+            &help_vk,
+            &tcp_proof,
+            &"00".repeat(32),
+            spending_tx,
+            prev_lock_script.clone(),
+            1,
+        )
+        .unwrap()
+        .public_input()
+    };
+    assert_eq!(public_input(parsed_witness_tx), public_input(tx));
+
+    // Missing witnesses and trailing data are rejected
+    let truncated = format!("{}0001{}0201ab{}", &body[..8], &body[8..], lock_time);
+    assert!(matches!(read_tx(&truncated), Err(TokenError::TxParse { .. })));
+    assert!(matches!(read_tx(&format!("{}00", witness_tx)), Err(TokenError::TxParse { .. })));
+}
+
+/// Prove and verify the burn of `proving_data.toml` from a P2PKH previous output
+/// The PoB verifies the TCP proof in-circuit, so the test needs the keys of the TCP and the proof named in
+/// `proving_data.toml`, generated by `tcp_proof_system`
+/// Run with `cargo test --release -- --ignored`
+#[test]
+#[ignore = "slow: runs the setup of the PoB and needs the data of the TCP"]
+fn test_prove_p2pkh() {
+    let proving_data = ProvingData::load("proving_data.toml").unwrap();
+    let (crh_pp, help_vk) = load_tcp_keys().unwrap();
+    let (tcp_proof, _processed_input) = load_tcp_proof(&proving_data.tcp_proof_name).unwrap();
+    let prev_lock_script = PrevLockScript::P2pkh("cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e".to_string())
+        .to_script()
+        .unwrap();
+    assert_eq!(prev_lock_script.0.len(), P2PKH_LEN);
+
+    let mut rng = ChaChaRng::seed_from_u64(0);
+    let (pk, vk) = setup::<P2PKH_LEN, _>(&crh_pp, &help_vk, &mut rng).unwrap();
+    let (proof, public_input) = prove::<P2PKH_LEN, _>(
+        &pk,
+        &crh_pp,
+        &help_vk,
+        &tcp_proof,
+        &proving_data.genesis_txid,
+        read_tx(&proving_data.spending_tx).unwrap(),
+        prev_lock_script,
+        proving_data.prev_amount,
+        &mut rng,
+    )
+    .unwrap();
+    assert!(verify(&vk, &public_input, &proof).unwrap());
+
+    // The proof is bound to the previous locking script: the public input for the P2PKH of another public key
+    // hash commits to a different integrity tag
+    let other_public_input = reftx::<P2PKH_LEN>(
+        &crh_pp,
+        &help_vk,
+        &tcp_proof,
+        &proving_data.genesis_txid,
+        read_tx(&proving_data.spending_tx).unwrap(),
+        PrevLockScript::P2pkh("00".repeat(20)).to_script().unwrap(),
+        proving_data.prev_amount,
+    )
+    .unwrap()
+    .public_input();
+    assert!(!verify(&vk, &other_public_input, &proof).unwrap());
+}
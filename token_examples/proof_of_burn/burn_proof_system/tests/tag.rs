@@ -0,0 +1,51 @@
+use std::process::Command;
+
+/// Spending tx of `proving_data.toml`: the RefTx input is the second one
+const SPENDING_TX: &str = "010000000340fd94950241edad515321861661a1a6f0717dbfbaefb8d2d3f7f3fa8c46ad45000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d112000000000000000000e7f5604e8695d00662862fea02029f57c7b5737c888e23108ae6ce9893a5d11201000000000000000002000000000000000002006aecef0200000000001976a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac00000000";
+
+/// Sighash (SIGHASH_ALL | SIGHASH_FORKID) of the second input of `SPENDING_TX`, spending an output locked by
+/// `OP_CHECKSIG` with amount `1`, computed independently following BIP143
+const EXPECTED_TAG: &str = "d91ecfbe6425d418b7f3104a6202644e50e21e64f1c1144994c8143820cbd1f0";
+
+fn tag(args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_burn_proof_system"))
+        .arg("tag")
+        .args(args)
+        .output()
+        .unwrap();
+    (output.status.code(), String::from_utf8(output.stdout).unwrap().trim().to_string())
+}
+
+#[test]
+fn test_tag() {
+    let expected = (Some(0), EXPECTED_TAG.to_string());
+    assert_eq!(tag(&["--tx", SPENDING_TX, "--prev-amount", "1"]), expected);
+    assert_eq!(tag(&["--tx", SPENDING_TX, "--prev-lock-script", "ac", "--prev-amount", "1"]), expected);
+
+    // The tag depends on the amount
+    let (code, other_tag) = tag(&["--tx", SPENDING_TX, "--prev-amount", "2"]);
+    assert_eq!(code, Some(0));
+    assert_ne!(other_tag, EXPECTED_TAG);
+}
+
+#[test]
+fn test_tag_invalid_tx() {
+    let (code, _) = tag(&["--tx", "zz", "--prev-amount", "1"]);
+    assert_ne!(code, Some(0));
+}
+
+#[test]
+fn test_tag_p2pkh() {
+    // Sighash of the second input of `SPENDING_TX`, spending a P2PKH output with amount `1`, computed independently
+    // following BIP143
+    let expected = (
+        Some(0),
+        "8e62280a2f39a93e5ffa1e3a728defec18d803254369743046b1b7074e4940ef".to_string(),
+    );
+    let p2pkh = "76a914cecdc7e19ac9b16e6d9a1a5c5195a97914dc430e88ac";
+    assert_eq!(tag(&["--tx", SPENDING_TX, "--prev-lock-script", p2pkh, "--prev-amount", "1"]), expected);
+
+    // Only the lengths of `OP_CHECKSIG` and P2PKH are supported
+    let (code, _) = tag(&["--tx", SPENDING_TX, "--prev-lock-script", "acac", "--prev-amount", "1"]);
+    assert_ne!(code, Some(0));
+}
@@ -0,0 +1,94 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use ark_crypto_primitives::SNARK;
+use ark_groth16::Groth16;
+use ark_mnt4_753::{Fr as ScalarFieldMNT4, MNT4_753};
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_serialize::CanonicalSerialize;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use rand_chacha::ChaChaRng;
+use rand_chacha::rand_core::SeedableRng;
+
+/// Circuit proving knowledge of a square root `x` of the public input `y`
+struct SquareCircuit {
+    x: Option<ScalarFieldMNT4>,
+}
+
+impl ConstraintSynthesizer<ScalarFieldMNT4> for SquareCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<ScalarFieldMNT4>) -> Result<(), SynthesisError> {
+        let x = cs.new_witness_variable(|| self.x.ok_or(SynthesisError::AssignmentMissing))?;
+        let y = cs.new_input_variable(|| self.x.map(|x| x * x).ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + y)
+    }
+}
+
+fn serialise(item: &impl CanonicalSerialize) -> Vec<u8> {
+    let mut serialised_data: Vec<u8> = vec![0; item.uncompressed_size()];
+    item.serialize_unchecked(&mut serialised_data[..]).unwrap();
+    serialised_data
+}
+
+/// Return the serialisations of a vk, a proof valid for the public input `[9]`, and the public input `public_input`
+fn bundle_data(public_input: u64) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let mut rng = ChaChaRng::seed_from_u64(0);
+    let (pk, vk) = Groth16::<MNT4_753>::circuit_specific_setup(SquareCircuit { x: None }, &mut rng).unwrap();
+    let proof = Groth16::<MNT4_753>::prove(
+        &pk,
+        SquareCircuit {
+            x: Some(ScalarFieldMNT4::from(3u64)),
+        },
+        &mut rng,
+    )
+    .unwrap();
+    let public_input = vec![ScalarFieldMNT4::from(public_input)];
+    (serialise(&vk), serialise(&proof), serialise(&public_input))
+}
+
+/// Run `verify --stdin` with `bundle` piped to stdin, returning the exit code and the output
+fn verify_stdin(bundle: &str) -> (Option<i32>, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_burn_proof_system"))
+        .args(["verify", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(bundle.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (output.status.code(), String::from_utf8(output.stdout).unwrap().trim().to_string())
+}
+
+#[test]
+fn test_verify_stdin() {
+    let (vk, proof, public_input) = bundle_data(9);
+
+    let hex_bundle = format!(
+        r#"{{"encoding": "hex", "vk": "{}", "proof": "{}", "public_input": "{}"}}"#,
+        hex::encode(&vk),
+        hex::encode(&proof),
+        hex::encode(&public_input)
+    );
+    assert_eq!(verify_stdin(&hex_bundle), (Some(0), "true".to_string()));
+
+    let base64_bundle = format!(
+        r#"{{"encoding": "base64", "vk": "{}", "proof": "{}", "public_input": "{}"}}"#,
+        STANDARD.encode(&vk),
+        STANDARD.encode(&proof),
+        STANDARD.encode(&public_input)
+    );
+    assert_eq!(verify_stdin(&base64_bundle), (Some(0), "true".to_string()));
+}
+
+#[test]
+fn test_verify_stdin_invalid_proof() {
+    let (vk, proof, public_input) = bundle_data(10);
+
+    let bundle = format!(
+        r#"{{"vk": "{}", "proof": "{}", "public_input": "{}"}}"#,
+        hex::encode(&vk),
+        hex::encode(&proof),
+        hex::encode(&public_input)
+    );
+    assert_eq!(verify_stdin(&bundle), (Some(1), "false".to_string()));
+}
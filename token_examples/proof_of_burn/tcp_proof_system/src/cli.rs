@@ -1,7 +1,7 @@
 use clap::Parser;
 
 /// CLI of the application
-/// It can be run in either `setup`, `process`, `prove`, `verify` mode
+/// It can be run in either `setup`, `process`, `prove`, `verify`, `fingerprint` mode
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub(crate) struct Cli {
@@ -21,7 +21,19 @@ pub(crate) struct Cli {
     #[arg(short, long)]
     pub verify: bool,
 
+    // In verification mode, recompute the processed input and check it against the saved one
+    #[arg(short, long, requires = "verify")]
+    pub recompute: bool,
+
+    // In verification mode, reject the proof if its genesis txid is not this one
+    #[arg(long, requires = "verify")]
+    pub expected_genesis: Option<String>,
+
+    // Print the fingerprint of the verifying key
+    #[arg(long)]
+    pub fingerprint: bool,
+
     // File path
-    #[arg(short, long)]
-    pub file: String,
+    #[arg(short, long, required_unless_present = "fingerprint")]
+    pub file: Option<String>,
 }
@@ -1,3 +1,4 @@
+pub mod proof_bundle;
 pub mod proving_data;
 pub mod setup_data;
 pub mod verifying_data;
@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::Path;
+
+use ark_serialize::CanonicalDeserialize;
+use chain_gang::{messages::OutPoint, util::Hash256};
+use serde::{Deserialize, Serialize};
+use transaction_chain_proof::snarks::universal_tcp_snark::UniversalTransactionChainProofPublicInput;
+
+use crate::data_structures::proving_data::ProvingData;
+use crate::error::TokenError;
+
+/// Proof saved together with its public input, so that a verifier can read the public input from the proof itself
+/// In the bundle file, the proof is the hex encoding of its serialisation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofBundle {
+    pub chain_index: u32,
+    pub outpoint_txid: String,
+    pub genesis_txid: String,
+    pub proof: String,
+}
+
+impl ProofBundle {
+    /// Bundle the serialisation of the proof generated from `proving_data` with its public input
+    pub fn new(proving_data: &ProvingData, proof_serialised: &[u8]) -> Self {
+        Self {
+            chain_index: proving_data.chain_parameters.chain_index,
+            outpoint_txid: proving_data.public_inputs.outpoint_txid.clone(),
+            genesis_txid: proving_data.public_inputs.genesis_txid.clone(),
+            proof: hex::encode(proof_serialised),
+        }
+    }
+
+    pub fn load(file_path: &str) -> Result<Self, TokenError> {
+        let file_data = fs::read_to_string(file_path).map_err(|e| TokenError::DataLoad {
+            name: "proof bundle".to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str::<ProofBundle>(&file_data).map_err(|e| TokenError::DataLoad {
+            name: "proof bundle".to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    pub fn save(&self, file_path: &str) -> Result<(), TokenError> {
+        let save_error = |reason: String| TokenError::Save {
+            name: "proof bundle".to_string(),
+            reason,
+        };
+        let file_data = toml::to_string(self).map_err(|e| save_error(e.to_string()))?;
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent).map_err(|e| save_error(e.to_string()))?;
+        }
+        fs::write(file_path, file_data).map_err(|e| save_error(e.to_string()))
+    }
+
+    /// Return the public input of the bundled proof
+    pub fn public_input(&self) -> Result<UniversalTransactionChainProofPublicInput, TokenError> {
+        let decode = |name: &str, txid: &str| {
+            Hash256::decode(txid).map_err(|e| TokenError::DataLoad {
+                name: name.to_string(),
+                reason: e.to_string(),
+            })
+        };
+        Ok(UniversalTransactionChainProofPublicInput {
+            outpoint: OutPoint {
+                hash: decode("outpoint txid", &self.outpoint_txid)?,
+                index: self.chain_index,
+            },
+            genesis_txid: decode("genesis txid", &self.genesis_txid)?.0,
+        })
+    }
+
+    /// Return the bundled proof
+    pub fn proof<T: CanonicalDeserialize>(&self) -> Result<T, TokenError> {
+        let proof_error = |reason: String| TokenError::ProofDeserialize {
+            name: "bundled proof".to_string(),
+            reason,
+        };
+        let proof_bytes = hex::decode(&self.proof).map_err(|e| proof_error(e.to_string()))?;
+        T::deserialize_unchecked(proof_bytes.as_slice()).map_err(|e| proof_error(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::data_to_serialisation;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::UniformRand;
+    use ark_groth16::Proof;
+    use ark_mnt6_753::{G1Projective, G2Projective, MNT6_753};
+    use rand_chacha::ChaChaRng;
+    use rand_chacha::rand_core::SeedableRng;
+
+    const PROVING_DATA: &str = r#"
+proof_name = "proof_for_bundle_test"
+
+[chain_parameters]
+chain_index = 0
+
+[public_inputs]
+outpoint_txid = "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa"
+genesis_txid = "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c"
+
+[witness]
+tx = ""
+prior_proof_path = ""
+"#;
+
+    #[test]
+    fn test_proof_bundle_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let proof = Proof::<MNT6_753> {
+            a: G1Projective::rand(&mut rng).into_affine(),
+            b: G2Projective::rand(&mut rng).into_affine(),
+            c: G1Projective::rand(&mut rng).into_affine(),
+        };
+        let proving_data: ProvingData = toml::from_str(PROVING_DATA).unwrap();
+
+        let bundle_path = std::env::temp_dir().join("tcp_proof_bundle_test/proof_for_bundle_test_bundle.toml");
+        let bundle_path = bundle_path.to_str().unwrap();
+        ProofBundle::new(&proving_data, &data_to_serialisation(&proof)).save(bundle_path).unwrap();
+
+        let bundle = ProofBundle::load(bundle_path).unwrap();
+        let public_input = bundle.public_input().unwrap();
+        let expected_public_input: UniversalTransactionChainProofPublicInput = proving_data.into();
+        assert_eq!(public_input.outpoint.index, expected_public_input.outpoint.index);
+        assert_eq!(public_input.outpoint.hash, expected_public_input.outpoint.hash);
+        assert_eq!(public_input.genesis_txid, expected_public_input.genesis_txid);
+        assert_eq!(bundle.proof::<Proof<MNT6_753>>().unwrap(), proof);
+
+        let bundle = ProofBundle {
+            outpoint_txid: "zz".to_string(),
+            ..bundle
+        };
+        assert!(matches!(bundle.public_input(), Err(TokenError::DataLoad { .. })));
+    }
+}
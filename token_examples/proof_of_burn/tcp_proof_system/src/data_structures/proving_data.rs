@@ -1,10 +1,11 @@
-use anyhow::{Result, anyhow};
 use std::fs;
 
-use chain_gang::{messages::OutPoint, util::Hash256};
+use chain_gang::{messages::OutPoint, script::Script, util::Hash256};
 use serde::Deserialize;
 use transaction_chain_proof::snarks::universal_tcp_snark::UniversalTransactionChainProofPublicInput;
 
+use crate::error::TokenError;
+
 /// Data required to generate a Transaction Chain Proof
 #[derive(Clone, Deserialize)]
 pub struct ProvingData {
@@ -32,14 +33,37 @@ pub struct PublicInputs {
 pub struct Witness {
     pub tx: String,
     pub prior_proof_path: String,
+    /// Hex encoded locking script of the output spent by `tx`. If set, the unlocking script of `tx` is checked
+    /// against it before proving. The check is advisory: the proof does not commit to the script
+    #[serde(default)]
+    pub prev_lock_script_hex: Option<String>,
 }
 
 impl ProvingData {
-    pub fn load(file_path: String) -> Result<Self> {
-        let file_data = fs::read_to_string(file_path)
-            .map_err(|e| anyhow!("Failed to read proving data. Error: {}", e))?;
-        toml::from_str::<ProvingData>(&file_data)
-            .map_err(|e| anyhow!("Failed to parse proving data. Error {}", e))
+    pub fn load(file_path: String) -> Result<Self, TokenError> {
+        let file_data = fs::read_to_string(file_path).map_err(|e| TokenError::DataLoad {
+            name: "proving data".to_string(),
+            reason: e.to_string(),
+        })?;
+        toml::from_str::<ProvingData>(&file_data).map_err(|e| TokenError::DataLoad {
+            name: "proving data".to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
+
+impl Witness {
+    /// Parse `prev_lock_script_hex` (if set) into a [Script]
+    pub fn prev_lock_script(&self) -> Result<Option<Script>, TokenError> {
+        self.prev_lock_script_hex
+            .as_ref()
+            .map(|script| {
+                hex::decode(script).map(Script).map_err(|e| TokenError::TxParse {
+                    name: "previous locking script".to_string(),
+                    reason: e.to_string(),
+                })
+            })
+            .transpose()
     }
 }
 
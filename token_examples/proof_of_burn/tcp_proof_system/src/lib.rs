@@ -0,0 +1,9 @@
+//! NFT application of PCD as a library
+//! The scheme is exposed through the [nft::NFT] trait, implemented by [nft::groth16_nft::UniversalTCPSnark], so that
+//! other Rust programs can run the setup, prove and verify the NFT in-process. The CLI of the crate is a thin wrapper
+//! around it
+
+pub mod data_structures;
+pub mod error;
+pub mod nft;
+pub mod util;
@@ -1,34 +1,54 @@
 use clap::Parser;
 use cli::Cli;
+use tracing_subscriber::EnvFilter;
 
-pub mod cli;
-pub mod data_structures;
-pub mod nft;
-pub mod util;
-
-use data_structures::{
+use nft::data_structures::{
     proving_data::ProvingData, setup_data::SetupData, verifying_data::VerifyingData,
 };
-use nft::{NFT, groth16_nft::UniversalTCPSnark};
+use nft::nft::{NFT, groth16_nft::UniversalTCPSnark};
+
+mod cli;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
+    // Logs (spans and events of setup, prove and verify) are written to stderr, filtered by `RUST_LOG`
+    // (e.g., `RUST_LOG=info`)
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
     let cli = Cli::parse();
+    // The file is required in every mode except `fingerprint`
+    let file = cli.file.clone().unwrap_or_default();
 
     if cli.setup {
-        let setup_data = SetupData::load(cli.file).unwrap();
-        <UniversalTCPSnark as NFT>::setup(setup_data).unwrap();
+        let setup_data = SetupData::load(file)?;
+        <UniversalTCPSnark as NFT>::setup(setup_data)?;
     } else if cli.process {
-        let proving_data = ProvingData::load(cli.file).unwrap();
-        <UniversalTCPSnark as NFT>::process_input(proving_data).unwrap();
+        let proving_data = ProvingData::load(file)?;
+        <UniversalTCPSnark as NFT>::process_input(proving_data)?;
     } else if cli.prove {
-        let proving_data = ProvingData::load(cli.file).unwrap();
-        <UniversalTCPSnark as NFT>::prove(proving_data).unwrap();
+        let proving_data = ProvingData::load(file)?;
+        <UniversalTCPSnark as NFT>::prove(proving_data)?;
     } else if cli.verify {
-        let verifying_data = VerifyingData::load(cli.file).unwrap();
+        let mut verifying_data = VerifyingData::load(file)?;
+        if cli.expected_genesis.is_some() {
+            verifying_data.expected_genesis_txid = cli.expected_genesis.clone();
+        }
+        if cli.recompute {
+            <UniversalTCPSnark as NFT>::check_processed_input(verifying_data.clone())?;
+        }
         assert!(
-            <UniversalTCPSnark as NFT>::verify(verifying_data).unwrap(),
+            <UniversalTCPSnark as NFT>::verify(verifying_data)?,
             "\nProof not valid.\n"
         );
         println!("\nValid proof.\n")
+    } else if cli.fingerprint {
+        let vk = <UniversalTCPSnark as NFT>::load_vk()?;
+        println!(
+            "\nVerifying key fingerprint: {}\n",
+            <UniversalTCPSnark as NFT>::vk_fingerprint(&vk)
+        );
     }
+
+    Ok(())
 }
@@ -1,4 +1,3 @@
-use anyhow::{Result, anyhow};
 use std::io::Cursor;
 
 use ark_mnt4_753::{
@@ -16,11 +15,15 @@ use ark_groth16::{
 use ark_mnt4_753::{MNT4_753, constraints::PairingVar as MNT4PairingVar};
 use ark_mnt6_753::{MNT6_753, constraints::PairingVar as MNT6PairingVar};
 
-use ark_serialize::CanonicalDeserialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use bitcoin_r1cs::constraints::tx::TxVarConfig;
 use chain_gang::messages::Tx;
+use chain_gang::script::Script;
+use chain_gang::script::op_codes::{OP_CHECKSIG, OP_DUP, OP_EQUALVERIFY, OP_HASH160};
+use chain_gang::util::hash160;
 use chain_gang::util::Serializable;
 use rand_chacha::ChaChaRng;
+use tracing::{info, instrument};
 
 use transaction_chain_proof::predicates::universal_tcp::UniversalTransactionChainProofPredicate;
 use transaction_chain_proof::snarks::universal_tcp_snark::{
@@ -28,12 +31,17 @@ use transaction_chain_proof::snarks::universal_tcp_snark::{
     UniversalTransactionChainProofSNARK, UniversalTransactionChainProofWitness,
 };
 
-use crate::data_structures::proving_data::ProvingData;
+use crate::data_structures::proof_bundle::ProofBundle;
+use crate::data_structures::proving_data::{ProvingData, Witness};
 use crate::data_structures::setup_data::SetupData;
 use crate::data_structures::verifying_data::VerifyingData;
+use crate::error::TokenError;
 
 use crate::nft::NFT;
-use crate::util::{data_to_serialisation, read_from_file, save_to_file};
+use crate::util::{
+    data_to_serialisation, fingerprint, key_from_serialisation, key_to_serialisation, read_from_file,
+    save_to_file,
+};
 
 /// PCD with Groth16 as MainSNARK and HelpSNARK
 /// Over the MNT4_753 - MNT6_753 cycle, with MNT6_753 as the HelpSNARK curve
@@ -50,11 +58,11 @@ impl ECCyclePCDConfig<ScalarFieldMNT4, ScalarFieldMNT6> for PCDGroth16Mnt4 {
 type PCD = ECCyclePCD<ScalarFieldMNT4, ScalarFieldMNT6, PCDGroth16Mnt4>;
 
 /// Structure of the transactions in the chain
-/// Inputs are P2PK
-/// Unlocking scripts are <Sig> w/ r.len = 33, s.len = 32
-/// Outputs are P2PK
+/// Inputs are P2PKH
+/// Unlocking scripts are <Sig> <PubKey> w/ r.len = 33, s.len = 32 and a compressed public key
+/// Outputs are P2PKH (see [lock_script])
 #[derive(Clone)]
-pub(crate) struct Config;
+pub struct Config;
 impl TxVarConfig for Config {
     const N_INPUTS: usize = 1;
     const N_OUTPUTS: usize = 1;
@@ -64,9 +72,96 @@ impl TxVarConfig for Config {
     const PRE_SIGHASH_N_INPUT: Option<usize> = None;
 }
 
-pub(crate) type UniversalTCPSnark =
+pub type UniversalTCPSnark =
     UniversalTransactionChainProofSNARK<ScalarFieldMNT4, Config, PCD, ChaChaRng>;
 
+/// Length of a compressed public key
+pub const COMPRESSED_PUBKEY_LEN: usize = 33;
+
+/// Return the locking script of the outputs of the chain transactions for the compressed public key `pubkey`:
+/// `OP_DUP OP_HASH160 <hash160(pubkey)> OP_EQUALVERIFY OP_CHECKSIG`, whose length is the one declared in [Config]
+/// The public key must be compressed, as the length of the unlocking scripts in [Config] assumes it
+pub fn lock_script(pubkey: &[u8]) -> Result<Script, TokenError> {
+    let pubkey_error = |reason: String| TokenError::TxParse {
+        name: "public key".to_string(),
+        reason,
+    };
+    if pubkey.len() != COMPRESSED_PUBKEY_LEN {
+        return Err(pubkey_error(format!(
+            "expected {} bytes, got {}",
+            COMPRESSED_PUBKEY_LEN,
+            pubkey.len()
+        )));
+    }
+    if pubkey[0] != 0x02 && pubkey[0] != 0x03 {
+        return Err(pubkey_error(format!("invalid prefix {:#04x}", pubkey[0])));
+    }
+
+    let mut script = vec![OP_DUP, OP_HASH160, 20];
+    script.extend(hash160(pubkey).0);
+    script.extend([OP_EQUALVERIFY, OP_CHECKSIG]);
+    Ok(Script(script))
+}
+
+/// Check that the input of `tx` spends an output locked by `prev_lock_script`
+/// The unlocking scripts of the chain are `<Sig> <PubKey>` (see [Config]), so `prev_lock_script` must be the P2PKH
+/// locking script of the public key in the unlocking script, as returned by [lock_script]
+/// The check is advisory: the circuit does not allocate the previous locking script (`LEN_PREV_LOCK_SCRIPT` is `None`
+/// in [Config]), so it only catches mistakes of an honest prover before proving. A proof does not bind the prover to
+/// `prev_lock_script`, and a verifier learns nothing about it
+pub fn check_prev_lock_script(tx: &Tx, prev_lock_script: &Script) -> Result<(), TokenError> {
+    let prev_lock_script_error = |reason: String| TokenError::TxParse {
+        name: "previous locking script".to_string(),
+        reason,
+    };
+    let unlock_script = &tx
+        .inputs
+        .first()
+        .ok_or_else(|| prev_lock_script_error("the witness tx has no inputs".to_string()))?
+        .unlock_script
+        .0;
+    // The public key is the last push of the unlocking script
+    let pubkey = match unlock_script.len().checked_sub(COMPRESSED_PUBKEY_LEN + 1) {
+        Some(start) if unlock_script[start] as usize == COMPRESSED_PUBKEY_LEN => &unlock_script[start + 1..],
+        _ => {
+            return Err(prev_lock_script_error(
+                "the unlocking script of the witness tx does not end with a compressed public key".to_string(),
+            ));
+        }
+    };
+    match lock_script(pubkey)?.0 == prev_lock_script.0 {
+        true => Ok(()),
+        false => Err(prev_lock_script_error(format!(
+            "the witness tx does not spend an output locked by {}",
+            hex::encode(&prev_lock_script.0)
+        ))),
+    }
+}
+
+/// Parse the transaction of `witness` (`None` in the base case) and, if `witness.prev_lock_script_hex` is set, check
+/// that it spends an output locked by it
+pub fn read_witness_tx(witness: &Witness) -> Result<Option<Tx>, TokenError> {
+    let witness_tx_error = |reason: String| TokenError::TxParse {
+        name: "witness tx".to_string(),
+        reason,
+    };
+    let prev_lock_script = witness.prev_lock_script()?;
+    if witness.tx.is_empty() {
+        return match prev_lock_script {
+            None => Ok(None),
+            Some(_) => Err(witness_tx_error(
+                "a previous locking script is given, but the witness tx is empty".to_string(),
+            )),
+        };
+    }
+    let tx_bytes = hex::decode(&witness.tx).map_err(|e| witness_tx_error(e.to_string()))?;
+    let tx = Tx::read(&mut Cursor::new(tx_bytes)).map_err(|e| witness_tx_error(e.to_string()))?;
+    if let Some(prev_lock_script) = prev_lock_script {
+        check_prev_lock_script(&tx, &prev_lock_script)?;
+    }
+    Ok(Some(tx))
+}
+
 impl NFT for UniversalTCPSnark {
     type ProvingKeyMainCircuit = ProvingKey<MNT4_753>;
     type ProvingKeyHelpCircuit = ProvingKey<MNT6_753>;
@@ -79,46 +174,29 @@ impl NFT for UniversalTCPSnark {
     const PROOFS_PATH: &str = "data/proofs/";
 
     /// Perform the setup based on the provided `chain_index`
-    fn setup(setup_data: SetupData) -> Result<()> {
-        let (pk, _vk) = Self::setup(&setup_data.chain_index).unwrap();
-        save_to_file(
-            &pk.crh_pp.seed,
-            &(Self::KEYS_PATH.to_owned() + "crh_pp_seed.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save crh_pp. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.main_pk),
-            &(Self::KEYS_PATH.to_owned() + "main_pk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save main_pk. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.help_pk),
-            &(Self::KEYS_PATH.to_owned() + "help_pk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save help_pk. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.help_vk),
-            &(Self::KEYS_PATH.to_owned() + "help_vk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save help_vk. Error: {}", e))?;
-        save_to_file(
-            &data_to_serialisation(&pk.main_pvk.vk),
-            &(Self::KEYS_PATH.to_owned() + "main_vk.bin"),
-        )
-        .map_err(|e| anyhow!("Failed to save main_vk. Error: {}", e))?;
+    #[instrument(skip_all, fields(chain_index = setup_data.chain_index))]
+    fn setup(setup_data: SetupData) -> Result<(), TokenError> {
+        let (pk, _vk) = Self::setup(&setup_data.chain_index)
+            .map_err(|e| TokenError::Setup(format!("{:?}", e)))?;
+        save_key(&pk.crh_pp.seed, "crh_pp_seed")?;
+        save_key(&key_to_serialisation(&pk.main_pk, setup_data.key_format), "main_pk")?;
+        save_key(&key_to_serialisation(&pk.help_pk, setup_data.key_format), "help_pk")?;
+        save_key(&data_to_serialisation(&pk.help_vk), "help_vk")?;
+        save_key(&data_to_serialisation(&pk.main_pvk.vk), "main_vk")?;
+        info!(keys_path = <Self as NFT>::KEYS_PATH, "keys saved");
 
         Ok(())
     }
 
     /// Process the input contained in `ProvingData` (i.e., compute the Pedersen hash)
-    fn process_input(proving_data: ProvingData) -> Result<()> {
+    #[instrument(
+        skip_all,
+        fields(chain_index = proving_data.chain_parameters.chain_index, proof_name = %proving_data.proof_name)
+    )]
+    fn process_input(proving_data: ProvingData) -> Result<(), TokenError> {
         // Generate processed input
-        let vk = Self::load_vk().map_err(|e| anyhow!("Failed to load vk. Error: {}", e))?;
-        let public_input: UniversalTransactionChainProofPublicInput = proving_data.clone().into();
-        let processed_input = PCD::msg_to_input_hash::<
-            UniversalTransactionChainProofPredicate<Config>,
-        >(&vk, &public_input.into())
-        .map_err(|e| anyhow!("Failed to process the public input. Error: {}", e))?;
+        let vk = Self::load_vk()?;
+        let processed_input = compute_processed_input(&vk, proving_data.clone().into())?;
 
         // Save processed input to file
         let processed_input_path =
@@ -127,81 +205,98 @@ impl NFT for UniversalTCPSnark {
             &data_to_serialisation(&processed_input),
             &processed_input_path,
         )
-        .map_err(|e| anyhow!("Failed to save the processed public input. Error: {}", e))?;
+        .map_err(|e| TokenError::Save {
+            name: "the processed public input".to_string(),
+            reason: e.to_string(),
+        })?;
+        info!(%processed_input_path, "processed input saved");
 
         Ok(())
     }
 
     /// Generate a proof for the provided `ProvingData`
-    fn prove(proving_data: ProvingData) -> Result<()> {
-        let pk = Self::load_pk().map_err(|e| anyhow!("Failed to load pk. Error: {}", e))?;
+    #[instrument(
+        skip_all,
+        fields(chain_index = proving_data.chain_parameters.chain_index, proof_name = %proving_data.proof_name)
+    )]
+    fn prove(proving_data: ProvingData) -> Result<(), TokenError> {
+        let pk = Self::load_pk()?;
 
         // Proving data
         let chain_index = proving_data.chain_parameters.chain_index;
-        let tx = match proving_data.witness.tx.is_empty() {
-            true => None,
-            false => Some(
-                Tx::read(&mut Cursor::new(
-                    hex::decode(proving_data.witness.tx.clone())
-                        .map_err(|e| anyhow!("Failed to hex decode witness tx. Error: {}", e))?,
-                ))
-                .map_err(|e| anyhow!("Failed to read witness tx. Error: {}", e))?,
-            ),
-        };
+        let tx = read_witness_tx(&proving_data.witness)?;
         let prior_proof = match proving_data.witness.prior_proof_path.is_empty() {
             true => None,
             false => {
                 let prior_proof_path =
                     Self::PROOFS_PATH.to_owned() + &proving_data.witness.prior_proof_path + ".bin";
-                Self::Proof::deserialize_unchecked(Cursor::new(
-                    read_from_file(&prior_proof_path)
-                        .map_err(|e| anyhow!("Failed to read prior proof. Error: {}", e))?,
-                ))
-                .ok()
+                Some(load_proof::<Self::Proof>(&prior_proof_path, "prior proof")?)
             }
         };
 
         // Proof generation
         let public_input: UniversalTransactionChainProofPublicInput = proving_data.clone().into();
         let witness = UniversalTransactionChainProofWitness::<Self::Proof> { tx, prior_proof };
-        let proof = Self::prove(&chain_index, &pk, &public_input, &witness).unwrap();
+        let proof = Self::prove(&chain_index, &pk, &public_input, &witness)
+            .map_err(|e| TokenError::Proving(format!("{:?}", e)))?;
 
         // Save proof to file
         let proof_path = Self::PROOFS_PATH.to_owned() + &proving_data.proof_name + ".bin";
-        save_to_file(&data_to_serialisation(&proof), &proof_path)
-            .map_err(|e| anyhow!("Failed to save proof. Error: {}", e))?;
+        let proof_serialised = data_to_serialisation(&proof);
+        save_to_file(&proof_serialised, &proof_path).map_err(|e| TokenError::Save {
+            name: "proof".to_string(),
+            reason: e.to_string(),
+        })?;
+        info!(%proof_path, "proof saved");
+
+        // Save the proof bundled with its public input
+        let bundle_path = Self::PROOFS_PATH.to_owned() + &proving_data.proof_name + "_bundle.toml";
+        ProofBundle::new(&proving_data, &proof_serialised).save(&bundle_path)?;
+        info!(%bundle_path, "proof bundle saved");
 
         Ok(())
     }
 
     /// Verify the proof contained in `VerifyingData`
-    fn verify(verifying_data: VerifyingData) -> Result<bool> {
-        let vk = Self::load_vk().map_err(|e| anyhow!("Failed to load vk. Error: {}", e))?;
+    #[instrument(
+        skip_all,
+        fields(chain_index = verifying_data.chain_parameters.chain_index, proof_name = %verifying_data.proof_path)
+    )]
+    fn verify(verifying_data: VerifyingData) -> Result<bool, TokenError> {
+        verifying_data.check_genesis()?;
+        let vk = Self::load_vk()?;
         let proof_path = Self::PROOFS_PATH.to_owned() + &verifying_data.proof_path + ".bin";
-        let proof =
-            Self::Proof::deserialize_unchecked(Cursor::new(read_from_file(&proof_path).unwrap()))
-                .map_err(|e| anyhow!("Failed to deserialize proof. Error: {}", e))?;
+        let proof = load_proof::<Self::Proof>(&proof_path, "proof")?;
         let public_input: UniversalTransactionChainProofPublicInput = verifying_data.into();
-        Self::verify(&vk, &public_input, &proof)
-            .map_err(|e| anyhow!("Failed to verify the proof. Error: {:?}", e))
+        let is_valid = Self::verify(&vk, &public_input, &proof)
+            .map_err(|e| TokenError::Verification(format!("{:?}", e)))?;
+        info!(is_valid, "proof verified");
+
+        Ok(is_valid)
     }
 
-    /// Load the proving key
-    fn load_pk() -> Result<Self::ProvingKey> {
-        let crh_pp_seed_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/crh_pp_seed.bin"))
-            .map_err(|e| anyhow!("Failed to read crh_pp. Error: {}", e))?;
-        let main_pk_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/main_pk.bin"))
-            .map_err(|e| anyhow!("Failed to read main_pk. Error: {}", e))?;
-        let help_pk_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/help_pk.bin"))
-            .map_err(|e| anyhow!("Failed to read help_pk. Error: {}", e))?;
+    /// Recompute the processed input from the public inputs contained in `VerifyingData` and check that it is
+    /// equal to the one saved by `process_input`
+    #[instrument(
+        skip_all,
+        fields(chain_index = verifying_data.chain_parameters.chain_index, proof_name = %verifying_data.proof_path)
+    )]
+    fn check_processed_input(verifying_data: VerifyingData) -> Result<(), TokenError> {
+        let vk = Self::load_vk()?;
+        let processed_input_path =
+            Self::PROOFS_PATH.to_owned() + &verifying_data.proof_path + "_processed_input.bin";
+        let processed_input = compute_processed_input(&vk, verifying_data.into())?;
+        check_saved_processed_input(&processed_input, &processed_input_path)
+    }
 
+    /// Load the proving key
+    #[instrument]
+    fn load_pk() -> Result<Self::ProvingKey, TokenError> {
         let crh_pp = VariableLengthPedersenParameters {
-            seed: crh_pp_seed_bytes,
+            seed: read_key("crh_pp_seed")?,
         };
-        let main_pk = Self::ProvingKeyMainCircuit::deserialize_unchecked(main_pk_bytes.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize main_pk. Error: {}", e))?;
-        let help_pk = Self::ProvingKeyHelpCircuit::deserialize_unchecked(help_pk_bytes.as_slice())
-            .map_err(|e| anyhow!("Failed to deserialize help_pk. Error: {}", e))?;
+        let main_pk: Self::ProvingKeyMainCircuit = deserialize_proving_key(&read_key("main_pk")?, "main_pk")?;
+        let help_pk: Self::ProvingKeyHelpCircuit = deserialize_proving_key(&read_key("help_pk")?, "help_pk")?;
         let help_vk = help_pk.vk.clone();
         let main_pvk: PreparedVerifyingKey<MNT4_753> = main_pk.vk.clone().into();
         Ok(Self::ProvingKey {
@@ -214,19 +309,338 @@ impl NFT for UniversalTCPSnark {
     }
 
     /// Load the verifying key
-    fn load_vk() -> Result<Self::VerifyingKey> {
-        let crh_pp_seed_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/crh_pp_seed.bin"))
-            .map_err(|e| anyhow!("Failed to read crh_pp. Error: {}", e))?;
-        let help_vk_bytes = read_from_file(&(Self::KEYS_PATH.to_owned() + "/help_vk.bin"))
-            .map_err(|e: std::io::Error| anyhow!("Failed to read help_vk. Error: {}", e))?;
-
+    #[instrument]
+    fn load_vk() -> Result<Self::VerifyingKey, TokenError> {
         let crh_pp = VariableLengthPedersenParameters {
-            seed: crh_pp_seed_bytes,
+            seed: read_key("crh_pp_seed")?,
         };
-        let help_vk =
-            Self::VerifyingKeyHelpCircuit::deserialize_unchecked(help_vk_bytes.as_slice())
-                .map_err(|e| anyhow!("Failed to deserialize help_vk. Error: {}", e))?;
+        let help_vk: Self::VerifyingKeyHelpCircuit = deserialize_key(&read_key("help_vk")?, "help_vk")?;
 
         Ok(Self::VerifyingKey { crh_pp, help_vk })
     }
+
+    /// Compute the SHA256 fingerprint of the verifying key (the Pedersen parameters and the help verifying key)
+    fn vk_fingerprint(vk: &Self::VerifyingKey) -> String {
+        fingerprint(&[vk.crh_pp.seed.clone(), data_to_serialisation(&vk.help_vk)])
+    }
+}
+
+/// Compute the processed input (i.e., the Pedersen hash) of `public_input`
+fn compute_processed_input(
+    vk: &<UniversalTCPSnark as NFT>::VerifyingKey,
+    public_input: UniversalTransactionChainProofPublicInput,
+) -> Result<Vec<ScalarFieldMNT6>, TokenError> {
+    PCD::msg_to_input_hash::<UniversalTransactionChainProofPredicate<Config>>(vk, &public_input.into())
+        .map_err(|e| TokenError::InputProcessing(format!("{:?}", e)))
+}
+
+/// Check that the processed input saved at `processed_input_path` is equal to `processed_input`
+fn check_saved_processed_input(
+    processed_input: &impl CanonicalSerialize,
+    processed_input_path: &str,
+) -> Result<(), TokenError> {
+    let saved_processed_input =
+        read_from_file(processed_input_path).map_err(|e| TokenError::ProofDeserialize {
+            name: "the processed public input".to_string(),
+            reason: e.to_string(),
+        })?;
+    match saved_processed_input == data_to_serialisation(processed_input) {
+        true => Ok(()),
+        false => Err(TokenError::StaleProcessedInput {
+            path: processed_input_path.to_string(),
+        }),
+    }
+}
+
+/// Save the key `key` to `<KEYS_PATH>/<key>.bin`
+fn save_key(data: &[u8], key: &str) -> Result<(), TokenError> {
+    save_to_file(data, &format!("{}{}.bin", <UniversalTCPSnark as NFT>::KEYS_PATH, key)).map_err(|e| {
+        TokenError::Save {
+            name: key.to_string(),
+            reason: e.to_string(),
+        }
+    })
+}
+
+/// Read the key `key` from `<KEYS_PATH>/<key>.bin`
+pub(crate) fn read_key(key: &str) -> Result<Vec<u8>, TokenError> {
+    read_from_file(&format!("{}{}.bin", <UniversalTCPSnark as NFT>::KEYS_PATH, key)).map_err(|e| {
+        TokenError::KeyLoad {
+            key: key.to_string(),
+            reason: e.to_string(),
+        }
+    })
+}
+
+/// Deserialize the key `key` from `bytes`
+fn deserialize_key<T: CanonicalDeserialize>(bytes: &[u8], key: &str) -> Result<T, TokenError> {
+    T::deserialize_unchecked(bytes).map_err(|e| TokenError::KeyLoad {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Deserialize the proving key `key` from `bytes`, saved with [key_to_serialisation] in either format
+fn deserialize_proving_key<T: CanonicalDeserialize>(bytes: &[u8], key: &str) -> Result<T, TokenError> {
+    key_from_serialisation(bytes).map_err(|e| TokenError::KeyLoad {
+        key: key.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Read and deserialize the proof `name` from `proof_path`
+fn load_proof<T: CanonicalDeserialize>(proof_path: &str, name: &str) -> Result<T, TokenError> {
+    let proof_bytes = read_from_file(proof_path).map_err(|e| TokenError::ProofDeserialize {
+        name: name.to_string(),
+        reason: e.to_string(),
+    })?;
+    T::deserialize_unchecked(Cursor::new(proof_bytes)).map_err(|e| TokenError::ProofDeserialize {
+        name: name.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::UniformRand;
+    use ark_mnt6_753::{G1Projective, G2Projective};
+    use crate::data_structures::verifying_data::{ChainParameters, PublicInputs};
+    use crate::util::KeyFormat;
+    use rand_chacha::rand_core::SeedableRng;
+
+    fn random_help_vk(rng: &mut ChaChaRng) -> VerifyingKey<MNT6_753> {
+        VerifyingKey {
+            alpha_g1: G1Projective::rand(rng).into_affine(),
+            beta_g2: G2Projective::rand(rng).into_affine(),
+            gamma_g2: G2Projective::rand(rng).into_affine(),
+            delta_g2: G2Projective::rand(rng).into_affine(),
+            gamma_abc_g1: vec![G1Projective::rand(rng).into_affine(), G1Projective::rand(rng).into_affine()],
+        }
+    }
+
+    #[test]
+    fn test_lock_script() {
+        let pubkey = hex::decode("029e5d4215de7fdbe3145a37d460a71f04657b557a25b2491a9aae34f49a8e549f").unwrap();
+        let script = lock_script(&pubkey).unwrap();
+        assert_eq!(script.0.len(), <Config as TxVarConfig>::LEN_LOCK_SCRIPTS[0]);
+        assert_eq!(script.0.len(), 0x19);
+
+        // Uncompressed public key
+        assert!(matches!(lock_script(&[0x04; 65]), Err(TokenError::TxParse { .. })));
+        // Wrong prefix
+        assert!(matches!(lock_script(&[0x04; COMPRESSED_PUBKEY_LEN]), Err(TokenError::TxParse { .. })));
+    }
+
+    /// Return the proving data of the first recursive step of `configs`, and the P2PKH locking script of the
+    /// output spent by its witness tx
+    fn recursive_step_with_prev_lock_script() -> (ProvingData, Script) {
+        let proving_data = ProvingData::load("configs/config_rec_1/prove.toml".to_string()).unwrap();
+        let tx = read_witness_tx(&proving_data.witness).unwrap().unwrap();
+        let unlock_script = &tx.inputs[0].unlock_script.0;
+        let pubkey = &unlock_script[unlock_script.len() - COMPRESSED_PUBKEY_LEN..];
+        (proving_data, lock_script(pubkey).unwrap())
+    }
+
+    #[test]
+    fn test_check_prev_lock_script() {
+        let proving_data = ProvingData::load("configs/config_rec_1/prove.toml".to_string()).unwrap();
+        let read_tx = || Tx::read(&mut Cursor::new(hex::decode(&proving_data.witness.tx).unwrap())).unwrap();
+        let tx = read_tx();
+        let unlock_script = &tx.inputs[0].unlock_script.0;
+        let prev_lock_script = lock_script(&unlock_script[unlock_script.len() - COMPRESSED_PUBKEY_LEN..]).unwrap();
+        assert!(check_prev_lock_script(&tx, &prev_lock_script).is_ok());
+
+        // P2PKH of a different public key
+        let other_pubkey = hex::decode("029e5d4215de7fdbe3145a37d460a71f04657b557a25b2491a9aae34f49a8e549f").unwrap();
+        let error = check_prev_lock_script(&tx, &lock_script(&other_pubkey).unwrap()).unwrap_err().to_string();
+        assert!(error.contains("does not spend an output locked by"), "{}", error);
+
+        // Not a P2PKH script
+        assert!(matches!(
+            check_prev_lock_script(&tx, &Script(vec![OP_CHECKSIG])),
+            Err(TokenError::TxParse { name, .. }) if name == "previous locking script"
+        ));
+
+        // The unlocking script does not end with a compressed public key
+        let mut truncated_tx = read_tx();
+        truncated_tx.inputs[0].unlock_script.0.pop();
+        assert!(matches!(check_prev_lock_script(&truncated_tx, &prev_lock_script), Err(TokenError::TxParse { .. })));
+
+        // No inputs
+        let mut no_inputs_tx = read_tx();
+        no_inputs_tx.inputs.clear();
+        assert!(matches!(check_prev_lock_script(&no_inputs_tx, &prev_lock_script), Err(TokenError::TxParse { .. })));
+    }
+
+    #[test]
+    fn test_read_witness_tx() {
+        let (mut proving_data, prev_lock_script) = recursive_step_with_prev_lock_script();
+        proving_data.witness.prev_lock_script_hex = Some(hex::encode(&prev_lock_script.0));
+        assert!(read_witness_tx(&proving_data.witness).unwrap().is_some());
+
+        // P2PKH of a different public key
+        let other_pubkey = hex::decode("029e5d4215de7fdbe3145a37d460a71f04657b557a25b2491a9aae34f49a8e549f").unwrap();
+        proving_data.witness.prev_lock_script_hex = Some(hex::encode(lock_script(&other_pubkey).unwrap().0));
+        assert!(matches!(
+            read_witness_tx(&proving_data.witness),
+            Err(TokenError::TxParse { name, .. }) if name == "previous locking script"
+        ));
+
+        // Not hex
+        proving_data.witness.prev_lock_script_hex = Some("76a9zz".to_string());
+        assert!(matches!(read_witness_tx(&proving_data.witness), Err(TokenError::TxParse { .. })));
+
+        // Base case: there is no tx spending the previous output
+        let mut base_case = ProvingData::load("configs/config_base/prove.toml".to_string()).unwrap();
+        assert!(base_case.witness.prev_lock_script_hex.is_none());
+        assert!(read_witness_tx(&base_case.witness).unwrap().is_none());
+        base_case.witness.prev_lock_script_hex = Some(hex::encode(&prev_lock_script.0));
+        assert!(matches!(read_witness_tx(&base_case.witness), Err(TokenError::TxParse { .. })));
+    }
+
+    /// Prove the base case and the first recursive step of `configs`, the latter with a P2PKH previous locking
+    /// script, and verify the proof of the recursive step
+    /// Run with `cargo test --release -- --ignored`
+    #[test]
+    #[ignore = "slow: runs the setup of the PCD"]
+    fn test_prove_with_prev_lock_script() {
+        let base_case = ProvingData::load("configs/config_base/prove.toml".to_string()).unwrap();
+        let (mut recursive_step, prev_lock_script) = recursive_step_with_prev_lock_script();
+        recursive_step.witness.prev_lock_script_hex = Some(hex::encode(&prev_lock_script.0));
+        let chain_index = recursive_step.chain_parameters.chain_index;
+        let (pk, vk) = UniversalTCPSnark::setup(&chain_index).unwrap();
+
+        let base_case_witness = UniversalTransactionChainProofWitness::<<UniversalTCPSnark as NFT>::Proof> {
+            tx: read_witness_tx(&base_case.witness).unwrap(),
+            prior_proof: None,
+        };
+        let base_case_proof =
+            UniversalTCPSnark::prove(&chain_index, &pk, &base_case.clone().into(), &base_case_witness).unwrap();
+
+        let witness = UniversalTransactionChainProofWitness::<<UniversalTCPSnark as NFT>::Proof> {
+            tx: read_witness_tx(&recursive_step.witness).unwrap(),
+            prior_proof: Some(base_case_proof),
+        };
+        let public_input: UniversalTransactionChainProofPublicInput = recursive_step.clone().into();
+        let proof = UniversalTCPSnark::prove(&chain_index, &pk, &public_input, &witness).unwrap();
+        assert!(UniversalTCPSnark::verify(&vk, &public_input, &proof).unwrap());
+        // The proof is bound to the outpoint of the recursive step
+        assert!(!UniversalTCPSnark::verify(&vk, &base_case.into(), &proof).unwrap());
+    }
+
+    #[test]
+    fn test_missing_key_file() {
+        let missing_key = "missing_key_for_test";
+        assert!(matches!(
+            read_key(missing_key),
+            Err(TokenError::KeyLoad { key, .. }) if key == missing_key
+        ));
+    }
+
+    #[test]
+    fn test_stale_processed_input() {
+        let processed_input_path = std::env::temp_dir().join("tcp_test_processed_input.bin");
+        let processed_input_path = processed_input_path.to_str().unwrap();
+        let processed_input = vec![ScalarFieldMNT6::from(1u64), ScalarFieldMNT6::from(2u64)];
+        save_to_file(&data_to_serialisation(&processed_input), processed_input_path).unwrap();
+
+        assert!(check_saved_processed_input(&processed_input, processed_input_path).is_ok());
+
+        let updated_processed_input = vec![ScalarFieldMNT6::from(1u64), ScalarFieldMNT6::from(3u64)];
+        assert!(matches!(
+            check_saved_processed_input(&updated_processed_input, processed_input_path),
+            Err(TokenError::StaleProcessedInput { path }) if path == processed_input_path
+        ));
+    }
+
+    #[test]
+    fn test_verify_expected_genesis() {
+        let data = |expected_genesis_txid: Option<&str>| VerifyingData {
+            chain_parameters: ChainParameters { chain_index: 0 },
+            public_inputs: PublicInputs {
+                outpoint_txid: "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa".to_string(),
+                genesis_txid: "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c".to_string(),
+            },
+            proof_path: "proof_for_genesis_test".to_string(),
+            expected_genesis_txid: expected_genesis_txid.map(str::to_string),
+        };
+
+        // The genesis txid is checked before loading the keys and the proof, so a proof for the wrong lineage is
+        // rejected whatever its validity
+        let wrong_genesis = "62623f437e4b26dc30c2a2484a84c12cdddb521c56d2ee2780e2428d60c89cfa";
+        assert!(matches!(
+            <UniversalTCPSnark as NFT>::verify(data(Some(wrong_genesis))),
+            Err(TokenError::GenesisMismatch { expected, .. }) if expected == wrong_genesis
+        ));
+        assert!(matches!(
+            <UniversalTCPSnark as NFT>::verify(data(Some("zz"))),
+            Err(TokenError::DataLoad { .. })
+        ));
+
+        // With the right (or no) expected genesis, the verification goes on and stops at the missing keys
+        let genesis = "4857fbb2da973cab3a42cf42bc705b56a8748d2ea0fa1527abfa54021ec2f86c";
+        for expected_genesis_txid in [Some(genesis), None] {
+            assert!(data(expected_genesis_txid).check_genesis().is_ok());
+            assert!(matches!(
+                <UniversalTCPSnark as NFT>::verify(data(expected_genesis_txid)),
+                Err(TokenError::KeyLoad { .. })
+            ));
+        }
+    }
+
+    fn random_help_pk(rng: &mut ChaChaRng) -> ProvingKey<MNT6_753> {
+        let random_g1 = |rng: &mut ChaChaRng, n: usize| {
+            (0..n).map(|_| G1Projective::rand(rng).into_affine()).collect::<Vec<_>>()
+        };
+        ProvingKey {
+            vk: random_help_vk(rng),
+            beta_g1: G1Projective::rand(rng).into_affine(),
+            delta_g1: G1Projective::rand(rng).into_affine(),
+            a_query: random_g1(rng, 4),
+            b_g1_query: random_g1(rng, 4),
+            b_g2_query: (0..4).map(|_| G2Projective::rand(rng).into_affine()).collect(),
+            h_query: random_g1(rng, 3),
+            l_query: random_g1(rng, 2),
+        }
+    }
+
+    #[test]
+    fn test_proving_key_formats_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let help_pk = random_help_pk(&mut rng);
+
+        let uncompressed = key_to_serialisation(&help_pk, KeyFormat::Uncompressed);
+        let compressed = key_to_serialisation(&help_pk, KeyFormat::Compressed);
+        assert!(compressed.len() < uncompressed.len());
+
+        for bytes in [uncompressed, compressed] {
+            let deserialized_help_pk: ProvingKey<MNT6_753> = deserialize_proving_key(&bytes, "help_pk").unwrap();
+            assert_eq!(deserialized_help_pk, help_pk);
+        }
+
+        assert!(matches!(
+            deserialize_proving_key::<ProvingKey<MNT6_753>>(&[2u8, 0u8], "help_pk"),
+            Err(TokenError::KeyLoad { .. })
+        ));
+    }
+
+    #[test]
+    fn test_vk_fingerprint_round_trip() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let seed = vec![1u8; 32];
+        let help_vk = random_help_vk(&mut rng);
+        let vk_fingerprint = fingerprint(&[seed.clone(), data_to_serialisation(&help_vk)]);
+
+        let deserialized_help_vk: VerifyingKey<MNT6_753> =
+            deserialize_key(&data_to_serialisation(&help_vk), "help_vk").unwrap();
+        assert_eq!(
+            vk_fingerprint,
+            fingerprint(&[seed.clone(), data_to_serialisation(&deserialized_help_vk)])
+        );
+
+        let other_help_vk = random_help_vk(&mut rng);
+        assert_ne!(vk_fingerprint, fingerprint(&[seed, data_to_serialisation(&other_help_vk)]));
+    }
 }
@@ -1,9 +1,9 @@
-use anyhow::Result;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use crate::data_structures::proving_data::ProvingData;
 use crate::data_structures::setup_data::SetupData;
 use crate::data_structures::verifying_data::VerifyingData;
+use crate::error::TokenError;
 
 pub mod groth16_nft;
 
@@ -20,20 +20,26 @@ pub trait NFT {
     const PROOFS_PATH: &str;
 
     // Perform the setup of the NFT and save the keys to file
-    fn setup(setup_data: SetupData) -> Result<()>;
+    fn setup(setup_data: SetupData) -> Result<(), TokenError>;
 
     // Process the public input contained in `ProvingData` and save it to file
-    fn process_input(proving_data: ProvingData) -> Result<()>;
+    fn process_input(proving_data: ProvingData) -> Result<(), TokenError>;
 
     // Prove that an NFT is held in the output of a given tx and save the proof to file
-    fn prove(proving_data: ProvingData) -> Result<()>;
+    fn prove(proving_data: ProvingData) -> Result<(), TokenError>;
 
     // Verify that an NFT is held in the output of a given tx
-    fn verify(verifying_data: VerifyingData) -> Result<bool>;
+    fn verify(verifying_data: VerifyingData) -> Result<bool, TokenError>;
+
+    // Check that the processed input saved by `process_input` matches the public inputs in `VerifyingData`
+    fn check_processed_input(verifying_data: VerifyingData) -> Result<(), TokenError>;
 
     // Load the proving key of the NFT scheme
-    fn load_pk() -> Result<Self::ProvingKey>;
+    fn load_pk() -> Result<Self::ProvingKey, TokenError>;
 
     // Load the verifying key of the NFT scheme
-    fn load_vk() -> Result<Self::VerifyingKey>;
+    fn load_vk() -> Result<Self::VerifyingKey, TokenError>;
+
+    // Compute the fingerprint of the verifying key, to check that prover and verifier share the same key
+    fn vk_fingerprint(vk: &Self::VerifyingKey) -> String;
 }
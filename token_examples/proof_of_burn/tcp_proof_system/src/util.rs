@@ -2,7 +2,9 @@ use std::fs::{self, File};
 use std::io::{Read, Result as IoResult, Write};
 use std::path::Path;
 
-use ark_serialize::CanonicalSerialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
 /// Save a list of bytes to `file_path`
 pub(crate) fn save_to_file(data: &[u8], file_path: &str) -> IoResult<()> {
@@ -37,3 +39,44 @@ pub(crate) fn data_to_serialisation(item: &impl CanonicalSerialize) -> Vec<u8> {
     item.serialize_unchecked(&mut serialized_data[..]).unwrap();
     serialized_data
 }
+
+/// Serialisation format of the proving keys, saved as the first byte of the serialisation
+/// - `Uncompressed`: fast to deserialise, as points are read without any check
+/// - `Compressed`: roughly half the size, but much slower to deserialise, as every point must be decompressed
+///   (which requires a square root) and checked
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyFormat {
+    #[default]
+    Uncompressed = 0,
+    Compressed = 1,
+}
+
+/// Serialise a key in `format`, prefixing the serialisation with the format byte
+pub(crate) fn key_to_serialisation(item: &impl CanonicalSerialize, format: KeyFormat) -> Vec<u8> {
+    let mut serialized_data: Vec<u8> = vec![format as u8];
+    match format {
+        KeyFormat::Uncompressed => serialized_data.extend(data_to_serialisation(item)),
+        KeyFormat::Compressed => item.serialize(&mut serialized_data).unwrap(),
+    }
+    serialized_data
+}
+
+/// Deserialise a key serialised with [key_to_serialisation], detecting its format from the first byte
+pub(crate) fn key_from_serialisation<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, SerializationError> {
+    match bytes.split_first() {
+        Some((&0, data)) => T::deserialize_unchecked(data),
+        Some((&1, data)) => T::deserialize(data),
+        _ => Err(SerializationError::InvalidData),
+    }
+}
+
+/// Compute the fingerprint of a verifying key from the serialisation of its components:
+/// the hex encoding of the SHA256 of their concatenation
+pub(crate) fn fingerprint(serialised_components: &[Vec<u8>]) -> String {
+    let mut hasher = Sha256::new();
+    for component in serialised_components {
+        hasher.update(component);
+    }
+    hex::encode(hasher.finalize())
+}